@@ -10,7 +10,7 @@ use anyhow::{Context, Result, bail};
 use clap::Subcommand;
 use uuid::Uuid;
 
-use synapse_core::{Config, Role, create_storage, text::truncate};
+use synapse_core::{Config, Role, TokenUsage, create_storage, text::truncate};
 
 /// Top-level subcommands for the `synapse` binary.
 #[derive(Subcommand)]
@@ -42,7 +42,7 @@ pub(crate) enum SessionAction {
 /// Handle session management subcommands.
 pub(crate) async fn handle_command(command: Commands, config_path: Option<&Path>) -> Result<()> {
     let config = Config::load(config_path)?;
-    let session_config = config.session.unwrap_or_default();
+    let session_config = config.session.clone().unwrap_or_default();
     let storage = create_storage(session_config.database_url.as_deref())
         .await
         .context("Failed to create storage")?;
@@ -94,6 +94,10 @@ pub(crate) async fn handle_command(command: Commands, config_path: Option<&Path>
                     .get_messages(id)
                     .await
                     .context("Failed to get messages")?;
+                let usage = storage
+                    .get_session_usage(id)
+                    .await
+                    .context("Failed to get session usage")?;
 
                 // Print session info
                 println!("Session: {}", session.id);
@@ -103,6 +107,10 @@ pub(crate) async fn handle_command(command: Commands, config_path: Option<&Path>
                     "Created: {}",
                     session.created_at.format("%Y-%m-%d %H:%M:%S")
                 );
+                println!(
+                    "Tokens: {}",
+                    format_usage(usage, config.estimate_cost(&session.model, usage))
+                );
                 println!();
 
                 if messages.is_empty() {
@@ -119,7 +127,12 @@ pub(crate) async fn handle_command(command: Commands, config_path: Option<&Path>
                         Role::Tool => "[TOOL]",
                     };
                     println!("{}", role_label);
-                    println!("{}", msg.content);
+                    if !msg.content.is_empty() {
+                        println!("{}", msg.content);
+                    }
+                    for call in msg.to_message().tool_calls.unwrap_or_default() {
+                        println!("-> {} {}", call.name, call.input);
+                    }
                     println!();
                 }
             }
@@ -140,3 +153,31 @@ pub(crate) async fn handle_command(command: Commands, config_path: Option<&Path>
 
     Ok(())
 }
+
+/// Format token counts with the estimated cost, if known.
+fn format_usage(usage: TokenUsage, cost: Option<f64>) -> String {
+    let tokens = format!("{} in / {} out", usage.input_tokens, usage.output_tokens);
+    match cost {
+        Some(cost) => format!("{} (est. ${:.4})", tokens, cost),
+        None => tokens,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_usage_with_cost() {
+        let usage = TokenUsage::new(1200, 300);
+        assert_eq!(
+            format_usage(usage, Some(0.0081)),
+            "1200 in / 300 out (est. $0.0081)"
+        );
+    }
+
+    #[test]
+    fn test_format_usage_without_cost() {
+        assert_eq!(format_usage(TokenUsage::new(5, 1), None), "5 in / 1 out");
+    }
+}
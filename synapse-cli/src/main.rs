@@ -5,15 +5,19 @@ mod repl;
 mod session;
 
 use std::io::{self, IsTerminal, Read, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use clap::Parser;
 use futures::StreamExt;
 use uuid::Uuid;
 
 use commands::{Commands, handle_command};
-use synapse_core::{Agent, Config, Message, Role, StoredMessage, StreamEvent, init_mcp_client};
+use synapse_core::{
+    Agent, Attachment, Config, Message, Role, StopReason, StoredMessage, StreamEvent, TokenUsage,
+    ToolRegistry, init_mcp_client,
+};
 
 /// Synapse CLI - AI agent command-line interface
 #[derive(Parser)]
@@ -35,6 +39,18 @@ struct Args {
     #[arg(short = 'p', long)]
     provider: Option<String>,
 
+    /// Override the model from config
+    #[arg(short = 'm', long)]
+    model: Option<String>,
+
+    /// Use a named profile from the config's [profiles] section
+    #[arg(long)]
+    profile: Option<String>,
+
+    /// Attach an image (png, jpg, gif, webp) or PDF to the message; repeatable
+    #[arg(short = 'a', long = "attach", value_name = "FILE")]
+    attachments: Vec<PathBuf>,
+
     /// Path to a custom config file (overrides default search locations)
     #[arg(short = 'c', long)]
     config: Option<PathBuf>,
@@ -53,10 +69,17 @@ async fn main() -> Result<()> {
     let args = Args::parse();
     let mut config = Config::load(args.config.as_deref())?;
 
-    // Apply provider override from CLI flag
+    // Apply profile, provider and model overrides from CLI flags
+    if let Some(ref profile) = args.profile {
+        config.apply_profile(profile)?;
+    }
     if let Some(ref provider) = args.provider {
         config.provider = provider.clone();
     }
+    if let Some(ref model) = args.model {
+        config.model = model.clone();
+    }
+    let explicit_model = args.profile.is_some() || args.provider.is_some() || args.model.is_some();
 
     // Handle subcommands
     if let Some(command) = args.command {
@@ -67,8 +90,11 @@ async fn main() -> Result<()> {
     if args.repl {
         let session_config = config.session.clone().unwrap_or_default();
         let storage = session::init_storage(&session_config).await?;
-        let (session, history) =
+        let (mut session, history) =
             session::load_or_create_session(storage.as_ref(), &config, args.session).await?;
+        let config =
+            session::session_config(storage.as_ref(), &config, &mut session, explicit_model)
+                .await?;
 
         let mcp_path = config.mcp.as_ref().and_then(|m| m.config_path.as_deref());
         let mcp_client = init_mcp_client(mcp_path).await;
@@ -90,20 +116,31 @@ async fn main() -> Result<()> {
     let storage = session::init_storage(&session_config).await?;
 
     // Load or create session
-    let (session, history) =
+    let (mut session, history) =
         session::load_or_create_session(storage.as_ref(), &config, args.session).await?;
+    let config =
+        session::session_config(storage.as_ref(), &config, &mut session, explicit_model).await?;
 
     // Build conversation history
-    let mut messages: Vec<Message> = history
-        .iter()
-        .map(|m| Message::new(m.role, &m.content))
-        .collect();
-
-    // Add new user message
-    messages.push(Message::new(Role::User, &message));
+    let mut messages: Vec<Message> = history.iter().map(StoredMessage::to_message).collect();
+
+    // Add new user message with its attachments; models without vision
+    // support get text placeholders instead.
+    let vision = config.supports_vision();
+    if !vision && !args.attachments.is_empty() {
+        eprintln!(
+            "[{} can't read images or PDFs; sending placeholders]",
+            config.model
+        );
+    }
+    let mut user_message = Message::new(Role::User, &message);
+    for path in &args.attachments {
+        user_message = add_attachment(user_message, read_attachment(path)?, vision);
+    }
 
     // Store user message
-    let user_msg = StoredMessage::new(session.id, Role::User, &message);
+    let user_msg = StoredMessage::from_message(session.id, &user_message);
+    messages.push(user_message);
     storage
         .add_message(&user_msg)
         .await
@@ -112,15 +149,27 @@ async fn main() -> Result<()> {
     // Create agent from config and MCP client
     let mcp_path = config.mcp.as_ref().and_then(|m| m.config_path.as_deref());
     let mcp_client = init_mcp_client(mcp_path).await;
-    let agent = Agent::from_config(&config, mcp_client).context("Failed to create agent")?;
+    let agent = Agent::from_config(&config, mcp_client)
+        .context("Failed to create agent")?
+        .with_native_tools(ToolRegistry::from_config(
+            &config.tools,
+            Some(Arc::clone(&storage)),
+        ));
+
+    // Summarize older turns if the history outgrows the context window
+    let mut messages = agent
+        .compact_history(storage.as_ref(), session.id, messages)
+        .await;
 
     // Stream response via agent (scoped to release borrows before shutdown)
-    let response_content = {
+    let (response_content, response_usage) = {
         let stream = agent.stream(&mut messages);
         tokio::pin!(stream);
 
         let mut stdout = io::stdout();
         let mut content = String::new();
+        // Usage of the current turn; tool-calling turns carry their own.
+        let mut usage: Option<TokenUsage> = None;
 
         loop {
             tokio::select! {
@@ -131,7 +180,42 @@ async fn main() -> Result<()> {
                             print!("{}", text);
                             stdout.flush().context("Failed to flush stdout")?;
                         }
-                        Some(Ok(StreamEvent::Done)) | None => {
+                        Some(Ok(StreamEvent::ToolCallStarted { name, .. })) => {
+                            // Tool activity goes to stderr so stdout stays pipeable.
+                            eprintln!("[calling {}]", name);
+                        }
+                        Some(Ok(StreamEvent::ToolCall(_))) => {
+                            // Text so far belonged to the tool-calling turn;
+                            // only the final answer is stored.
+                            content.clear();
+                        }
+                        Some(Ok(StreamEvent::ToolResult { content: result, is_error: true, .. })) => {
+                            eprintln!("[tool failed: {}]", result);
+                        }
+                        Some(Ok(StreamEvent::MessageAppended(appended))) => {
+                            let stored = StoredMessage::from_message(session.id, &appended);
+                            storage
+                                .add_message(&stored)
+                                .await
+                                .context("Failed to store tool message")?;
+                            usage = None;
+                        }
+                        Some(Ok(StreamEvent::Usage { input, output })) => {
+                            tracing::debug!(input, output, "token usage");
+                            *usage.get_or_insert_default() += TokenUsage::new(input, output);
+                        }
+                        Some(Ok(
+                            StreamEvent::ToolCallArgumentsDelta { .. }
+                            | StreamEvent::ToolResult { .. },
+                        )) => {}
+                        Some(Ok(StreamEvent::Done { stop_reason })) => {
+                            println!(); // Final newline
+                            if stop_reason != StopReason::EndTurn {
+                                eprintln!("[stopped: {}]", stop_reason);
+                            }
+                            break;
+                        }
+                        None => {
                             println!(); // Final newline
                             break;
                         }
@@ -147,12 +231,13 @@ async fn main() -> Result<()> {
             }
         }
 
-        content
+        (content, usage)
     };
 
     // Store assistant response
     if !response_content.is_empty() {
-        let assistant_msg = StoredMessage::new(session.id, Role::Assistant, &response_content);
+        let mut assistant_msg = StoredMessage::new(session.id, Role::Assistant, &response_content);
+        assistant_msg.usage = response_usage;
         storage
             .add_message(&assistant_msg)
             .await
@@ -189,6 +274,44 @@ fn get_message(args: &Args) -> io::Result<String> {
     Ok(buffer.trim_end().to_string())
 }
 
+/// Read a file given with `--attach`, typed by its extension.
+fn read_attachment(path: &Path) -> Result<Attachment> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    let media_type = match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "pdf" => "application/pdf",
+        _ => bail!(
+            "Cannot attach {}: only png, jpg, gif, webp and pdf files are supported",
+            path.display()
+        ),
+    };
+    let data = std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    if media_type.starts_with("image/") {
+        Ok(Attachment::image(media_type, data))
+    } else {
+        let name = path.file_name().map(|n| n.to_string_lossy().into_owned());
+        Ok(Attachment::document(media_type, name, data))
+    }
+}
+
+/// Attach `attachment` to `message`, or append its
+/// [`describe`](Attachment::describe) placeholder when the model lacks vision.
+fn add_attachment(mut message: Message, attachment: Attachment, vision: bool) -> Message {
+    if vision {
+        return message.with_attachment(attachment);
+    }
+    message.content.push('\n');
+    message.content.push_str(&attachment.describe());
+    message
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -269,9 +392,61 @@ mod tests {
         assert!(args.repl);
     }
 
+    #[test]
+    fn test_args_with_model_and_profile() {
+        let args = Args::parse_from(["synapse", "-m", "gpt-4o", "--profile", "work", "Hello"]);
+        assert_eq!(args.model, Some("gpt-4o".to_string()));
+        assert_eq!(args.profile, Some("work".to_string()));
+        assert_eq!(args.message, Some("Hello".to_string()));
+    }
+
     #[test]
     fn test_args_provider_default_none() {
         let args = Args::parse_from(["synapse", "Hello"]);
         assert!(args.provider.is_none());
     }
+
+    #[test]
+    fn test_args_attach_repeatable() {
+        let args = Args::parse_from(["synapse", "-a", "a.png", "--attach", "b.pdf", "Compare"]);
+        assert_eq!(
+            args.attachments,
+            vec![PathBuf::from("a.png"), PathBuf::from("b.pdf")]
+        );
+        assert_eq!(args.message, Some("Compare".to_string()));
+    }
+
+    #[test]
+    fn test_read_attachment_types_by_extension() {
+        let dir = std::env::temp_dir().join(format!("synapse_attach_{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["shot.PNG", "report.pdf", "notes.txt"] {
+            std::fs::write(dir.join(name), b"data").unwrap();
+        }
+
+        let image = read_attachment(&dir.join("shot.PNG")).unwrap();
+        assert_eq!(image, Attachment::image("image/png", b"data".to_vec()));
+        let pdf = read_attachment(&dir.join("report.pdf")).unwrap();
+        assert_eq!(pdf.describe(), "[document: report.pdf (application/pdf)]");
+        assert!(read_attachment(&dir.join("notes.txt")).is_err());
+        assert!(read_attachment(&dir.join("missing.png")).is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_add_attachment_placeholder_without_vision() {
+        let image = Attachment::image("image/png", b"data".to_vec());
+
+        let message = add_attachment(
+            Message::new(Role::User, "What is this?"),
+            image.clone(),
+            true,
+        );
+        assert_eq!(message.attachments, vec![image.clone()]);
+        assert_eq!(message.content, "What is this?");
+
+        let message = add_attachment(Message::new(Role::User, "What is this?"), image, false);
+        assert!(message.attachments.is_empty());
+        assert_eq!(message.content, "What is this?\n[image: image/png]");
+    }
 }
@@ -9,12 +9,15 @@
 //! - `app`   — [`ReplApp`] struct, state fields, transitions, and helpers
 //! - `render` — `render_ui` and layout/draw functions
 //! - `input`  — `handle_key_event` and key bindings
+//! - `approval` — `ReplApprover`, the y/n/always tool approval prompt
 
 mod app;
+mod approval;
 mod input;
 mod render;
 
 use std::io;
+use std::sync::Arc;
 
 use anyhow::{Context, Result};
 use crossterm::{
@@ -25,18 +28,134 @@ use crossterm::{
 use futures::StreamExt;
 use ratatui::layout::{Constraint, Layout};
 
-use app::{DisplayMessage, ReplApp};
+use app::{DisplayMessage, ReplApp, format_prompts, format_resources};
+use approval::{ApprovalAnswer, ReplApprover};
 use input::{KeyAction, handle_key_event};
 use render::{REPL_INPUT_HEIGHT, REPL_MIN_HISTORY_HEIGHT, REPL_STATUS_HEIGHT, render_ui};
+use synapse_core::mcp::parse_prompt_command;
 use synapse_core::{
-    Agent, AgentError, Config, McpClient, Message, Role, Session, SessionStore, StoredMessage,
-    StreamEvent,
+    Agent, AgentError, Config, McpClient, Message, Role, Session, SessionStore, StopReason,
+    StoredMessage, StreamEvent, TokenUsage, ToolOutput, ToolRegistry, load_mcp_config,
 };
+use uuid::Uuid;
 
 /// A pinned, boxed stream of agent stream events.
 type AgentStream<'a> =
     std::pin::Pin<Box<dyn futures::Stream<Item = Result<StreamEvent, AgentError>> + Send + 'a>>;
 
+/// Stream `messages` through `agent`, holding the agent until the stream ends.
+///
+/// Owning the agent lets `/model` replace it without borrowing conflicts.
+fn start_stream(agent: Arc<Agent>, messages: Vec<Message>) -> AgentStream<'static> {
+    Box::pin(async_stream::stream! {
+        let mut stream = agent.stream_owned(messages);
+        while let Some(event) = stream.next().await {
+            yield event;
+        }
+    })
+}
+
+/// IDs of the tool calls in the last assistant message that have no result yet.
+fn unanswered_tool_calls(conversation: &[Message]) -> Vec<String> {
+    let Some(pos) = conversation
+        .iter()
+        .rposition(|m| m.role == Role::Assistant && m.tool_calls.is_some())
+    else {
+        return Vec::new();
+    };
+    let answered: Vec<_> = conversation[pos + 1..]
+        .iter()
+        .filter_map(|m| m.tool_call_id.as_deref())
+        .collect();
+    conversation[pos]
+        .tool_calls
+        .iter()
+        .flatten()
+        .filter(|call| !answered.contains(&call.id.as_str()))
+        .map(|call| call.id.clone())
+        .collect()
+}
+
+/// Switch to the profile or model named by `spec`.
+///
+/// The new agent is built before the session is updated, so an unusable
+/// choice (e.g. a missing API key) leaves the current model in place.
+async fn switch_model(
+    agent: &Agent,
+    config: &Config,
+    spec: &str,
+    storage: &dyn SessionStore,
+    session_id: Uuid,
+) -> Result<(Agent, Config)> {
+    let config = config.switch_model(spec)?;
+    let agent = agent.fork(&config)?;
+    storage
+        .update_session_model(
+            session_id,
+            &config.provider,
+            &config.model,
+            config.profile.as_deref(),
+        )
+        .await?;
+    Ok((agent, config))
+}
+
+/// Run `/mcp [reload]`: list the connected MCP servers, or re-read the MCP
+/// config file and apply it to the running servers.
+async fn mcp_command(agent: &Agent, config: &Config, arg: &str) -> Result<String> {
+    let client = agent.mcp_client().context("MCP is not available")?;
+    match arg {
+        "" => {
+            let names = client.server_names();
+            Ok(if names.is_empty() {
+                "No MCP servers connected.".to_string()
+            } else {
+                format!("MCP servers: {}", names.join(", "))
+            })
+        }
+        "reload" => {
+            let mcp_path = config.mcp.as_ref().and_then(|m| m.config_path.as_deref());
+            let mcp_config = load_mcp_config(mcp_path)?.unwrap_or_default();
+            Ok(format!(
+                "MCP reloaded: {}",
+                client.reload(&mcp_config).await
+            ))
+        }
+        _ => anyhow::bail!("Usage: /mcp [reload]"),
+    }
+}
+
+/// Build the messages a submitted input adds to the conversation, along with
+/// how they are shown in the history.
+///
+/// `/prompt name key=value ...` renders an MCP prompt; any other input is a
+/// user message with its `@server:uri` resources attached.
+async fn prepare_turn(agent: &Agent, input: &str) -> Result<(Vec<Message>, Vec<DisplayMessage>)> {
+    if let Some(arg) = ReplApp::command_arg(input, "/prompt") {
+        let (name, arguments) = parse_prompt_command(arg)?;
+        let messages = agent.render_prompt(&name, arguments).await?;
+        let display = messages
+            .iter()
+            .map(|m| DisplayMessage {
+                role: m.role,
+                content: m.content.clone(),
+            })
+            .collect();
+        return Ok((messages, display));
+    }
+
+    let (text, attached) = agent.attach_resources(input).await?;
+    let mut display = vec![DisplayMessage {
+        role: Role::User,
+        content: input.to_string(),
+    }];
+    display.extend(attached.iter().map(|resource| DisplayMessage {
+        role: Role::System,
+        content: format!("Attached {}", resource),
+    }));
+    Ok((vec![Message::new(Role::User, text)], display))
+}
+
 /// Guard that restores terminal state on drop.
 ///
 /// Enables raw mode and enters alternate screen on creation.
@@ -75,23 +194,52 @@ impl Drop for TerminalGuard {
 /// * `mcp_client` - Optional MCP client for tool execution
 pub async fn run_repl(
     config: &Config,
-    storage: Box<dyn SessionStore>,
+    storage: Arc<dyn SessionStore>,
     session: Session,
     history: Vec<StoredMessage>,
     mcp_client: Option<McpClient>,
 ) -> Result<()> {
-    // Create agent from config and MCP client
-    let agent = Agent::from_config(config, mcp_client).context("Failed to create agent")?;
+    // Tool calls that need approval are prompted for in the status bar.
+    let (approver, mut approval_prompts) = ReplApprover::new();
+
+    // Create agent from config and MCP client; replaced on `/model`. Without
+    // servers an empty client still lets `/mcp reload` add them later.
+    let mcp_client = mcp_client.unwrap_or_else(McpClient::empty);
+    let mut agent = Arc::new(
+        Agent::from_config(config, Some(mcp_client))
+            .context("Failed to create agent")?
+            .with_native_tools(ToolRegistry::from_config(
+                &config.tools,
+                Some(Arc::clone(&storage)),
+            ))
+            .with_approver(Arc::new(approver)),
+    );
+    let mut config = config.clone();
 
     // Initialize app state
     let mut app = ReplApp::new(session.id, &config.provider, &config.model);
 
-    // Populate display messages from history (for session resume)
-    for msg in &history {
-        app.messages.push(DisplayMessage {
-            role: msg.role,
-            content: msg.content.clone(),
-        });
+    // Conversation sent to the agent, including tool calls and results.
+    let mut conversation: Vec<Message> = history.iter().map(StoredMessage::to_message).collect();
+
+    // Populate display messages from history (for session resume). Tool
+    // results are not shown; tool calls are shown as notices.
+    for msg in &conversation {
+        match msg.role {
+            Role::Tool => {}
+            Role::Assistant if msg.tool_calls.is_some() => {
+                for call in msg.tool_calls.iter().flatten() {
+                    app.messages.push(DisplayMessage {
+                        role: Role::Tool,
+                        content: format!("Called {}", call.name),
+                    });
+                }
+            }
+            _ => app.messages.push(DisplayMessage {
+                role: msg.role,
+                content: msg.content.clone(),
+            }),
+        }
     }
 
     // Set up terminal
@@ -102,12 +250,16 @@ pub async fn run_repl(
     let mut event_reader = EventStream::new();
 
     // Active agent stream (None when not streaming).
-    // Uses stream_owned() so the stream takes ownership of messages,
-    // avoiding borrow conflicts in the event loop.
-    let mut agent_stream: Option<AgentStream<'_>> = None;
+    // Owns both the agent handle and the messages, avoiding borrow
+    // conflicts in the event loop.
+    let mut agent_stream: Option<AgentStream<'static>> = None;
 
-    // Accumulated response content for storage
+    // Reply channel of the approval prompt shown in the status bar.
+    let mut approval_reply: Option<tokio::sync::oneshot::Sender<ApprovalAnswer>> = None;
+
+    // Accumulated response content and usage for storage
     let mut response_content = String::new();
+    let mut response_usage: Option<TokenUsage> = None;
 
     // Compute initial history height for page scroll
     let initial_area = terminal.get_frame().area();
@@ -137,41 +289,139 @@ pub async fn run_repl(
                         match handle_key_event(&mut app, key, history_height) {
                             KeyAction::Continue => {}
                             KeyAction::Exit => break,
+                            KeyAction::Approve(answer) => {
+                                app.pending_approval = None;
+                                if let Some(reply) = approval_reply.take() {
+                                    let _ = reply.send(answer);
+                                }
+                            }
+                            KeyAction::Cancel => {
+                                // Dropping the stream cancels running tool calls.
+                                agent_stream = None;
+                                app.is_streaming = false;
+                                app.cancel_tool_calls();
+
+                                // Answer the calls the model is waiting on so the
+                                // history stays valid for the next turn.
+                                let mut appended = Vec::new();
+                                for id in unanswered_tool_calls(&conversation) {
+                                    let output = ToolOutput::error("cancelled by the user");
+                                    appended.push(Message::tool_output(&id, output));
+                                }
+                                if !response_content.is_empty() {
+                                    appended.push(Message::new(Role::Assistant, &response_content));
+                                }
+                                for message in appended {
+                                    let stored = StoredMessage::from_message(session.id, &message);
+                                    if let Err(e) = storage.add_message(&stored).await {
+                                        app.status_message = Some(format!("Storage error: {}", e));
+                                    }
+                                    conversation.push(message);
+                                }
+                                let _ = storage.touch_session(session.id).await;
+                                app.status_message = Some(" Response cancelled".to_string());
+                            }
                             KeyAction::Submit(input) => {
-                                // Add user message to display
-                                app.messages.push(DisplayMessage {
-                                    role: Role::User,
-                                    content: input.clone(),
-                                });
-
-                                // Store user message
-                                let user_msg = StoredMessage::new(
-                                    session.id,
-                                    Role::User,
-                                    &input,
-                                );
-                                if let Err(e) = storage.add_message(&user_msg).await {
-                                    app.status_message = Some(
-                                        format!("Storage error: {}", e),
-                                    );
+                                if let Some(spec) = ReplApp::model_command_arg(&input) {
+                                    app.status_message = Some(if spec.is_empty() {
+                                        format!(" Model: {} / {}", config.provider, config.model)
+                                    } else {
+                                        match switch_model(
+                                            &agent,
+                                            &config,
+                                            spec,
+                                            storage.as_ref(),
+                                            session.id,
+                                        )
+                                        .await
+                                        {
+                                            Ok((new_agent, new_config)) => {
+                                                agent = Arc::new(new_agent);
+                                                config = new_config;
+                                                app.provider_name = config.provider.clone();
+                                                app.model_name = config.model.clone();
+                                                format!(
+                                                    " Switched to {} / {}",
+                                                    config.provider, config.model
+                                                )
+                                            }
+                                            Err(e) => format!(" Model switch failed: {:#}", e),
+                                        }
+                                    });
                                     continue;
                                 }
 
-                                // Build full conversation for agent from app.messages,
-                                // which already contains history (populated during session
-                                // resume) plus any new messages from this REPL session.
-                                let conv_messages: Vec<Message> = app
-                                    .messages
-                                    .iter()
-                                    .map(|m| Message::new(m.role, &m.content))
-                                    .collect();
+                                if ReplApp::command_arg(&input, "/resources").is_some() {
+                                    let resources = agent
+                                        .mcp_client()
+                                        .map(McpClient::resources)
+                                        .unwrap_or_default();
+                                    app.push_notice(format_resources(&resources));
+                                    continue;
+                                }
+                                if ReplApp::command_arg(&input, "/prompts").is_some() {
+                                    let prompts = agent
+                                        .mcp_client()
+                                        .map(McpClient::prompts)
+                                        .unwrap_or_default();
+                                    app.push_notice(format_prompts(&prompts));
+                                    continue;
+                                }
+                                if let Some(arg) = ReplApp::command_arg(&input, "/mcp") {
+                                    let notice = match mcp_command(&agent, &config, arg).await {
+                                        Ok(notice) => notice,
+                                        Err(e) => format!("{:#}", e),
+                                    };
+                                    app.push_notice(notice);
+                                    continue;
+                                }
+
+                                let (turn, display) = match prepare_turn(&agent, &input).await {
+                                    Ok(prepared) => prepared,
+                                    Err(e) => {
+                                        app.status_message = Some(format!(" {:#}", e));
+                                        continue;
+                                    }
+                                };
+                                app.messages.extend(display);
+
+                                // Store the new messages
+                                let mut stored = true;
+                                for message in &turn {
+                                    let stored_msg = StoredMessage::from_message(session.id, message);
+                                    if let Err(e) = storage.add_message(&stored_msg).await {
+                                        app.status_message = Some(
+                                            format!("Storage error: {}", e),
+                                        );
+                                        stored = false;
+                                        break;
+                                    }
+                                }
+                                if !stored {
+                                    continue;
+                                }
+                                conversation.extend(turn);
+
+                                // A prompt ending with an assistant message waits
+                                // for the user's reply.
+                                if conversation.last().is_none_or(|m| m.role != Role::User) {
+                                    continue;
+                                }
+
+                                // Summarize older turns if the history outgrows the
+                                // context window (only with the summarize strategy).
+                                let history = agent
+                                    .compact_history(storage.as_ref(), session.id, conversation.clone())
+                                    .await;
 
                                 // Start streaming via agent (stream_owned takes ownership
                                 // of the messages vec, avoiding borrow issues)
                                 app.is_streaming = true;
                                 app.auto_scroll = true;
+                                app.turn_usage = None;
                                 response_content.clear();
-                                agent_stream = Some(agent.stream_owned(conv_messages));
+                                response_usage = None;
+                                agent_stream = Some(start_stream(Arc::clone(&agent), history));
                             }
                         }
                     }
@@ -186,6 +436,12 @@ pub async fn run_repl(
                 }
             }
 
+            // Tool approval requests from the agent
+            Some(prompt) = approval_prompts.recv() => {
+                app.pending_approval = Some(prompt.text);
+                approval_reply = Some(prompt.reply);
+            }
+
             // Agent stream events (only when streaming)
             event = async {
                 if let Some(ref mut stream) = agent_stream {
@@ -200,28 +456,59 @@ pub async fn run_repl(
                         response_content.push_str(&text);
                         app.append_stream_delta(&text);
                     }
-                    Some(Ok(StreamEvent::Done)) | None => {
+                    Some(Ok(StreamEvent::ToolCallStarted { id, name })) => {
+                        app.start_tool_call(&id, &name);
+                    }
+                    Some(Ok(StreamEvent::ToolCall(_))) => {
+                        // Text so far belonged to the tool-calling turn;
+                        // only the final answer is stored.
+                        response_content.clear();
+                    }
+                    Some(Ok(StreamEvent::ToolResult { id, content, is_error })) => {
+                        app.finish_tool_call(&id, &content, is_error);
+                    }
+                    Some(Ok(StreamEvent::MessageAppended(appended))) => {
+                        let stored = StoredMessage::from_message(session.id, &appended);
+                        if let Err(e) = storage.add_message(&stored).await {
+                            app.status_message = Some(format!("Storage error: {}", e));
+                        }
+                        conversation.push(appended);
+                        // Tool-calling turns carry their own usage.
+                        response_usage = None;
+                    }
+                    Some(Ok(StreamEvent::Usage { input, output })) => {
+                        app.add_usage(input, output);
+                        *response_usage.get_or_insert_default() += TokenUsage::new(input, output);
+                    }
+                    Some(Ok(StreamEvent::ToolCallArgumentsDelta { .. })) => {}
+                    event @ (Some(Ok(StreamEvent::Done { .. })) | None) => {
                         app.is_streaming = false;
                         agent_stream = None;
 
                         // Store assistant response
                         if !response_content.is_empty() {
-                            let assistant_msg = StoredMessage::new(
-                                session.id,
-                                Role::Assistant,
-                                &response_content,
-                            );
+                            let mut assistant = Message::new(Role::Assistant, &response_content);
+                            assistant.usage = response_usage;
+                            let assistant_msg = StoredMessage::from_message(session.id, &assistant);
                             if let Err(e) = storage.add_message(&assistant_msg).await {
                                 app.status_message = Some(
                                     format!("Storage error: {}", e),
                                 );
                             }
+                            conversation.push(assistant);
                         }
 
                         // Touch session
                         let _ = storage.touch_session(session.id).await;
 
-                        app.status_message = None;
+                        app.status_message = match event {
+                            Some(Ok(StreamEvent::Done { stop_reason }))
+                                if !matches!(stop_reason, StopReason::EndTurn) =>
+                            {
+                                Some(format!(" Response stopped: {}", stop_reason))
+                            }
+                            _ => None,
+                        };
                     }
                     Some(Err(e)) => {
                         app.is_streaming = false;
@@ -241,10 +528,65 @@ pub async fn run_repl(
     ratatui::restore();
 
     // Shutdown agent (MCP connections)
-    agent.shutdown().await;
+    if let Ok(agent) = Arc::try_unwrap(agent) {
+        agent.shutdown().await;
+    }
 
     // Log session ID for future resumption
     tracing::info!("Session: {}", session.id);
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use synapse_core::message::ToolCallData;
+
+    fn assistant_with_calls(ids: &[&str]) -> Message {
+        let mut message = Message::new(Role::Assistant, "");
+        message.tool_calls = Some(
+            ids.iter()
+                .map(|id| ToolCallData {
+                    id: id.to_string(),
+                    name: "read_file".to_string(),
+                    input: serde_json::json!({}),
+                })
+                .collect(),
+        );
+        message
+    }
+
+    #[test]
+    fn test_unanswered_tool_calls_partial_results() {
+        let conversation = vec![
+            Message::new(Role::User, "read both"),
+            assistant_with_calls(&["call_1", "call_2", "call_3"]),
+            Message::tool_result("call_2", "ok"),
+        ];
+        assert_eq!(
+            unanswered_tool_calls(&conversation),
+            vec!["call_1".to_string(), "call_3".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_unanswered_tool_calls_without_tool_calls() {
+        let conversation = vec![
+            Message::new(Role::User, "hi"),
+            Message::new(Role::Assistant, "hello"),
+        ];
+        assert!(unanswered_tool_calls(&conversation).is_empty());
+    }
+
+    #[test]
+    fn test_unanswered_tool_calls_last_round_answered() {
+        let conversation = vec![
+            Message::new(Role::User, "read it"),
+            assistant_with_calls(&["call_1"]),
+            Message::tool_result("call_1", "ok"),
+            Message::new(Role::Assistant, "done"),
+        ];
+        assert!(unanswered_tool_calls(&conversation).is_empty());
+    }
+}
@@ -3,11 +3,14 @@
 //! Defines [`ReplApp`] with all state fields and helper methods
 //! for input editing, scrolling, and message management.
 
+use std::collections::HashMap;
+
 use ratatui::text::Line;
 use uuid::Uuid;
 
 use super::render::build_history_lines;
 use synapse_core::Role;
+use synapse_core::mcp::{PromptDefinition, ResourceDefinition};
 
 /// A display message in the conversation history.
 #[derive(Debug, Clone)]
@@ -40,6 +43,12 @@ pub(super) struct ReplApp {
     pub(super) provider_name: String,
     /// Model name for display.
     pub(super) model_name: String,
+    /// In-flight tool calls: tool call ID to (display message index, tool name).
+    pub(super) pending_tool_calls: HashMap<String, (usize, String)>,
+    /// Token usage (input, output) summed over the current or last turn.
+    pub(super) turn_usage: Option<(u32, u32)>,
+    /// Tool approval prompt waiting for a y/n/a answer.
+    pub(super) pending_approval: Option<String>,
 }
 
 impl ReplApp {
@@ -56,6 +65,9 @@ impl ReplApp {
             status_message: None,
             provider_name: provider_name.to_string(),
             model_name: model_name.to_string(),
+            pending_tool_calls: HashMap::new(),
+            turn_usage: None,
+            pending_approval: None,
         }
     }
 
@@ -122,6 +134,30 @@ impl ReplApp {
         input.trim() == "/quit"
     }
 
+    /// Parse a `/model [spec]` command, returning the (possibly empty) argument.
+    pub(super) fn model_command_arg(input: &str) -> Option<&str> {
+        Self::command_arg(input, "/model")
+    }
+
+    /// Parse a `<command> [args]` input, returning the (possibly empty) argument.
+    pub(super) fn command_arg<'a>(input: &'a str, command: &str) -> Option<&'a str> {
+        let rest = input.trim().strip_prefix(command)?;
+        if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+            Some(rest.trim())
+        } else {
+            None
+        }
+    }
+
+    /// Show a notice (command output) in the history.
+    pub(super) fn push_notice(&mut self, content: String) {
+        self.messages.push(DisplayMessage {
+            role: Role::System,
+            content,
+        });
+        self.auto_scroll = true;
+    }
+
     /// Scroll the history up by one line (decrease offset to show earlier content).
     pub(super) fn scroll_up(&mut self) {
         self.scroll_offset = self.scroll_offset.saturating_sub(1);
@@ -159,6 +195,49 @@ impl ReplApp {
         });
     }
 
+    /// Show a tool call notice in the history.
+    ///
+    /// The notice is updated in place by [`finish_tool_call`](Self::finish_tool_call).
+    pub(super) fn start_tool_call(&mut self, id: &str, name: &str) {
+        self.pending_tool_calls
+            .insert(id.to_string(), (self.messages.len(), name.to_string()));
+        self.messages.push(DisplayMessage {
+            role: Role::Tool,
+            content: format!("Calling {}…", name),
+        });
+    }
+
+    /// Update a tool call notice with the outcome of the call.
+    ///
+    /// Successful results are not shown in full; errors show the error text.
+    pub(super) fn finish_tool_call(&mut self, id: &str, content: &str, is_error: bool) {
+        let Some((idx, name)) = self.pending_tool_calls.remove(id) else {
+            return;
+        };
+        if let Some(msg) = self.messages.get_mut(idx) {
+            msg.content = if is_error {
+                format!("{} failed: {}", name, content)
+            } else {
+                format!("Called {}", name)
+            };
+        }
+    }
+
+    /// Mark every tool call still running as cancelled.
+    pub(super) fn cancel_tool_calls(&mut self) {
+        for (_, (idx, name)) in self.pending_tool_calls.drain() {
+            if let Some(msg) = self.messages.get_mut(idx) {
+                msg.content = format!("{} cancelled", name);
+            }
+        }
+    }
+
+    /// Add one provider request's token usage to the turn total.
+    pub(super) fn add_usage(&mut self, input: u32, output: u32) {
+        let (total_in, total_out) = self.turn_usage.unwrap_or((0, 0));
+        self.turn_usage = Some((total_in + input, total_out + output));
+    }
+
     /// Get the content of the last assistant message (for storage).
     #[cfg(test)]
     pub(super) fn last_assistant_content(&self) -> Option<&str> {
@@ -174,10 +253,53 @@ impl ReplApp {
     }
 }
 
+/// Format the reply to `/resources`: one `@server:uri` reference per resource.
+pub(super) fn format_resources(resources: &[ResourceDefinition]) -> String {
+    if resources.is_empty() {
+        return "No MCP resources available.".to_string();
+    }
+    let mut output = String::from("Resources (attach with @server:uri):");
+    for resource in resources {
+        output.push_str(&format!(
+            "\n  @{}:{} — {}",
+            resource.server, resource.uri, resource.name
+        ));
+        if let Some(ref description) = resource.description {
+            output.push_str(&format!(": {}", description));
+        }
+    }
+    output
+}
+
+/// Format the reply to `/prompts`: each prompt with its arguments.
+///
+/// Optional arguments are shown in brackets.
+pub(super) fn format_prompts(prompts: &[PromptDefinition]) -> String {
+    if prompts.is_empty() {
+        return "No MCP prompts available.".to_string();
+    }
+    let mut output = String::from("Prompts (run with /prompt name key=value):");
+    for prompt in prompts {
+        output.push_str(&format!("\n  {}", prompt.name));
+        for arg in &prompt.arguments {
+            if arg.required {
+                output.push_str(&format!(" {}=…", arg.name));
+            } else {
+                output.push_str(&format!(" [{}=…]", arg.name));
+            }
+        }
+        if let Some(ref description) = prompt.description {
+            output.push_str(&format!(" — {}", description));
+        }
+    }
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use synapse_core::Message;
+    use synapse_core::mcp::PromptArgument;
 
     #[test]
     fn test_repl_app_new() {
@@ -309,6 +431,28 @@ mod tests {
         assert!(!ReplApp::is_quit_command("hello"));
     }
 
+    #[test]
+    fn test_model_command_arg() {
+        assert_eq!(ReplApp::model_command_arg("/model"), Some(""));
+        assert_eq!(
+            ReplApp::model_command_arg(" /model  gpt-4o "),
+            Some("gpt-4o")
+        );
+        assert_eq!(ReplApp::model_command_arg("/models"), None);
+    }
+
+    #[test]
+    fn test_command_arg_requires_word_boundary() {
+        assert_eq!(
+            ReplApp::command_arg("/prompt review lang=rust", "/prompt"),
+            Some("review lang=rust")
+        );
+        assert_eq!(ReplApp::command_arg("/prompts", "/prompts"), Some(""));
+        assert_eq!(ReplApp::command_arg("/prompts", "/prompt"), None);
+        assert_eq!(ReplApp::command_arg("hello /prompt", "/prompt"), None);
+        assert_eq!(ReplApp::model_command_arg("use /model"), None);
+    }
+
     #[test]
     fn test_scroll() {
         let id = Uuid::new_v4();
@@ -393,6 +537,67 @@ mod tests {
         assert_eq!(app.messages[1].content, "Hi");
     }
 
+    #[test]
+    fn test_tool_call_notice_lifecycle() {
+        let id = Uuid::new_v4();
+        let mut app = ReplApp::new(id, "test", "test");
+
+        app.append_stream_delta("Let me check.");
+        app.start_tool_call("call_1", "get_weather");
+        assert_eq!(app.messages.len(), 2);
+        assert_eq!(app.messages[1].role, Role::Tool);
+        assert_eq!(app.messages[1].content, "Calling get_weather…");
+
+        app.finish_tool_call("call_1", "Sunny", false);
+        assert_eq!(app.messages[1].content, "Called get_weather");
+        assert!(app.pending_tool_calls.is_empty());
+
+        // Text after a tool call starts a new assistant message.
+        app.append_stream_delta("It is sunny.");
+        assert_eq!(app.messages.len(), 3);
+        assert_eq!(app.messages[2].role, Role::Assistant);
+    }
+
+    #[test]
+    fn test_finish_tool_call_error() {
+        let id = Uuid::new_v4();
+        let mut app = ReplApp::new(id, "test", "test");
+
+        app.start_tool_call("call_1", "search");
+        app.finish_tool_call("call_1", "Error: timeout", true);
+        assert_eq!(app.messages[0].content, "search failed: Error: timeout");
+
+        // Unknown IDs are ignored.
+        app.finish_tool_call("call_2", "x", false);
+        assert_eq!(app.messages.len(), 1);
+    }
+
+    #[test]
+    fn test_cancel_tool_calls() {
+        let id = Uuid::new_v4();
+        let mut app = ReplApp::new(id, "test", "test");
+
+        app.start_tool_call("call_1", "search");
+        app.start_tool_call("call_2", "fetch");
+        app.finish_tool_call("call_1", "ok", false);
+        app.cancel_tool_calls();
+
+        assert_eq!(app.messages[0].content, "Called search");
+        assert_eq!(app.messages[1].content, "fetch cancelled");
+        assert!(app.pending_tool_calls.is_empty());
+    }
+
+    #[test]
+    fn test_add_usage_accumulates() {
+        let id = Uuid::new_v4();
+        let mut app = ReplApp::new(id, "test", "test");
+
+        assert_eq!(app.turn_usage, None);
+        app.add_usage(100, 20);
+        app.add_usage(150, 30);
+        assert_eq!(app.turn_usage, Some((250, 50)));
+    }
+
     #[test]
     fn test_last_assistant_content() {
         let id = Uuid::new_v4();
@@ -545,4 +750,36 @@ mod tests {
         assert_eq!(app.input, "\u{00E9}b");
         assert_eq!(app.cursor_position, 0);
     }
+
+    #[test]
+    fn test_format_resources() {
+        assert_eq!(format_resources(&[]), "No MCP resources available.");
+
+        let output = format_resources(&[ResourceDefinition {
+            server: "fs".to_string(),
+            uri: "file:///notes.md".to_string(),
+            name: "notes".to_string(),
+            description: Some("Meeting notes".to_string()),
+            mime_type: None,
+        }]);
+
+        assert!(output.contains("@fs:file:///notes.md — notes: Meeting notes"));
+    }
+
+    #[test]
+    fn test_format_prompts_marks_optional_arguments() {
+        let argument = |name: &str, required| PromptArgument {
+            name: name.to_string(),
+            description: None,
+            required,
+        };
+        let output = format_prompts(&[PromptDefinition {
+            server: "code".to_string(),
+            name: "review".to_string(),
+            description: Some("Review code".to_string()),
+            arguments: vec![argument("language", true), argument("focus", false)],
+        }]);
+
+        assert!(output.contains("review language=… [focus=…] — Review code"));
+    }
 }
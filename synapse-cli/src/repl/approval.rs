@@ -0,0 +1,177 @@
+//! Tool call approval prompts for the REPL.
+//!
+//! [`ReplApprover`] forwards approval requests from the agent to the
+//! `run_repl` event loop, which shows them in the status bar and answers
+//! with the key the user presses (`y`, `n` or `a` for always).
+
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use synapse_core::message::ToolCallData;
+use synapse_core::text::truncate;
+use synapse_core::{ApprovalDecision, ToolApprover};
+use tokio::sync::{mpsc, oneshot};
+
+/// Maximum characters of tool arguments shown in an approval prompt.
+const ARGUMENTS_PREVIEW_CHARS: usize = 80;
+
+/// The user's answer to an approval prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ApprovalAnswer {
+    /// Run this call.
+    Yes,
+    /// Deny this call.
+    No,
+    /// Run this call and every later call to the same tool.
+    Always,
+}
+
+/// An approval request waiting for the user's answer.
+pub(super) struct ApprovalPrompt {
+    /// Status bar text describing the call.
+    pub(super) text: String,
+    /// Sends the answer back to the waiting agent.
+    pub(super) reply: oneshot::Sender<ApprovalAnswer>,
+}
+
+/// [`ToolApprover`] that asks the user in the REPL status bar.
+///
+/// Tools answered with "always" are approved without asking for the rest of
+/// the REPL session.
+pub(super) struct ReplApprover {
+    /// Sends prompts to the event loop.
+    prompts: mpsc::UnboundedSender<ApprovalPrompt>,
+    /// Tools the user approved for the rest of the session.
+    always: Mutex<HashSet<String>>,
+}
+
+impl ReplApprover {
+    /// Create an approver and the receiver the event loop reads prompts from.
+    pub(super) fn new() -> (Self, mpsc::UnboundedReceiver<ApprovalPrompt>) {
+        let (prompts, receiver) = mpsc::unbounded_channel();
+        let approver = Self {
+            prompts,
+            always: Mutex::new(HashSet::new()),
+        };
+        (approver, receiver)
+    }
+}
+
+#[async_trait]
+impl ToolApprover for ReplApprover {
+    async fn approve(&self, call: &ToolCallData, server: Option<&str>) -> ApprovalDecision {
+        if self.always.lock().expect("lock").contains(&call.name) {
+            return ApprovalDecision::Allow;
+        }
+
+        let (reply, answer) = oneshot::channel();
+        let prompt = ApprovalPrompt {
+            text: prompt_text(call, server),
+            reply,
+        };
+        if self.prompts.send(prompt).is_err() {
+            return ApprovalDecision::Deny;
+        }
+        match answer.await {
+            Ok(ApprovalAnswer::Yes) => ApprovalDecision::Allow,
+            Ok(ApprovalAnswer::Always) => {
+                self.always.lock().expect("lock").insert(call.name.clone());
+                ApprovalDecision::Allow
+            }
+            Ok(ApprovalAnswer::No) | Err(_) => ApprovalDecision::Deny,
+        }
+    }
+}
+
+/// Build the status bar text for an approval request.
+fn prompt_text(call: &ToolCallData, server: Option<&str>) -> String {
+    let tool = match server {
+        Some(server) => format!("{}/{}", server, call.name),
+        None => call.name.clone(),
+    };
+    format!(
+        " Allow {} {}? [y]es / [n]o / [a]lways",
+        tool,
+        truncate(&call.input.to_string(), ARGUMENTS_PREVIEW_CHARS)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    fn call(name: &str) -> ToolCallData {
+        ToolCallData {
+            id: "call_1".to_string(),
+            name: name.to_string(),
+            input: serde_json::json!({"path": "/tmp/a"}),
+        }
+    }
+
+    /// Ask `approver` about `name` and answer the prompt with `answer`.
+    async fn ask(
+        approver: &Arc<ReplApprover>,
+        prompts: &mut mpsc::UnboundedReceiver<ApprovalPrompt>,
+        name: &str,
+        answer: ApprovalAnswer,
+    ) -> ApprovalDecision {
+        let pending = tokio::spawn({
+            let approver = Arc::clone(approver);
+            let call = call(name);
+            async move { approver.approve(&call, Some("fs")).await }
+        });
+        let prompt = prompts.recv().await.unwrap();
+        prompt.reply.send(answer).unwrap();
+        pending.await.unwrap()
+    }
+
+    #[test]
+    fn test_prompt_text_names_server_and_arguments() {
+        assert_eq!(
+            prompt_text(&call("write_file"), Some("fs")),
+            r#" Allow fs/write_file {"path":"/tmp/a"}? [y]es / [n]o / [a]lways"#
+        );
+    }
+
+    #[tokio::test]
+    async fn test_approve_yes_and_no() {
+        let (approver, mut prompts) = ReplApprover::new();
+        let approver = Arc::new(approver);
+
+        let decision = ask(&approver, &mut prompts, "write_file", ApprovalAnswer::Yes).await;
+        assert_eq!(decision, ApprovalDecision::Allow);
+        let decision = ask(&approver, &mut prompts, "write_file", ApprovalAnswer::No).await;
+        assert_eq!(decision, ApprovalDecision::Deny);
+    }
+
+    #[tokio::test]
+    async fn test_approve_always_skips_later_prompts() {
+        let (approver, mut prompts) = ReplApprover::new();
+        let approver = Arc::new(approver);
+
+        let decision = ask(
+            &approver,
+            &mut prompts,
+            "write_file",
+            ApprovalAnswer::Always,
+        )
+        .await;
+        assert_eq!(decision, ApprovalDecision::Allow);
+
+        let decision = approver.approve(&call("write_file"), Some("fs")).await;
+        assert_eq!(decision, ApprovalDecision::Allow);
+        assert!(prompts.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_approve_denies_when_event_loop_is_gone() {
+        let (approver, prompts) = ReplApprover::new();
+        drop(prompts);
+
+        let decision = approver.approve(&call("write_file"), None).await;
+        assert_eq!(decision, ApprovalDecision::Deny);
+    }
+}
@@ -6,6 +6,7 @@
 use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 
 use super::app::ReplApp;
+use super::approval::ApprovalAnswer;
 
 /// Result of handling a key event.
 pub(super) enum KeyAction {
@@ -13,6 +14,10 @@ pub(super) enum KeyAction {
     Continue,
     /// Submit the input for processing.
     Submit(String),
+    /// Answer the pending tool approval prompt.
+    Approve(ApprovalAnswer),
+    /// Abort the response being streamed, cancelling running tool calls.
+    Cancel,
     /// Exit the REPL.
     Exit,
 }
@@ -50,9 +55,22 @@ pub(super) fn handle_key_event(app: &mut ReplApp, key: KeyEvent, history_height:
         _ => {}
     }
 
-    // Don't accept text input while streaming
+    // A pending approval prompt takes y/n/a (Esc denies) until answered
+    if app.pending_approval.is_some() {
+        return match key.code {
+            KeyCode::Char('y' | 'Y') => KeyAction::Approve(ApprovalAnswer::Yes),
+            KeyCode::Char('n' | 'N') | KeyCode::Esc => KeyAction::Approve(ApprovalAnswer::No),
+            KeyCode::Char('a' | 'A') => KeyAction::Approve(ApprovalAnswer::Always),
+            _ => KeyAction::Continue,
+        };
+    }
+
+    // Don't accept text input while streaming; Esc aborts the response
     if app.is_streaming {
-        return KeyAction::Continue;
+        return match key.code {
+            KeyCode::Esc => KeyAction::Cancel,
+            _ => KeyAction::Continue,
+        };
     }
 
     match key.code {
@@ -206,6 +224,46 @@ mod tests {
         assert_eq!(app.scroll_offset, 18); // 0 + (20-2) = 18
     }
 
+    #[test]
+    fn test_handle_key_event_approval_keys() {
+        let id = Uuid::new_v4();
+        let mut app = ReplApp::new(id, "test", "test");
+        app.is_streaming = true;
+        app.pending_approval = Some(" Allow write_file?".to_string());
+
+        let key = KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE);
+        let action = handle_key_event(&mut app, key, 20);
+        assert!(matches!(action, KeyAction::Approve(ApprovalAnswer::Yes)));
+
+        let key = KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE);
+        let action = handle_key_event(&mut app, key, 20);
+        assert!(matches!(action, KeyAction::Approve(ApprovalAnswer::Always)));
+
+        let key = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
+        let action = handle_key_event(&mut app, key, 20);
+        assert!(matches!(action, KeyAction::Approve(ApprovalAnswer::No)));
+
+        // Other keys are ignored while the prompt is pending
+        let key = KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE);
+        let action = handle_key_event(&mut app, key, 20);
+        assert!(matches!(action, KeyAction::Continue));
+        assert!(app.input.is_empty());
+    }
+
+    #[test]
+    fn test_handle_key_event_esc_cancels_streaming() {
+        let id = Uuid::new_v4();
+        let mut app = ReplApp::new(id, "test", "test");
+
+        let key = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
+        let action = handle_key_event(&mut app, key, 20);
+        assert!(matches!(action, KeyAction::Continue));
+
+        app.is_streaming = true;
+        let action = handle_key_event(&mut app, key, 20);
+        assert!(matches!(action, KeyAction::Cancel));
+    }
+
     #[test]
     fn test_handle_key_event_release_ignored() {
         let id = Uuid::new_v4();
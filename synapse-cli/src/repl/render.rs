@@ -99,14 +99,30 @@ pub(super) fn render_input(frame: &mut Frame, app: &ReplApp, area: Rect) {
 
 /// Render the status bar at the bottom.
 pub(super) fn render_status_bar(frame: &mut Frame, app: &ReplApp, area: Rect) {
+    if let Some(ref prompt) = app.pending_approval {
+        let approval = Paragraph::new(prompt.as_str()).style(
+            Style::default()
+                .bg(Color::Yellow)
+                .fg(Color::Black)
+                .add_modifier(Modifier::BOLD),
+        );
+        frame.render_widget(approval, area);
+        return;
+    }
+
     let status_text = if let Some(ref msg) = app.status_message {
         msg.clone()
     } else {
+        let usage = match app.turn_usage {
+            Some((input, output)) => format!(" | Tokens: {} in / {} out", input, output),
+            None => String::new(),
+        };
         format!(
-            " Session: {} | Provider: {} | Model: {} | /quit to exit",
+            " Session: {} | Provider: {} | Model: {}{} | /quit to exit",
             &app.session_id.to_string()[..8],
             app.provider_name,
             app.model_name,
+            usage,
         )
     };
 
@@ -3,6 +3,8 @@
 //! Extracted shared logic that was previously duplicated between the REPL
 //! path and the one-shot path in `main.rs`.
 
+use std::sync::Arc;
+
 use anyhow::{Context, Result};
 use uuid::Uuid;
 
@@ -14,7 +16,7 @@ use synapse_core::{Config, Session, SessionStore, StoredMessage, create_storage}
 /// Reads the database URL from `session_config` and delegates construction to
 /// [`create_storage`]. If `auto_cleanup` is enabled, triggers a cleanup pass
 /// (failures are intentionally ignored to avoid aborting the main operation).
-pub async fn init_storage(session_config: &SessionConfig) -> Result<Box<dyn SessionStore>> {
+pub async fn init_storage(session_config: &SessionConfig) -> Result<Arc<dyn SessionStore>> {
     let storage = create_storage(session_config.database_url.as_deref())
         .await
         .context("Failed to create storage")?;
@@ -23,7 +25,7 @@ pub async fn init_storage(session_config: &SessionConfig) -> Result<Box<dyn Sess
         let _ = storage.cleanup(session_config).await;
     }
 
-    Ok(storage)
+    Ok(Arc::from(storage))
 }
 
 /// Load an existing session by ID, or create a new session.
@@ -52,7 +54,8 @@ pub async fn load_or_create_session(
 
         Ok((session, messages))
     } else {
-        let session = Session::new(&config.provider, &config.model);
+        let session =
+            Session::new(&config.provider, &config.model).with_profile(config.profile.clone());
         storage
             .create_session(&session)
             .await
@@ -60,3 +63,44 @@ pub async fn load_or_create_session(
         Ok((session, Vec::new()))
     }
 }
+
+/// Resolve the configuration a session runs with.
+///
+/// A resumed session continues with the provider, model and profile it was
+/// recorded with. If the user chose a provider, model or profile explicitly
+/// (`explicit`), that choice wins and is saved to the session instead.
+pub async fn session_config(
+    storage: &dyn SessionStore,
+    config: &Config,
+    session: &mut Session,
+    explicit: bool,
+) -> Result<Config> {
+    if session.provider == config.provider
+        && session.model == config.model
+        && (session.profile.is_none() || session.profile == config.profile)
+    {
+        return Ok(config.clone());
+    }
+
+    if explicit {
+        storage
+            .update_session_model(
+                session.id,
+                &config.provider,
+                &config.model,
+                config.profile.as_deref(),
+            )
+            .await
+            .context("Failed to update session model")?;
+        session.provider = config.provider.clone();
+        session.model = config.model.clone();
+        session.profile = config.profile.clone();
+        return Ok(config.clone());
+    }
+
+    Ok(config.for_session(
+        &session.provider,
+        &session.model,
+        session.profile.as_deref(),
+    ))
+}
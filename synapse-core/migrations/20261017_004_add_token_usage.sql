@@ -0,0 +1,5 @@
+-- Add per-message token usage reported by the provider.
+-- Both columns are NULL for user and tool messages and for messages stored
+-- before usage tracking existed.
+ALTER TABLE messages ADD COLUMN input_tokens INTEGER;
+ALTER TABLE messages ADD COLUMN output_tokens INTEGER;
@@ -0,0 +1,14 @@
+-- Data derived from a session's messages, such as summaries of older turns
+-- produced by context-window management.
+-- message_count: number of leading session messages the artifact covers.
+CREATE TABLE IF NOT EXISTS session_artifacts (
+    id TEXT PRIMARY KEY NOT NULL,
+    session_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    content TEXT NOT NULL,
+    message_count INTEGER NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_session_artifacts_session ON session_artifacts(session_id, kind);
@@ -0,0 +1,14 @@
+-- Images and documents sent with a message, in the order they were attached.
+-- kind: "image" or "document".
+-- Exactly one of data (inline file contents) and url (fetched by the provider) is set.
+CREATE TABLE IF NOT EXISTS message_attachments (
+    message_id TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    media_type TEXT NOT NULL,
+    name TEXT,
+    url TEXT,
+    data BLOB,
+    PRIMARY KEY (message_id, position),
+    FOREIGN KEY (message_id) REFERENCES messages(id) ON DELETE CASCADE
+);
@@ -0,0 +1,4 @@
+-- Configuration profile a session runs with, recorded when a profile is
+-- selected (e.g. `/model <profile>`). NULL for sessions running the
+-- configured defaults and for sessions stored before profiles were recorded.
+ALTER TABLE sessions ADD COLUMN profile TEXT;
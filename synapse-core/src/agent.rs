@@ -1,19 +1,35 @@
 //! Agent orchestrator for tool calling.
 //!
-//! Provides [`Agent`] which coordinates an LLM provider and an optional
-//! MCP client to implement the detect-execute-return tool call loop.
+//! Provides [`Agent`] which coordinates an LLM provider, native tools and an
+//! optional MCP client to implement the detect-execute-return tool call loop,
+//! asking a [`ToolApprover`] before running tools that require approval.
 
+use std::collections::HashMap;
 use std::pin::Pin;
-
-use futures::Stream;
-
-use crate::config::Config;
-use crate::mcp::McpClient;
-use crate::message::Message;
-use crate::provider::{LlmProvider, ProviderError, StreamEvent};
-
-/// Maximum number of tool call iterations before giving up.
-const MAX_ITERATIONS: usize = 10;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures::{Stream, StreamExt};
+use uuid::Uuid;
+
+use crate::approval::{ApprovalDecision, ToolApprover, ToolPolicy};
+use crate::config::{AgentConfig, ApprovalConfig, Config, ToolsConfig};
+use crate::context::{
+    ContextManager, ContextStrategy, estimate_text_tokens, estimate_tokens, summary_message,
+    summary_request,
+};
+use crate::mcp::{McpClient, ResourceRef, ToolDefinition};
+use crate::message::{Message, Role, TokenUsage, ToolCallData, ToolOutput};
+use crate::provider::{GenerationOptions, LlmProvider, ProviderError, StopReason, StreamEvent};
+use crate::session::{ArtifactKind, SessionArtifact};
+use crate::storage::SessionStore;
+use crate::tools::ToolRegistry;
+
+/// Instruction closing the request for a final answer once a turn has run
+/// out of tool iterations or budget.
+const WRAP_UP_PROMPT: &str = "You cannot call any more tools for this request. Answer it now \
+     from the tool results above: say what you found and, if the results are incomplete, what \
+     is still missing.";
 
 /// Error type for agent operations.
 #[derive(Debug, thiserror::Error)]
@@ -25,10 +41,6 @@ pub enum AgentError {
     /// Error from MCP tool execution.
     #[error("MCP error: {0}")]
     Mcp(#[from] crate::mcp::McpError),
-
-    /// Tool call loop exceeded the maximum iteration limit.
-    #[error("max tool call iterations exceeded")]
-    MaxIterationsExceeded,
 }
 
 /// Agent orchestrator that coordinates LLM providers and MCP tools.
@@ -39,6 +51,10 @@ pub enum AgentError {
 /// 3. If response contains tool calls: execute tools, append results, go to 1
 /// 4. If response is text only: return to caller
 ///
+/// A turn that runs out of iterations or of its token or time budget (see
+/// [`AgentConfig`]) ends with one more call without tools, asking the model
+/// to answer from the tool results gathered so far.
+///
 /// # Examples
 ///
 /// ```
@@ -59,13 +75,27 @@ pub enum AgentError {
 pub struct Agent {
     /// The LLM provider for generating responses.
     provider: Box<dyn LlmProvider>,
-    /// Optional MCP client for tool execution.
-    mcp_client: Option<McpClient>,
+    /// Optional MCP client for tool execution, shared with forked agents.
+    mcp_client: Option<Arc<McpClient>>,
+    /// Native tools, offered before MCP tools with the same name.
+    native_tools: ToolRegistry,
     /// Optional system prompt prepended to every provider call.
     ///
     /// Injected on-the-fly via `build_messages()` and never stored in the
     /// session database.
     system_prompt: Option<String>,
+    /// Sampling parameters sent with every provider call.
+    options: GenerationOptions,
+    /// Fits the history into the model's context window before each call.
+    context: ContextManager,
+    /// Which tool calls run, are denied, or need the user's approval.
+    approval: ApprovalConfig,
+    /// Asks the user about tool calls with the `ask` policy.
+    approver: Option<Arc<dyn ToolApprover>>,
+    /// Concurrency limit and timeout for tool calls.
+    tools: ToolsConfig,
+    /// Iteration limit and budgets of the tool call loop.
+    limits: AgentConfig,
 }
 
 impl Agent {
@@ -81,8 +111,15 @@ impl Agent {
     pub fn new(provider: Box<dyn LlmProvider>, mcp_client: Option<McpClient>) -> Self {
         Self {
             provider,
-            mcp_client,
+            mcp_client: mcp_client.map(Arc::new),
+            native_tools: ToolRegistry::new(),
             system_prompt: None,
+            options: GenerationOptions::default(),
+            context: ContextManager::default(),
+            approval: ApprovalConfig::default(),
+            approver: None,
+            tools: ToolsConfig::default(),
+            limits: AgentConfig::default(),
         }
     }
 
@@ -92,7 +129,12 @@ impl Agent {
     /// [`create_provider`](crate::provider::create_provider) internally so callers
     /// do not need to construct the provider themselves.
     ///
-    /// The system prompt is resolved from `config.system_prompt` if set.
+    /// The system prompt is resolved from `config.system_prompt` if set,
+    /// sampling parameters from `config.generation`, context-window
+    /// management from the model and `config.context`, the tool approval
+    /// policy from `config.approval`, tool execution limits and native tools
+    /// from `config.tools`, and loop limits from `config.agent`. The `search_sessions` tool needs session
+    /// storage; add it with [`with_native_tools`](Agent::with_native_tools).
     ///
     /// # Errors
     ///
@@ -103,13 +145,100 @@ impl Agent {
         mcp_client: Option<McpClient>,
     ) -> Result<Self, ProviderError> {
         let provider = crate::provider::create_provider(config)?;
-        let agent = Self::new(provider, mcp_client);
+        let agent = Self::new(provider, mcp_client)
+            .with_generation_options(config.generation.clone())
+            .with_context(ContextManager::from_config(config))
+            .with_approval(config.approval.clone())
+            .with_tools_config(config.tools.clone())
+            .with_agent_config(config.agent.clone())
+            .with_native_tools(ToolRegistry::from_config(&config.tools, None));
         Ok(match config.system_prompt {
             Some(ref prompt) => agent.with_system_prompt(prompt),
             None => agent,
         })
     }
 
+    /// Create an agent for another provider configuration that shares this
+    /// agent's MCP connections.
+    ///
+    /// Used to honour a per-session provider or model without reconnecting
+    /// MCP servers. The provider, system prompt, sampling parameters, context
+    /// window, approval policy, tool limits and loop limits come from
+    /// `config`; the
+    /// approver and native tools are kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProviderError`] if the configured provider cannot be created.
+    pub fn fork(&self, config: &Config) -> Result<Self, ProviderError> {
+        let provider = crate::provider::create_provider(config)?;
+        Ok(Self {
+            provider,
+            mcp_client: self.mcp_client.clone(),
+            native_tools: self.native_tools.clone(),
+            system_prompt: config.system_prompt.clone(),
+            options: config.generation.clone(),
+            context: ContextManager::from_config(config),
+            approval: config.approval.clone(),
+            approver: self.approver.clone(),
+            tools: config.tools.clone(),
+            limits: config.agent.clone(),
+        })
+    }
+
+    /// Set the sampling parameters sent with every provider call.
+    pub fn with_generation_options(mut self, options: GenerationOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Set the context-window manager applied before every provider call.
+    ///
+    /// Agents created with [`new`](Agent::new) never drop history.
+    pub fn with_context(mut self, context: ContextManager) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// Set the policy deciding which tool calls run, are denied, or need approval.
+    ///
+    /// Agents created with [`new`](Agent::new) run every tool call.
+    pub fn with_approval(mut self, approval: ApprovalConfig) -> Self {
+        self.approval = approval;
+        self
+    }
+
+    /// Set the approver asked about tool calls with the [`ToolPolicy::Ask`] policy.
+    ///
+    /// Without an approver such calls are denied.
+    pub fn with_approver(mut self, approver: Arc<dyn ToolApprover>) -> Self {
+        self.approver = Some(approver);
+        self
+    }
+
+    /// Set how many tool calls run at once and how long each may take.
+    pub fn with_tools_config(mut self, tools: ToolsConfig) -> Self {
+        self.tools = tools;
+        self
+    }
+
+    /// Set the iteration limit and token and time budgets of one turn.
+    ///
+    /// Agents created with [`new`](Agent::new) allow 10 iterations and have
+    /// no budget.
+    pub fn with_agent_config(mut self, limits: AgentConfig) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Set the native tools offered to the model next to MCP tools.
+    ///
+    /// A native tool hides an MCP tool with the same name.
+    pub fn with_native_tools(mut self, native_tools: ToolRegistry) -> Self {
+        self.native_tools = native_tools;
+        self
+    }
+
     /// Set the system prompt prepended to every provider call.
     ///
     /// The system prompt is injected on-the-fly via `build_messages()` and
@@ -133,16 +262,30 @@ impl Agent {
     /// when configured.
     ///
     /// Creates a new `Vec<Message>` each time. The caller's original messages are
-    /// never mutated with the system message.
+    /// never mutated with the system message. History that would overflow the
+    /// model's context window is truncated by the [`ContextManager`].
+    fn build_messages(&self, messages: &[Message], tools: &[ToolDefinition]) -> Vec<Message> {
+        let system = self.system_message(tools);
+        let fixed_tokens = system.as_ref().map_or(0, estimate_tokens) + tool_tokens(tools);
+        let history = self.context.truncate(messages, fixed_tokens);
+
+        match system {
+            Some(system) => {
+                let mut result = Vec::with_capacity(history.len() + 1);
+                result.push(system);
+                result.extend(history);
+                result
+            }
+            None => history,
+        }
+    }
+
+    /// Build the system message from the system prompt and tool list.
     ///
     /// When `tools` is non-empty, a tool availability note is appended to the system
     /// prompt so that providers which ignore the API-level `tools` field (e.g. DeepSeek)
     /// still receive the list through the system message.
-    fn build_messages(
-        &self,
-        messages: &[Message],
-        tools: &[crate::mcp::ToolDefinition],
-    ) -> Vec<Message> {
+    fn system_message(&self, tools: &[ToolDefinition]) -> Option<Message> {
         let tool_note = if tools.is_empty() {
             None
         } else {
@@ -163,21 +306,95 @@ impl Agent {
             ))
         };
 
-        let system_content = match (&self.system_prompt, &tool_note) {
-            (Some(prompt), Some(note)) => Some(format!("{prompt}{note}")),
-            (Some(prompt), None) => Some(prompt.clone()),
-            (None, Some(note)) => Some(note.trim_start().to_string()),
-            (None, None) => None,
+        let content = match (&self.system_prompt, &tool_note) {
+            (Some(prompt), Some(note)) => format!("{prompt}{note}"),
+            (Some(prompt), None) => prompt.clone(),
+            (None, Some(note)) => note.trim_start().to_string(),
+            (None, None) => return None,
+        };
+        Some(Message::new(crate::message::Role::System, content))
+    }
+
+    /// Prepare a session's history for the next provider call.
+    ///
+    /// `messages` must be the session's full history in storage order. With
+    /// the [`ContextStrategy::Summarize`] strategy, the latest stored summary
+    /// replaces the messages it covers, and once the rest no longer fits the
+    /// context window, older turns are summarized by the model and the new
+    /// summary is stored as an [`ArtifactKind::Summary`] artifact. The summary
+    /// is passed to the model as a system message at the start of the history.
+    ///
+    /// Other strategies return `messages` unchanged. Storage and provider
+    /// failures are logged and leave the history to plain truncation.
+    pub async fn compact_history(
+        &self,
+        storage: &dyn SessionStore,
+        session_id: Uuid,
+        mut messages: Vec<Message>,
+    ) -> Vec<Message> {
+        if self.context.strategy() != ContextStrategy::Summarize {
+            return messages;
+        }
+
+        let previous = match storage
+            .get_latest_artifact(session_id, ArtifactKind::Summary)
+            .await
+        {
+            Ok(artifact) => artifact.filter(|a| a.message_count as usize <= messages.len()),
+            Err(e) => {
+                tracing::warn!(%session_id, "context: failed to load summary: {}", e);
+                None
+            }
         };
+        let covered = previous.as_ref().map_or(0, |a| a.message_count as usize);
+        let mut recent = messages.split_off(covered);
+        let mut summary = previous.map(|a| a.content);
+
+        let tools = self.get_tool_definitions();
+        let fixed_tokens = self
+            .system_message(&tools)
+            .as_ref()
+            .map_or(0, estimate_tokens)
+            + tool_tokens(&tools)
+            + summary
+                .as_deref()
+                .map_or(0, |s| estimate_tokens(&summary_message(s)));
+        if let Some(split) = self.context.summary_split(&recent, fixed_tokens) {
+            let older = self.context.truncate(&recent[..split], 0);
+            match self
+                .provider
+                .complete(&summary_request(summary.as_deref(), &older))
+                .await
+            {
+                Ok(response) if !response.content.trim().is_empty() => {
+                    let content = response.content.trim().to_string();
+                    let message_count = u32::try_from(covered + split).unwrap_or(u32::MAX);
+                    let artifact = SessionArtifact::new(
+                        session_id,
+                        ArtifactKind::Summary,
+                        &content,
+                        message_count,
+                    );
+                    if let Err(e) = storage.add_artifact(&artifact).await {
+                        tracing::warn!(%session_id, "context: failed to store summary: {}", e);
+                    }
+                    tracing::info!(%session_id, summarized = split, "context: summarized older turns");
+                    recent = recent.split_off(split);
+                    summary = Some(content);
+                }
+                Ok(_) => tracing::warn!(%session_id, "context: model returned an empty summary"),
+                Err(e) => tracing::warn!(%session_id, "context: summarization failed: {}", e),
+            }
+        }
 
-        match system_content {
-            Some(content) => {
-                let mut result = Vec::with_capacity(messages.len() + 1);
-                result.push(Message::new(crate::message::Role::System, &content));
-                result.extend_from_slice(messages);
+        match summary {
+            Some(summary) => {
+                let mut result = Vec::with_capacity(recent.len() + 1);
+                result.push(summary_message(&summary));
+                result.extend(recent);
                 result
             }
-            None => messages.to_vec(),
+            None => recent,
         }
     }
 
@@ -185,25 +402,25 @@ impl Agent {
     ///
     /// Returns the final assistant text response after all tool calls
     /// have been resolved. The `messages` vec is extended in-place with
-    /// tool call and tool result messages.
+    /// tool call and tool result messages. If the turn runs out of
+    /// iterations or budget, the response is the model's answer to a final
+    /// request without tools.
     ///
     /// # Errors
     ///
-    /// Returns [`AgentError::MaxIterationsExceeded`] if the tool call loop
-    /// exceeds the maximum iteration limit (10).
+    /// Returns [`AgentError::Provider`] if a provider call fails.
     pub async fn complete(&self, messages: &mut Vec<Message>) -> Result<Message, AgentError> {
         let tools = self.get_tool_definitions();
+        let mut budget = TurnBudget::new(&self.limits);
 
-        for iteration in 0..MAX_ITERATIONS {
+        while let Some(iteration) = budget.next_iteration() {
             tracing::debug!(iteration, "agent: starting tool call iteration");
             let provider_messages = self.build_messages(messages, &tools);
-            let response = if tools.is_empty() {
-                self.provider.complete(&provider_messages).await?
-            } else {
-                self.provider
-                    .complete_with_tools(&provider_messages, &tools)
-                    .await?
-            };
+            let response = self
+                .provider
+                .complete_with_options(&provider_messages, &tools, &self.options)
+                .await?;
+            budget.record(response.usage);
 
             // Check for tool calls
             if let Some(ref tool_calls) = response.tool_calls
@@ -215,18 +432,13 @@ impl Agent {
                 // Append assistant message with tool calls
                 messages.push(response);
 
-                for tool_call in &tool_calls_to_execute {
-                    tracing::debug!(tool = %tool_call.name, "agent: calling tool");
-                    let result = self.execute_tool(&tool_call.name, &tool_call.input).await;
-                    let result_content = match result {
-                        Ok(value) => match value {
-                            serde_json::Value::String(s) => s,
-                            other => other.to_string(),
-                        },
-                        Err(e) => format!("Error: {}", e),
-                    };
-
-                    messages.push(Message::tool_result(&tool_call.id, result_content));
+                let results: Vec<_> = self
+                    .run_tools(&tool_calls_to_execute, self.approver.as_deref())
+                    .await
+                    .collect()
+                    .await;
+                for (tool_call, output) in results {
+                    messages.push(Message::tool_output(&tool_call.id, output));
                 }
 
                 continue;
@@ -236,47 +448,139 @@ impl Agent {
             return Ok(response);
         }
 
-        Err(AgentError::MaxIterationsExceeded)
+        let provider_messages = self.build_messages(&wrap_up_messages(messages), &[]);
+        let mut response = self
+            .provider
+            .complete_with_options(&provider_messages, &[], &self.options)
+            .await?;
+        response.tool_calls = None;
+        Ok(response)
     }
 
     /// Stream a conversation response, handling tool calls automatically.
     ///
-    /// Tool call iterations happen internally using non-streaming completions.
-    /// Only the final text response is streamed (or yielded as a single delta
-    /// if tool calls were involved).
+    /// Every iteration of the tool call loop is streamed: provider events
+    /// (text, tool call progress, usage) are forwarded as they arrive, and a
+    /// [`StreamEvent::ToolResult`] is yielded after each tool runs. Independent
+    /// tool calls of one response run concurrently, but their results are
+    /// yielded and appended in the order the model requested them. As with
+    /// [`complete`](Agent::complete), `messages` is extended in-place with
+    /// tool call and tool result messages, each also reported as a
+    /// [`StreamEvent::MessageAppended`]; assistant tool call messages carry
+    /// the usage reported for their turn. The final assistant text is not
+    /// appended.
     ///
-    /// When no tools are available, delegates directly to `provider.stream()`.
+    /// Intermediate `Done` events are swallowed; the stream ends with a single
+    /// [`StreamEvent::Done`] carrying the final stop reason once the model
+    /// answers without requesting tools. A turn that runs out of iterations
+    /// or budget streams the answer to a final request without tools.
+    ///
+    /// Dropping the stream aborts the turn, cancelling tool calls that are
+    /// still running.
     pub fn stream<'a>(
         &'a self,
         messages: &'a mut Vec<Message>,
     ) -> Pin<Box<dyn Stream<Item = Result<StreamEvent, AgentError>> + Send + 'a>> {
-        let tools = self.get_tool_definitions();
+        self.stream_with_approver(messages, self.approver.clone())
+    }
 
-        if tools.is_empty() {
-            // No tools: direct streaming, no loop needed
-            return Box::pin(async_stream::stream! {
-                let provider_messages = self.build_messages(messages, &[]);
-                let mut stream = self.provider.stream(&provider_messages);
-                use futures::StreamExt;
+    /// Stream a conversation response, asking `approver` instead of the
+    /// agent's own approver about tool calls that need approval.
+    ///
+    /// Lets a shared agent ask the user of each conversation, e.g. the chat
+    /// a Telegram message came from. Otherwise identical to
+    /// [`stream`](Agent::stream).
+    pub fn stream_with_approver<'a>(
+        &'a self,
+        messages: &'a mut Vec<Message>,
+        approver: Option<Arc<dyn ToolApprover>>,
+    ) -> Pin<Box<dyn Stream<Item = Result<StreamEvent, AgentError>> + Send + 'a>> {
+        Box::pin(async_stream::stream! {
+            let tools = self.get_tool_definitions();
+            let mut budget = TurnBudget::new(&self.limits);
+
+            while let Some(iteration) = budget.next_iteration() {
+                tracing::debug!(iteration, "agent: starting streaming iteration");
+                let provider_messages = self.build_messages(messages, &tools);
+                let mut stream =
+                    self.provider
+                        .stream_with_options(&provider_messages, &tools, &self.options);
+
+                let mut content = String::new();
+                let mut tool_calls = Vec::new();
+                let mut stop_reason = StopReason::EndTurn;
+                let mut usage: Option<TokenUsage> = None;
                 while let Some(event) = stream.next().await {
-                    yield event.map_err(AgentError::Provider);
+                    match event {
+                        Ok(StreamEvent::TextDelta(text)) => {
+                            content.push_str(&text);
+                            yield Ok(StreamEvent::TextDelta(text));
+                        }
+                        Ok(StreamEvent::ToolCall(call)) => {
+                            tool_calls.push(call.clone());
+                            yield Ok(StreamEvent::ToolCall(call));
+                        }
+                        Ok(StreamEvent::Usage { input, output }) => {
+                            *usage.get_or_insert_default() += TokenUsage::new(input, output);
+                            yield Ok(StreamEvent::Usage { input, output });
+                        }
+                        Ok(StreamEvent::Done { stop_reason: reason }) => {
+                            stop_reason = reason;
+                            break;
+                        }
+                        Ok(other) => yield Ok(other),
+                        Err(e) => {
+                            yield Err(AgentError::Provider(e));
+                            return;
+                        }
+                    }
                 }
-            });
-        }
+                drop(stream);
+                budget.record(usage);
 
-        // With tools: use complete for tool iterations, yield final as stream
-        Box::pin(async_stream::stream! {
-            match self.complete(messages).await {
-                Ok(response) => {
-                    if !response.content.is_empty() {
-                        yield Ok(StreamEvent::TextDelta(response.content));
-                    }
-                    yield Ok(StreamEvent::Done);
+                if tool_calls.is_empty() {
+                    yield Ok(StreamEvent::Done { stop_reason });
+                    return;
                 }
-                Err(e) => {
-                    yield Err(e);
+
+                let mut assistant = Message::new(crate::message::Role::Assistant, content);
+                assistant.tool_calls = Some(tool_calls.clone());
+                assistant.usage = usage;
+                messages.push(assistant.clone());
+                yield Ok(StreamEvent::MessageAppended(assistant));
+
+                let mut results = std::pin::pin!(self.run_tools(&tool_calls, approver.as_deref()).await);
+                while let Some((tool_call, output)) = results.next().await {
+                    yield Ok(StreamEvent::ToolResult {
+                        id: tool_call.id.clone(),
+                        content: output.render(),
+                        is_error: output.is_error,
+                    });
+                    let result = Message::tool_output(&tool_call.id, output);
+                    messages.push(result.clone());
+                    yield Ok(StreamEvent::MessageAppended(result));
+                }
+            }
+
+            let provider_messages = self.build_messages(&wrap_up_messages(messages), &[]);
+            let mut stream = self
+                .provider
+                .stream_with_options(&provider_messages, &[], &self.options);
+            let mut stop_reason = StopReason::EndTurn;
+            while let Some(event) = stream.next().await {
+                match event {
+                    Ok(StreamEvent::Done { stop_reason: reason }) => {
+                        stop_reason = reason;
+                        break;
+                    }
+                    Ok(event) => yield Ok(event),
+                    Err(e) => {
+                        yield Err(AgentError::Provider(e));
+                        return;
+                    }
                 }
             }
+            yield Ok(StreamEvent::Done { stop_reason });
         })
     }
 
@@ -289,71 +593,318 @@ impl Agent {
         &self,
         mut messages: Vec<Message>,
     ) -> Pin<Box<dyn Stream<Item = Result<StreamEvent, AgentError>> + Send + '_>> {
-        let tools = self.get_tool_definitions();
+        Box::pin(async_stream::stream! {
+            let mut stream = self.stream(&mut messages);
+            while let Some(event) = stream.next().await {
+                yield event;
+            }
+        })
+    }
 
-        if tools.is_empty() {
-            // No tools: direct streaming, no loop needed
-            return Box::pin(async_stream::stream! {
-                let provider_messages = self.build_messages(&messages, &[]);
-                let mut stream = self.provider.stream(&provider_messages);
-                use futures::StreamExt;
-                while let Some(event) = stream.next().await {
-                    yield event.map_err(AgentError::Provider);
-                }
-            });
+    /// Get the native tool definitions followed by the MCP tool definitions
+    /// not hidden by a native tool.
+    fn get_tool_definitions(&self) -> Vec<ToolDefinition> {
+        let mut definitions = self.native_tools.definitions();
+        if let Some(client) = self.mcp_client.as_ref().filter(|c| c.has_tools()) {
+            definitions.extend(
+                client
+                    .tool_definitions()
+                    .into_iter()
+                    .filter(|tool| self.native_tools.get(&tool.name).is_none()),
+            );
         }
+        definitions
+    }
 
-        // With tools: use complete for tool iterations, yield final as stream
-        Box::pin(async_stream::stream! {
-            match self.complete(&mut messages).await {
-                Ok(response) => {
-                    if !response.content.is_empty() {
-                        yield Ok(StreamEvent::TextDelta(response.content));
+    /// Run the tool calls of one response and yield their outputs in call order.
+    ///
+    /// Calls are authorized one at a time so approval prompts never overlap;
+    /// the approved calls then run concurrently, at most
+    /// [`ToolsConfig::max_concurrency`] at once. Failures, timeouts and denied
+    /// calls are returned as error outputs so the model can react to them
+    /// instead of aborting the loop. Dropping the stream cancels the calls
+    /// still running.
+    async fn run_tools<'a>(
+        &'a self,
+        tool_calls: &'a [ToolCallData],
+        approver: Option<&dyn ToolApprover>,
+    ) -> impl Stream<Item = (&'a ToolCallData, ToolOutput)> + Send + use<'a> {
+        let mut permits = Vec::with_capacity(tool_calls.len());
+        for tool_call in tool_calls {
+            permits.push(self.authorize(tool_call, approver).await);
+        }
+
+        futures::stream::iter(tool_calls.iter().zip(permits))
+            .map(move |(tool_call, permit)| async move {
+                let output = match permit {
+                    Ok(()) => self.run_tool(tool_call).await,
+                    Err(reason) => {
+                        tracing::info!(tool = %tool_call.name, "agent: tool call denied");
+                        ToolOutput::error(reason)
                     }
-                    yield Ok(StreamEvent::Done);
-                }
-                Err(e) => {
-                    yield Err(e);
+                };
+                (tool_call, output)
+            })
+            .buffered(self.tools.max_concurrency.max(1))
+    }
+
+    /// Execute an approved tool call under the configured timeout.
+    async fn run_tool(&self, tool_call: &ToolCallData) -> ToolOutput {
+        tracing::debug!(tool = %tool_call.name, "agent: calling tool");
+        let execution = self.execute_tool(&tool_call.name, &tool_call.input);
+        let result = match self.tools.timeout_secs {
+            0 => execution.await,
+            secs => match tokio::time::timeout(Duration::from_secs(secs), execution).await {
+                Ok(result) => result,
+                Err(_) => {
+                    tracing::warn!(tool = %tool_call.name, "agent: tool call timed out");
+                    return ToolOutput::error(format!(
+                        "tool '{}' timed out after {}s",
+                        tool_call.name, secs
+                    ));
                 }
-            }
-        })
+            },
+        };
+        result.unwrap_or_else(ToolOutput::error)
     }
 
-    /// Get tool definitions from the MCP client, or empty if no client.
-    fn get_tool_definitions(&self) -> Vec<crate::mcp::ToolDefinition> {
-        match &self.mcp_client {
-            Some(client) if client.has_tools() => client.tool_definitions().to_vec(),
-            _ => Vec::new(),
+    /// Check a tool call against the approval policy, asking `approver` if
+    /// the policy is [`ToolPolicy::Ask`].
+    ///
+    /// Returns the reason reported to the model when the call may not run.
+    async fn authorize(
+        &self,
+        tool_call: &ToolCallData,
+        approver: Option<&dyn ToolApprover>,
+    ) -> Result<(), String> {
+        let (server, aliases) = match (self.native_tools.get(&tool_call.name), &self.mcp_client) {
+            (None, Some(client)) => (
+                client.tool_server(&tool_call.name),
+                client.tool_aliases(&tool_call.name),
+            ),
+            _ => (None, Vec::new()),
+        };
+        let server = server.as_deref();
+        // Rules may name the tool as exposed, by its alias or by its original
+        // name; prefixing on a name collision must not bypass them.
+        let mut names = vec![tool_call.name.as_str()];
+        names.extend(aliases.iter().map(String::as_str));
+        match self.approval.policy_for(&names, server) {
+            ToolPolicy::Allow => Ok(()),
+            ToolPolicy::Deny => Err(format!(
+                "tool '{}' is disabled by the approval policy",
+                tool_call.name
+            )),
+            ToolPolicy::Ask => match approver {
+                Some(approver) => match approver.approve(tool_call, server).await {
+                    ApprovalDecision::Allow => Ok(()),
+                    ApprovalDecision::Deny => {
+                        Err(format!("the user denied the call to '{}'", tool_call.name))
+                    }
+                },
+                None => Err(format!(
+                    "tool '{}' requires approval, but no one can be asked",
+                    tool_call.name
+                )),
+            },
         }
     }
 
-    /// Execute a tool call via the MCP client.
+    /// Execute a tool call with the native tool of that name, or else via
+    /// the MCP client.
+    ///
+    /// Returns the error message reported to the model if the call fails.
     async fn execute_tool(
         &self,
         name: &str,
         input: &serde_json::Value,
-    ) -> Result<serde_json::Value, crate::mcp::McpError> {
-        match &self.mcp_client {
+    ) -> Result<ToolOutput, String> {
+        if let Some(tool) = self.native_tools.get(name) {
+            return tool.call(input.clone()).await.map_err(|e| e.to_string());
+        }
+        let result = match &self.mcp_client {
             Some(client) => client.call_tool(name, input.clone()).await,
             None => Err(crate::mcp::McpError::ToolError(
                 "no MCP client available".to_string(),
             )),
+        };
+        result.map_err(|e| e.to_string())
+    }
+
+    /// The MCP client, if one is configured.
+    ///
+    /// Frontends use it to list resources and prompts.
+    pub fn mcp_client(&self) -> Option<&McpClient> {
+        self.mcp_client.as_deref()
+    }
+
+    /// Attach the MCP resources referenced as `@server:uri` in a user message.
+    ///
+    /// Returns the message text with the resource contents appended, and the
+    /// references that were attached. Without an MCP client the text is
+    /// returned unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AgentError::Mcp`] if a referenced resource cannot be read.
+    pub async fn attach_resources(
+        &self,
+        text: &str,
+    ) -> Result<(String, Vec<ResourceRef>), AgentError> {
+        match &self.mcp_client {
+            Some(client) => Ok(client.attach_resources(text).await?),
+            None => Ok((text.to_string(), Vec::new())),
+        }
+    }
+
+    /// Render an MCP prompt into messages to append to the conversation.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AgentError::Mcp`] if no MCP client is configured, the prompt
+    /// is unknown, or rendering fails.
+    pub async fn render_prompt(
+        &self,
+        name: &str,
+        arguments: HashMap<String, String>,
+    ) -> Result<Vec<Message>, AgentError> {
+        match &self.mcp_client {
+            Some(client) => Ok(client.get_prompt(name, arguments).await?),
+            None => {
+                Err(crate::mcp::McpError::PromptError("no MCP client available".to_string()).into())
+            }
         }
     }
 
     /// Gracefully shut down the agent, including MCP connections.
+    ///
+    /// MCP connections still shared with a forked agent are left open; they
+    /// close when the last agent using them is shut down or dropped.
     pub async fn shutdown(self) {
-        if let Some(client) = self.mcp_client {
+        if let Some(client) = self.mcp_client.and_then(Arc::into_inner) {
             client.shutdown().await;
         }
     }
 }
 
+/// Iterations, tokens and time used by one turn of the tool call loop.
+struct TurnBudget<'a> {
+    /// Limits of the turn.
+    limits: &'a AgentConfig,
+    /// When the turn started.
+    started: Instant,
+    /// Model calls made so far.
+    iterations: usize,
+    /// Input and output tokens reported so far.
+    tokens: u64,
+}
+
+impl<'a> TurnBudget<'a> {
+    fn new(limits: &'a AgentConfig) -> Self {
+        Self {
+            limits,
+            started: Instant::now(),
+            iterations: 0,
+            tokens: 0,
+        }
+    }
+
+    /// Start another model call that may request tools, returning its
+    /// index, or `None` if the turn is out of iterations or budget.
+    fn next_iteration(&mut self) -> Option<usize> {
+        let exhausted = if self.iterations >= self.limits.max_iterations() {
+            Some("iterations")
+        } else if self
+            .limits
+            .max_turn_tokens
+            .is_some_and(|max| self.tokens >= max)
+        {
+            Some("tokens")
+        } else if self
+            .limits
+            .max_turn_secs
+            .is_some_and(|max| self.started.elapsed() >= Duration::from_secs(max))
+        {
+            Some("time")
+        } else {
+            None
+        };
+        if let Some(budget) = exhausted {
+            tracing::warn!(
+                budget,
+                iterations = self.iterations,
+                tokens = self.tokens,
+                "agent: turn budget exhausted; asking for a final answer without tools"
+            );
+            return None;
+        }
+        self.iterations += 1;
+        Some(self.iterations - 1)
+    }
+
+    /// Add the usage reported for a model call.
+    fn record(&mut self, usage: Option<TokenUsage>) {
+        self.tokens += usage.map_or(0, |usage| usage.total());
+    }
+}
+
+/// Rewrite `messages` for the final request of a turn that ran out of
+/// iterations or budget, which is sent without tools.
+///
+/// Providers reject tool calls and results in a request without tool
+/// definitions, so they are folded as text into the assistant messages,
+/// followed by an instruction to answer now. Other parts of the messages,
+/// such as the user's attachments, are kept.
+fn wrap_up_messages(messages: &[Message]) -> Vec<Message> {
+    let mut wrap_up: Vec<Message> = Vec::with_capacity(messages.len() + 1);
+    for message in messages {
+        let mut folded = match message.role {
+            Role::Tool => {
+                Message::new(Role::Assistant, format!("Tool result: {}", message.content))
+            }
+            _ => Message {
+                tool_calls: None,
+                tool_call_id: None,
+                tool_output: None,
+                ..message.clone()
+            },
+        };
+        for call in message.tool_calls.iter().flatten() {
+            if !folded.content.is_empty() {
+                folded.content.push('\n');
+            }
+            folded
+                .content
+                .push_str(&format!("Called {}({})", call.name, call.input));
+        }
+        match wrap_up.last_mut() {
+            Some(last) if folded.role == Role::Assistant && last.role == Role::Assistant => {
+                last.content.push('\n');
+                last.content.push_str(&folded.content);
+                last.attachments.append(&mut folded.attachments);
+            }
+            _ => wrap_up.push(folded),
+        }
+    }
+    wrap_up.push(Message::new(Role::User, WRAP_UP_PROMPT));
+    wrap_up
+}
+
+/// Estimate the tokens taken by the tool schemas sent with a request.
+fn tool_tokens(tools: &[ToolDefinition]) -> u32 {
+    tools.iter().fold(0, |total, tool| {
+        let description = tool.description.as_deref().unwrap_or_default();
+        total
+            .saturating_add(estimate_text_tokens(&tool.name))
+            .saturating_add(estimate_text_tokens(description))
+            .saturating_add(estimate_text_tokens(&tool.input_schema.to_string()))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::mcp::ToolDefinition;
-    use crate::message::{Role, ToolCallData};
+    use crate::message::{Attachment, Role};
     use crate::provider::MockProvider;
 
     // --- Task 4: Agent builder tests ---
@@ -372,6 +923,68 @@ mod tests {
         assert_eq!(agent.system_prompt, Some("test".to_string()));
     }
 
+    #[test]
+    fn test_agent_fork_uses_config_and_shares_mcp_client() {
+        let mcp_client = McpClient::with_test_tools(vec![ToolDefinition {
+            name: "echo".to_string(),
+            description: None,
+            input_schema: serde_json::json!({"type": "object"}),
+        }]);
+        let agent = Agent::new(Box::new(MockProvider::new()), Some(mcp_client))
+            .with_system_prompt("base prompt");
+        let config = Config {
+            provider: "openai-compatible".to_string(),
+            base_url: Some("http://localhost:11434/v1".to_string()),
+            system_prompt: Some("forked prompt".to_string()),
+            ..Config::default()
+        };
+
+        let forked = agent.fork(&config).unwrap();
+
+        assert_eq!(forked.system_prompt.as_deref(), Some("forked prompt"));
+        assert!(Arc::ptr_eq(
+            agent.mcp_client.as_ref().unwrap(),
+            forked.mcp_client.as_ref().unwrap()
+        ));
+        assert_eq!(forked.get_tool_definitions().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_attach_resources_without_mcp_client() {
+        let agent = Agent::new(Box::new(MockProvider::new()), None);
+
+        let (text, attached) = agent.attach_resources("see @fs:file:///a").await.unwrap();
+
+        assert_eq!(text, "see @fs:file:///a");
+        assert!(attached.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_render_prompt_without_mcp_client() {
+        let agent = Agent::new(Box::new(MockProvider::new()), None);
+
+        let result = agent.render_prompt("review", HashMap::new()).await;
+
+        assert!(matches!(
+            result,
+            Err(AgentError::Mcp(crate::mcp::McpError::PromptError(_)))
+        ));
+    }
+
+    #[test]
+    fn test_agent_from_config_uses_generation_options() {
+        let config = Config {
+            provider: "openai-compatible".to_string(),
+            base_url: Some("http://localhost:8000/v1".to_string()),
+            generation: GenerationOptions::default().with_temperature(0.4),
+            ..Config::default()
+        };
+
+        let agent = Agent::from_config(&config, None).unwrap();
+
+        assert_eq!(agent.options.temperature, Some(0.4));
+    }
+
     // --- Task 7: build_messages() and complete() integration tests ---
 
     #[test]
@@ -595,40 +1208,187 @@ mod tests {
         }
     }
 
-    #[tokio::test]
-    async fn test_agent_complete_max_iterations() {
-        // AC4: returns MaxIterationsExceeded after 10 iterations
-        // Create a provider that always returns tool calls
-        let mut provider = MockProvider::new();
-
-        // Push 11 tool call responses (more than MAX_ITERATIONS)
-        for i in 0..11 {
-            let tool_calls = vec![ToolCallData {
+    /// Provider that requests `infinite_tool` on each of its first
+    /// `tool_turns` calls, then answers "Wrapped up.".
+    fn looping_provider(tool_turns: usize) -> MockProvider {
+        let mut provider = MockProvider::new().with_response("Wrapped up.");
+        for i in (0..tool_turns).rev() {
+            provider = provider.with_tool_call_response(vec![ToolCallData {
                 id: format!("call_{}", i),
                 name: "infinite_tool".to_string(),
                 input: serde_json::json!({}),
-            }];
-            provider = provider.with_tool_call_response(tool_calls);
+            }]);
         }
+        provider
+    }
 
+    /// Agent with the `infinite_tool` MCP tool, which fails without a server.
+    fn looping_agent(provider: MockProvider) -> Agent {
         let mcp_client = McpClient::with_test_tools(vec![ToolDefinition {
             name: "infinite_tool".to_string(),
             description: None,
             input_schema: serde_json::json!({}),
         }]);
+        Agent::new(Box::new(provider), Some(mcp_client))
+    }
 
-        let agent = Agent::new(Box::new(provider), Some(mcp_client));
+    #[tokio::test]
+    async fn test_agent_complete_max_iterations() {
+        // The model keeps calling tools; after 10 iterations it is asked for
+        // a final answer without tools.
+        let agent = looping_agent(looping_provider(10));
         let mut messages = vec![Message::new(Role::User, "Loop forever")];
 
-        let result = agent.complete(&mut messages).await;
-        assert!(matches!(result, Err(AgentError::MaxIterationsExceeded)));
+        let response = agent.complete(&mut messages).await.unwrap();
+
+        assert_eq!(response.content, "Wrapped up.");
+        assert!(response.tool_calls.is_none());
+        // The tool transcript of all 10 iterations is kept.
+        assert_eq!(messages.len(), 1 + 2 * 10);
+        assert_eq!(messages.last().unwrap().role, Role::Tool);
+    }
+
+    #[tokio::test]
+    async fn test_agent_complete_configured_max_iterations() {
+        let agent = looping_agent(looping_provider(2)).with_agent_config(AgentConfig {
+            max_iterations: Some(2),
+            ..Default::default()
+        });
+        let mut messages = vec![Message::new(Role::User, "Loop")];
+
+        let response = agent.complete(&mut messages).await.unwrap();
+
+        assert_eq!(response.content, "Wrapped up.");
+        assert_eq!(messages.len(), 1 + 2 * 2);
+    }
+
+    #[tokio::test]
+    async fn test_agent_complete_token_budget() {
+        let mut tool_turn = Message::new(Role::Assistant, "");
+        tool_turn.tool_calls = Some(vec![ToolCallData {
+            id: "call_0".to_string(),
+            name: "infinite_tool".to_string(),
+            input: serde_json::json!({}),
+        }]);
+        tool_turn.usage = Some(TokenUsage::new(900, 200));
+        let provider = looping_provider(0).with_message(tool_turn);
+        let agent = looping_agent(provider).with_agent_config(AgentConfig {
+            max_turn_tokens: Some(1_000),
+            ..Default::default()
+        });
+        let mut messages = vec![Message::new(Role::User, "Loop")];
+
+        let response = agent.complete(&mut messages).await.unwrap();
+
+        // The first call used 1100 tokens, so no second tool iteration runs.
+        assert_eq!(response.content, "Wrapped up.");
+        assert_eq!(messages.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_agent_complete_time_budget() {
+        let agent = looping_agent(looping_provider(0)).with_agent_config(AgentConfig {
+            max_turn_secs: Some(0),
+            ..Default::default()
+        });
+        let mut messages = vec![Message::new(Role::User, "Loop")];
+
+        let response = agent.complete(&mut messages).await.unwrap();
+
+        assert_eq!(response.content, "Wrapped up.");
+        // The budget is spent before the first call, which runs without tools.
+        assert_eq!(messages.len(), 1);
+    }
+
+    /// Provider that records the messages of every request it receives.
+    struct RecordingProvider {
+        inner: MockProvider,
+        requests: Arc<std::sync::Mutex<Vec<Vec<Message>>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl LlmProvider for RecordingProvider {
+        async fn complete(&self, messages: &[Message]) -> Result<Message, ProviderError> {
+            self.requests.lock().unwrap().push(messages.to_vec());
+            self.inner.complete(messages).await
+        }
+
+        fn stream(
+            &self,
+            messages: &[Message],
+        ) -> Pin<Box<dyn Stream<Item = Result<StreamEvent, ProviderError>> + Send + '_>> {
+            self.requests.lock().unwrap().push(messages.to_vec());
+            self.inner.stream(messages)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_agent_wrap_up_keeps_attachments() {
+        let requests = Arc::default();
+        let provider = RecordingProvider {
+            inner: looping_provider(1),
+            requests: Arc::clone(&requests),
+        };
+        let mcp_client = McpClient::with_test_tools(vec![ToolDefinition {
+            name: "infinite_tool".to_string(),
+            description: None,
+            input_schema: serde_json::json!({}),
+        }]);
+        let agent =
+            Agent::new(Box::new(provider), Some(mcp_client)).with_agent_config(AgentConfig {
+                max_iterations: Some(1),
+                ..Default::default()
+            });
+        let mut question = Message::new(Role::User, "What is in this picture?");
+        question
+            .attachments
+            .push(Attachment::image("image/png", vec![1, 2, 3]));
+        let mut messages = vec![question];
+
+        let response = agent.complete(&mut messages).await.unwrap();
+
+        assert_eq!(response.content, "Wrapped up.");
+        let requests = requests.lock().unwrap();
+        let wrap_up = requests.last().unwrap();
+        assert_eq!(wrap_up.last().unwrap().content, WRAP_UP_PROMPT);
+        let question = wrap_up.iter().find(|m| m.role == Role::User).unwrap();
+        assert_eq!(
+            question.attachments,
+            vec![Attachment::image("image/png", vec![1, 2, 3])]
+        );
+    }
+
+    #[test]
+    fn test_wrap_up_messages_folds_tool_turns_into_text() {
+        let mut call = Message::new(Role::Assistant, "Checking.");
+        call.tool_calls = Some(vec![ToolCallData {
+            id: "call_1".to_string(),
+            name: "get_weather".to_string(),
+            input: serde_json::json!({"city": "Oslo"}),
+        }]);
+        let messages = vec![
+            Message::new(Role::User, "Weather in Oslo?"),
+            call,
+            Message::tool_output("call_1", ToolOutput::text("Rain")),
+        ];
+
+        let wrap_up = wrap_up_messages(&messages);
+
+        assert_eq!(wrap_up.len(), 3);
+        assert_eq!(wrap_up[0].content, "Weather in Oslo?");
+        assert_eq!(wrap_up[1].role, Role::Assistant);
+        assert_eq!(
+            wrap_up[1].content,
+            "Checking.\nCalled get_weather({\"city\":\"Oslo\"})\nTool result: Rain"
+        );
+        assert!(wrap_up.iter().all(|m| m.tool_calls.is_none()));
+        assert_eq!(wrap_up[2].role, Role::User);
+        assert_eq!(wrap_up[2].content, WRAP_UP_PROMPT);
     }
 
     #[tokio::test]
     async fn test_agent_stream_no_tools() {
         // AC5: streaming without tools returns provider stream directly
-        use futures::StreamExt;
-
         let provider =
             Box::new(MockProvider::new().with_stream_tokens(vec!["Hello", " ", "world"]));
         let agent = Agent::new(provider, None);
@@ -640,7 +1400,8 @@ mod tests {
         while let Some(event) = stream.next().await {
             match event {
                 Ok(StreamEvent::TextDelta(text)) => tokens.push(text),
-                Ok(StreamEvent::Done) => break,
+                Ok(StreamEvent::Done { .. }) => break,
+                Ok(other) => panic!("Unexpected event: {:?}", other),
                 Err(e) => panic!("Unexpected error: {}", e),
             }
         }
@@ -688,4 +1449,561 @@ mod tests {
             }
         }
     }
+
+    /// Approver that records the tools it was asked about and answers `decision`.
+    struct RecordingApprover {
+        decision: ApprovalDecision,
+        asked: std::sync::Mutex<Vec<(String, Option<String>)>>,
+    }
+
+    #[async_trait::async_trait]
+    impl ToolApprover for RecordingApprover {
+        async fn approve(&self, call: &ToolCallData, server: Option<&str>) -> ApprovalDecision {
+            self.asked
+                .lock()
+                .unwrap()
+                .push((call.name.clone(), server.map(str::to_string)));
+            self.decision
+        }
+    }
+
+    /// Agent whose model calls `get_weather` once, then answers "Done.".
+    fn approval_agent(approval: ApprovalConfig) -> Agent {
+        let provider = Box::new(
+            MockProvider::new()
+                .with_response("Done.")
+                .with_tool_call_response(vec![ToolCallData {
+                    id: "call_1".to_string(),
+                    name: "get_weather".to_string(),
+                    input: serde_json::json!({}),
+                }]),
+        );
+        let mcp_client = McpClient::with_test_tools(vec![ToolDefinition {
+            name: "get_weather".to_string(),
+            description: None,
+            input_schema: serde_json::json!({}),
+        }]);
+        Agent::new(provider, Some(mcp_client)).with_approval(approval)
+    }
+
+    /// Run one turn through `agent` and return the tool result message.
+    async fn approval_tool_result(agent: &Agent) -> Message {
+        let mut messages = vec![Message::new(Role::User, "Weather?")];
+        agent.complete(&mut messages).await.unwrap();
+        messages.into_iter().find(|m| m.role == Role::Tool).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_agent_tool_denied_by_policy() {
+        let approval = ApprovalConfig {
+            tools: HashMap::from([("get_weather".to_string(), ToolPolicy::Deny)]),
+            ..Default::default()
+        };
+        let approver = Arc::new(RecordingApprover {
+            decision: ApprovalDecision::Allow,
+            asked: Default::default(),
+        });
+        let agent = approval_agent(approval).with_approver(approver.clone());
+
+        let result = approval_tool_result(&agent).await;
+
+        assert_eq!(
+            result.content,
+            "Error: tool 'get_weather' is disabled by the approval policy"
+        );
+        assert!(result.tool_output.unwrap().is_error);
+        assert!(approver.asked.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_agent_tool_denied_by_policy_after_name_collision() {
+        let weather_tool = || ToolDefinition {
+            name: "get_weather".to_string(),
+            description: None,
+            input_schema: serde_json::json!({}),
+        };
+        let provider = Box::new(
+            MockProvider::new()
+                .with_response("Done.")
+                .with_tool_call_response(vec![ToolCallData {
+                    id: "call_1".to_string(),
+                    name: "backup__get_weather".to_string(),
+                    input: serde_json::json!({}),
+                }]),
+        );
+        let mcp_client = McpClient::with_test_servers(vec![
+            ("backup", vec![weather_tool()]),
+            ("weather", vec![weather_tool()]),
+        ]);
+        let approval = ApprovalConfig {
+            tools: HashMap::from([("get_weather".to_string(), ToolPolicy::Deny)]),
+            ..Default::default()
+        };
+        let agent = Agent::new(provider, Some(mcp_client)).with_approval(approval);
+
+        let result = approval_tool_result(&agent).await;
+
+        assert_eq!(
+            result.content,
+            "Error: tool 'backup__get_weather' is disabled by the approval policy"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_agent_tool_ask_denied_by_user() {
+        let approval = ApprovalConfig {
+            servers: HashMap::from([("test-server".to_string(), ToolPolicy::Ask)]),
+            ..Default::default()
+        };
+        let approver = Arc::new(RecordingApprover {
+            decision: ApprovalDecision::Deny,
+            asked: Default::default(),
+        });
+        let agent = approval_agent(approval).with_approver(approver.clone());
+
+        let result = approval_tool_result(&agent).await;
+
+        assert_eq!(
+            result.content,
+            "Error: the user denied the call to 'get_weather'"
+        );
+        assert_eq!(
+            *approver.asked.lock().unwrap(),
+            vec![("get_weather".to_string(), Some("test-server".to_string()))]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_agent_tool_ask_allowed_runs_tool() {
+        let approval = ApprovalConfig {
+            default: ToolPolicy::Ask,
+            ..Default::default()
+        };
+        let approver = Arc::new(RecordingApprover {
+            decision: ApprovalDecision::Allow,
+            asked: Default::default(),
+        });
+        let agent = approval_agent(approval).with_approver(approver.clone());
+
+        let result = approval_tool_result(&agent).await;
+
+        // The test MCP client has no live server, so the approved call fails there.
+        assert!(result.content.contains("not connected"));
+        assert_eq!(approver.asked.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_agent_tool_ask_without_approver_denied() {
+        let approval = ApprovalConfig {
+            default: ToolPolicy::Ask,
+            ..Default::default()
+        };
+        let agent = approval_agent(approval);
+
+        let result = approval_tool_result(&agent).await;
+
+        assert!(result.content.contains("requires approval"));
+    }
+
+    #[tokio::test]
+    async fn test_agent_stream_with_approver_overrides_agent_approver() {
+        let approval = ApprovalConfig {
+            default: ToolPolicy::Ask,
+            ..Default::default()
+        };
+        let own = Arc::new(RecordingApprover {
+            decision: ApprovalDecision::Allow,
+            asked: Default::default(),
+        });
+        let scoped = Arc::new(RecordingApprover {
+            decision: ApprovalDecision::Deny,
+            asked: Default::default(),
+        });
+        let agent = approval_agent(approval).with_approver(own.clone());
+
+        let mut messages = vec![Message::new(Role::User, "Weather?")];
+        let events: Vec<_> = agent
+            .stream_with_approver(&mut messages, Some(scoped.clone()))
+            .collect()
+            .await;
+
+        assert!(events.iter().any(|e| matches!(
+            e,
+            Ok(StreamEvent::ToolResult { is_error: true, content, .. })
+                if content.contains("denied")
+        )));
+        assert!(own.asked.lock().unwrap().is_empty());
+        assert_eq!(scoped.asked.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_agent_stream_with_tool_call() {
+        let provider = Box::new(
+            MockProvider::new()
+                .with_response("Done checking.")
+                .with_tool_call_response(vec![ToolCallData {
+                    id: "call_1".to_string(),
+                    name: "get_weather".to_string(),
+                    input: serde_json::json!({"location": "London"}),
+                }]),
+        );
+        let mcp_client = McpClient::with_test_tools(vec![ToolDefinition {
+            name: "get_weather".to_string(),
+            description: None,
+            input_schema: serde_json::json!({}),
+        }]);
+        let agent = Agent::new(provider, Some(mcp_client));
+
+        let mut messages = vec![Message::new(Role::User, "Weather?")];
+        let events: Vec<_> = agent.stream(&mut messages).collect().await;
+
+        let mut text = String::new();
+        let mut started = Vec::new();
+        let mut calls = Vec::new();
+        let mut results = Vec::new();
+        for event in &events {
+            match event {
+                Ok(StreamEvent::TextDelta(t)) => text.push_str(t),
+                Ok(StreamEvent::ToolCallStarted { name, .. }) => started.push(name.clone()),
+                Ok(StreamEvent::ToolCall(call)) => calls.push(call.name.clone()),
+                Ok(StreamEvent::ToolResult { id, is_error, .. }) => {
+                    results.push((id.clone(), *is_error))
+                }
+                Ok(_) => {}
+                Err(e) => panic!("Unexpected error: {}", e),
+            }
+        }
+        assert_eq!(text, "Done checking.");
+        assert_eq!(started, vec!["get_weather"]);
+        assert_eq!(calls, vec!["get_weather"]);
+        // The test MCP client has no live server, so the call fails.
+        assert_eq!(results, vec![("call_1".to_string(), true)]);
+
+        // Only the final Done is forwarded.
+        let done_count = events
+            .iter()
+            .filter(|e| matches!(e, Ok(StreamEvent::Done { .. })))
+            .count();
+        assert_eq!(done_count, 1);
+        assert!(matches!(
+            events.last(),
+            Some(Ok(StreamEvent::Done {
+                stop_reason: StopReason::EndTurn
+            }))
+        ));
+
+        // user, assistant (tool call), tool result; final text is not appended.
+        assert_eq!(messages.len(), 3);
+        assert!(messages[2].content.starts_with("Error: "));
+        assert!(messages[2].tool_output.as_ref().is_some_and(|o| o.is_error));
+        let appended: Vec<&Message> = events
+            .iter()
+            .filter_map(|e| match e {
+                Ok(StreamEvent::MessageAppended(m)) => Some(m),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(appended, vec![&messages[1], &messages[2]]);
+        assert_eq!(messages[1].role, Role::Assistant);
+        assert!(messages[1].tool_calls.is_some());
+        assert_eq!(messages[2].role, Role::Tool);
+        assert_eq!(messages[2].tool_call_id.as_deref(), Some("call_1"));
+    }
+
+    #[tokio::test]
+    async fn test_agent_stream_max_iterations() {
+        let agent = looping_agent(looping_provider(10));
+
+        let mut messages = vec![Message::new(Role::User, "Loop forever")];
+        let events: Vec<_> = agent.stream(&mut messages).collect().await;
+
+        let n = events.len();
+        assert!(matches!(
+            &events[n - 2],
+            Ok(StreamEvent::TextDelta(text)) if text == "Wrapped up."
+        ));
+        assert!(matches!(
+            events[n - 1],
+            Ok(StreamEvent::Done {
+                stop_reason: StopReason::EndTurn
+            })
+        ));
+        assert_eq!(messages.len(), 1 + 2 * 10);
+    }
+
+    // --- Context-window management ---
+
+    #[test]
+    fn test_build_messages_truncates_to_context_window() {
+        let provider = Box::new(MockProvider::new());
+        let agent = Agent::new(provider, None)
+            .with_system_prompt("Be brief.")
+            .with_context(ContextManager::new(100).with_reserve(20));
+
+        let messages = vec![
+            Message::new(Role::User, "q".repeat(200)),
+            Message::new(Role::Assistant, "a".repeat(200)),
+            Message::new(Role::User, "Latest question"),
+        ];
+        let result = agent.build_messages(&messages, &[]);
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].role, Role::System);
+        assert_eq!(result[1].content, "Latest question");
+    }
+
+    #[tokio::test]
+    async fn test_compact_history_summarizes_and_stores_artifact() {
+        use crate::session::Session;
+        use crate::storage::SqliteStore;
+
+        let db_path = std::env::temp_dir().join(format!("synapse_test_{}.db", Uuid::new_v4()));
+        let store = SqliteStore::new(&format!("sqlite:{}", db_path.display()))
+            .await
+            .expect("failed to create test store");
+        let session = Session::new("mock", "mock-model");
+        store.create_session(&session).await.unwrap();
+
+        let provider = Box::new(MockProvider::new().with_response("They discussed Rust."));
+        let agent = Agent::new(provider, None)
+            .with_context(ContextManager::new(100).with_strategy(ContextStrategy::Summarize));
+        let messages = vec![
+            Message::new(Role::User, "q".repeat(120)),
+            Message::new(Role::Assistant, "a".repeat(120)),
+            Message::new(Role::User, "q".repeat(40)),
+            Message::new(Role::Assistant, "a".repeat(40)),
+            Message::new(Role::User, "Latest question"),
+        ];
+
+        let compacted = agent
+            .compact_history(&store, session.id, messages.clone())
+            .await;
+
+        assert_eq!(compacted[0], summary_message("They discussed Rust."));
+        assert_eq!(compacted[1..], messages[2..]);
+        let artifact = store
+            .get_latest_artifact(session.id, ArtifactKind::Summary)
+            .await
+            .unwrap()
+            .expect("summary stored");
+        assert_eq!(artifact.content, "They discussed Rust.");
+        assert_eq!(artifact.message_count, 2);
+
+        // The stored summary is reused without asking the model again.
+        let again = agent.compact_history(&store, session.id, messages).await;
+        assert_eq!(again, compacted);
+    }
+
+    #[tokio::test]
+    async fn test_compact_history_truncate_strategy_is_noop() {
+        use crate::storage::SqliteStore;
+
+        let db_path = std::env::temp_dir().join(format!("synapse_test_{}.db", Uuid::new_v4()));
+        let store = SqliteStore::new(&format!("sqlite:{}", db_path.display()))
+            .await
+            .expect("failed to create test store");
+        let agent =
+            Agent::new(Box::new(MockProvider::new()), None).with_context(ContextManager::new(10));
+        let messages = vec![Message::new(Role::User, "q".repeat(200))];
+
+        let result = agent
+            .compact_history(&store, Uuid::new_v4(), messages.clone())
+            .await;
+
+        assert_eq!(result, messages);
+    }
+
+    /// Agent whose model calls `sleep` once per `(ms, text)` pair, then
+    /// answers "Done.", connected to a local MCP test server.
+    async fn sleep_agent(
+        server: &crate::mcp::test_server::TestServer,
+        calls: &[(u64, &str)],
+    ) -> Agent {
+        let tool_calls = calls
+            .iter()
+            .enumerate()
+            .map(|(i, (ms, text))| ToolCallData {
+                id: format!("call_{}", i),
+                name: "sleep".to_string(),
+                input: serde_json::json!({"ms": ms, "text": text}),
+            })
+            .collect();
+        let provider = Box::new(
+            MockProvider::new()
+                .with_response("Done.")
+                .with_tool_call_response(tool_calls),
+        );
+        let server = crate::mcp::McpServerConfig {
+            url: Some(server.url().to_string()),
+            ..Default::default()
+        };
+        let config = crate::mcp::McpConfig {
+            mcp_servers: HashMap::from([("test".to_string(), server)]),
+        };
+        let mcp_client = McpClient::new(&config).await.unwrap();
+        Agent::new(provider, Some(mcp_client))
+    }
+
+    #[tokio::test]
+    async fn test_agent_parallel_tool_calls_keep_call_order() {
+        let server = crate::mcp::test_server::TestServer::streamable_http().await;
+        let agent = sleep_agent(&server, &[(400, "first"), (50, "second"), (200, "third")])
+            .await
+            .with_tools_config(ToolsConfig {
+                max_concurrency: 3,
+                timeout_secs: 10,
+                ..Default::default()
+            });
+
+        let mut messages = vec![Message::new(Role::User, "Go")];
+        let started = std::time::Instant::now();
+        agent.complete(&mut messages).await.unwrap();
+        let elapsed = started.elapsed();
+
+        let results: Vec<_> = messages
+            .iter()
+            .filter(|m| m.role == Role::Tool)
+            .map(|m| (m.tool_call_id.clone().unwrap(), m.content.clone()))
+            .collect();
+        assert_eq!(
+            results,
+            vec![
+                ("call_0".to_string(), "first".to_string()),
+                ("call_1".to_string(), "second".to_string()),
+                ("call_2".to_string(), "third".to_string()),
+            ]
+        );
+        // Run one by one the calls would take at least 650ms.
+        assert!(
+            elapsed < std::time::Duration::from_millis(600),
+            "{:?}",
+            elapsed
+        );
+        agent.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_agent_tool_call_timeout() {
+        let server = crate::mcp::test_server::TestServer::streamable_http().await;
+        let agent = sleep_agent(&server, &[(5_000, "late"), (0, "prompt")])
+            .await
+            .with_tools_config(ToolsConfig {
+                max_concurrency: 2,
+                timeout_secs: 1,
+                ..Default::default()
+            });
+
+        let mut messages = vec![Message::new(Role::User, "Go")];
+        let events: Vec<_> = agent.stream(&mut messages).collect().await;
+
+        let results: Vec<_> = events
+            .iter()
+            .filter_map(|e| match e {
+                Ok(StreamEvent::ToolResult {
+                    id,
+                    content,
+                    is_error,
+                }) => Some((id.as_str(), content.as_str(), *is_error)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            results,
+            vec![
+                ("call_0", "Error: tool 'sleep' timed out after 1s", true),
+                ("call_1", "prompt", false),
+            ]
+        );
+        agent.shutdown().await;
+    }
+
+    /// Native tool answering with its name and input, to tell it apart from
+    /// an MCP tool with the same name.
+    struct EchoTool(&'static str);
+
+    #[async_trait::async_trait]
+    impl crate::tools::Tool for EchoTool {
+        fn definition(&self) -> ToolDefinition {
+            ToolDefinition {
+                name: self.0.to_string(),
+                description: Some("Native echo".to_string()),
+                input_schema: serde_json::json!({"type": "object"}),
+            }
+        }
+
+        async fn call(
+            &self,
+            input: serde_json::Value,
+        ) -> Result<ToolOutput, crate::tools::ToolError> {
+            Ok(ToolOutput::text(format!("native {}: {}", self.0, input)))
+        }
+    }
+
+    #[test]
+    fn test_get_tool_definitions_native_tools_hide_mcp_tools() {
+        let mcp_client = McpClient::with_test_tools(vec![
+            ToolDefinition {
+                name: "get_weather".to_string(),
+                description: Some("MCP weather".to_string()),
+                input_schema: serde_json::json!({"type": "object"}),
+            },
+            ToolDefinition {
+                name: "list_files".to_string(),
+                description: Some("MCP files".to_string()),
+                input_schema: serde_json::json!({"type": "object"}),
+            },
+        ]);
+        let agent = Agent::new(Box::new(MockProvider::new()), Some(mcp_client))
+            .with_native_tools(ToolRegistry::new().with_tool(EchoTool("get_weather")));
+
+        let tools: Vec<_> = agent
+            .get_tool_definitions()
+            .into_iter()
+            .map(|t| (t.name, t.description.unwrap()))
+            .collect();
+
+        assert_eq!(
+            tools,
+            vec![
+                ("get_weather".to_string(), "Native echo".to_string()),
+                ("list_files".to_string(), "MCP files".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_agent_native_tool_call() {
+        let provider = Box::new(
+            MockProvider::new()
+                .with_response("Done")
+                .with_tool_call_response(vec![ToolCallData {
+                    id: "call_1".to_string(),
+                    name: "echo".to_string(),
+                    input: serde_json::json!({"text": "hi"}),
+                }]),
+        );
+        let approver = Arc::new(RecordingApprover {
+            decision: ApprovalDecision::Allow,
+            asked: Default::default(),
+        });
+        let agent = Agent::new(provider, None)
+            .with_native_tools(ToolRegistry::new().with_tool(EchoTool("echo")))
+            .with_approval(ApprovalConfig {
+                default: ToolPolicy::Ask,
+                ..Default::default()
+            })
+            .with_approver(approver.clone());
+
+        let mut messages = vec![Message::new(Role::User, "Echo hi")];
+        let response = agent.complete(&mut messages).await.unwrap();
+
+        assert_eq!(response.content, "Done");
+        let result = messages.iter().find(|m| m.role == Role::Tool).unwrap();
+        assert_eq!(result.content, r#"native echo: {"text":"hi"}"#);
+        // Native tools are not served by an MCP server.
+        assert_eq!(
+            *approver.asked.lock().unwrap(),
+            vec![("echo".to_string(), None)]
+        );
+    }
 }
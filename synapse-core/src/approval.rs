@@ -0,0 +1,71 @@
+//! Human-in-the-loop approval of tool calls.
+//!
+//! Every tool call the model requests is checked against the
+//! [`ApprovalConfig`](crate::config::ApprovalConfig) policy before it runs.
+//! Calls with the [`ToolPolicy::Ask`] policy are passed to a [`ToolApprover`]
+//! implemented by the frontend (a y/n/always prompt in the REPL, inline
+//! keyboard buttons in Telegram). Denied calls are returned to the model as
+//! tool errors.
+
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::message::ToolCallData;
+
+/// What to do when the model calls a tool.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ToolPolicy {
+    /// Run the tool without asking.
+    #[default]
+    Allow,
+    /// Never run the tool.
+    Deny,
+    /// Ask the user through the frontend's [`ToolApprover`].
+    Ask,
+}
+
+/// The user's answer to an approval request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApprovalDecision {
+    /// Run the tool.
+    Allow,
+    /// Do not run the tool; the model receives a tool error.
+    Deny,
+}
+
+/// Asks the user whether a tool call may run.
+///
+/// Implemented by frontends and attached with
+/// [`Agent::with_approver`](crate::agent::Agent::with_approver) or passed to
+/// [`Agent::stream_with_approver`](crate::agent::Agent::stream_with_approver).
+/// Only consulted for calls whose policy is [`ToolPolicy::Ask`].
+/// Implementations that offer an "always allow" answer remember it themselves.
+///
+/// # Examples
+///
+/// ```
+/// use async_trait::async_trait;
+/// use synapse_core::approval::{ApprovalDecision, ToolApprover};
+/// use synapse_core::message::ToolCallData;
+///
+/// struct ReadOnly;
+///
+/// #[async_trait]
+/// impl ToolApprover for ReadOnly {
+///     async fn approve(&self, call: &ToolCallData, _server: Option<&str>) -> ApprovalDecision {
+///         if call.name.starts_with("read_") {
+///             ApprovalDecision::Allow
+///         } else {
+///             ApprovalDecision::Deny
+///         }
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait ToolApprover: Send + Sync {
+    /// Decide whether `call`, provided by MCP server `server`, may run.
+    ///
+    /// The agent waits for the answer before running the tool.
+    async fn approve(&self, call: &ToolCallData, server: Option<&str>) -> ApprovalDecision;
+}
@@ -3,11 +3,23 @@
 //! Provides configuration loading from TOML files with support for
 //! multiple file locations, environment variable overrides, and sensible defaults.
 
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 use serde::Deserialize;
 use thiserror::Error;
 
+use crate::approval::ToolPolicy;
+use crate::context::ContextStrategy;
+use crate::message::TokenUsage;
+use crate::provider::GenerationOptions;
+
+/// Default for [`AgentConfig::max_iterations`].
+const DEFAULT_MAX_ITERATIONS: usize = 10;
+
+/// Model-name prefixes known to accept image and PDF input.
+const VISION_MODEL_PREFIXES: &[&str] = &["claude-", "gpt-4o", "gpt-4.1", "gpt-4-turbo", "gpt-5"];
+
 /// Errors that can occur when loading configuration.
 #[derive(Debug, Error)]
 pub enum ConfigError {
@@ -32,6 +44,10 @@ pub enum ConfigError {
     /// No configuration file found in any of the default search locations.
     #[error("config file not found; searched ./config.toml and ~/.config/synapse/config.toml")]
     NotFound,
+
+    /// A profile was requested that is not defined under `[profiles]`.
+    #[error("unknown profile '{0}'")]
+    UnknownProfile(String),
 }
 
 /// Application configuration loaded from TOML file.
@@ -49,10 +65,32 @@ pub struct Config {
     #[serde(default = "default_model")]
     pub model: String,
 
+    /// Override the provider's API base URL (e.g. a proxy or local server).
+    ///
+    /// Required for the `openai-compatible` provider, whose requests go to
+    /// `{base_url}/chat/completions`.
+    #[serde(default)]
+    pub base_url: Option<String>,
+
+    /// Name of the environment variable holding the API key.
+    ///
+    /// Defaults to the provider's conventional variable (e.g. `OPENAI_API_KEY`).
+    #[serde(default)]
+    pub api_key_env: Option<String>,
+
+    /// Extra HTTP headers sent with every `openai-compatible` provider request.
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+
     /// Maximum tokens for LLM responses (default: 4096).
     #[serde(default = "default_max_tokens")]
     pub max_tokens: u32,
 
+    /// Sampling parameters (temperature, top_p, stop, ...) from the
+    /// `[generation]` section. Unset options use the provider's defaults.
+    #[serde(default)]
+    pub generation: GenerationOptions,
+
     /// System prompt prepended to every LLM conversation.
     ///
     /// Shapes the AI's personality and instructions across all interactions.
@@ -81,9 +119,107 @@ pub struct Config {
     #[serde(default)]
     pub telegram: Option<TelegramConfig>,
 
+    /// Speech-to-text settings for voice messages; unset disables transcription.
+    #[serde(default)]
+    pub transcription: Option<TranscriptionConfig>,
+
     /// File logging configuration (rotation, directory, max files).
     #[serde(default)]
     pub logging: Option<LoggingConfig>,
+
+    /// Retry and timeout settings for provider HTTP requests.
+    #[serde(default)]
+    pub retry: Option<RetryConfig>,
+
+    /// Context-window management settings from the `[context]` section.
+    #[serde(default)]
+    pub context: ContextConfig,
+
+    /// Tool call approval policy from the `[approval]` section.
+    #[serde(default)]
+    pub approval: ApprovalConfig,
+
+    /// Tool execution settings (concurrency, timeouts) from the `[tools]` section.
+    #[serde(default)]
+    pub tools: ToolsConfig,
+
+    /// Tool call loop limits from the `[agent]` section.
+    #[serde(default)]
+    pub agent: AgentConfig,
+
+    /// Per-model token prices used to estimate spend, keyed by model name.
+    ///
+    /// Models without an entry report token counts only.
+    #[serde(default)]
+    pub pricing: HashMap<String, ModelPricing>,
+
+    /// Extra model-name prefixes that accept image and PDF input, on top of
+    /// the built-in list (Claude, GPT-4o, GPT-4.1, GPT-5).
+    #[serde(default)]
+    pub vision_models: Vec<String>,
+
+    /// Name of the profile applied on load, if any.
+    #[serde(default)]
+    pub profile: Option<String>,
+
+    /// Named provider profiles from `[profiles.<name>]` tables.
+    ///
+    /// Selected with the top-level `profile` key, the `--profile` CLI flag or
+    /// the `/model` command. See [`Config::apply_profile`].
+    #[serde(default)]
+    pub profiles: HashMap<String, ProfileConfig>,
+}
+
+/// A named provider profile.
+///
+/// Deserialized from a `[profiles.<name>]` table in `config.toml`. Fields left
+/// unset keep the value of the top-level configuration.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct ProfileConfig {
+    /// LLM provider name (e.g., "anthropic", "openai-compatible").
+    #[serde(default)]
+    pub provider: Option<String>,
+
+    /// Model name to use.
+    #[serde(default)]
+    pub model: Option<String>,
+
+    /// Maximum tokens for LLM responses.
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+
+    /// System prompt for this profile.
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+
+    /// Path to a file whose contents become the system prompt.
+    #[serde(default)]
+    pub system_prompt_file: Option<String>,
+
+    /// API key for the profile's provider.
+    #[serde(default)]
+    pub api_key: Option<String>,
+
+    /// Name of the environment variable holding the API key.
+    #[serde(default)]
+    pub api_key_env: Option<String>,
+
+    /// API base URL override.
+    #[serde(default)]
+    pub base_url: Option<String>,
+
+    /// Extra HTTP headers for an `openai-compatible` provider; replace the
+    /// top-level `headers` when set.
+    #[serde(default)]
+    pub headers: Option<HashMap<String, String>>,
+
+    /// Sampling parameters; set options override the top-level `[generation]`.
+    #[serde(default)]
+    pub generation: GenerationOptions,
+
+    /// Tool call loop limits; set limits override the top-level `[agent]`.
+    #[serde(default)]
+    pub agent: AgentConfig,
 }
 
 /// Session storage configuration.
@@ -148,28 +284,432 @@ pub struct TelegramConfig {
     /// An empty list rejects all users (secure by default).
     #[serde(default)]
     pub allowed_users: Vec<u64>,
+    /// Telegram user IDs allowed to run admin commands such as `/mcp reload`.
+    /// They must also be in `allowed_users`. An empty list allows nobody.
+    #[serde(default)]
+    pub admin_users: Vec<u64>,
     /// Maximum number of sessions allowed per Telegram chat (default: 10).
     ///
     /// When the cap is exceeded during `/new`, the oldest session is automatically
     /// deleted before the new one is created.
     #[serde(default = "default_max_sessions_per_chat")]
     pub max_sessions_per_chat: u32,
+    /// Largest photo or document the bot will download, in bytes (default: 10 MiB).
+    #[serde(default = "default_max_file_bytes")]
+    pub max_file_bytes: u64,
+    /// Group chat IDs the bot answers in. An empty list allows any group;
+    /// senders must still be in `allowed_users`.
+    #[serde(default)]
+    pub allowed_groups: Vec<i64>,
+    /// Whether group members share one session per group or each get their own.
+    #[serde(default)]
+    pub group_sessions: GroupSessions,
+}
+
+/// Session sharing in Telegram group chats.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupSessions {
+    /// One conversation per group, shared by all members.
+    #[default]
+    Shared,
+    /// A separate conversation for each member of the group.
+    PerUser,
 }
 
 fn default_max_sessions_per_chat() -> u32 {
     10
 }
 
+fn default_max_file_bytes() -> u64 {
+    10 * 1024 * 1024
+}
+
 impl Default for TelegramConfig {
     fn default() -> Self {
         Self {
             token: None,
             allowed_users: vec![],
+            admin_users: vec![],
             max_sessions_per_chat: default_max_sessions_per_chat(),
+            max_file_bytes: default_max_file_bytes(),
+            allowed_groups: vec![],
+            group_sessions: GroupSessions::default(),
+        }
+    }
+}
+
+/// Speech-to-text settings.
+///
+/// Deserialized from the `[transcription]` section in `config.toml`. Any
+/// server implementing the OpenAI `/audio/transcriptions` endpoint works,
+/// including local whisper servers.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TranscriptionConfig {
+    /// API base URL; requests go to `{base_url}/audio/transcriptions`
+    /// (default: `https://api.openai.com/v1`).
+    #[serde(default = "default_transcription_base_url")]
+    pub base_url: String,
+    /// Transcription model (default: `whisper-1`).
+    #[serde(default = "default_transcription_model")]
+    pub model: String,
+    /// API key. Overridden by the `api_key_env` environment variable.
+    /// Without either, no `Authorization` header is sent.
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Environment variable holding the API key (default: `OPENAI_API_KEY`).
+    #[serde(default)]
+    pub api_key_env: Option<String>,
+    /// Spoken language hint (ISO-639-1, e.g. `en`); auto-detected when unset.
+    #[serde(default)]
+    pub language: Option<String>,
+}
+
+fn default_transcription_base_url() -> String {
+    "https://api.openai.com/v1".to_string()
+}
+
+fn default_transcription_model() -> String {
+    "whisper-1".to_string()
+}
+
+impl Default for TranscriptionConfig {
+    fn default() -> Self {
+        Self {
+            base_url: default_transcription_base_url(),
+            model: default_transcription_model(),
+            api_key: None,
+            api_key_env: None,
+            language: None,
         }
     }
 }
 
+/// Retry and timeout settings for provider HTTP requests.
+///
+/// Deserialized from the `[retry]` section in `config.toml`. Rate limits,
+/// overload and server errors, timeouts and connection failures are retried
+/// with jittered exponential backoff, honouring `Retry-After` when present.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RetryConfig {
+    /// Retries after the first attempt (default: 3). `0` disables retrying.
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+
+    /// Delay before the first retry in milliseconds (default: 500); doubled
+    /// on each further retry.
+    #[serde(default = "default_initial_backoff_ms")]
+    pub initial_backoff_ms: u64,
+
+    /// Maximum delay between retries in milliseconds (default: 30000).
+    #[serde(default = "default_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+
+    /// Connection timeout in seconds (default: 10).
+    #[serde(default = "default_connect_timeout_secs")]
+    pub connect_timeout_secs: u64,
+
+    /// Maximum wait for response data in seconds (default: 120). Applies to
+    /// each read, so long streaming responses are not cut off.
+    #[serde(default = "default_read_timeout_secs")]
+    pub read_timeout_secs: u64,
+}
+
+fn default_max_retries() -> u32 {
+    3
+}
+
+fn default_initial_backoff_ms() -> u64 {
+    500
+}
+
+fn default_max_backoff_ms() -> u64 {
+    30_000
+}
+
+fn default_connect_timeout_secs() -> u64 {
+    10
+}
+
+fn default_read_timeout_secs() -> u64 {
+    120
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: default_max_retries(),
+            initial_backoff_ms: default_initial_backoff_ms(),
+            max_backoff_ms: default_max_backoff_ms(),
+            connect_timeout_secs: default_connect_timeout_secs(),
+            read_timeout_secs: default_read_timeout_secs(),
+        }
+    }
+}
+
+/// Context-window management settings.
+///
+/// Deserialized from the `[context]` section in `config.toml`. Histories that
+/// would overflow the model's context window are shrunk with `strategy`
+/// before each provider call.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct ContextConfig {
+    /// How to shrink an oversized history (default: `truncate`).
+    #[serde(default)]
+    pub strategy: ContextStrategy,
+
+    /// Context window sizes in tokens keyed by model name, overriding the
+    /// built-in values (see [`context_window`](crate::context::context_window)).
+    #[serde(default)]
+    pub windows: HashMap<String, u32>,
+}
+
+/// Tool call approval policy.
+///
+/// Deserialized from the `[approval]` section in `config.toml`. A policy set
+/// for a tool name wins over one set for its MCP server, which wins over
+/// `default`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct ApprovalConfig {
+    /// Policy for tools without a more specific entry (default: `allow`).
+    #[serde(default)]
+    pub default: ToolPolicy,
+
+    /// Policies keyed by MCP server name.
+    #[serde(default)]
+    pub servers: HashMap<String, ToolPolicy>,
+
+    /// Policies keyed by tool name.
+    #[serde(default)]
+    pub tools: HashMap<String, ToolPolicy>,
+}
+
+impl ApprovalConfig {
+    /// The policy for tool `tool` provided by MCP server `server`.
+    pub fn policy(&self, tool: &str, server: Option<&str>) -> ToolPolicy {
+        self.policy_for(&[tool], server)
+    }
+
+    /// The policy for a tool known by several `names`, such as an MCP tool's
+    /// exposed `server__tool` name, its alias and its original name.
+    ///
+    /// The first name with an entry in `tools` wins, so a rule for the
+    /// original name still applies after the tool was prefixed or renamed.
+    pub fn policy_for(&self, names: &[&str], server: Option<&str>) -> ToolPolicy {
+        names
+            .iter()
+            .find_map(|name| self.tools.get(*name))
+            .or_else(|| server.and_then(|s| self.servers.get(s)))
+            .copied()
+            .unwrap_or(self.default)
+    }
+}
+
+/// Tool call loop limits for one turn.
+///
+/// Deserialized from the `[agent]` section in `config.toml`, or from
+/// `[profiles.<name>.agent]` to override it for a profile. When a turn runs
+/// out of iterations, tokens or time, the model is asked once more, without
+/// tools, to answer from the tool results gathered so far.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct AgentConfig {
+    /// Maximum model calls that may request tools in one turn (default: 10).
+    #[serde(default)]
+    pub max_iterations: Option<usize>,
+
+    /// Token budget for one turn: input plus output tokens of every model
+    /// call. Unset means no budget; providers that report no usage never
+    /// exhaust it.
+    #[serde(default)]
+    pub max_turn_tokens: Option<u64>,
+
+    /// Time budget for one turn in seconds. Unset means no budget.
+    #[serde(default)]
+    pub max_turn_secs: Option<u64>,
+}
+
+impl AgentConfig {
+    /// Maximum model calls that may request tools in one turn.
+    pub fn max_iterations(&self) -> usize {
+        self.max_iterations.unwrap_or(DEFAULT_MAX_ITERATIONS)
+    }
+
+    /// Combine with `overrides`, whose set fields take precedence.
+    pub fn merge(&self, overrides: &Self) -> Self {
+        Self {
+            max_iterations: overrides.max_iterations.or(self.max_iterations),
+            max_turn_tokens: overrides.max_turn_tokens.or(self.max_turn_tokens),
+            max_turn_secs: overrides.max_turn_secs.or(self.max_turn_secs),
+        }
+    }
+}
+
+/// Tool execution settings.
+///
+/// Deserialized from the `[tools]` section in `config.toml`. Tool calls the
+/// model requests in one turn run concurrently; their results are added to
+/// the conversation in the order the calls were made. The `[tools.*]`
+/// subsections enable the native tools built into Synapse, all disabled by
+/// default.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ToolsConfig {
+    /// Maximum tool calls running at once (default: 4). `1` runs them one by one.
+    #[serde(default = "default_max_concurrency")]
+    pub max_concurrency: usize,
+
+    /// Time limit for a single tool call in seconds (default: 120). `0`
+    /// disables the limit. Calls that run out of time return a tool error.
+    #[serde(default = "default_tool_timeout_secs")]
+    pub timeout_secs: u64,
+
+    /// The `current_time` tool.
+    #[serde(default)]
+    pub current_time: CurrentTimeToolConfig,
+
+    /// The `read_file` tool.
+    #[serde(default)]
+    pub read_file: ReadFileToolConfig,
+
+    /// The `fetch` tool.
+    #[serde(default)]
+    pub fetch: FetchToolConfig,
+
+    /// The `search_sessions` tool.
+    #[serde(default)]
+    pub search_sessions: SearchSessionsToolConfig,
+}
+
+fn default_max_concurrency() -> usize {
+    4
+}
+
+fn default_tool_timeout_secs() -> u64 {
+    120
+}
+
+impl Default for ToolsConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrency: default_max_concurrency(),
+            timeout_secs: default_tool_timeout_secs(),
+            current_time: CurrentTimeToolConfig::default(),
+            read_file: ReadFileToolConfig::default(),
+            fetch: FetchToolConfig::default(),
+            search_sessions: SearchSessionsToolConfig::default(),
+        }
+    }
+}
+
+/// Settings for the `current_time` tool, from `[tools.current_time]`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct CurrentTimeToolConfig {
+    /// Offer the tool to the model.
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+/// Settings for the `read_file` tool, from `[tools.read_file]`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ReadFileToolConfig {
+    /// Offer the tool to the model.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Directories whose files may be read; `~` expands to the home
+    /// directory. Relative paths in calls resolve against the first root.
+    #[serde(default)]
+    pub roots: Vec<PathBuf>,
+
+    /// Bytes of a file returned before it is truncated (default: 100000).
+    #[serde(default = "default_tool_max_bytes")]
+    pub max_bytes: usize,
+}
+
+impl Default for ReadFileToolConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            roots: Vec::new(),
+            max_bytes: default_tool_max_bytes(),
+        }
+    }
+}
+
+/// Settings for the `fetch` tool, from `[tools.fetch]`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct FetchToolConfig {
+    /// Offer the tool to the model.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Hosts that may be fetched, including their subdomains. Redirects to
+    /// other hosts are refused.
+    #[serde(default)]
+    pub allowed_hosts: Vec<String>,
+
+    /// Bytes of a response body returned before it is truncated (default: 100000).
+    #[serde(default = "default_tool_max_bytes")]
+    pub max_bytes: usize,
+}
+
+impl Default for FetchToolConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            allowed_hosts: Vec::new(),
+            max_bytes: default_tool_max_bytes(),
+        }
+    }
+}
+
+fn default_tool_max_bytes() -> usize {
+    100_000
+}
+
+/// Settings for the `search_sessions` tool, from `[tools.search_sessions]`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SearchSessionsToolConfig {
+    /// Offer the tool to the model.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Most matching messages returned by one search (default: 10).
+    #[serde(default = "default_search_max_results")]
+    pub max_results: usize,
+}
+
+fn default_search_max_results() -> usize {
+    10
+}
+
+impl Default for SearchSessionsToolConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_results: default_search_max_results(),
+        }
+    }
+}
+
+/// Token prices for a single model, in USD per million tokens.
+///
+/// Deserialized from a `[pricing."<model>"]` table in `config.toml`.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct ModelPricing {
+    /// Price per million input (prompt) tokens.
+    pub input: f64,
+    /// Price per million output (completion) tokens.
+    pub output: f64,
+}
+
+impl ModelPricing {
+    /// Estimated cost in USD of the given usage.
+    pub fn cost(&self, usage: TokenUsage) -> f64 {
+        (f64::from(usage.input_tokens) * self.input + f64::from(usage.output_tokens) * self.output)
+            / 1_000_000.0
+    }
+}
+
 /// Log rotation strategy.
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -293,9 +833,172 @@ impl Config {
                 source,
             })?;
         config.resolve_system_prompt()?;
+        if let Some(name) = config.profile.clone() {
+            config.apply_profile(&name)?;
+        }
+        Ok(config)
+    }
+
+    /// Apply the named profile on top of this configuration.
+    ///
+    /// Fields set in the profile replace the current values. When the profile
+    /// switches to a different provider, the key source, base URL and headers
+    /// of the previous provider are dropped rather than reused.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigError::UnknownProfile`] if no such profile is defined.
+    /// Returns [`ConfigError::IoError`] if the profile's `system_prompt_file`
+    /// cannot be read.
+    pub fn apply_profile(&mut self, name: &str) -> Result<(), ConfigError> {
+        let profile = self
+            .profiles
+            .get(name)
+            .cloned()
+            .ok_or_else(|| ConfigError::UnknownProfile(name.to_string()))?;
+        tracing::debug!(profile = name, "config: applying profile");
+
+        if let Some(provider) = profile.provider
+            && provider != self.provider
+        {
+            self.provider = provider;
+            self.api_key = None;
+            self.api_key_env = None;
+            self.base_url = None;
+            self.headers.clear();
+        }
+        if let Some(model) = profile.model {
+            self.model = model;
+        }
+        if let Some(max_tokens) = profile.max_tokens {
+            self.max_tokens = max_tokens;
+        }
+        if profile.api_key.is_some() {
+            self.api_key = profile.api_key;
+        }
+        if profile.api_key_env.is_some() {
+            self.api_key_env = profile.api_key_env;
+        }
+        if profile.base_url.is_some() {
+            self.base_url = profile.base_url;
+        }
+        if let Some(headers) = profile.headers {
+            self.headers = headers;
+        }
+        self.generation = self.generation.merge(&profile.generation);
+        self.agent = self.agent.merge(&profile.agent);
+        if profile.system_prompt.is_some() {
+            self.system_prompt = profile.system_prompt;
+        } else if profile.system_prompt_file.is_some() {
+            self.system_prompt = None;
+            self.system_prompt_file = profile.system_prompt_file;
+            self.resolve_system_prompt()?;
+        }
+        self.profile = Some(name.to_string());
+        Ok(())
+    }
+
+    /// Configuration for running `model` on `provider`, e.g. when resuming a
+    /// session recorded with a different model.
+    ///
+    /// Same-provider switches keep the current settings. Otherwise the first
+    /// profile for that provider (preferring one for the same model) supplies
+    /// the key source and base URL; failing that, the provider's default
+    /// environment variable is used.
+    pub fn for_model(&self, provider: &str, model: &str) -> Config {
+        let mut config = self.clone();
+        if provider != self.provider {
+            let mut candidates: Vec<_> = self
+                .profiles
+                .iter()
+                .filter(|(_, p)| p.provider.as_deref() == Some(provider))
+                .collect();
+            candidates.sort_by_key(|(name, p)| (p.model.as_deref() != Some(model), *name));
+            let applied = candidates
+                .first()
+                .is_some_and(|(name, _)| config.apply_profile(name).is_ok());
+            if !applied {
+                config.provider = provider.to_string();
+                config.api_key = None;
+                config.api_key_env = None;
+                config.base_url = None;
+                config.headers.clear();
+            }
+        }
+        config.model = model.to_string();
+        config
+    }
+
+    /// Configuration for a stored session recorded with `provider`, `model`
+    /// and, if one was selected, `profile`.
+    ///
+    /// The profile is applied first, so its system prompt, token limit,
+    /// endpoint, key source and generation settings carry over even when it
+    /// uses the default provider. Without a profile, or if it no longer
+    /// exists or now names another provider, this is [`for_model`](Self::for_model).
+    pub fn for_session(&self, provider: &str, model: &str, profile: Option<&str>) -> Config {
+        if let Some(name) = profile {
+            let mut config = self.clone();
+            match config.apply_profile(name) {
+                Ok(()) if config.provider == provider => {
+                    config.model = model.to_string();
+                    return config;
+                }
+                Ok(()) => {
+                    tracing::debug!(
+                        profile = name,
+                        provider,
+                        "config: session profile changed provider"
+                    )
+                }
+                Err(e) => tracing::warn!(
+                    profile = name,
+                    "config: cannot apply session profile: {}",
+                    e
+                ),
+            }
+        }
+        self.for_model(provider, model)
+    }
+
+    /// Configuration selected by a `/model` argument.
+    ///
+    /// A defined profile name applies that profile; anything else is taken as
+    /// a model name for the current provider.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigError::IoError`] if the profile's `system_prompt_file`
+    /// cannot be read.
+    pub fn switch_model(&self, spec: &str) -> Result<Config, ConfigError> {
+        let mut config = self.clone();
+        if self.profiles.contains_key(spec) {
+            config.apply_profile(spec)?;
+        } else {
+            config.model = spec.to_string();
+        }
         Ok(config)
     }
 
+    /// Estimate the cost in USD of `usage` on `model`.
+    ///
+    /// Returns `None` if no price is configured for the model.
+    pub fn estimate_cost(&self, model: &str, usage: TokenUsage) -> Option<f64> {
+        self.pricing.get(model).map(|price| price.cost(usage))
+    }
+
+    /// Whether the configured model accepts image and PDF attachments.
+    ///
+    /// Matches `model` against the built-in vision model prefixes and any
+    /// listed in `vision_models`.
+    pub fn supports_vision(&self) -> bool {
+        VISION_MODEL_PREFIXES
+            .iter()
+            .copied()
+            .chain(self.vision_models.iter().map(String::as_str))
+            .any(|prefix| self.model.starts_with(prefix))
+    }
+
     /// Resolve `system_prompt` from `system_prompt_file` if not already set inline.
     ///
     /// Priority: inline `system_prompt` wins over `system_prompt_file`.
@@ -326,13 +1029,27 @@ impl Default for Config {
             provider: default_provider(),
             api_key: None,
             model: default_model(),
+            base_url: None,
+            api_key_env: None,
+            headers: HashMap::new(),
             max_tokens: default_max_tokens(),
+            generation: GenerationOptions::default(),
             system_prompt: None,
             system_prompt_file: None,
             session: None,
             mcp: None,
             telegram: None,
+            transcription: None,
             logging: None,
+            retry: None,
+            context: ContextConfig::default(),
+            approval: ApprovalConfig::default(),
+            tools: ToolsConfig::default(),
+            agent: AgentConfig::default(),
+            pricing: HashMap::new(),
+            vision_models: Vec::new(),
+            profile: None,
+            profiles: HashMap::new(),
         }
     }
 }
@@ -206,12 +206,14 @@ provider = "deepseek"
 [telegram]
 token = "123456:ABC-DEF"
 allowed_users = [123456789, 987654321]
+admin_users = [123456789]
 "#;
     let config: Config = toml::from_str(toml).unwrap();
     assert!(config.telegram.is_some());
     let tg = config.telegram.unwrap();
     assert_eq!(tg.token, Some("123456:ABC-DEF".to_string()));
     assert_eq!(tg.allowed_users, vec![123456789u64, 987654321u64]);
+    assert_eq!(tg.admin_users, vec![123456789u64]);
 }
 
 #[test]
@@ -231,7 +233,24 @@ fn test_telegram_config_default() {
     let tg = TelegramConfig::default();
     assert!(tg.token.is_none());
     assert!(tg.allowed_users.is_empty());
+    assert!(tg.admin_users.is_empty());
     assert_eq!(tg.max_sessions_per_chat, 10);
+    assert_eq!(tg.max_file_bytes, 10 * 1024 * 1024);
+    assert!(tg.allowed_groups.is_empty());
+    assert_eq!(tg.group_sessions, GroupSessions::Shared);
+}
+
+#[test]
+fn test_config_telegram_group_settings() {
+    let toml = r#"
+[telegram]
+allowed_groups = [-1001234567890]
+group_sessions = "per_user"
+"#;
+    let config: Config = toml::from_str(toml).unwrap();
+    let tg = config.telegram.unwrap();
+    assert_eq!(tg.allowed_groups, vec![-1001234567890]);
+    assert_eq!(tg.group_sessions, GroupSessions::PerUser);
 }
 
 #[test]
@@ -478,3 +497,458 @@ fn test_resolve_system_prompt_trims_whitespace() {
     );
     std::fs::remove_file(&path).ok();
 }
+
+#[test]
+fn test_parse_pricing_toml() {
+    let toml = r#"
+[pricing."claude-sonnet-4-5"]
+input = 3.0
+output = 15.0
+"#;
+    let config: Config = toml::from_str(toml).unwrap();
+    assert_eq!(
+        config.pricing.get("claude-sonnet-4-5"),
+        Some(&ModelPricing {
+            input: 3.0,
+            output: 15.0
+        })
+    );
+}
+
+#[test]
+fn test_estimate_cost() {
+    let mut config = Config::default();
+    config.pricing.insert(
+        "test-model".to_string(),
+        ModelPricing {
+            input: 3.0,
+            output: 15.0,
+        },
+    );
+
+    let cost = config
+        .estimate_cost("test-model", TokenUsage::new(1_000_000, 100_000))
+        .unwrap();
+    assert!((cost - 4.5).abs() < 1e-9);
+    assert_eq!(
+        config.estimate_cost("unpriced-model", TokenUsage::new(10, 10)),
+        None
+    );
+}
+
+#[test]
+fn test_supports_vision_builtin_models() {
+    let mut config = Config::default();
+    assert!(!config.supports_vision());
+
+    config.model = "claude-sonnet-4-5".to_string();
+    assert!(config.supports_vision());
+    config.model = "gpt-4o-mini".to_string();
+    assert!(config.supports_vision());
+}
+
+#[test]
+fn test_supports_vision_configured_prefix() {
+    let toml = r#"
+model = "llava:13b"
+vision_models = ["llava", "qwen2.5-vl"]
+"#;
+    let config: Config = toml::from_str(toml).unwrap();
+    assert!(config.supports_vision());
+}
+
+#[test]
+fn test_parse_transcription_toml() {
+    let toml = r#"
+[transcription]
+base_url = "http://localhost:8000/v1"
+language = "de"
+"#;
+    let config: Config = toml::from_str(toml).unwrap();
+    let transcription = config.transcription.unwrap();
+    assert_eq!(transcription.base_url, "http://localhost:8000/v1");
+    assert_eq!(transcription.model, "whisper-1");
+    assert_eq!(transcription.language.as_deref(), Some("de"));
+    assert!(transcription.api_key.is_none());
+}
+
+#[test]
+fn test_parse_retry_toml() {
+    let toml = r#"
+[retry]
+max_retries = 5
+read_timeout_secs = 300
+"#;
+    let config: Config = toml::from_str(toml).unwrap();
+    let retry = config.retry.unwrap();
+    assert_eq!(retry.max_retries, 5);
+    assert_eq!(retry.read_timeout_secs, 300);
+    assert_eq!(retry.initial_backoff_ms, 500); // default
+    assert_eq!(retry.max_backoff_ms, 30_000); // default
+    assert_eq!(retry.connect_timeout_secs, 10); // default
+}
+
+#[test]
+fn test_parse_openai_compatible_toml() {
+    let toml = r#"
+provider = "openai-compatible"
+model = "llama3.1"
+base_url = "http://localhost:11434/v1"
+api_key_env = "OLLAMA_API_KEY"
+
+[headers]
+X-Gateway-Token = "secret"
+"#;
+    let config: Config = toml::from_str(toml).unwrap();
+    assert_eq!(config.provider, "openai-compatible");
+    assert_eq!(
+        config.base_url.as_deref(),
+        Some("http://localhost:11434/v1")
+    );
+    assert_eq!(config.api_key_env.as_deref(), Some("OLLAMA_API_KEY"));
+    assert_eq!(
+        config.headers.get("X-Gateway-Token").map(String::as_str),
+        Some("secret")
+    );
+}
+
+const PROFILES_TOML: &str = r#"
+provider = "deepseek"
+model = "deepseek-chat"
+api_key = "deepseek-key"
+
+[profiles.claude]
+provider = "anthropic"
+model = "claude-sonnet-4-5"
+max_tokens = 8192
+system_prompt = "You are Claude."
+
+[profiles.local]
+provider = "openai-compatible"
+model = "llama3.1"
+base_url = "http://localhost:11434/v1"
+
+[profiles.local.headers]
+X-Gateway-Token = "local-secret"
+
+[profiles.reasoner]
+model = "deepseek-reasoner"
+"#;
+
+#[test]
+fn test_parse_profiles_toml() {
+    let config: Config = toml::from_str(PROFILES_TOML).unwrap();
+    assert_eq!(config.profiles.len(), 3);
+    let claude = &config.profiles["claude"];
+    assert_eq!(claude.provider.as_deref(), Some("anthropic"));
+    assert_eq!(claude.max_tokens, Some(8192));
+    assert_eq!(config.profile, None);
+}
+
+#[test]
+fn test_apply_profile_switches_provider() {
+    let mut config: Config = toml::from_str(PROFILES_TOML).unwrap();
+    config.apply_profile("claude").unwrap();
+
+    assert_eq!(config.provider, "anthropic");
+    assert_eq!(config.model, "claude-sonnet-4-5");
+    assert_eq!(config.max_tokens, 8192);
+    assert_eq!(config.system_prompt.as_deref(), Some("You are Claude."));
+    // The DeepSeek key must not leak to another provider.
+    assert_eq!(config.api_key, None);
+    assert_eq!(config.profile.as_deref(), Some("claude"));
+}
+
+#[test]
+fn test_apply_profile_same_provider_keeps_key() {
+    let mut config: Config = toml::from_str(PROFILES_TOML).unwrap();
+    config.apply_profile("reasoner").unwrap();
+
+    assert_eq!(config.provider, "deepseek");
+    assert_eq!(config.model, "deepseek-reasoner");
+    assert_eq!(config.api_key.as_deref(), Some("deepseek-key"));
+}
+
+#[test]
+fn test_apply_profile_unknown() {
+    let mut config: Config = toml::from_str(PROFILES_TOML).unwrap();
+    let result = config.apply_profile("missing");
+    assert!(matches!(result, Err(ConfigError::UnknownProfile(name)) if name == "missing"));
+}
+
+#[test]
+fn test_load_from_applies_default_profile() {
+    let path = std::env::temp_dir().join("synapse_test_default_profile.toml");
+    std::fs::write(&path, format!("profile = \"local\"\n{}", PROFILES_TOML)).unwrap();
+
+    let config = Config::load_from(&path).unwrap();
+    std::fs::remove_file(&path).ok();
+
+    assert_eq!(config.provider, "openai-compatible");
+    assert_eq!(config.model, "llama3.1");
+    assert_eq!(
+        config.base_url.as_deref(),
+        Some("http://localhost:11434/v1")
+    );
+    assert_eq!(
+        config.headers.get("X-Gateway-Token").map(String::as_str),
+        Some("local-secret")
+    );
+}
+
+#[test]
+fn test_for_model_same_provider() {
+    let config: Config = toml::from_str(PROFILES_TOML).unwrap();
+    let resumed = config.for_model("deepseek", "deepseek-reasoner");
+
+    assert_eq!(resumed.provider, "deepseek");
+    assert_eq!(resumed.model, "deepseek-reasoner");
+    assert_eq!(resumed.api_key.as_deref(), Some("deepseek-key"));
+}
+
+#[test]
+fn test_for_model_uses_matching_profile() {
+    let config: Config = toml::from_str(PROFILES_TOML).unwrap();
+    let resumed = config.for_model("openai-compatible", "qwen2.5");
+
+    assert_eq!(resumed.provider, "openai-compatible");
+    assert_eq!(resumed.model, "qwen2.5");
+    assert_eq!(
+        resumed.base_url.as_deref(),
+        Some("http://localhost:11434/v1")
+    );
+}
+
+#[test]
+fn test_for_session_applies_profile_with_default_provider() {
+    let toml = format!(
+        "{}system_prompt = \"Think step by step.\"\nmax_tokens = 16000\n",
+        PROFILES_TOML
+    );
+    let config: Config = toml::from_str(&toml).unwrap();
+    let resumed = config.for_session("deepseek", "deepseek-reasoner", Some("reasoner"));
+
+    assert_eq!(resumed.provider, "deepseek");
+    assert_eq!(resumed.model, "deepseek-reasoner");
+    assert_eq!(resumed.profile.as_deref(), Some("reasoner"));
+    assert_eq!(resumed.max_tokens, 16000);
+    assert_eq!(
+        resumed.system_prompt.as_deref(),
+        Some("Think step by step.")
+    );
+    assert_eq!(resumed.api_key.as_deref(), Some("deepseek-key"));
+
+    // `for_model` alone cannot tell which same-provider profile was chosen.
+    let guessed = config.for_model("deepseek", "deepseek-reasoner");
+    assert_eq!(guessed.system_prompt, None);
+}
+
+#[test]
+fn test_for_session_unknown_profile_falls_back() {
+    let config: Config = toml::from_str(PROFILES_TOML).unwrap();
+    let resumed = config.for_session("openai-compatible", "qwen2.5", Some("gone"));
+
+    assert_eq!(resumed.model, "qwen2.5");
+    assert_eq!(
+        resumed.base_url.as_deref(),
+        Some("http://localhost:11434/v1")
+    );
+}
+
+#[test]
+fn test_for_model_without_profile_drops_key() {
+    let config: Config = toml::from_str(PROFILES_TOML).unwrap();
+    let resumed = config.for_model("openai", "gpt-4o");
+
+    assert_eq!(resumed.provider, "openai");
+    assert_eq!(resumed.model, "gpt-4o");
+    assert_eq!(resumed.api_key, None);
+}
+
+#[test]
+fn test_switch_model_profile_or_model_name() {
+    let config: Config = toml::from_str(PROFILES_TOML).unwrap();
+
+    let switched = config.switch_model("claude").unwrap();
+    assert_eq!(switched.provider, "anthropic");
+    assert_eq!(switched.model, "claude-sonnet-4-5");
+
+    let switched = config.switch_model("deepseek-reasoner").unwrap();
+    assert_eq!(switched.provider, "deepseek");
+    assert_eq!(switched.model, "deepseek-reasoner");
+}
+
+#[test]
+fn test_parse_generation_toml() {
+    let toml = r#"
+[generation]
+temperature = 0.2
+stop = ["END"]
+seed = 42
+"#;
+    let config: Config = toml::from_str(toml).unwrap();
+    assert_eq!(config.generation.temperature, Some(0.2));
+    assert_eq!(config.generation.stop, Some(vec!["END".to_string()]));
+    assert_eq!(config.generation.seed, Some(42));
+    assert_eq!(config.generation.top_p, None);
+}
+
+#[test]
+fn test_apply_profile_merges_generation() {
+    let toml = r#"
+[generation]
+temperature = 0.2
+top_p = 0.9
+
+[profiles.creative]
+model = "deepseek-chat"
+
+[profiles.creative.generation]
+temperature = 1.1
+"#;
+    let mut config: Config = toml::from_str(toml).unwrap();
+    config.apply_profile("creative").unwrap();
+
+    assert_eq!(config.generation.temperature, Some(1.1));
+    assert_eq!(config.generation.top_p, Some(0.9));
+}
+
+#[test]
+fn test_parse_context_toml() {
+    let toml = r#"
+[context]
+strategy = "summarize"
+
+[context.windows]
+"llama3.1" = 131072
+"#;
+    let config: Config = toml::from_str(toml).unwrap();
+    assert_eq!(config.context.strategy, ContextStrategy::Summarize);
+    assert_eq!(config.context.windows.get("llama3.1"), Some(&131_072));
+
+    let config: Config = toml::from_str("").unwrap();
+    assert_eq!(config.context, ContextConfig::default());
+    assert_eq!(config.context.strategy, ContextStrategy::Truncate);
+}
+
+#[test]
+fn test_parse_approval_toml() {
+    let toml = r#"
+[approval]
+default = "ask"
+
+[approval.servers]
+filesystem = "deny"
+
+[approval.tools]
+read_file = "allow"
+"#;
+    let config: Config = toml::from_str(toml).unwrap();
+    let approval = &config.approval;
+
+    assert_eq!(
+        approval.policy("read_file", Some("filesystem")),
+        ToolPolicy::Allow
+    );
+    assert_eq!(
+        approval.policy("write_file", Some("filesystem")),
+        ToolPolicy::Deny
+    );
+    assert_eq!(approval.policy("search", Some("web")), ToolPolicy::Ask);
+    assert_eq!(approval.policy("search", None), ToolPolicy::Ask);
+
+    // A rule for any of a tool's names applies; the first listed name wins.
+    assert_eq!(
+        approval.policy_for(&["web__read_file", "read_file"], Some("filesystem")),
+        ToolPolicy::Allow
+    );
+
+    let config: Config = toml::from_str("").unwrap();
+    assert_eq!(config.approval.policy("anything", None), ToolPolicy::Allow);
+}
+
+#[test]
+fn test_parse_approval_invalid_policy() {
+    let toml = r#"
+[approval]
+default = "maybe"
+"#;
+    assert!(toml::from_str::<Config>(toml).is_err());
+}
+
+#[test]
+fn test_parse_tools_toml() {
+    let toml = r#"
+[tools]
+max_concurrency = 8
+timeout_secs = 0
+"#;
+    let config: Config = toml::from_str(toml).unwrap();
+    assert_eq!(config.tools.max_concurrency, 8);
+    assert_eq!(config.tools.timeout_secs, 0);
+
+    let config: Config = toml::from_str("").unwrap();
+    assert_eq!(config.tools, ToolsConfig::default());
+    assert_eq!(config.tools.max_concurrency, 4);
+    assert_eq!(config.tools.timeout_secs, 120);
+}
+
+#[test]
+fn test_parse_native_tools_toml() {
+    let toml = r#"
+[tools.current_time]
+enabled = true
+
+[tools.read_file]
+enabled = true
+roots = ["~/notes", "/srv/docs"]
+
+[tools.fetch]
+enabled = true
+allowed_hosts = ["example.com"]
+max_bytes = 5000
+
+[tools.search_sessions]
+max_results = 3
+"#;
+    let config: Config = toml::from_str(toml).unwrap();
+    let tools = &config.tools;
+    assert!(tools.current_time.enabled);
+    assert!(tools.read_file.enabled);
+    assert_eq!(
+        tools.read_file.roots,
+        vec![PathBuf::from("~/notes"), PathBuf::from("/srv/docs")]
+    );
+    assert_eq!(tools.read_file.max_bytes, 100_000);
+    assert_eq!(tools.fetch.allowed_hosts, vec!["example.com"]);
+    assert_eq!(tools.fetch.max_bytes, 5000);
+    assert!(!tools.search_sessions.enabled);
+    assert_eq!(tools.search_sessions.max_results, 3);
+    assert_eq!(tools.max_concurrency, 4);
+}
+
+#[test]
+fn test_apply_profile_merges_agent_limits() {
+    let toml = r#"
+[agent]
+max_iterations = 20
+max_turn_secs = 300
+
+[profiles.cheap]
+model = "deepseek-chat"
+
+[profiles.cheap.agent]
+max_iterations = 5
+max_turn_tokens = 50000
+"#;
+    let mut config: Config = toml::from_str(toml).unwrap();
+    assert_eq!(config.agent.max_iterations(), 20);
+    assert_eq!(config.agent.max_turn_tokens, None);
+
+    config.apply_profile("cheap").unwrap();
+
+    assert_eq!(config.agent.max_iterations(), 5);
+    assert_eq!(config.agent.max_turn_tokens, Some(50_000));
+    assert_eq!(config.agent.max_turn_secs, Some(300));
+    assert_eq!(AgentConfig::default().max_iterations(), 10);
+}
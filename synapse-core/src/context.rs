@@ -0,0 +1,522 @@
+//! Context-window management for conversation history.
+//!
+//! Provides [`ContextManager`], which estimates the token size of a
+//! conversation and shrinks histories that would overflow the model's context
+//! window, along with the built-in window sizes returned by [`context_window`].
+//!
+//! Token counts are estimated from text length rather than computed with the
+//! provider's tokenizer, so budgets are approximate and err on the large side
+//! for non-ASCII text.
+
+use serde::Deserialize;
+
+use crate::config::Config;
+use crate::message::{Message, Role};
+
+/// Context window assumed for models without a built-in or configured size.
+pub const DEFAULT_CONTEXT_WINDOW: u32 = 32_768;
+
+/// Approximate number of bytes of text per token.
+const BYTES_PER_TOKEN: usize = 4;
+
+/// Estimated per-message overhead for role markers and formatting.
+const MESSAGE_OVERHEAD_TOKENS: u32 = 4;
+
+/// Estimated tokens of one image or document attachment, about what a
+/// full-size image costs.
+const ATTACHMENT_TOKENS: u32 = 1_600;
+
+/// Built-in context windows, matched against the model name by prefix in order.
+const KNOWN_WINDOWS: &[(&str, u32)] = &[
+    ("claude-", 200_000),
+    ("gpt-4.1", 1_047_576),
+    ("gpt-4o", 128_000),
+    ("gpt-4-turbo", 128_000),
+    ("gpt-4", 8_192),
+    ("gpt-3.5-turbo", 16_385),
+    ("gpt-5", 400_000),
+    ("o1", 200_000),
+    ("o3", 200_000),
+    ("o4", 200_000),
+    ("deepseek-", 64_000),
+];
+
+/// Instructions for the model that summarizes older turns.
+const SUMMARY_PROMPT: &str = "You compress conversation history. Summarize the conversation \
+     below so that an assistant can continue it without the original messages. Keep facts, \
+     decisions, names, numbers, open questions and the results of tool calls. Reply with the \
+     summary only.";
+
+/// Heading of the system message that carries a summary into the history.
+const SUMMARY_HEADING: &str = "Summary of the earlier conversation:";
+
+/// How to shrink a history that does not fit the context window.
+///
+/// Both strategies cut the history only between turns (a user message and
+/// everything answering it), so assistant tool calls are never separated from
+/// their results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContextStrategy {
+    /// Drop the oldest turns. If the latest turn alone is too large, its
+    /// oldest tool call/result groups are dropped instead.
+    #[default]
+    Truncate,
+    /// Replace older turns with an LLM-written summary that is stored as a
+    /// session artifact, then truncate whatever still does not fit.
+    Summarize,
+}
+
+/// Context window size in tokens for `model`.
+///
+/// Uses the built-in table of well-known model families and falls back to
+/// [`DEFAULT_CONTEXT_WINDOW`] for anything else (e.g. local models).
+///
+/// # Examples
+///
+/// ```
+/// use synapse_core::context::{DEFAULT_CONTEXT_WINDOW, context_window};
+///
+/// assert_eq!(context_window("claude-sonnet-4-5"), 200_000);
+/// assert_eq!(context_window("llama3.1"), DEFAULT_CONTEXT_WINDOW);
+/// ```
+pub fn context_window(model: &str) -> u32 {
+    KNOWN_WINDOWS
+        .iter()
+        .find(|(prefix, _)| model.starts_with(prefix))
+        .map_or(DEFAULT_CONTEXT_WINDOW, |&(_, window)| window)
+}
+
+/// Estimate the tokens `text` occupies in a request.
+pub fn estimate_text_tokens(text: &str) -> u32 {
+    u32::try_from(text.len().div_ceil(BYTES_PER_TOKEN)).unwrap_or(u32::MAX)
+}
+
+/// Estimate the tokens a single message occupies in a request, including
+/// its tool calls and attachments.
+pub fn estimate_tokens(message: &Message) -> u32 {
+    let tool_calls = message
+        .tool_calls
+        .iter()
+        .flatten()
+        .map(|call| {
+            estimate_text_tokens(&call.name) + estimate_text_tokens(&call.input.to_string())
+        })
+        .sum::<u32>();
+    let attachments = u32::try_from(message.attachments.len())
+        .unwrap_or(u32::MAX)
+        .saturating_mul(ATTACHMENT_TOKENS);
+    MESSAGE_OVERHEAD_TOKENS
+        .saturating_add(estimate_text_tokens(&message.content))
+        .saturating_add(tool_calls)
+        .saturating_add(attachments)
+}
+
+/// Estimate the tokens of a whole conversation.
+pub fn estimate_history_tokens(messages: &[Message]) -> u32 {
+    messages
+        .iter()
+        .fold(0, |total, m| total.saturating_add(estimate_tokens(m)))
+}
+
+/// Fits conversation history into a model's context window.
+///
+/// The budget for history is the context window minus the tokens reserved
+/// for the response (the configured `max_tokens`) and the fixed part of the
+/// request such as the system prompt and tool schemas.
+///
+/// # Examples
+///
+/// ```
+/// use synapse_core::context::ContextManager;
+/// use synapse_core::message::{Message, Role};
+///
+/// let manager = ContextManager::new(100).with_reserve(40);
+/// let messages = vec![
+///     Message::new(Role::User, "a".repeat(200)),
+///     Message::new(Role::Assistant, "b".repeat(200)),
+///     Message::new(Role::User, "What now?"),
+/// ];
+///
+/// let kept = manager.truncate(&messages, 0);
+/// assert_eq!(kept.len(), 1);
+/// assert_eq!(kept[0].content, "What now?");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextManager {
+    /// Context window of the model in tokens.
+    window: u32,
+    /// Tokens reserved for the model's response.
+    reserve: u32,
+    /// How oversized histories are shrunk.
+    strategy: ContextStrategy,
+}
+
+impl Default for ContextManager {
+    /// A manager with an unlimited window, which never drops messages.
+    fn default() -> Self {
+        Self::new(u32::MAX)
+    }
+}
+
+impl ContextManager {
+    /// Create a manager for a model with a `window`-token context window.
+    ///
+    /// No tokens are reserved for the response and the strategy is
+    /// [`ContextStrategy::Truncate`].
+    pub fn new(window: u32) -> Self {
+        Self {
+            window,
+            reserve: 0,
+            strategy: ContextStrategy::default(),
+        }
+    }
+
+    /// Create a manager for the configured model.
+    ///
+    /// The window comes from `[context.windows]` or the built-in table, the
+    /// response reserve from `max_tokens` and the strategy from
+    /// `[context] strategy`.
+    pub fn from_config(config: &Config) -> Self {
+        let window = config
+            .context
+            .windows
+            .get(&config.model)
+            .copied()
+            .unwrap_or_else(|| context_window(&config.model));
+        Self::new(window)
+            .with_reserve(config.max_tokens)
+            .with_strategy(config.context.strategy)
+    }
+
+    /// Set the number of tokens reserved for the model's response.
+    pub fn with_reserve(mut self, tokens: u32) -> Self {
+        self.reserve = tokens;
+        self
+    }
+
+    /// Set how oversized histories are shrunk.
+    pub fn with_strategy(mut self, strategy: ContextStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Context window of the model in tokens.
+    pub fn window(&self) -> u32 {
+        self.window
+    }
+
+    /// How oversized histories are shrunk.
+    pub fn strategy(&self) -> ContextStrategy {
+        self.strategy
+    }
+
+    /// Tokens available for history once the response reserve and
+    /// `fixed_tokens` (system prompt, tool schemas) are accounted for.
+    pub fn budget(&self, fixed_tokens: u32) -> u32 {
+        self.window
+            .saturating_sub(self.reserve)
+            .saturating_sub(fixed_tokens)
+    }
+
+    /// Drop the oldest turns of `messages` until the rest fits the budget.
+    ///
+    /// Leading system messages (such as a summary of earlier turns) are always
+    /// kept and count against the budget. If even the latest turn does not
+    /// fit, its opening message and its most recent tool call/result group
+    /// are kept, dropping the groups in between oldest first.
+    pub fn truncate(&self, messages: &[Message], fixed_tokens: u32) -> Vec<Message> {
+        let pinned = messages
+            .iter()
+            .take_while(|m| m.role == Role::System)
+            .count();
+        let (pinned, history) = messages.split_at(pinned);
+        let budget = self.budget(fixed_tokens.saturating_add(estimate_history_tokens(pinned)));
+
+        let mut kept = pinned.to_vec();
+        if let Some(start) = fitting_start(history, budget, is_turn_start) {
+            if start > 0 {
+                tracing::debug!(dropped = start, "context: dropping oldest turns");
+            }
+            kept.extend_from_slice(&history[start..]);
+            return kept;
+        }
+
+        // The latest turn alone overflows: keep its opening message and drop
+        // the oldest tool call/result groups that follow it.
+        let turn_start = history.iter().rposition(is_turn_start).unwrap_or(0);
+        let Some((first, rest)) = history[turn_start..].split_first() else {
+            return kept;
+        };
+        let rest_budget = budget.saturating_sub(estimate_tokens(first));
+        let start = fitting_start(rest, rest_budget, is_group_start)
+            .unwrap_or_else(|| rest.iter().rposition(is_group_start).unwrap_or(0));
+        tracing::warn!(
+            dropped = turn_start + start,
+            "context: latest turn exceeds the context window; dropping tool results"
+        );
+        kept.push(first.clone());
+        kept.extend_from_slice(&rest[start..]);
+        kept
+    }
+
+    /// Number of leading `messages` to summarize so that the rest takes at
+    /// most half of the budget, leaving room for the conversation to grow
+    /// before the next summary.
+    ///
+    /// Returns `None` if the history already fits the budget or if no turn
+    /// boundary leaves anything to summarize.
+    pub(crate) fn summary_split(&self, messages: &[Message], fixed_tokens: u32) -> Option<usize> {
+        let budget = self.budget(fixed_tokens);
+        if estimate_history_tokens(messages) <= budget {
+            return None;
+        }
+        let start = fitting_start(messages, budget / 2, is_turn_start)
+            .or_else(|| messages.iter().rposition(is_turn_start))?;
+        (start > 0).then_some(start)
+    }
+}
+
+/// System message carrying a summary of earlier turns into the history.
+pub fn summary_message(summary: &str) -> Message {
+    Message::new(Role::System, format!("{SUMMARY_HEADING}\n{summary}"))
+}
+
+/// Build the request asking the model to summarize `messages`, folding in
+/// the `previous` summary if there is one.
+pub(crate) fn summary_request(previous: Option<&str>, messages: &[Message]) -> Vec<Message> {
+    let mut transcript = String::new();
+    if let Some(previous) = previous {
+        transcript.push_str(&format!("Earlier summary:\n{previous}\n\n"));
+    }
+    for message in messages {
+        let speaker = match message.role {
+            Role::System => "System",
+            Role::User => "User",
+            Role::Assistant => "Assistant",
+            Role::Tool => "Tool result",
+        };
+        if !message.content.is_empty() {
+            transcript.push_str(&format!("{speaker}: {}\n", message.content));
+        }
+        for call in message.tool_calls.iter().flatten() {
+            transcript.push_str(&format!("Assistant called {}({})\n", call.name, call.input));
+        }
+    }
+    vec![
+        Message::new(Role::System, SUMMARY_PROMPT),
+        Message::new(Role::User, transcript),
+    ]
+}
+
+/// Whether `message` opens a turn.
+fn is_turn_start(message: &Message) -> bool {
+    message.role == Role::User
+}
+
+/// Whether `message` opens a group within a turn; tool results belong to the
+/// assistant message that requested them.
+fn is_group_start(message: &Message) -> bool {
+    message.role != Role::Tool
+}
+
+/// Earliest index `i` such that `messages[i..]` fits `budget` and `i` is 0 or
+/// a boundary according to `is_boundary`.
+///
+/// Returns `None` if no such suffix fits.
+fn fitting_start(
+    messages: &[Message],
+    budget: u32,
+    is_boundary: impl Fn(&Message) -> bool,
+) -> Option<usize> {
+    let mut total: u32 = 0;
+    let mut start = None;
+    for (i, message) in messages.iter().enumerate().rev() {
+        total = total.saturating_add(estimate_tokens(message));
+        if total > budget {
+            break;
+        }
+        if i == 0 || is_boundary(message) {
+            start = Some(i);
+        }
+    }
+    start
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ContextConfig;
+    use crate::message::ToolCallData;
+
+    /// A message whose estimated size is exactly `tokens`.
+    fn sized(role: Role, tokens: u32) -> Message {
+        let len = (tokens - MESSAGE_OVERHEAD_TOKENS) as usize * BYTES_PER_TOKEN;
+        Message::new(role, "x".repeat(len))
+    }
+
+    fn tool_call(id: &str) -> Message {
+        let mut message = Message::new(Role::Assistant, "");
+        message.tool_calls = Some(vec![ToolCallData {
+            id: id.to_string(),
+            name: "search".to_string(),
+            input: serde_json::json!({}),
+        }]);
+        message
+    }
+
+    #[test]
+    fn test_context_window_known_and_unknown_models() {
+        assert_eq!(context_window("claude-3-5-sonnet-20241022"), 200_000);
+        assert_eq!(context_window("gpt-4o-mini"), 128_000);
+        assert_eq!(context_window("gpt-4"), 8_192);
+        assert_eq!(context_window("deepseek-chat"), 64_000);
+        assert_eq!(context_window("llama3.1"), DEFAULT_CONTEXT_WINDOW);
+    }
+
+    #[test]
+    fn test_estimate_tokens_counts_content_and_tool_calls() {
+        assert_eq!(estimate_tokens(&sized(Role::User, 10)), 10);
+        assert_eq!(estimate_text_tokens("abcde"), 2);
+        assert!(estimate_tokens(&tool_call("call_1")) > MESSAGE_OVERHEAD_TOKENS);
+
+        let with_image = Message::new(Role::User, "")
+            .with_attachment(crate::message::Attachment::image("image/png", vec![0; 10]));
+        assert_eq!(
+            estimate_tokens(&with_image),
+            MESSAGE_OVERHEAD_TOKENS + ATTACHMENT_TOKENS
+        );
+    }
+
+    #[test]
+    fn test_context_manager_from_config_prefers_configured_window() {
+        let mut config = Config {
+            model: "llama3.1".to_string(),
+            max_tokens: 1000,
+            context: ContextConfig {
+                strategy: ContextStrategy::Summarize,
+                ..ContextConfig::default()
+            },
+            ..Config::default()
+        };
+        let manager = ContextManager::from_config(&config);
+        assert_eq!(manager.window(), DEFAULT_CONTEXT_WINDOW);
+        assert_eq!(manager.strategy(), ContextStrategy::Summarize);
+        assert_eq!(manager.budget(0), DEFAULT_CONTEXT_WINDOW - 1000);
+
+        config
+            .context
+            .windows
+            .insert("llama3.1".to_string(), 131_072);
+        assert_eq!(ContextManager::from_config(&config).window(), 131_072);
+    }
+
+    #[test]
+    fn test_truncate_keeps_history_that_fits() {
+        let manager = ContextManager::new(100);
+        let messages = vec![sized(Role::User, 20), sized(Role::Assistant, 20)];
+        assert_eq!(manager.truncate(&messages, 10), messages);
+    }
+
+    #[test]
+    fn test_truncate_drops_oldest_turns() {
+        let manager = ContextManager::new(100).with_reserve(20);
+        let messages = vec![
+            sized(Role::User, 30),
+            sized(Role::Assistant, 30),
+            sized(Role::User, 20),
+            sized(Role::Assistant, 20),
+            sized(Role::User, 10),
+        ];
+
+        let kept = manager.truncate(&messages, 10);
+
+        assert_eq!(kept, messages[2..]);
+    }
+
+    #[test]
+    fn test_truncate_keeps_tool_results_with_their_call() {
+        let manager = ContextManager::new(60);
+        let messages = vec![
+            sized(Role::User, 10),
+            tool_call("call_1"),
+            sized(Role::Tool, 40),
+            sized(Role::User, 10),
+            tool_call("call_2"),
+            sized(Role::Tool, 20),
+        ];
+
+        let kept = manager.truncate(&messages, 0);
+
+        // The cut falls before the second user message, not between a call
+        // and its result.
+        assert_eq!(kept, messages[3..]);
+    }
+
+    #[test]
+    fn test_truncate_oversized_turn_drops_oldest_tool_groups() {
+        let manager = ContextManager::new(80);
+        let messages = vec![
+            sized(Role::User, 10),
+            tool_call("call_1"),
+            sized(Role::Tool, 40),
+            tool_call("call_2"),
+            sized(Role::Tool, 40),
+        ];
+
+        let kept = manager.truncate(&messages, 0);
+
+        assert_eq!(kept.len(), 3);
+        assert_eq!(kept[0], messages[0]);
+        assert_eq!(kept[1].tool_calls.as_ref().unwrap()[0].id, "call_2");
+        assert_eq!(kept[2], messages[4]);
+    }
+
+    #[test]
+    fn test_truncate_keeps_leading_summary() {
+        let manager = ContextManager::new(60);
+        let messages = vec![
+            sized(Role::System, 20),
+            sized(Role::User, 20),
+            sized(Role::Assistant, 20),
+            sized(Role::User, 10),
+        ];
+
+        let kept = manager.truncate(&messages, 0);
+
+        assert_eq!(kept, vec![messages[0].clone(), messages[3].clone()]);
+    }
+
+    #[test]
+    fn test_summary_split_leaves_half_the_budget() {
+        let manager = ContextManager::new(100);
+        let messages = vec![
+            sized(Role::User, 30),
+            sized(Role::Assistant, 30),
+            sized(Role::User, 20),
+            sized(Role::Assistant, 20),
+            sized(Role::User, 10),
+        ];
+
+        assert_eq!(manager.summary_split(&messages, 0), Some(2));
+        assert_eq!(manager.summary_split(&messages[2..], 0), None);
+    }
+
+    #[test]
+    fn test_summary_request_renders_transcript() {
+        let messages = vec![
+            Message::new(Role::User, "Find flights"),
+            tool_call("call_1"),
+            Message::tool_result("call_1", "3 flights"),
+        ];
+
+        let request = summary_request(Some("User is in Paris."), &messages);
+
+        assert_eq!(request[0].role, Role::System);
+        let transcript = &request[1].content;
+        assert!(transcript.starts_with("Earlier summary:\nUser is in Paris."));
+        assert!(transcript.contains("User: Find flights"));
+        assert!(transcript.contains("Assistant called search({})"));
+        assert!(transcript.contains("Tool result: 3 flights"));
+    }
+}
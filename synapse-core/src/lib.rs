@@ -1,21 +1,33 @@
 //! Synapse core library.
 //!
 //! Provides the agent orchestrator, LLM provider abstraction,
-//! session management, and MCP integration.
+//! context-window management, tool call approval, session management, and MCP integration.
 
 pub mod agent;
+pub mod approval;
 pub mod config;
+pub mod context;
 pub mod mcp;
 pub mod message;
 pub mod provider;
 pub mod session;
 pub mod storage;
 pub mod text;
+pub mod tools;
 
 pub use agent::{Agent, AgentError};
-pub use config::{Config, TelegramConfig};
-pub use mcp::{McpClient, init_mcp_client, load_mcp_config};
-pub use message::{Message, Role};
-pub use provider::{LlmProvider, StreamEvent, create_provider};
-pub use session::{Session, SessionSummary, StoredMessage};
+pub use approval::{ApprovalDecision, ToolApprover, ToolPolicy};
+pub use config::{
+    AgentConfig, ApprovalConfig, Config, ContextConfig, GroupSessions, ModelPricing,
+    TelegramConfig, ToolsConfig, TranscriptionConfig,
+};
+pub use context::{ContextManager, ContextStrategy};
+pub use mcp::{McpClient, McpReload, init_mcp_client, load_mcp_config};
+pub use message::{Attachment, Message, Role, TokenUsage, ToolContent, ToolOutput};
+pub use provider::{
+    GenerationOptions, LlmProvider, StopReason, StreamEvent, Transcriber, create_provider,
+    create_transcriber,
+};
+pub use session::{ArtifactKind, Session, SessionArtifact, SessionSummary, StoredMessage};
 pub use storage::{SessionStore, create_storage};
+pub use tools::{Tool, ToolRegistry};
@@ -1,13 +1,26 @@
 //! MCP (Model Context Protocol) integration.
 //!
 //! Provides configuration loading, client management, tool discovery,
-//! and tool execution for MCP servers.
+//! tool execution, resources and prompts for MCP servers reached over stdio,
+//! streamable HTTP or legacy HTTP+SSE.
 
+mod prompts;
 mod protocol;
+mod registry;
+mod resources;
+mod sse;
+#[cfg(test)]
+pub(crate) mod test_server;
 mod tools;
 
-pub use protocol::{McpConfig, McpServerConfig, ToolDefinition};
-pub use tools::McpClient;
+pub use prompts::parse_prompt_command;
+pub use protocol::{
+    McpConfig, McpServerConfig, McpTransport, PromptArgument, PromptDefinition, ResourceDefinition,
+    ToolDefinition,
+};
+pub use registry::sanitize_tool_name;
+pub use resources::{ResourceRef, find_resource_refs};
+pub use tools::{McpClient, McpReload};
 
 use std::path::PathBuf;
 
@@ -31,6 +44,14 @@ pub enum McpError {
     #[error("MCP tool error: {0}")]
     ToolError(String),
 
+    /// Reading a resource failed.
+    #[error("MCP resource error: {0}")]
+    ResourceError(String),
+
+    /// Rendering a prompt failed.
+    #[error("MCP prompt error: {0}")]
+    PromptError(String),
+
     /// IO error during MCP operations.
     #[error("MCP IO error: {0}")]
     IoError(String),
@@ -44,13 +65,17 @@ pub enum McpError {
 /// - No config file exists at the resolved path
 /// - The config file fails to parse
 /// - `McpClient::new()` fails
-/// - The connected client has no tools registered
+/// - The connected client has no tools, resources or prompts registered
 pub async fn init_mcp_client(config_path: Option<&str>) -> Option<McpClient> {
     match load_mcp_config(config_path) {
         Ok(Some(config)) => match McpClient::new(&config).await {
-            Ok(client) if client.has_tools() => Some(client),
+            Ok(client) if client.has_tools() || client.has_resources() || client.has_prompts() => {
+                Some(client)
+            }
             Ok(_) => {
-                tracing::info!("MCP servers connected but registered no tools — disabling MCP");
+                tracing::info!(
+                    "MCP servers connected but registered no tools, resources or prompts — disabling MCP"
+                );
                 None
             }
             Err(e) => {
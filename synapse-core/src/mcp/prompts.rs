@@ -0,0 +1,172 @@
+//! MCP prompt invocation.
+//!
+//! Parses `/prompt name key=value ...` commands and converts rendered prompt
+//! messages into conversation [`Message`]s.
+
+use std::collections::HashMap;
+
+use rmcp::model::{PromptMessage, PromptMessageContent, PromptMessageRole};
+
+use super::McpError;
+use super::resources::contents_text;
+use crate::message::{Message, Role};
+
+/// Parse the arguments of a `/prompt` command.
+///
+/// The first word is the prompt name; the rest are `key=value` pairs. Values
+/// containing spaces can be wrapped in single or double quotes.
+///
+/// # Errors
+///
+/// Returns [`McpError::PromptError`] if the name is missing, an argument is
+/// not `key=value`, or a quote is left open.
+///
+/// # Examples
+///
+/// ```
+/// use synapse_core::mcp::parse_prompt_command;
+///
+/// let (name, args) = parse_prompt_command(r#"review lang=rust focus="error handling""#).unwrap();
+/// assert_eq!(name, "review");
+/// assert_eq!(args["focus"], "error handling");
+/// ```
+pub fn parse_prompt_command(input: &str) -> Result<(String, HashMap<String, String>), McpError> {
+    let mut words = split_words(input)?.into_iter();
+    let name = words
+        .next()
+        .ok_or_else(|| McpError::PromptError("missing prompt name".to_string()))?;
+
+    let mut arguments = HashMap::new();
+    for word in words {
+        let (key, value) = word
+            .split_once('=')
+            .filter(|(key, _)| !key.is_empty())
+            .ok_or_else(|| {
+                McpError::PromptError(format!("expected key=value argument, got '{}'", word))
+            })?;
+        arguments.insert(key.to_string(), value.to_string());
+    }
+    Ok((name, arguments))
+}
+
+/// Split on whitespace, keeping quoted sections together and dropping the quotes.
+fn split_words(input: &str) -> Result<Vec<String>, McpError> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+
+    for c in input.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => current.push(c),
+            None if c == '"' || c == '\'' => {
+                quote = Some(c);
+                in_word = true;
+            }
+            None if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            None => {
+                current.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if quote.is_some() {
+        return Err(McpError::PromptError("unterminated quote".to_string()));
+    }
+    if in_word {
+        words.push(current);
+    }
+    Ok(words)
+}
+
+/// Convert rendered prompt messages into conversation messages.
+///
+/// Embedded resources are inlined as text; images and resource links are
+/// replaced by short placeholders since messages carry text only.
+pub(super) fn prompt_messages(messages: Vec<PromptMessage>) -> Vec<Message> {
+    messages
+        .into_iter()
+        .map(|message| {
+            let role = match message.role {
+                PromptMessageRole::User => Role::User,
+                PromptMessageRole::Assistant => Role::Assistant,
+            };
+            let content = match message.content {
+                PromptMessageContent::Text { text } => text,
+                PromptMessageContent::Image { image } => {
+                    format!("[image: {}]", image.mime_type)
+                }
+                PromptMessageContent::Resource { resource } => {
+                    contents_text(std::slice::from_ref(&resource.resource))
+                }
+                PromptMessageContent::ResourceLink { link } => {
+                    format!("[resource: {}]", link.uri)
+                }
+            };
+            Message::new(role, content)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_prompt_command_name_only() {
+        let (name, args) = parse_prompt_command("  summarize ").unwrap();
+        assert_eq!(name, "summarize");
+        assert!(args.is_empty());
+    }
+
+    #[test]
+    fn test_parse_prompt_command_quoted_values() {
+        let (name, args) =
+            parse_prompt_command(r#"review file=src/main.rs focus="error handling" tone='calm'"#)
+                .unwrap();
+
+        assert_eq!(name, "review");
+        assert_eq!(args["file"], "src/main.rs");
+        assert_eq!(args["focus"], "error handling");
+        assert_eq!(args["tone"], "calm");
+    }
+
+    #[test]
+    fn test_parse_prompt_command_errors() {
+        assert!(matches!(
+            parse_prompt_command(""),
+            Err(McpError::PromptError(_))
+        ));
+        assert!(matches!(
+            parse_prompt_command("review rust"),
+            Err(McpError::PromptError(_))
+        ));
+        assert!(matches!(
+            parse_prompt_command("review =x"),
+            Err(McpError::PromptError(_))
+        ));
+        assert!(matches!(
+            parse_prompt_command(r#"review focus="open"#),
+            Err(McpError::PromptError(_))
+        ));
+    }
+
+    #[test]
+    fn test_prompt_messages_maps_roles_and_text() {
+        let messages = prompt_messages(vec![
+            PromptMessage::new_text(PromptMessageRole::User, "Review this"),
+            PromptMessage::new_text(PromptMessageRole::Assistant, "Sure"),
+        ]);
+
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].role, Role::User);
+        assert_eq!(messages[0].content, "Review this");
+        assert_eq!(messages[1].role, Role::Assistant);
+    }
+}
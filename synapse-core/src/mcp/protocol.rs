@@ -7,23 +7,98 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
+/// Default cap on the characters of text in one tool result.
+const DEFAULT_MAX_OUTPUT_CHARS: usize = 100_000;
+
+/// Default number of times a crashed server is restarted.
+const DEFAULT_MAX_RESTARTS: u32 = 3;
+
 /// Configuration for a single MCP server.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// A server is either a local process started with `command` (stdio) or a
+/// remote service reached at `url` over streamable HTTP or legacy HTTP+SSE.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct McpServerConfig {
-    /// Command to execute to start the server.
-    pub command: String,
+    /// Command to execute to start a stdio server.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
     /// Arguments to pass to the command.
     #[serde(default)]
     pub args: Vec<String>,
     /// Environment variables to set for the server process.
     #[serde(default)]
     pub env: HashMap<String, String>,
+    /// Endpoint URL of a remote server.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Transport for a remote server. When unset, streamable HTTP is tried
+    /// first and legacy SSE is used if the server rejects it.
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub transport: Option<McpTransport>,
+    /// Extra HTTP headers sent with every request to a remote server.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    /// Bearer token sent as `Authorization: Bearer <token>` to a remote server.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bearer_token: Option<String>,
+    /// Time limit in seconds for each request to the server. Unset means no
+    /// limit at the MCP level.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    /// Characters a tool result may contain before it is truncated, counting
+    /// text and base64 media data (default: 100000). `0` disables truncation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_output_chars: Option<usize>,
+    /// How many times the server is restarted after its connection is lost
+    /// (default: 3). `0` disables restarts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_restarts: Option<u32>,
+    /// Tools to offer to the model; when non-empty, all others are hidden.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include_tools: Vec<String>,
+    /// Tools to hide from the model.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude_tools: Vec<String>,
+    /// Names to offer tools under, keyed by the server's tool name.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tool_aliases: HashMap<String, String>,
+}
+
+impl McpServerConfig {
+    /// Tool result cap in characters, with the default applied.
+    pub fn max_output_chars(&self) -> usize {
+        self.max_output_chars.unwrap_or(DEFAULT_MAX_OUTPUT_CHARS)
+    }
+
+    /// Restart limit, with the default applied.
+    pub fn max_restarts(&self) -> u32 {
+        self.max_restarts.unwrap_or(DEFAULT_MAX_RESTARTS)
+    }
+
+    /// Whether tool `name` passes the `include_tools` / `exclude_tools` filters.
+    pub fn allows_tool(&self, name: &str) -> bool {
+        (self.include_tools.is_empty() || self.include_tools.iter().any(|t| t == name))
+            && !self.exclude_tools.iter().any(|t| t == name)
+    }
+}
+
+/// Transport used to reach an MCP server, set with the `type` key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum McpTransport {
+    /// Child process speaking JSON-RPC over stdin/stdout.
+    Stdio,
+    /// Streamable HTTP (MCP 2025-03-26 and later).
+    #[serde(alias = "streamable-http", alias = "streamable_http")]
+    Http,
+    /// Legacy HTTP+SSE (MCP 2024-11-05).
+    Sse,
 }
 
 /// Top-level MCP configuration file format.
 ///
 /// Compatible with Claude Desktop / Windsurf standard format.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct McpConfig {
     /// Map of server names to their configurations.
     #[serde(rename = "mcpServers")]
@@ -45,6 +120,45 @@ pub struct ToolDefinition {
     pub input_schema: serde_json::Value,
 }
 
+/// A resource exposed by an MCP server, such as a file or database record.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResourceDefinition {
+    /// Name of the server that provides the resource.
+    pub server: String,
+    /// URI identifying the resource on its server.
+    pub uri: String,
+    /// Human-readable resource name.
+    pub name: String,
+    /// Optional description of the resource.
+    pub description: Option<String>,
+    /// MIME type of the resource content, if known.
+    pub mime_type: Option<String>,
+}
+
+/// A prompt template exposed by an MCP server.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptDefinition {
+    /// Name of the server that provides the prompt.
+    pub server: String,
+    /// Prompt name, used to invoke it.
+    pub name: String,
+    /// Optional description of the prompt.
+    pub description: Option<String>,
+    /// Arguments the template accepts.
+    pub arguments: Vec<PromptArgument>,
+}
+
+/// An argument accepted by a [`PromptDefinition`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptArgument {
+    /// Argument name.
+    pub name: String,
+    /// Optional description of the argument.
+    pub description: Option<String>,
+    /// Whether the argument must be supplied.
+    pub required: bool,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -65,7 +179,7 @@ mod tests {
         assert_eq!(config.mcp_servers.len(), 1);
 
         let fs_server = config.mcp_servers.get("filesystem").unwrap();
-        assert_eq!(fs_server.command, "npx");
+        assert_eq!(fs_server.command.as_deref(), Some("npx"));
         assert_eq!(fs_server.args.len(), 3);
         assert!(fs_server.env.is_empty());
     }
@@ -95,7 +209,7 @@ mod tests {
 
         let config: McpConfig = serde_json::from_str(json).unwrap();
         let server = config.mcp_servers.get("test-server").unwrap();
-        assert_eq!(server.command, "/usr/bin/test-server");
+        assert_eq!(server.command.as_deref(), Some("/usr/bin/test-server"));
         assert_eq!(server.args, vec!["--port", "3000"]);
         assert_eq!(server.env.len(), 2);
         assert_eq!(server.env.get("API_KEY").unwrap(), "secret123");
@@ -150,4 +264,83 @@ mod tests {
         assert!(config.mcp_servers.contains_key("filesystem"));
         assert!(config.mcp_servers.contains_key("web-search"));
     }
+
+    #[test]
+    fn test_mcp_config_remote_server() {
+        let json = r#"{
+            "mcpServers": {
+                "search": {
+                    "type": "sse",
+                    "url": "https://mcp.example.com/sse",
+                    "headers": {"X-Team": "core"},
+                    "bearer_token": "token123"
+                },
+                "docs": {
+                    "url": "https://docs.example.com/mcp"
+                }
+            }
+        }"#;
+
+        let config: McpConfig = serde_json::from_str(json).unwrap();
+        let search = config.mcp_servers.get("search").unwrap();
+        assert_eq!(search.command, None);
+        assert_eq!(search.url.as_deref(), Some("https://mcp.example.com/sse"));
+        assert_eq!(search.transport, Some(McpTransport::Sse));
+        assert_eq!(search.headers.get("X-Team").unwrap(), "core");
+        assert_eq!(search.bearer_token.as_deref(), Some("token123"));
+
+        let docs = config.mcp_servers.get("docs").unwrap();
+        assert_eq!(docs.transport, None);
+        assert!(docs.headers.is_empty());
+    }
+
+    #[test]
+    fn test_mcp_config_limits() {
+        let json = r#"{
+            "mcpServers": {
+                "slow": {
+                    "command": "slow-server",
+                    "timeout_secs": 30,
+                    "max_output_chars": 0,
+                    "max_restarts": 1
+                },
+                "plain": {
+                    "command": "plain-server"
+                }
+            }
+        }"#;
+
+        let config: McpConfig = serde_json::from_str(json).unwrap();
+        let slow = config.mcp_servers.get("slow").unwrap();
+        assert_eq!(slow.timeout_secs, Some(30));
+        assert_eq!(slow.max_output_chars(), 0);
+        assert_eq!(slow.max_restarts(), 1);
+
+        let plain = config.mcp_servers.get("plain").unwrap();
+        assert_eq!(plain.timeout_secs, None);
+        assert_eq!(plain.max_output_chars(), DEFAULT_MAX_OUTPUT_CHARS);
+        assert_eq!(plain.max_restarts(), DEFAULT_MAX_RESTARTS);
+    }
+
+    #[test]
+    fn test_mcp_config_tool_filters_and_aliases() {
+        let json = r#"{
+            "mcpServers": {
+                "web": {
+                    "url": "https://mcp.example.com/mcp",
+                    "include_tools": ["search", "fetch"],
+                    "exclude_tools": ["fetch"],
+                    "tool_aliases": {"search": "web_search"}
+                }
+            }
+        }"#;
+
+        let config: McpConfig = serde_json::from_str(json).unwrap();
+        let web = config.mcp_servers.get("web").unwrap();
+        assert!(web.allows_tool("search"));
+        assert!(!web.allows_tool("fetch"));
+        assert!(!web.allows_tool("delete"));
+        assert_eq!(web.tool_aliases.get("search").unwrap(), "web_search");
+        assert!(McpServerConfig::default().allows_tool("delete"));
+    }
 }
@@ -0,0 +1,288 @@
+//! Tool registry with collision-free, provider-safe tool names.
+//!
+//! Each server's tools are filtered by its `include_tools` / `exclude_tools`
+//! lists, renamed by its `tool_aliases`, and sanitized to match the tool name
+//! pattern providers accept (`^[a-zA-Z0-9_-]{1,64}$`). Names still exposed by
+//! more than one server are prefixed with the server name as
+//! `server__tool`, so no server silently shadows another.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use super::protocol::{McpServerConfig, ToolDefinition};
+
+/// Longest tool name providers accept.
+const MAX_TOOL_NAME_LEN: usize = 64;
+
+/// Separator between server and tool name in prefixed tool names.
+const PREFIX_SEPARATOR: &str = "__";
+
+/// Where a tool name exposed to the model is routed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct ToolRoute {
+    /// Name of the server that provides the tool.
+    pub(super) server: String,
+    /// Name of the tool on that server.
+    pub(super) tool: String,
+    /// Name of the tool before any `server__` prefix: its alias, if it has
+    /// one, else its sanitized original name.
+    pub(super) name: String,
+}
+
+/// Tool routing shared by all servers, refreshed when a server restarts or
+/// the configuration is reloaded.
+#[derive(Clone, Default)]
+pub(super) struct ToolRegistry {
+    /// Each server's tools, keyed by server name, with their unprefixed names.
+    by_server: BTreeMap<String, Vec<(String, ToolDefinition)>>,
+    /// Exposed tool name -> server and original tool name.
+    routes: HashMap<String, ToolRoute>,
+    /// Definitions of all exposed tools, carrying their exposed names.
+    definitions: Vec<ToolDefinition>,
+}
+
+impl ToolRegistry {
+    /// Register the tools of `server`, replacing those it registered before.
+    ///
+    /// Tools excluded by `config` are skipped; the others are renamed by its
+    /// aliases and sanitized. All exposed names are recomputed, since a new
+    /// tool can collide with one registered earlier.
+    pub(super) fn register(
+        &mut self,
+        server: &str,
+        config: &McpServerConfig,
+        tools: Vec<ToolDefinition>,
+    ) {
+        let tools = tools
+            .into_iter()
+            .filter(|tool| config.allows_tool(&tool.name))
+            .map(|tool| {
+                let name = match config.tool_aliases.get(&tool.name) {
+                    Some(alias) => sanitize_tool_name(alias),
+                    None => sanitize_tool_name(&tool.name),
+                };
+                (name, tool)
+            })
+            .collect();
+        self.by_server.insert(server.to_string(), tools);
+        self.rebuild();
+    }
+
+    /// Remove the tools of `server`, e.g. after it was removed from the
+    /// configuration or failed to restart.
+    pub(super) fn unregister(&mut self, server: &str) {
+        if self.by_server.remove(server).is_some() {
+            self.rebuild();
+        }
+    }
+
+    /// Recompute exposed names, routes and definitions from `by_server`.
+    fn rebuild(&mut self) {
+        let mut owners: HashMap<&str, usize> = HashMap::new();
+        for tools in self.by_server.values() {
+            let names: HashSet<&str> = tools.iter().map(|(name, _)| name.as_str()).collect();
+            for name in names {
+                *owners.entry(name).or_default() += 1;
+            }
+        }
+
+        let mut routes = HashMap::new();
+        let mut definitions = Vec::new();
+        for (server, tools) in &self.by_server {
+            for (name, tool) in tools {
+                let mut exposed = if owners[name.as_str()] > 1 {
+                    tracing::debug!(
+                        server = %server,
+                        tool = %name,
+                        "mcp: tool name collides with another server, prefixing"
+                    );
+                    sanitize_tool_name(&format!("{}{}{}", server, PREFIX_SEPARATOR, name))
+                } else {
+                    name.clone()
+                };
+                // Sanitizing or truncation can still make two names equal.
+                let mut suffix = 2;
+                while routes.contains_key(&exposed) {
+                    exposed = with_suffix(&exposed, suffix);
+                    suffix += 1;
+                }
+
+                routes.insert(
+                    exposed.clone(),
+                    ToolRoute {
+                        server: server.clone(),
+                        tool: tool.name.clone(),
+                        name: name.clone(),
+                    },
+                );
+                definitions.push(ToolDefinition {
+                    name: exposed,
+                    ..tool.clone()
+                });
+            }
+        }
+        self.routes = routes;
+        self.definitions = definitions;
+    }
+
+    /// Where tool `name`, as exposed to the model, is routed.
+    pub(super) fn route(&self, name: &str) -> Option<&ToolRoute> {
+        self.routes.get(name)
+    }
+
+    /// Definitions of all exposed tools.
+    pub(super) fn definitions(&self) -> &[ToolDefinition] {
+        &self.definitions
+    }
+}
+
+/// Make `name` match the tool name pattern providers accept.
+///
+/// Characters other than ASCII letters, digits, `_` and `-` become `_`, the
+/// name is cut to 64 characters, and an empty name becomes `tool`.
+///
+/// # Examples
+///
+/// ```
+/// use synapse_core::mcp::sanitize_tool_name;
+///
+/// assert_eq!(sanitize_tool_name("files.read"), "files_read");
+/// assert_eq!(sanitize_tool_name("get-weather"), "get-weather");
+/// ```
+pub fn sanitize_tool_name(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(MAX_TOOL_NAME_LEN)
+        .collect();
+    if sanitized.is_empty() {
+        "tool".to_string()
+    } else {
+        sanitized
+    }
+}
+
+/// Append `_<suffix>` to `name`, cutting it to stay within the length limit.
+fn with_suffix(name: &str, suffix: usize) -> String {
+    let suffix = format!("_{}", suffix);
+    let keep = MAX_TOOL_NAME_LEN - suffix.len();
+    format!("{}{}", &name[..name.len().min(keep)], suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(name: &str) -> ToolDefinition {
+        ToolDefinition {
+            name: name.to_string(),
+            description: None,
+            input_schema: serde_json::json!({}),
+        }
+    }
+
+    fn exposed_names(registry: &ToolRegistry) -> Vec<&str> {
+        registry
+            .definitions()
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_sanitize_tool_name() {
+        assert_eq!(sanitize_tool_name("read_file"), "read_file");
+        assert_eq!(sanitize_tool_name("files.read/all"), "files_read_all");
+        assert_eq!(sanitize_tool_name("héllo"), "h_llo");
+        assert_eq!(sanitize_tool_name(""), "tool");
+        assert_eq!(
+            sanitize_tool_name(&"x".repeat(100)).len(),
+            MAX_TOOL_NAME_LEN
+        );
+    }
+
+    #[test]
+    fn test_register_replaces_server_tools() {
+        let config = McpServerConfig::default();
+        let mut registry = ToolRegistry::default();
+        registry.register("a", &config, vec![tool("read"), tool("write")]);
+        registry.register("b", &config, vec![tool("search")]);
+
+        registry.register("a", &config, vec![tool("read_v2")]);
+
+        assert_eq!(exposed_names(&registry), vec!["read_v2", "search"]);
+        assert_eq!(registry.route("read_v2").unwrap().server, "a");
+        assert!(registry.route("write").is_none());
+    }
+
+    #[test]
+    fn test_unregister_drops_prefixes() {
+        let config = McpServerConfig::default();
+        let mut registry = ToolRegistry::default();
+        registry.register("a", &config, vec![tool("search")]);
+        registry.register("b", &config, vec![tool("search")]);
+        assert_eq!(exposed_names(&registry), vec!["a__search", "b__search"]);
+
+        registry.unregister("a");
+
+        assert_eq!(exposed_names(&registry), vec!["search"]);
+        assert_eq!(registry.route("search").unwrap().server, "b");
+    }
+
+    #[test]
+    fn test_register_prefixes_colliding_names() {
+        let config = McpServerConfig::default();
+        let mut registry = ToolRegistry::default();
+        registry.register("web", &config, vec![tool("search"), tool("fetch")]);
+        registry.register("docs", &config, vec![tool("search")]);
+
+        assert_eq!(
+            exposed_names(&registry),
+            vec!["docs__search", "web__search", "fetch"]
+        );
+        assert_eq!(
+            registry.route("web__search"),
+            Some(&ToolRoute {
+                server: "web".to_string(),
+                tool: "search".to_string(),
+                name: "search".to_string(),
+            })
+        );
+        assert!(registry.route("search").is_none());
+    }
+
+    #[test]
+    fn test_register_applies_aliases_and_filters() {
+        let web = McpServerConfig {
+            tool_aliases: HashMap::from([("search".to_string(), "web_search".to_string())]),
+            exclude_tools: vec!["fetch".to_string()],
+            ..Default::default()
+        };
+        let docs = McpServerConfig {
+            include_tools: vec!["search".to_string()],
+            ..Default::default()
+        };
+        let mut registry = ToolRegistry::default();
+        registry.register("web", &web, vec![tool("search"), tool("fetch")]);
+        registry.register("docs", &docs, vec![tool("search"), tool("delete")]);
+
+        assert_eq!(exposed_names(&registry), vec!["search", "web_search"]);
+        assert_eq!(registry.route("web_search").unwrap().tool, "search");
+        assert_eq!(registry.route("search").unwrap().server, "docs");
+    }
+
+    #[test]
+    fn test_register_deduplicates_sanitized_names() {
+        let config = McpServerConfig::default();
+        let mut registry = ToolRegistry::default();
+        registry.register("a", &config, vec![tool("read.file"), tool("read/file")]);
+
+        assert_eq!(exposed_names(&registry), vec!["read_file", "read_file_2"]);
+        assert_eq!(registry.route("read_file_2").unwrap().tool, "read/file");
+    }
+}
@@ -0,0 +1,163 @@
+//! MCP resource references and rendering.
+//!
+//! Users attach resources to a message by writing `@server:uri`; the
+//! referenced contents are read from the server and appended to the message
+//! as context for the model.
+
+use rmcp::model::ResourceContents;
+
+/// A `@server:uri` reference to an MCP resource.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceRef {
+    /// Name of the server that provides the resource.
+    pub server: String,
+    /// URI of the resource on that server.
+    pub uri: String,
+}
+
+impl std::fmt::Display for ResourceRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "@{}:{}", self.server, self.uri)
+    }
+}
+
+/// Find `@server:uri` references in `text`.
+///
+/// A reference is a whitespace-delimited word starting with `@` whose server
+/// name and URI are separated by the first `:`. Trailing sentence
+/// punctuation is not part of the URI. Duplicates are returned once, in order
+/// of first appearance.
+///
+/// # Examples
+///
+/// ```
+/// use synapse_core::mcp::find_resource_refs;
+///
+/// let refs = find_resource_refs("Summarize @docs:file:///notes.md, please");
+/// assert_eq!(refs.len(), 1);
+/// assert_eq!(refs[0].server, "docs");
+/// assert_eq!(refs[0].uri, "file:///notes.md");
+/// ```
+pub fn find_resource_refs(text: &str) -> Vec<ResourceRef> {
+    let mut refs: Vec<ResourceRef> = Vec::new();
+    for word in text.split_whitespace() {
+        let Some((server, uri)) = word.strip_prefix('@').and_then(|rest| rest.split_once(':'))
+        else {
+            continue;
+        };
+        let uri = uri.trim_end_matches(['.', ',', ';', '!', '?', ')']);
+        if server.is_empty() || uri.is_empty() {
+            continue;
+        }
+        let resource = ResourceRef {
+            server: server.to_string(),
+            uri: uri.to_string(),
+        };
+        if !refs.contains(&resource) {
+            refs.push(resource);
+        }
+    }
+    refs
+}
+
+/// Render resource contents as text for the model.
+///
+/// Binary contents are replaced by a short placeholder naming their MIME type.
+pub(super) fn contents_text(contents: &[ResourceContents]) -> String {
+    contents
+        .iter()
+        .map(|content| match content {
+            ResourceContents::TextResourceContents { text, .. } => text.clone(),
+            ResourceContents::BlobResourceContents { mime_type, .. } => format!(
+                "[binary content: {}]",
+                mime_type.as_deref().unwrap_or("application/octet-stream")
+            ),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Append attached resource contents to a user message.
+pub(super) fn attach(text: &str, attachments: &[(ResourceRef, String)]) -> String {
+    let mut output = text.to_string();
+    for (resource, content) in attachments {
+        output.push_str(&format!(
+            "\n\n<resource server=\"{}\" uri=\"{}\">\n{}\n</resource>",
+            resource.server,
+            resource.uri,
+            content.trim_end()
+        ));
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_resource_refs_parses_server_and_uri() {
+        let refs = find_resource_refs("Compare @fs:file:///a.txt and @db:table://users.");
+
+        assert_eq!(
+            refs,
+            vec![
+                ResourceRef {
+                    server: "fs".to_string(),
+                    uri: "file:///a.txt".to_string(),
+                },
+                ResourceRef {
+                    server: "db".to_string(),
+                    uri: "table://users".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_resource_refs_ignores_non_references() {
+        assert!(find_resource_refs("mail me at user@example.com").is_empty());
+        assert!(find_resource_refs("ping @alice about it").is_empty());
+        assert!(find_resource_refs("@:file:///a.txt @fs:").is_empty());
+    }
+
+    #[test]
+    fn test_find_resource_refs_deduplicates() {
+        let refs = find_resource_refs("@fs:a @fs:a @fs:b");
+        assert_eq!(refs.len(), 2);
+        assert_eq!(refs[1].to_string(), "@fs:b");
+    }
+
+    #[test]
+    fn test_contents_text_placeholder_for_blobs() {
+        let contents = vec![
+            ResourceContents::text("hello", "mem://a"),
+            ResourceContents::BlobResourceContents {
+                uri: "mem://b".to_string(),
+                mime_type: Some("image/png".to_string()),
+                blob: "AAAA".to_string(),
+                meta: None,
+            },
+        ];
+
+        assert_eq!(
+            contents_text(&contents),
+            "hello\n[binary content: image/png]"
+        );
+    }
+
+    #[test]
+    fn test_attach_appends_resource_blocks() {
+        let resource = ResourceRef {
+            server: "fs".to_string(),
+            uri: "file:///a.txt".to_string(),
+        };
+
+        let text = attach("Explain", &[(resource, "contents\n".to_string())]);
+
+        assert_eq!(
+            text,
+            "Explain\n\n<resource server=\"fs\" uri=\"file:///a.txt\">\ncontents\n</resource>"
+        );
+    }
+}
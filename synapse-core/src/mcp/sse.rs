@@ -0,0 +1,133 @@
+//! Legacy HTTP+SSE transport for MCP clients.
+//!
+//! Implements the transport from MCP protocol revision 2024-11-05, which
+//! `rmcp` no longer ships: the client opens a `GET` event stream, the server
+//! names a message endpoint in an `endpoint` event, and the client `POST`s
+//! JSON-RPC messages there while responses arrive as `message` events.
+
+use std::pin::Pin;
+
+use eventsource_stream::{Event, EventStreamError, Eventsource};
+use futures::{Stream, StreamExt};
+use reqwest::Url;
+use reqwest::header::{ACCEPT, HeaderMap, HeaderValue};
+use rmcp::RoleClient;
+use rmcp::model::{ClientJsonRpcMessage, ServerJsonRpcMessage};
+use rmcp::transport::Transport;
+
+/// Stream of server-sent events from the `GET` connection.
+type EventStream =
+    Pin<Box<dyn Stream<Item = Result<Event, EventStreamError<reqwest::Error>>> + Send>>;
+
+/// Error raised by the legacy SSE transport.
+#[derive(Debug, thiserror::Error)]
+#[error("SSE transport error: {0}")]
+pub(super) struct SseError(String);
+
+impl From<reqwest::Error> for SseError {
+    fn from(e: reqwest::Error) -> Self {
+        Self(e.to_string())
+    }
+}
+
+/// Client side of the legacy HTTP+SSE transport.
+pub(super) struct SseClientTransport {
+    /// HTTP client shared by the event stream and message posts.
+    http: reqwest::Client,
+    /// Endpoint announced by the server for client messages.
+    endpoint: Url,
+    /// Headers sent with every request (auth, custom headers).
+    headers: HeaderMap,
+    /// Events from the server, positioned after the `endpoint` event.
+    events: EventStream,
+}
+
+impl SseClientTransport {
+    /// Open the event stream at `url` and wait for the message endpoint.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SseError`] if the stream cannot be opened, the server
+    /// responds with an error status, or the stream ends before announcing
+    /// an endpoint.
+    pub(super) async fn connect(url: &str, headers: HeaderMap) -> Result<Self, SseError> {
+        let base = Url::parse(url).map_err(|e| SseError(format!("invalid URL '{url}': {e}")))?;
+        let http = reqwest::Client::new();
+        let response = http
+            .get(base.clone())
+            .headers(headers.clone())
+            .header(ACCEPT, HeaderValue::from_static("text/event-stream"))
+            .send()
+            .await?
+            .error_for_status()?;
+        let mut events: EventStream = Box::pin(response.bytes_stream().eventsource());
+
+        let endpoint = loop {
+            match events.next().await {
+                Some(Ok(event)) if event.event == "endpoint" => {
+                    break base.join(event.data.trim()).map_err(|e| {
+                        SseError(format!("invalid endpoint '{}': {e}", event.data))
+                    })?;
+                }
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(SseError(e.to_string())),
+                None => {
+                    return Err(SseError(
+                        "stream closed before the endpoint event".to_string(),
+                    ));
+                }
+            }
+        };
+        tracing::debug!(%endpoint, "mcp: SSE endpoint received");
+
+        Ok(Self {
+            http,
+            endpoint,
+            headers,
+            events,
+        })
+    }
+}
+
+impl Transport<RoleClient> for SseClientTransport {
+    type Error = SseError;
+
+    fn send(
+        &mut self,
+        item: ClientJsonRpcMessage,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send + 'static {
+        let request = self
+            .http
+            .post(self.endpoint.clone())
+            .headers(self.headers.clone())
+            .json(&item);
+        async move {
+            request.send().await?.error_for_status()?;
+            Ok(())
+        }
+    }
+
+    async fn receive(&mut self) -> Option<ServerJsonRpcMessage> {
+        while let Some(event) = self.events.next().await {
+            match event {
+                Ok(event) if event.event == "message" || event.event.is_empty() => {
+                    match serde_json::from_str(&event.data) {
+                        Ok(message) => return Some(message),
+                        Err(e) => tracing::warn!("mcp: invalid SSE message: {}", e),
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::warn!("mcp: SSE stream failed: {}", e);
+                    return None;
+                }
+            }
+        }
+        None
+    }
+
+    async fn close(&mut self) -> Result<(), Self::Error> {
+        self.events = Box::pin(futures::stream::empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,270 @@
+//! In-process MCP servers for transport tests.
+//!
+//! Serves `echo` and `sleep` tools, a `mem://greeting` resource and a
+//! `review` prompt over streamable HTTP or the legacy HTTP+SSE transport on
+//! an ephemeral local port, and records the headers of every request it
+//! receives.
+
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::Router;
+use axum::extract::{Request, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::sse::{Event, Sse};
+use axum::routing::{get, post};
+use futures::channel::mpsc;
+use futures::{SinkExt, Stream, StreamExt};
+use rmcp::model::{
+    AnnotateAble, CallToolRequestParams, CallToolResult, ClientJsonRpcMessage, Content,
+    GetPromptRequestParams, GetPromptResult, ListPromptsResult, ListResourcesResult,
+    ListToolsResult, PaginatedRequestParams, Prompt, PromptArgument, PromptMessage,
+    PromptMessageRole, RawResource, ReadResourceRequestParams, ReadResourceResult,
+    ResourceContents, ServerCapabilities, ServerInfo, Tool,
+};
+use rmcp::service::RequestContext;
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp::transport::streamable_http_server::{StreamableHttpServerConfig, StreamableHttpService};
+use rmcp::{ErrorData, RoleServer, ServerHandler, ServiceExt};
+use tokio::net::TcpListener;
+
+/// URI of the single resource served by [`EchoServer`].
+const GREETING_URI: &str = "mem://greeting";
+
+/// MCP server exposing an `echo` tool that returns its `text` argument, a
+/// `sleep` tool that returns it after `ms` milliseconds, a greeting resource
+/// and a `review` prompt.
+#[derive(Clone)]
+struct EchoServer;
+
+impl ServerHandler for EchoServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_resources()
+                .enable_prompts()
+                .build(),
+            ..Default::default()
+        }
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, ErrorData> {
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": { "text": { "type": "string" } }
+        });
+        let serde_json::Value::Object(schema) = schema else {
+            unreachable!("schema is an object");
+        };
+        let schema = Arc::new(schema);
+        Ok(ListToolsResult::with_all_items(vec![
+            Tool::new("echo", "Echo the given text", Arc::clone(&schema)),
+            Tool::new(
+                "sleep",
+                "Echo the given text after `ms` milliseconds",
+                schema,
+            ),
+        ]))
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let text = request
+            .arguments
+            .as_ref()
+            .and_then(|args| args.get("text"))
+            .and_then(|text| text.as_str())
+            .unwrap_or_default()
+            .to_string();
+        if request.name == "sleep" {
+            let ms = request
+                .arguments
+                .as_ref()
+                .and_then(|args| args.get("ms"))
+                .and_then(|ms| ms.as_u64())
+                .unwrap_or_default();
+            tokio::time::sleep(Duration::from_millis(ms)).await;
+        }
+        Ok(CallToolResult::success(vec![Content::text(text)]))
+    }
+
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, ErrorData> {
+        Ok(ListResourcesResult::with_all_items(vec![
+            RawResource::new(GREETING_URI, "greeting").no_annotation(),
+        ]))
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, ErrorData> {
+        if request.uri != GREETING_URI {
+            return Err(ErrorData::resource_not_found("no such resource", None));
+        }
+        Ok(ReadResourceResult {
+            contents: vec![ResourceContents::text("Hello from MCP", GREETING_URI)],
+        })
+    }
+
+    async fn list_prompts(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListPromptsResult, ErrorData> {
+        let language = PromptArgument {
+            name: "language".to_string(),
+            title: None,
+            description: Some("Language of the code".to_string()),
+            required: Some(true),
+        };
+        Ok(ListPromptsResult::with_all_items(vec![Prompt::new(
+            "review",
+            Some("Review a piece of code"),
+            Some(vec![language]),
+        )]))
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<GetPromptResult, ErrorData> {
+        let language = request
+            .arguments
+            .as_ref()
+            .and_then(|args| args.get("language"))
+            .and_then(|language| language.as_str())
+            .unwrap_or_default();
+        Ok(GetPromptResult {
+            description: None,
+            messages: vec![
+                PromptMessage::new_text(
+                    PromptMessageRole::User,
+                    format!("Review this {} code.", language),
+                ),
+                PromptMessage::new_text(PromptMessageRole::Assistant, "Paste the code."),
+            ],
+        })
+    }
+}
+
+/// A local MCP server listening on an ephemeral port.
+pub(crate) struct TestServer {
+    url: String,
+    headers: Arc<Mutex<Vec<HeaderMap>>>,
+}
+
+impl TestServer {
+    /// Start a server speaking the streamable HTTP transport at `/mcp`.
+    pub(crate) async fn streamable_http() -> Self {
+        let service = StreamableHttpService::new(
+            || Ok(EchoServer),
+            Arc::new(LocalSessionManager::default()),
+            StreamableHttpServerConfig::default(),
+        );
+        Self::start(Router::new().nest_service("/mcp", service), "/mcp").await
+    }
+
+    /// Start a server speaking the legacy HTTP+SSE transport at `/sse`.
+    pub(super) async fn sse() -> Self {
+        let sessions: SseSessions = Arc::new(Mutex::new(None));
+        let router = Router::new()
+            .route("/sse", get(sse_stream))
+            .route("/message", post(sse_message))
+            .with_state(sessions);
+        Self::start(router, "/sse").await
+    }
+
+    /// Server URL to put in the client config.
+    pub(crate) fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Whether any request carried `name` with exactly `value`.
+    pub(super) fn received_header(&self, name: &str, value: &str) -> bool {
+        self.headers
+            .lock()
+            .expect("lock")
+            .iter()
+            .any(|headers| headers.get(name).is_some_and(|v| v == value))
+    }
+
+    async fn start(router: Router, path: &str) -> Self {
+        let headers = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&headers);
+        let router = router.layer(middleware::from_fn(move |request: Request, next: Next| {
+            recorded
+                .lock()
+                .expect("lock")
+                .push(request.headers().clone());
+            next.run(request)
+        }));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let url = format!(
+            "http://{}{}",
+            listener.local_addr().expect("local addr"),
+            path
+        );
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, router).await;
+        });
+
+        Self { url, headers }
+    }
+}
+
+/// Sender feeding POSTed messages into the connected SSE session.
+type SseSessions = Arc<Mutex<Option<mpsc::Sender<ClientJsonRpcMessage>>>>;
+
+/// Open an SSE session: announce the message endpoint, then relay server messages.
+async fn sse_stream(
+    State(sessions): State<SseSessions>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let (client_tx, client_rx) = mpsc::channel(16);
+    let (server_tx, server_rx) = mpsc::channel(16);
+    *sessions.lock().expect("lock") = Some(client_tx);
+
+    tokio::spawn(async move {
+        if let Ok(service) = EchoServer.serve((server_tx, client_rx)).await {
+            let _ = service.waiting().await;
+        }
+    });
+
+    let endpoint =
+        futures::stream::once(async { Ok(Event::default().event("endpoint").data("/message")) });
+    let messages = server_rx.map(|message| {
+        let data = serde_json::to_string(&message).expect("serialize");
+        Ok(Event::default().event("message").data(data))
+    });
+    Sse::new(endpoint.chain(messages))
+}
+
+/// Accept a client message for the current SSE session.
+async fn sse_message(
+    State(sessions): State<SseSessions>,
+    axum::Json(message): axum::Json<ClientJsonRpcMessage>,
+) -> StatusCode {
+    let Some(mut sender) = sessions.lock().expect("lock").clone() else {
+        return StatusCode::NOT_FOUND;
+    };
+    match sender.send(message).await {
+        Ok(()) => StatusCode::ACCEPTED,
+        Err(_) => StatusCode::GONE,
+    }
+}
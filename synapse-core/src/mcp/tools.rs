@@ -1,91 +1,417 @@
 //! MCP client and tool registry.
 //!
 //! Provides [`McpClient`] which manages connections to MCP servers,
-//! tool discovery, and tool execution.
+//! tool discovery, and tool execution. Requests are bounded by each server's
+//! `timeout_secs`, tool results are capped at `max_output_chars`, and servers
+//! whose connection is lost are restarted up to `max_restarts` times. The
+//! server set can be changed at runtime with [`McpClient::reload`].
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Display};
 use std::process::Stdio;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
+use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderName, HeaderValue};
 use rmcp::ServiceExt;
-use rmcp::model::CallToolRequestParams;
-use rmcp::transport::TokioChildProcess;
+use rmcp::model::{
+    CallToolRequestParams, CallToolResult, GetPromptRequestParams, RawContent,
+    ReadResourceRequestParams, ResourceContents,
+};
+use rmcp::transport::streamable_http_client::StreamableHttpClientTransportConfig;
+use rmcp::transport::{StreamableHttpClientTransport, TokioChildProcess};
 use tokio::io::AsyncReadExt;
+use tokio::sync::{RwLock, RwLockReadGuard};
 
 use super::McpError;
-use super::protocol::{McpConfig, ToolDefinition};
+use super::prompts::prompt_messages;
+use super::protocol::{
+    McpConfig, McpServerConfig, McpTransport, PromptArgument, PromptDefinition, ResourceDefinition,
+    ToolDefinition,
+};
+use super::registry::ToolRegistry;
+use super::resources::{ResourceRef, attach, contents_text, find_resource_refs};
+use super::sse::SseClientTransport;
+use crate::message::{Message, ToolContent, ToolOutput};
+
+/// Handle to a connected MCP server session.
+type ClientService = rmcp::service::RunningService<rmcp::RoleClient, ()>;
 
 /// A connected MCP server client.
 struct RunningClient {
-    /// The rmcp client handle.
-    client: rmcp::service::RunningService<rmcp::RoleClient, ()>,
+    /// The rmcp client handle, replaced when the server is restarted.
+    client: RwLock<ClientService>,
+    /// Configuration the server was started with, used to restart it.
+    config: McpServerConfig,
+    /// Restarts left before a lost server is given up.
+    restarts_left: AtomicU32,
+    /// Resources listed when the server was started.
+    resources: Vec<ResourceDefinition>,
+    /// Prompt templates listed when the server was started.
+    prompts: Vec<PromptDefinition>,
+}
+
+impl RunningClient {
+    fn new(client: ClientService, config: McpServerConfig) -> Self {
+        Self {
+            client: RwLock::new(client),
+            restarts_left: AtomicU32::new(config.max_restarts()),
+            config,
+            resources: Vec::new(),
+            prompts: Vec::new(),
+        }
+    }
+}
+
+/// What a call to [`McpClient::reload`] changed, by server name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct McpReload {
+    /// Servers started because they are new in the configuration.
+    pub added: Vec<String>,
+    /// Servers disconnected because they are gone from the configuration.
+    pub removed: Vec<String>,
+    /// Servers restarted because their configuration changed or their
+    /// connection was lost.
+    pub restarted: Vec<String>,
+    /// Servers that failed to start, with the error.
+    pub failed: Vec<(String, String)>,
+}
+
+impl Display for McpReload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        for (label, names) in [
+            ("added", &self.added),
+            ("removed", &self.removed),
+            ("restarted", &self.restarted),
+        ] {
+            if !names.is_empty() {
+                parts.push(format!("{}: {}", label, names.join(", ")));
+            }
+        }
+        if !self.failed.is_empty() {
+            let failed: Vec<String> = self
+                .failed
+                .iter()
+                .map(|(name, error)| format!("{} ({})", name, error))
+                .collect();
+            parts.push(format!("failed: {}", failed.join(", ")));
+        }
+        if parts.is_empty() {
+            write!(f, "no changes")
+        } else {
+            write!(f, "{}", parts.join("; "))
+        }
+    }
 }
 
 /// Manages connections to MCP servers and provides tool execution.
 ///
-/// Handles tool discovery, registration, and routing tool calls
-/// to the appropriate MCP server.
+/// Handles tool, resource and prompt discovery, registration, and routing
+/// calls to the appropriate MCP server.
 pub struct McpClient {
-    /// Connected server clients, keyed by server name.
-    servers: HashMap<String, RunningClient>,
-    /// Unified tool registry: tool name -> server name.
-    tool_registry: HashMap<String, String>,
-    /// All discovered tool definitions.
-    tool_definitions: Vec<ToolDefinition>,
+    /// Connected server clients, keyed by server name. Requests hold their
+    /// server's `Arc`, so a server removed by a reload stays up until the
+    /// requests already sent to it finish.
+    servers: Mutex<BTreeMap<String, Arc<RunningClient>>>,
+    /// Unified tool registry and definitions.
+    tools: Mutex<ToolRegistry>,
+    /// Held while a reload runs, so reloads apply one at a time.
+    reloading: tokio::sync::Mutex<()>,
 }
 
 impl McpClient {
     /// Create a new MCP client from configuration.
     ///
-    /// Spawns child processes for each configured server, connects via stdio,
-    /// and discovers available tools. Servers that fail to start are logged
-    /// as warnings but do not prevent initialization.
+    /// Spawns child processes for stdio servers, connects to remote servers
+    /// over HTTP, and discovers available tools, resources and prompts.
+    /// Servers that fail to start are logged as warnings but do not prevent
+    /// initialization.
     pub async fn new(config: &McpConfig) -> Result<Self, McpError> {
-        let mut servers = HashMap::new();
-        let mut tool_registry = HashMap::new();
-        let mut tool_definitions = Vec::new();
-
-        for (name, server_config) in &config.mcp_servers {
-            match Self::connect_server(name, server_config).await {
-                Ok((client, tools)) => {
-                    // Register all discovered tools
-                    let tool_count = tools.len();
-                    for tool in &tools {
-                        tool_registry.insert(tool.name.clone(), name.clone());
+        let client = Self::empty();
+        client.reload(config).await;
+        Ok(client)
+    }
+
+    /// Create an MCP client with no servers.
+    ///
+    /// Servers can be added later with [`reload`](McpClient::reload).
+    pub fn empty() -> Self {
+        Self {
+            servers: Mutex::new(BTreeMap::new()),
+            tools: Mutex::new(ToolRegistry::default()),
+            reloading: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// Apply `config` to the running servers without restarting the process.
+    ///
+    /// Servers no longer configured are disconnected, new servers are
+    /// started, and servers whose configuration changed or whose connection
+    /// was lost are restarted; the others keep running untouched. New
+    /// connections are made first and the server set and tool registry are
+    /// then swapped together, so a concurrent request sees either the old or
+    /// the new tools, never a mix. Servers that fail to start are reported in
+    /// [`McpReload::failed`] and left out.
+    pub async fn reload(&self, config: &McpConfig) -> McpReload {
+        let _reloading = self.reloading.lock().await;
+        let current = self.servers.lock().expect("lock").clone();
+        let mut report = McpReload::default();
+        let mut servers = BTreeMap::new();
+        // Tool registrations to apply at the swap: `None` unregisters.
+        let mut changes = Vec::new();
+
+        for name in current.keys() {
+            if !config.mcp_servers.contains_key(name) {
+                tracing::info!(server = %name, "mcp: server removed");
+                report.removed.push(name.clone());
+                changes.push((name.clone(), None));
+            }
+        }
+
+        let configured: BTreeMap<_, _> = config.mcp_servers.iter().collect();
+        for (name, server_config) in configured {
+            let existing = current.get(name);
+            if let Some(server) = existing
+                && server.config == *server_config
+                && !is_lost(&*server.client.read().await)
+            {
+                servers.insert(name.clone(), Arc::clone(server));
+                continue;
+            }
+            match Self::start(name, server_config).await {
+                Ok((server, tools)) => {
+                    tracing::info!(server = %name, tool_count = tools.len(), "mcp: server connected");
+                    if existing.is_some() {
+                        report.restarted.push(name.clone());
+                    } else {
+                        report.added.push(name.clone());
                     }
-                    tool_definitions.extend(tools);
-                    servers.insert(name.clone(), RunningClient { client });
-                    tracing::info!(server = %name, tool_count, "mcp: server connected");
+                    servers.insert(name.clone(), Arc::new(server));
+                    changes.push((name.clone(), Some((server_config, tools))));
                 }
                 Err(e) => {
-                    tracing::warn!(
-                        server = %name,
-                        command = %server_config.command,
-                        "MCP server failed to start: {}", e
-                    );
+                    tracing::warn!(server = %name, "MCP server failed to start: {}", e);
+                    report.failed.push((name.clone(), e.to_string()));
+                    changes.push((name.clone(), None));
                 }
             }
         }
 
-        Ok(Self {
-            servers,
-            tool_registry,
-            tool_definitions,
-        })
+        let mut registry = self.tools.lock().expect("lock");
+        for (name, change) in changes {
+            match change {
+                Some((server_config, tools)) => registry.register(&name, server_config, tools),
+                None => registry.unregister(&name),
+            }
+        }
+        *self.servers.lock().expect("lock") = servers;
+        report
+    }
+
+    /// Start a server and discover its tools, resources and prompts.
+    async fn start(
+        name: &str,
+        config: &McpServerConfig,
+    ) -> Result<(RunningClient, Vec<ToolDefinition>), McpError> {
+        let (client, tools) = Self::connect_server(name, config).await?;
+        let resources = Self::discover_resources(name, &client).await;
+        let prompts = Self::discover_prompts(name, &client).await;
+        Ok((
+            RunningClient {
+                resources,
+                prompts,
+                ..RunningClient::new(client, config.clone())
+            },
+            tools,
+        ))
+    }
+
+    /// The running server named `name`, if connected.
+    fn server(&self, name: &str) -> Option<Arc<RunningClient>> {
+        self.servers.lock().expect("lock").get(name).cloned()
+    }
+
+    /// Get the connection to `server`, restarting the server first if its
+    /// connection was lost.
+    ///
+    /// A lost server is restarted at most `max_restarts` times; after that
+    /// the dead connection is returned and requests to it fail.
+    async fn connection<'a>(
+        &self,
+        name: &str,
+        server: &'a RunningClient,
+    ) -> RwLockReadGuard<'a, ClientService> {
+        let client = server.client.read().await;
+        if !is_lost(&client) {
+            return client;
+        }
+        drop(client);
+        self.restart(name, server).await;
+        server.client.read().await
+    }
+
+    /// Restart a server whose connection was lost and refresh its tools.
+    async fn restart(&self, name: &str, server: &RunningClient) {
+        let mut client = server.client.write().await;
+        // A concurrent request may have restarted it already.
+        if !is_lost(&client) {
+            return;
+        }
+        let left = server.restarts_left.load(Ordering::Relaxed);
+        if left == 0 {
+            tracing::debug!(server = %name, "mcp: server lost and out of restarts");
+            return;
+        }
+        server.restarts_left.store(left - 1, Ordering::Relaxed);
+
+        tracing::warn!(server = %name, "mcp: connection lost, restarting server");
+        match Self::connect_server(name, &server.config).await {
+            Ok((restarted, tools)) => {
+                let tool_count = tools.len();
+                *client = restarted;
+                let mut registry = self.tools.lock().expect("lock");
+                // A reload may have replaced or removed the server meanwhile.
+                let current = self
+                    .server(name)
+                    .is_some_and(|current| std::ptr::eq(current.as_ref(), server));
+                if current {
+                    registry.register(name, &server.config, tools);
+                }
+                tracing::info!(server = %name, tool_count, "mcp: server restarted");
+            }
+            Err(e) => {
+                tracing::warn!(server = %name, "mcp: failed to restart server: {}", e);
+            }
+        }
+    }
+
+    /// List the resources of a server that advertises the resources capability.
+    ///
+    /// Listing failures are logged and yield no resources.
+    async fn discover_resources(name: &str, client: &ClientService) -> Vec<ResourceDefinition> {
+        let supported = client
+            .peer_info()
+            .is_some_and(|info| info.capabilities.resources.is_some());
+        if !supported {
+            return Vec::new();
+        }
+        match client.list_all_resources().await {
+            Ok(resources) => resources
+                .into_iter()
+                .map(|resource| ResourceDefinition {
+                    server: name.to_string(),
+                    uri: resource.raw.uri,
+                    name: resource.raw.name,
+                    description: resource.raw.description,
+                    mime_type: resource.raw.mime_type,
+                })
+                .collect(),
+            Err(e) => {
+                tracing::warn!(server = %name, "mcp: failed to list resources: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// List the prompts of a server that advertises the prompts capability.
+    ///
+    /// Listing failures are logged and yield no prompts.
+    async fn discover_prompts(name: &str, client: &ClientService) -> Vec<PromptDefinition> {
+        let supported = client
+            .peer_info()
+            .is_some_and(|info| info.capabilities.prompts.is_some());
+        if !supported {
+            return Vec::new();
+        }
+        match client.list_all_prompts().await {
+            Ok(prompts) => prompts
+                .into_iter()
+                .map(|prompt| PromptDefinition {
+                    server: name.to_string(),
+                    name: prompt.name,
+                    description: prompt.description,
+                    arguments: prompt
+                        .arguments
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|arg| PromptArgument {
+                            name: arg.name,
+                            description: arg.description,
+                            required: arg.required.unwrap_or(false),
+                        })
+                        .collect(),
+                })
+                .collect(),
+            Err(e) => {
+                tracing::warn!(server = %name, "mcp: failed to list prompts: {}", e);
+                Vec::new()
+            }
+        }
     }
 
     /// Connect to a single MCP server and discover its tools.
     async fn connect_server(
         name: &str,
-        config: &super::protocol::McpServerConfig,
-    ) -> Result<
-        (
-            rmcp::service::RunningService<rmcp::RoleClient, ()>,
-            Vec<ToolDefinition>,
-        ),
-        McpError,
-    > {
+        config: &McpServerConfig,
+    ) -> Result<(ClientService, Vec<ToolDefinition>), McpError> {
+        let client = match (&config.command, &config.url) {
+            (Some(command), None) => Self::connect_stdio(name, command, config).await?,
+            (None, Some(url)) => Self::connect_remote(name, url, config).await?,
+            (Some(_), Some(_)) => {
+                return Err(McpError::ConfigError(format!(
+                    "server '{}' sets both 'command' and 'url'",
+                    name
+                )));
+            }
+            (None, None) => {
+                return Err(McpError::ConfigError(format!(
+                    "server '{}' needs either 'command' or 'url'",
+                    name
+                )));
+            }
+        };
+
+        let tools_result =
+            client
+                .list_tools(None)
+                .await
+                .map_err(|e| McpError::ConnectionError {
+                    server: name.to_string(),
+                    message: format!("failed to list tools: {}", e),
+                })?;
+
+        // Convert to our ToolDefinition format
+        let tools: Vec<ToolDefinition> = tools_result
+            .tools
+            .into_iter()
+            .map(|t| ToolDefinition {
+                name: t.name.to_string(),
+                description: t.description.map(|d| d.to_string()),
+                input_schema: serde_json::to_value(&t.input_schema)
+                    .unwrap_or(serde_json::json!({})),
+            })
+            .collect();
+
+        Ok((client, tools))
+    }
+
+    /// Spawn a stdio server process and perform the MCP handshake.
+    async fn connect_stdio(
+        name: &str,
+        command: &str,
+        config: &McpServerConfig,
+    ) -> Result<ClientService, McpError> {
+        if config.transport.is_some_and(|t| t != McpTransport::Stdio) {
+            return Err(McpError::ConfigError(format!(
+                "server '{}' uses 'command' but is not a stdio server",
+                name
+            )));
+        }
+
         // Build the child process command
-        let mut cmd = tokio::process::Command::new(&config.command);
+        let mut cmd = tokio::process::Command::new(command);
         cmd.args(&config.args);
         for (key, value) in &config.env {
             cmd.env(key, value);
@@ -97,7 +423,7 @@ impl McpClient {
             .spawn()
             .map_err(|e| McpError::ConnectionError {
                 server: name.to_string(),
-                message: format!("failed to spawn '{}': {}", config.command, e),
+                message: format!("failed to spawn '{}': {}", command, e),
             })?;
 
         // Drain stderr in background so it doesn't block the child process.
@@ -110,41 +436,13 @@ impl McpClient {
             buf
         });
 
-        // Handshake + tool discovery — capture result without early return
-        // so we can read stderr on failure.
-        let result: Result<_, McpError> = async {
-            let client =
-                ().serve(transport)
-                    .await
-                    .map_err(|e| McpError::ConnectionError {
-                        server: name.to_string(),
-                        message: format!("failed to connect: {}", e),
-                    })?;
-
-            let tools_result =
-                client
-                    .list_tools(None)
-                    .await
-                    .map_err(|e| McpError::ConnectionError {
-                        server: name.to_string(),
-                        message: format!("failed to list tools: {}", e),
-                    })?;
-
-            // Convert to our ToolDefinition format
-            let tools: Vec<ToolDefinition> = tools_result
-                .tools
-                .into_iter()
-                .map(|t| ToolDefinition {
-                    name: t.name.to_string(),
-                    description: t.description.map(|d| d.to_string()),
-                    input_schema: serde_json::to_value(&t.input_schema)
-                        .unwrap_or(serde_json::json!({})),
-                })
-                .collect();
-
-            Ok((client, tools))
-        }
-        .await;
+        let result =
+            ().serve(transport)
+                .await
+                .map_err(|e| McpError::ConnectionError {
+                    server: name.to_string(),
+                    message: format!("failed to connect: {}", e),
+                });
 
         // On failure, log server stderr for diagnostics.
         if result.is_err()
@@ -157,51 +455,122 @@ impl McpClient {
         result
     }
 
-    /// Create an MCP client with no servers (for testing).
-    #[cfg(test)]
-    pub fn empty() -> Self {
-        Self {
-            servers: HashMap::new(),
-            tool_registry: HashMap::new(),
-            tool_definitions: Vec::new(),
+    /// Connect to a remote server over streamable HTTP or legacy SSE.
+    ///
+    /// Without an explicit `type`, streamable HTTP is tried first and legacy
+    /// SSE is used as a fallback, following the MCP backwards-compatibility
+    /// guidance for clients.
+    async fn connect_remote(
+        name: &str,
+        url: &str,
+        config: &McpServerConfig,
+    ) -> Result<ClientService, McpError> {
+        let connection_error = |message: String| McpError::ConnectionError {
+            server: name.to_string(),
+            message,
+        };
+        let headers = remote_headers(config)
+            .map_err(|e| McpError::ConfigError(format!("server '{}': {}", name, e)))?;
+
+        if config.transport != Some(McpTransport::Sse) {
+            let mut http_config = StreamableHttpClientTransportConfig::with_uri(url)
+                .custom_headers(
+                    headers
+                        .iter()
+                        .map(|(name, value)| (name.clone(), value.clone()))
+                        .collect(),
+                );
+            if let Some(ref token) = config.bearer_token {
+                http_config = http_config.auth_header(token.clone());
+            }
+            let transport = StreamableHttpClientTransport::from_config(http_config);
+            match ().serve(transport).await {
+                Ok(client) => {
+                    tracing::debug!(server = %name, "mcp: connected over streamable HTTP");
+                    return Ok(client);
+                }
+                Err(e) if config.transport == Some(McpTransport::Http) => {
+                    return Err(connection_error(format!("failed to connect: {}", e)));
+                }
+                Err(e) => {
+                    tracing::debug!(
+                        server = %name,
+                        "mcp: streamable HTTP failed, trying legacy SSE: {}", e
+                    );
+                }
+            }
         }
+
+        let mut headers = headers;
+        if let Some(ref token) = config.bearer_token {
+            let value = HeaderValue::from_str(&format!("Bearer {}", token)).map_err(|e| {
+                McpError::ConfigError(format!("server '{}': invalid bearer token: {}", name, e))
+            })?;
+            headers.insert(AUTHORIZATION, value);
+        }
+        let transport = SseClientTransport::connect(url, headers)
+            .await
+            .map_err(|e| connection_error(format!("failed to connect: {}", e)))?;
+        let client = ()
+            .serve(transport)
+            .await
+            .map_err(|e| connection_error(format!("failed to connect: {}", e)))?;
+        tracing::debug!(server = %name, "mcp: connected over legacy SSE");
+        Ok(client)
     }
 
     /// Create an MCP client with pre-registered tool definitions (for testing).
     #[cfg(test)]
     pub fn with_test_tools(tools: Vec<ToolDefinition>) -> Self {
-        let mut tool_registry = HashMap::new();
-        for tool in &tools {
-            tool_registry.insert(tool.name.clone(), "test-server".to_string());
-        }
-        Self {
-            servers: HashMap::new(),
-            tool_registry,
-            tool_definitions: tools,
+        Self::with_test_servers(vec![("test-server", tools)])
+    }
+
+    /// Create an MCP client with tool definitions pre-registered for several
+    /// servers (for testing).
+    #[cfg(test)]
+    pub fn with_test_servers(servers: Vec<(&str, Vec<ToolDefinition>)>) -> Self {
+        let client = Self::empty();
+        {
+            let mut registry = client.tools.lock().expect("lock");
+            for (server, tools) in servers {
+                registry.register(server, &McpServerConfig::default(), tools);
+            }
         }
+        client
     }
 
     /// Execute a tool call on the appropriate MCP server.
     ///
-    /// Routes the call to the server that registered the tool.
+    /// `name` is the tool name exposed to the model, which may carry a
+    /// `server__` prefix or an alias; the call is routed to the server that
+    /// registered the tool under its original name. A tool that
+    /// runs but reports failure returns `Ok` with
+    /// [`ToolOutput::is_error`] set. Output beyond the server's
+    /// `max_output_chars` is cut off and the cut is marked in the output.
     ///
     /// # Errors
     ///
-    /// Returns [`McpError::ToolError`] if the tool is not found or execution fails.
+    /// Returns [`McpError::ToolError`] if the tool is not found, execution
+    /// fails, or the server does not answer within its `timeout_secs`.
     pub async fn call_tool(
         &self,
         name: &str,
         input: serde_json::Value,
-    ) -> Result<serde_json::Value, McpError> {
+    ) -> Result<ToolOutput, McpError> {
         tracing::debug!(tool = %name, "mcp: calling tool");
-        let server_name = self
-            .tool_registry
-            .get(name)
+        let route = self
+            .tools
+            .lock()
+            .expect("lock")
+            .route(name)
+            .cloned()
             .ok_or_else(|| McpError::ToolError(format!("unknown tool: {}", name)))?;
+        let server_name = route.server;
 
-        let server = self.servers.get(server_name).ok_or_else(|| {
+        let server = self.server(&server_name).ok_or_else(|| {
             McpError::ToolError(format!("server '{}' not connected", server_name))
         })?;
+        let client = self.connection(&server_name, &server).await;
 
         let arguments = if let serde_json::Value::Object(map) = input {
             Some(map)
@@ -209,49 +578,423 @@ impl McpClient {
             None
         };
 
-        let result = server
-            .client
-            .call_tool(CallToolRequestParams {
-                name: std::borrow::Cow::Owned(name.to_string()),
-                arguments,
-                meta: None,
-                task: None,
-            })
+        let request = client.call_tool(CallToolRequestParams {
+            name: std::borrow::Cow::Owned(route.tool),
+            arguments,
+            meta: None,
+            task: None,
+        });
+        let result = timed(&server_name, &server.config, request)
             .await
             .map_err(|e| McpError::ToolError(format!("tool call failed: {}", e)))?;
 
-        // Extract text content from the tool result
-        let content: Vec<String> = result
-            .content
+        Ok(cap_output(
+            tool_output(result),
+            server.config.max_output_chars(),
+        ))
+    }
+
+    /// Read a resource from a connected server and render it as text.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`McpError::ResourceError`] if the server is not connected or
+    /// the read fails.
+    pub async fn read_resource(&self, server: &str, uri: &str) -> Result<String, McpError> {
+        tracing::debug!(server = %server, uri = %uri, "mcp: reading resource");
+        let running = self
+            .server(server)
+            .ok_or_else(|| McpError::ResourceError(format!("unknown server: {}", server)))?;
+        let client = self.connection(server, &running).await;
+
+        let request = client.read_resource(ReadResourceRequestParams {
+            meta: None,
+            uri: uri.to_string(),
+        });
+        let result = timed(server, &running.config, request)
+            .await
+            .map_err(|e| McpError::ResourceError(format!("failed to read {}: {}", uri, e)))?;
+
+        Ok(contents_text(&result.contents))
+    }
+
+    /// Append the resources referenced as `@server:uri` in `text`.
+    ///
+    /// Only references naming a connected server are read, so words like
+    /// `@someone:` pass through untouched. Returns the expanded text and the
+    /// references that were attached.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`McpError::ResourceError`] if a referenced resource cannot be read.
+    pub async fn attach_resources(
+        &self,
+        text: &str,
+    ) -> Result<(String, Vec<ResourceRef>), McpError> {
+        let mut attachments = Vec::new();
+        for resource in find_resource_refs(text) {
+            if self.server(&resource.server).is_none() {
+                continue;
+            }
+            let content = self.read_resource(&resource.server, &resource.uri).await?;
+            attachments.push((resource, content));
+        }
+        let expanded = attach(text, &attachments);
+        Ok((
+            expanded,
+            attachments
+                .into_iter()
+                .map(|(resource, _)| resource)
+                .collect(),
+        ))
+    }
+
+    /// Render a prompt template into conversation messages.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`McpError::PromptError`] if the prompt is unknown, a required
+    /// argument is missing, or the server fails to render it.
+    pub async fn get_prompt(
+        &self,
+        name: &str,
+        arguments: HashMap<String, String>,
+    ) -> Result<Vec<Message>, McpError> {
+        tracing::debug!(prompt = %name, "mcp: rendering prompt");
+        let prompt = self
+            .prompts()
+            .into_iter()
+            .find(|p| p.name == name)
+            .ok_or_else(|| McpError::PromptError(format!("unknown prompt: {}", name)))?;
+
+        let missing: Vec<&str> = prompt
+            .arguments
             .iter()
-            .filter_map(|c| c.raw.as_text().map(|t| t.text.clone()))
+            .filter(|arg| arg.required && !arguments.contains_key(&arg.name))
+            .map(|arg| arg.name.as_str())
             .collect();
+        if !missing.is_empty() {
+            return Err(McpError::PromptError(format!(
+                "prompt '{}' requires: {}",
+                name,
+                missing.join(", ")
+            )));
+        }
 
-        Ok(serde_json::Value::String(content.join("\n")))
+        let server = self.server(&prompt.server).ok_or_else(|| {
+            McpError::PromptError(format!("server '{}' not connected", prompt.server))
+        })?;
+        let client = self.connection(&prompt.server, &server).await;
+
+        let arguments = (!arguments.is_empty()).then(|| {
+            arguments
+                .into_iter()
+                .map(|(key, value)| (key, serde_json::Value::String(value)))
+                .collect()
+        });
+        let request = client.get_prompt(GetPromptRequestParams {
+            meta: None,
+            name: name.to_string(),
+            arguments,
+        });
+        let result = timed(&prompt.server, &server.config, request)
+            .await
+            .map_err(|e| McpError::PromptError(format!("failed to get prompt: {}", e)))?;
+
+        Ok(prompt_messages(result.messages))
     }
 
-    /// Get all discovered tool definitions.
-    pub fn tool_definitions(&self) -> &[ToolDefinition] {
-        &self.tool_definitions
+    /// Get all discovered tool definitions, under the names exposed to the model.
+    ///
+    /// Returns a snapshot: the definitions of a server are refreshed when it
+    /// is restarted.
+    pub fn tool_definitions(&self) -> Vec<ToolDefinition> {
+        self.tools.lock().expect("lock").definitions().to_vec()
     }
 
     /// Check if any tools are available.
     pub fn has_tools(&self) -> bool {
-        !self.tool_definitions.is_empty()
+        !self.tools.lock().expect("lock").definitions().is_empty()
+    }
+
+    /// Name of the server that provides tool `name`, if the tool is known.
+    pub fn tool_server(&self, name: &str) -> Option<String> {
+        self.tools
+            .lock()
+            .expect("lock")
+            .route(name)
+            .map(|route| route.server.clone())
+    }
+
+    /// Other names of tool `name`: its name before any `server__` prefix and
+    /// its original name on the server, without duplicates.
+    pub fn tool_aliases(&self, name: &str) -> Vec<String> {
+        let tools = self.tools.lock().expect("lock");
+        let Some(route) = tools.route(name) else {
+            return Vec::new();
+        };
+        let mut names = Vec::new();
+        for other in [&route.name, &route.tool] {
+            if other != name && !names.contains(other) {
+                names.push(other.clone());
+            }
+        }
+        names
+    }
+
+    /// Names of the connected servers, sorted.
+    pub fn server_names(&self) -> Vec<String> {
+        self.servers.lock().expect("lock").keys().cloned().collect()
+    }
+
+    /// Get all discovered resources, ordered by server name.
+    pub fn resources(&self) -> Vec<ResourceDefinition> {
+        self.servers
+            .lock()
+            .expect("lock")
+            .values()
+            .flat_map(|server| server.resources.iter().cloned())
+            .collect()
+    }
+
+    /// Check if any resources are available.
+    pub fn has_resources(&self) -> bool {
+        self.servers
+            .lock()
+            .expect("lock")
+            .values()
+            .any(|server| !server.resources.is_empty())
+    }
+
+    /// Get all discovered prompt templates, ordered by server name.
+    pub fn prompts(&self) -> Vec<PromptDefinition> {
+        self.servers
+            .lock()
+            .expect("lock")
+            .values()
+            .flat_map(|server| server.prompts.iter().cloned())
+            .collect()
+    }
+
+    /// Check if any prompts are available.
+    pub fn has_prompts(&self) -> bool {
+        self.servers
+            .lock()
+            .expect("lock")
+            .values()
+            .any(|server| !server.prompts.is_empty())
     }
 
     /// Gracefully shut down all MCP server connections.
+    ///
+    /// Connections still used by a running request close when it finishes.
     pub async fn shutdown(self) {
-        for (_name, server) in self.servers {
-            let _ = server.client.cancel().await;
+        let servers = self.servers.into_inner().expect("lock");
+        for server in servers.into_values().filter_map(Arc::into_inner) {
+            let _ = server.client.into_inner().cancel().await;
+        }
+    }
+}
+
+/// Whether the connection behind `client` has closed, e.g. because the
+/// server process exited.
+fn is_lost(client: &ClientService) -> bool {
+    client.is_closed() || client.is_transport_closed()
+}
+
+/// Await `request`, giving up after the server's `timeout_secs` if set.
+async fn timed<T, E: Display>(
+    name: &str,
+    config: &McpServerConfig,
+    request: impl Future<Output = Result<T, E>>,
+) -> Result<T, String> {
+    let result = match config.timeout_secs {
+        Some(secs) if secs > 0 => tokio::time::timeout(Duration::from_secs(secs), request)
+            .await
+            .map_err(|_| format!("server '{}' did not answer within {}s", name, secs))?,
+        _ => request.await,
+    };
+    result.map_err(|e| e.to_string())
+}
+
+/// Cut `output` down to `max_chars` characters and mark the cut.
+///
+/// Text, base64 image and audio data, and the JSON of a structured-only
+/// result all count against the cap. Text past the cap is cut, media that no
+/// longer fits is replaced by a placeholder, and a structured-only result is
+/// turned into its (cut) JSON text. Structured content is dropped from
+/// truncated outputs, since it usually repeats the text. A `max_chars` of `0`
+/// disables the cap.
+fn cap_output(mut output: ToolOutput, max_chars: usize) -> ToolOutput {
+    if max_chars == 0 {
+        return output;
+    }
+    // Structured content only reaches the model when there are no blocks.
+    let structured_text = output
+        .structured
+        .as_ref()
+        .filter(|_| output.content.is_empty())
+        .map(|v| v.to_string());
+    let total: usize = output.content.iter().map(content_len).sum::<usize>()
+        + structured_text.as_deref().map_or(0, |t| t.chars().count());
+    if total <= max_chars {
+        return output;
+    }
+
+    if let Some(text) = structured_text {
+        output.content.push(ToolContent::Text { text });
+    }
+    let mut remaining = max_chars;
+    for content in &mut output.content {
+        let len = content_len(content);
+        if len <= remaining {
+            remaining -= len;
+            continue;
+        }
+        match content {
+            ToolContent::Text { text }
+            | ToolContent::Resource {
+                text: Some(text), ..
+            } => {
+                let cut = text
+                    .char_indices()
+                    .nth(remaining)
+                    .map_or(text.len(), |(i, _)| i);
+                text.truncate(cut);
+                remaining = 0;
+            }
+            ToolContent::Image { mime_type, .. } => {
+                *content = ToolContent::Text {
+                    text: format!("[image omitted: {}]", mime_type),
+                };
+            }
+            ToolContent::Audio { mime_type, .. } => {
+                *content = ToolContent::Text {
+                    text: format!("[audio omitted: {}]", mime_type),
+                };
+            }
+            _ => {}
         }
     }
+    output.content.push(ToolContent::Text {
+        text: format!(
+            "[output truncated: {} of {} characters shown]",
+            max_chars, total
+        ),
+    });
+    output.structured = None;
+    output
+}
+
+/// Characters of `content` that count against the output cap.
+///
+/// Images and audio count their base64 data; links and binary resources
+/// count nothing.
+fn content_len(content: &ToolContent) -> usize {
+    match content {
+        ToolContent::Text { text }
+        | ToolContent::Resource {
+            text: Some(text), ..
+        } => text.chars().count(),
+        ToolContent::Image { data, .. } | ToolContent::Audio { data, .. } => data.len(),
+        _ => 0,
+    }
+}
+
+/// Build the custom headers configured for a remote server.
+fn remote_headers(config: &McpServerConfig) -> Result<HeaderMap, String> {
+    let mut headers = HeaderMap::new();
+    for (name, value) in &config.headers {
+        let name = HeaderName::try_from(name.as_str())
+            .map_err(|e| format!("invalid header name '{}': {}", name, e))?;
+        let value = HeaderValue::from_str(value)
+            .map_err(|e| format!("invalid value for header '{}': {}", name, e))?;
+        headers.insert(name, value);
+    }
+    Ok(headers)
+}
+
+/// Convert an MCP tool result into a [`ToolOutput`], keeping every content kind.
+fn tool_output(result: CallToolResult) -> ToolOutput {
+    let content = result
+        .content
+        .into_iter()
+        .map(|content| match content.raw {
+            RawContent::Text(text) => ToolContent::Text { text: text.text },
+            RawContent::Image(image) => ToolContent::Image {
+                data: image.data,
+                mime_type: image.mime_type,
+            },
+            RawContent::Audio(audio) => ToolContent::Audio {
+                data: audio.data,
+                mime_type: audio.mime_type,
+            },
+            RawContent::Resource(embedded) => match embedded.resource {
+                ResourceContents::TextResourceContents {
+                    uri,
+                    mime_type,
+                    text,
+                    ..
+                } => ToolContent::Resource {
+                    uri,
+                    mime_type,
+                    text: Some(text),
+                },
+                ResourceContents::BlobResourceContents { uri, mime_type, .. } => {
+                    ToolContent::Resource {
+                        uri,
+                        mime_type,
+                        text: None,
+                    }
+                }
+            },
+            RawContent::ResourceLink(link) => ToolContent::ResourceLink {
+                uri: link.uri,
+                name: link.name,
+            },
+        })
+        .collect();
+
+    ToolOutput {
+        content,
+        structured: result.structured_content,
+        is_error: result.is_error.unwrap_or(false),
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::super::test_server::TestServer;
     use super::*;
 
+    fn remote_config(url: &str, transport: Option<McpTransport>) -> McpConfig {
+        let server = McpServerConfig {
+            url: Some(url.to_string()),
+            transport,
+            headers: HashMap::from([("X-Api-Key".to_string(), "key-123".to_string())]),
+            bearer_token: Some("secret".to_string()),
+            ..Default::default()
+        };
+        McpConfig {
+            mcp_servers: HashMap::from([("remote".to_string(), server)]),
+        }
+    }
+
+    async fn assert_echo_roundtrip(config: &McpConfig, server: &TestServer) {
+        let client = McpClient::new(config).await.unwrap();
+        assert_eq!(client.tool_definitions().len(), 2);
+        assert!(client.tool_definitions().iter().any(|t| t.name == "echo"));
+
+        let result = client
+            .call_tool("echo", serde_json::json!({"text": "hello"}))
+            .await
+            .unwrap();
+        assert_eq!(result, ToolOutput::text("hello"));
+        assert!(server.received_header("authorization", "Bearer secret"));
+        assert!(server.received_header("x-api-key", "key-123"));
+        client.shutdown().await;
+    }
+
     #[test]
     fn test_mcp_client_no_servers() {
         let client = McpClient::empty();
@@ -289,5 +1032,461 @@ mod tests {
         assert!(client.has_tools());
         assert_eq!(client.tool_definitions().len(), 1);
         assert_eq!(client.tool_definitions()[0].name, "test_tool");
+        assert_eq!(
+            client.tool_server("test_tool").as_deref(),
+            Some("test-server")
+        );
+        assert_eq!(client.tool_server("other_tool"), None);
+    }
+
+    #[test]
+    fn test_tool_output_keeps_all_content_kinds() {
+        use rmcp::model::{AnnotateAble, Content, RawResource};
+
+        let mut result = CallToolResult::error(vec![
+            Content::text("rendered"),
+            Content::image("iVBORw0KGgo=", "image/png"),
+            Content::resource(ResourceContents::text("notes", "mem://notes")),
+            RawContent::ResourceLink(RawResource::new("file:///out.csv", "out")).no_annotation(),
+        ]);
+        result.structured_content = Some(serde_json::json!({"rows": 3}));
+
+        let output = tool_output(result);
+
+        assert!(output.is_error);
+        assert_eq!(output.structured, Some(serde_json::json!({"rows": 3})));
+        assert_eq!(
+            output.content,
+            vec![
+                ToolContent::Text {
+                    text: "rendered".to_string(),
+                },
+                ToolContent::Image {
+                    data: "iVBORw0KGgo=".to_string(),
+                    mime_type: "image/png".to_string(),
+                },
+                ToolContent::Resource {
+                    uri: "mem://notes".to_string(),
+                    mime_type: Some("text".to_string()),
+                    text: Some("notes".to_string()),
+                },
+                ToolContent::ResourceLink {
+                    uri: "file:///out.csv".to_string(),
+                    name: "out".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_connect_streamable_http() {
+        let server = TestServer::streamable_http().await;
+        let config = remote_config(server.url(), Some(McpTransport::Http));
+        assert_echo_roundtrip(&config, &server).await;
+    }
+
+    #[tokio::test]
+    async fn test_discover_resources_and_prompts() {
+        let server = TestServer::streamable_http().await;
+        let client = McpClient::new(&remote_config(server.url(), None))
+            .await
+            .unwrap();
+
+        assert_eq!(client.resources().len(), 1);
+        assert_eq!(client.resources()[0].uri, "mem://greeting");
+        assert_eq!(client.resources()[0].server, "remote");
+        assert_eq!(client.prompts().len(), 1);
+        assert_eq!(client.prompts()[0].name, "review");
+        assert!(client.prompts()[0].arguments[0].required);
+        client.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_attach_resources_reads_known_servers() {
+        let server = TestServer::streamable_http().await;
+        let client = McpClient::new(&remote_config(server.url(), None))
+            .await
+            .unwrap();
+
+        let (text, attached) = client
+            .attach_resources("Translate @remote:mem://greeting for @bob:later")
+            .await
+            .unwrap();
+
+        assert_eq!(attached.len(), 1);
+        assert!(text.starts_with("Translate @remote:mem://greeting for @bob:later"));
+        assert!(text.contains("<resource server=\"remote\" uri=\"mem://greeting\">"));
+        assert!(text.contains("Hello from MCP"));
+
+        let missing = client.attach_resources("@remote:mem://missing").await;
+        assert!(matches!(missing, Err(McpError::ResourceError(_))));
+        client.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_prompt_renders_messages() {
+        let server = TestServer::streamable_http().await;
+        let client = McpClient::new(&remote_config(server.url(), None))
+            .await
+            .unwrap();
+
+        let arguments = HashMap::from([("language".to_string(), "Rust".to_string())]);
+        let messages = client.get_prompt("review", arguments).await.unwrap();
+
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].role, crate::message::Role::User);
+        assert_eq!(messages[0].content, "Review this Rust code.");
+        assert_eq!(messages[1].role, crate::message::Role::Assistant);
+        client.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_prompt_missing_required_argument() {
+        let server = TestServer::streamable_http().await;
+        let client = McpClient::new(&remote_config(server.url(), None))
+            .await
+            .unwrap();
+
+        let result = client.get_prompt("review", HashMap::new()).await;
+        match result {
+            Err(McpError::PromptError(msg)) => assert!(msg.contains("language")),
+            other => panic!("Expected PromptError, got: {:?}", other),
+        }
+        let unknown = client.get_prompt("nope", HashMap::new()).await;
+        assert!(matches!(unknown, Err(McpError::PromptError(_))));
+        client.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_connect_sse() {
+        let server = TestServer::sse().await;
+        let config = remote_config(server.url(), Some(McpTransport::Sse));
+        assert_echo_roundtrip(&config, &server).await;
+    }
+
+    #[tokio::test]
+    async fn test_connect_remote_detects_streamable_http() {
+        let server = TestServer::streamable_http().await;
+        let config = remote_config(server.url(), None);
+        assert_echo_roundtrip(&config, &server).await;
+    }
+
+    #[tokio::test]
+    async fn test_connect_remote_falls_back_to_sse() {
+        let server = TestServer::sse().await;
+        let config = remote_config(server.url(), None);
+        assert_echo_roundtrip(&config, &server).await;
+    }
+
+    #[tokio::test]
+    async fn test_connect_http_does_not_fall_back() {
+        let server = TestServer::sse().await;
+        let config = remote_config(server.url(), Some(McpTransport::Http));
+        let result = McpClient::connect_server("remote", &config.mcp_servers["remote"]).await;
+        assert!(matches!(result, Err(McpError::ConnectionError { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_connect_server_rejects_command_and_url() {
+        let config = McpServerConfig {
+            command: Some("npx".to_string()),
+            url: Some("http://localhost:1/mcp".to_string()),
+            ..Default::default()
+        };
+        let result = McpClient::connect_server("both", &config).await;
+        assert!(matches!(result, Err(McpError::ConfigError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_connect_server_requires_command_or_url() {
+        let result = McpClient::connect_server("empty", &McpServerConfig::default()).await;
+        assert!(matches!(result, Err(McpError::ConfigError(_))));
+    }
+
+    /// Connect to `server` with `configure` applied to its config.
+    async fn remote_client(server: &TestServer, configure: fn(&mut McpServerConfig)) -> McpClient {
+        let mut config = remote_config(server.url(), None);
+        configure(config.mcp_servers.get_mut("remote").unwrap());
+        McpClient::new(&config).await.unwrap()
+    }
+
+    /// Close the connection to the remote server as if it had crashed.
+    async fn lose_connection(client: &McpClient) {
+        let server = client.server("remote").unwrap();
+        server.client.read().await.cancellation_token().cancel();
+        assert!(is_lost(&*server.client.read().await));
+    }
+
+    #[test]
+    fn test_cap_output_truncates_text_and_marks_cut() {
+        let output = ToolOutput {
+            content: vec![
+                ToolContent::Text {
+                    text: "héllo".to_string(),
+                },
+                ToolContent::Image {
+                    data: "AAAA".to_string(),
+                    mime_type: "image/png".to_string(),
+                },
+                ToolContent::Text {
+                    text: "world".to_string(),
+                },
+            ],
+            structured: Some(serde_json::json!({"greeting": "héllo world"})),
+            is_error: false,
+        };
+
+        let capped = cap_output(output, 7);
+
+        assert_eq!(capped.content[0].to_text(), "héllo");
+        assert_eq!(capped.content[1].to_text(), "[image omitted: image/png]");
+        assert_eq!(capped.content[2].to_text(), "wo");
+        assert_eq!(
+            capped.content[3].to_text(),
+            "[output truncated: 7 of 14 characters shown]"
+        );
+        assert_eq!(capped.structured, None);
+    }
+
+    #[test]
+    fn test_cap_output_omits_oversized_image() {
+        let output = ToolOutput {
+            content: vec![
+                ToolContent::Text {
+                    text: "screenshot".to_string(),
+                },
+                ToolContent::Image {
+                    data: "A".repeat(1000),
+                    mime_type: "image/png".to_string(),
+                },
+            ],
+            ..ToolOutput::default()
+        };
+
+        let capped = cap_output(output, 100);
+
+        assert_eq!(
+            capped.content,
+            vec![
+                ToolContent::Text {
+                    text: "screenshot".to_string(),
+                },
+                ToolContent::Text {
+                    text: "[image omitted: image/png]".to_string(),
+                },
+                ToolContent::Text {
+                    text: "[output truncated: 100 of 1010 characters shown]".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cap_output_truncates_structured_only_result() {
+        let output = ToolOutput {
+            structured: Some(serde_json::json!({"rows": "x".repeat(1000)})),
+            ..ToolOutput::default()
+        };
+
+        let capped = cap_output(output, 10);
+
+        assert_eq!(capped.structured, None);
+        assert_eq!(capped.content[0].to_text(), r#"{"rows":"x"#);
+        assert_eq!(
+            capped.content[1].to_text(),
+            "[output truncated: 10 of 1011 characters shown]"
+        );
+    }
+
+    #[test]
+    fn test_cap_output_keeps_small_and_uncapped_outputs() {
+        let output = ToolOutput::text("hello");
+        assert_eq!(cap_output(output.clone(), 5), output);
+        assert_eq!(cap_output(output.clone(), 0), output);
+    }
+
+    #[tokio::test]
+    async fn test_call_tool_times_out() {
+        let server = TestServer::streamable_http().await;
+        let client = remote_client(&server, |config| config.timeout_secs = Some(1)).await;
+
+        let result = client
+            .call_tool("sleep", serde_json::json!({"ms": 5_000, "text": "late"}))
+            .await;
+
+        match result {
+            Err(McpError::ToolError(message)) => {
+                assert!(message.contains("did not answer within 1s"), "{}", message);
+            }
+            other => panic!("expected timeout, got {:?}", other),
+        }
+        client.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_call_tool_caps_output() {
+        let server = TestServer::streamable_http().await;
+        let client = remote_client(&server, |config| config.max_output_chars = Some(5)).await;
+
+        let output = client
+            .call_tool("echo", serde_json::json!({"text": "hello world"}))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            output.content_text(),
+            "hello\n[output truncated: 5 of 11 characters shown]"
+        );
+        client.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_call_tool_restarts_lost_server() {
+        let server = TestServer::streamable_http().await;
+        let client = remote_client(&server, |_| {}).await;
+
+        lose_connection(&client).await;
+        let output = client
+            .call_tool("echo", serde_json::json!({"text": "back"}))
+            .await
+            .unwrap();
+
+        assert_eq!(output, ToolOutput::text("back"));
+        assert_eq!(
+            client
+                .server("remote")
+                .unwrap()
+                .restarts_left
+                .load(Ordering::Relaxed),
+            2
+        );
+        assert!(client.tool_definitions().iter().any(|t| t.name == "echo"));
+        client.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_call_tool_lost_server_without_restarts() {
+        let server = TestServer::streamable_http().await;
+        let client = remote_client(&server, |config| config.max_restarts = Some(0)).await;
+
+        lose_connection(&client).await;
+        let result = client
+            .call_tool("echo", serde_json::json!({"text": "gone"}))
+            .await;
+
+        assert!(matches!(result, Err(McpError::ToolError(_))));
+        client.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_reload_adds_restarts_and_removes_servers() {
+        let first = TestServer::streamable_http().await;
+        let second = TestServer::streamable_http().await;
+        let mut config = remote_config(first.url(), None);
+        let client = McpClient::new(&config).await.unwrap();
+        let original = client.server("remote").unwrap();
+
+        assert_eq!(client.reload(&config).await, McpReload::default());
+        assert!(Arc::ptr_eq(&original, &client.server("remote").unwrap()));
+
+        let mut other = config.mcp_servers["remote"].clone();
+        other.url = Some(second.url().to_string());
+        config.mcp_servers.insert("other".to_string(), other);
+        config.mcp_servers.get_mut("remote").unwrap().timeout_secs = Some(5);
+        let report = client.reload(&config).await;
+
+        assert_eq!(report.added, vec!["other"]);
+        assert_eq!(report.restarted, vec!["remote"]);
+        assert_eq!(client.server_names(), vec!["other", "remote"]);
+        assert!(!Arc::ptr_eq(&original, &client.server("remote").unwrap()));
+        assert_eq!(client.tool_server("other__echo").as_deref(), Some("other"));
+
+        config.mcp_servers.remove("remote");
+        let report = client.reload(&config).await;
+
+        assert_eq!(report.removed, vec!["remote"]);
+        assert_eq!(report.to_string(), "removed: remote");
+        assert_eq!(client.server_names(), vec!["other"]);
+        // The collision is gone, so the remaining server's tools lose their prefix.
+        assert_eq!(client.tool_server("echo").as_deref(), Some("other"));
+        let output = client
+            .call_tool("echo", serde_json::json!({"text": "still here"}))
+            .await
+            .unwrap();
+        assert_eq!(output, ToolOutput::text("still here"));
+        client.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_reload_restarts_lost_and_reports_failed_servers() {
+        let server = TestServer::streamable_http().await;
+        let mut config = remote_config(server.url(), None);
+        let client = McpClient::new(&config).await.unwrap();
+
+        lose_connection(&client).await;
+        let report = client.reload(&config).await;
+        assert_eq!(report.restarted, vec!["remote"]);
+
+        config.mcp_servers.get_mut("remote").unwrap().url =
+            Some("http://127.0.0.1:1/mcp".to_string());
+        let report = client.reload(&config).await;
+
+        assert_eq!(report.failed.len(), 1);
+        assert!(report.to_string().starts_with("failed: remote ("));
+        assert!(client.server_names().is_empty());
+        assert!(client.tool_definitions().is_empty());
+        client.shutdown().await;
+    }
+
+    #[test]
+    fn test_mcp_reload_display() {
+        assert_eq!(McpReload::default().to_string(), "no changes");
+        let report = McpReload {
+            added: vec!["a".to_string(), "b".to_string()],
+            restarted: vec!["c".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(report.to_string(), "added: a, b; restarted: c");
+    }
+
+    #[tokio::test]
+    async fn test_call_tool_routes_prefixed_and_aliased_names() {
+        let first = TestServer::streamable_http().await;
+        let second = TestServer::streamable_http().await;
+        let mut config = remote_config(first.url(), None);
+        let mut other = config.mcp_servers["remote"].clone();
+        other.url = Some(second.url().to_string());
+        other.tool_aliases = HashMap::from([("sleep".to_string(), "nap".to_string())]);
+        config.mcp_servers.insert("other".to_string(), other);
+        let client = McpClient::new(&config).await.unwrap();
+
+        let mut names: Vec<_> = client
+            .tool_definitions()
+            .into_iter()
+            .map(|t| t.name)
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["nap", "other__echo", "remote__echo", "sleep"]);
+        assert_eq!(client.tool_server("nap").as_deref(), Some("other"));
+        assert_eq!(client.tool_aliases("nap"), vec!["sleep"]);
+        assert_eq!(client.tool_aliases("other__echo"), vec!["echo"]);
+
+        let output = client
+            .call_tool("other__echo", serde_json::json!({"text": "routed"}))
+            .await
+            .unwrap();
+        assert_eq!(output, ToolOutput::text("routed"));
+        let output = client
+            .call_tool("nap", serde_json::json!({"text": "aliased"}))
+            .await
+            .unwrap();
+        assert_eq!(output, ToolOutput::text("aliased"));
+        assert!(
+            client
+                .call_tool("echo", serde_json::json!({}))
+                .await
+                .is_err()
+        );
+        client.shutdown().await;
     }
 }
@@ -1,7 +1,8 @@
 //! Message types for LLM conversations.
 //!
-//! Provides the [`Role`] enum, [`Message`] struct, and [`ToolCallData`]
-//! that represent conversation messages across all LLM providers.
+//! Provides the [`Role`] enum, [`Message`] struct, [`Attachment`],
+//! [`ToolCallData`], [`ToolOutput`], and [`TokenUsage`] that represent
+//! conversation messages across all LLM providers.
 
 use serde::{Deserialize, Serialize};
 
@@ -59,6 +60,275 @@ pub struct ToolCallData {
     pub input: serde_json::Value,
 }
 
+/// A single block of tool output.
+///
+/// Mirrors the MCP content kinds. Binary data (images, audio) is base64-encoded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ToolContent {
+    /// Plain text.
+    Text {
+        /// The text.
+        text: String,
+    },
+    /// An image.
+    Image {
+        /// Base64-encoded image data.
+        data: String,
+        /// MIME type, e.g. `image/png`.
+        mime_type: String,
+    },
+    /// An audio clip.
+    Audio {
+        /// Base64-encoded audio data.
+        data: String,
+        /// MIME type, e.g. `audio/wav`.
+        mime_type: String,
+    },
+    /// A resource embedded in the result.
+    Resource {
+        /// URI of the resource.
+        uri: String,
+        /// MIME type of the resource, if known.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        mime_type: Option<String>,
+        /// Text contents; `None` for binary resources.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        text: Option<String>,
+    },
+    /// A link to a resource the client can read separately.
+    ResourceLink {
+        /// URI of the resource.
+        uri: String,
+        /// Name of the resource.
+        name: String,
+    },
+}
+
+impl ToolContent {
+    /// Render the block as text for providers that only accept text results.
+    ///
+    /// Binary data is replaced by a short placeholder naming its MIME type.
+    pub fn to_text(&self) -> String {
+        match self {
+            ToolContent::Text { text } => text.clone(),
+            ToolContent::Image { mime_type, .. } => format!("[image: {}]", mime_type),
+            ToolContent::Audio { mime_type, .. } => format!("[audio: {}]", mime_type),
+            ToolContent::Resource {
+                text: Some(text), ..
+            } => text.clone(),
+            ToolContent::Resource { uri, mime_type, .. } => format!(
+                "[resource: {} ({})]",
+                uri,
+                mime_type.as_deref().unwrap_or("binary")
+            ),
+            ToolContent::ResourceLink { uri, name } => {
+                format!("[resource link: {} ({})]", name, uri)
+            }
+        }
+    }
+}
+
+/// The result of a tool call.
+///
+/// Carries every content block the tool returned, its optional structured
+/// (JSON) result, and whether the tool reported an error.
+///
+/// # Examples
+///
+/// ```
+/// use synapse_core::message::ToolOutput;
+///
+/// let output = ToolOutput::error("file not found");
+/// assert!(output.is_error);
+/// assert_eq!(output.render(), "Error: file not found");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ToolOutput {
+    /// Content blocks in the order the tool returned them.
+    #[serde(default)]
+    pub content: Vec<ToolContent>,
+    /// Structured result, when the tool declares an output schema.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structured: Option<serde_json::Value>,
+    /// Whether the tool reported a failure.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_error: bool,
+}
+
+impl ToolOutput {
+    /// Create a successful output with a single text block.
+    pub fn text(text: impl Into<String>) -> Self {
+        Self {
+            content: vec![ToolContent::Text { text: text.into() }],
+            ..Self::default()
+        }
+    }
+
+    /// Create a failed output with a single text block describing the error.
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            is_error: true,
+            ..Self::text(message)
+        }
+    }
+
+    /// Whether any block carries binary data that text rendering would drop.
+    pub fn has_media(&self) -> bool {
+        self.content
+            .iter()
+            .any(|c| matches!(c, ToolContent::Image { .. } | ToolContent::Audio { .. }))
+    }
+
+    /// Render the content blocks as text, one block per line.
+    ///
+    /// Falls back to the structured result as JSON when there are no blocks.
+    pub fn content_text(&self) -> String {
+        if self.content.is_empty()
+            && let Some(ref structured) = self.structured
+        {
+            return structured.to_string();
+        }
+        self.content
+            .iter()
+            .map(ToolContent::to_text)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Render the output as tool message text, prefixed with `Error: ` on failure.
+    pub fn render(&self) -> String {
+        if self.is_error {
+            format!("Error: {}", self.content_text())
+        } else {
+            self.content_text()
+        }
+    }
+}
+
+/// Where the data of an [`Attachment`] comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MediaSource {
+    /// The raw file contents, sent inline.
+    Bytes(Vec<u8>),
+    /// A URL the provider fetches itself.
+    Url(String),
+}
+
+/// A non-text part of a message: an image or a document.
+///
+/// Providers send attachments before the message text. Providers or models
+/// that cannot take a kind of attachment get a text placeholder instead (see
+/// [`describe`](Attachment::describe)).
+///
+/// # Examples
+///
+/// ```
+/// use synapse_core::message::{Attachment, Message, Role};
+///
+/// let msg = Message::new(Role::User, "What is in this picture?")
+///     .with_attachment(Attachment::image("image/png", vec![0x89, 0x50, 0x4e, 0x47]));
+/// assert_eq!(msg.attachments[0].describe(), "[image: image/png]");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Attachment {
+    /// An image, e.g. a screenshot or photo.
+    Image {
+        /// MIME type, e.g. `image/png`.
+        media_type: String,
+        /// Image data or URL.
+        source: MediaSource,
+    },
+    /// A document, e.g. a PDF.
+    Document {
+        /// MIME type, e.g. `application/pdf`.
+        media_type: String,
+        /// File name shown to the model, if known.
+        name: Option<String>,
+        /// Document data or URL.
+        source: MediaSource,
+    },
+}
+
+impl Attachment {
+    /// Create an image attachment from raw image data.
+    pub fn image(media_type: impl Into<String>, data: Vec<u8>) -> Self {
+        Attachment::Image {
+            media_type: media_type.into(),
+            source: MediaSource::Bytes(data),
+        }
+    }
+
+    /// Create a document attachment from raw file data.
+    pub fn document(media_type: impl Into<String>, name: Option<String>, data: Vec<u8>) -> Self {
+        Attachment::Document {
+            media_type: media_type.into(),
+            name,
+            source: MediaSource::Bytes(data),
+        }
+    }
+
+    /// MIME type of the attachment.
+    pub fn media_type(&self) -> &str {
+        match self {
+            Attachment::Image { media_type, .. } | Attachment::Document { media_type, .. } => {
+                media_type
+            }
+        }
+    }
+
+    /// Where the attachment data comes from.
+    pub fn source(&self) -> &MediaSource {
+        match self {
+            Attachment::Image { source, .. } | Attachment::Document { source, .. } => source,
+        }
+    }
+
+    /// Describe the attachment as text, for providers that cannot take it.
+    pub fn describe(&self) -> String {
+        match self {
+            Attachment::Image { media_type, .. } => format!("[image: {}]", media_type),
+            Attachment::Document {
+                media_type,
+                name: Some(name),
+                ..
+            } => format!("[document: {} ({})]", name, media_type),
+            Attachment::Document { media_type, .. } => format!("[document: {}]", media_type),
+        }
+    }
+}
+
+/// Token counts reported by a provider for a single response.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    /// Prompt tokens consumed.
+    pub input_tokens: u32,
+    /// Completion tokens generated.
+    pub output_tokens: u32,
+}
+
+impl TokenUsage {
+    /// Create a usage record from input and output token counts.
+    pub fn new(input_tokens: u32, output_tokens: u32) -> Self {
+        Self {
+            input_tokens,
+            output_tokens,
+        }
+    }
+
+    /// Total tokens (input + output).
+    pub fn total(&self) -> u64 {
+        u64::from(self.input_tokens) + u64::from(self.output_tokens)
+    }
+}
+
+impl std::ops::AddAssign for TokenUsage {
+    fn add_assign(&mut self, other: Self) {
+        self.input_tokens = self.input_tokens.saturating_add(other.input_tokens);
+        self.output_tokens = self.output_tokens.saturating_add(other.output_tokens);
+    }
+}
+
 /// A single message in a conversation.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Message {
@@ -70,6 +340,14 @@ pub struct Message {
     pub tool_calls: Option<Vec<ToolCallData>>,
     /// Tool call ID this message responds to (present when role == Tool).
     pub tool_call_id: Option<String>,
+    /// Full tool result (present when role == Tool and the result came from a tool).
+    ///
+    /// `content` holds its text rendering for providers without rich tool results.
+    pub tool_output: Option<ToolOutput>,
+    /// Token usage reported for this response (assistant messages only).
+    pub usage: Option<TokenUsage>,
+    /// Images and documents sent with the text (user messages only).
+    pub attachments: Vec<Attachment>,
 }
 
 impl Message {
@@ -97,9 +375,18 @@ impl Message {
             content: content.into(),
             tool_calls: None,
             tool_call_id: None,
+            tool_output: None,
+            usage: None,
+            attachments: Vec::new(),
         }
     }
 
+    /// Add an image or document to the message.
+    pub fn with_attachment(mut self, attachment: Attachment) -> Self {
+        self.attachments.push(attachment);
+        self
+    }
+
     /// Create a tool result message.
     ///
     /// Creates a message with `Role::Tool` that carries the result of a tool
@@ -126,6 +413,31 @@ impl Message {
             content: content.into(),
             tool_calls: None,
             tool_call_id: Some(tool_call_id.into()),
+            tool_output: None,
+            usage: None,
+            attachments: Vec::new(),
+        }
+    }
+
+    /// Create a tool result message from a full [`ToolOutput`].
+    ///
+    /// The message content is the output's [`render`](ToolOutput::render)ed
+    /// text; the output itself is kept for providers that accept rich results.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use synapse_core::message::{Message, ToolOutput};
+    ///
+    /// let msg = Message::tool_output("call_1", ToolOutput::text("42"));
+    /// assert_eq!(msg.content, "42");
+    /// assert!(msg.tool_output.is_some());
+    /// ```
+    pub fn tool_output(tool_call_id: impl Into<String>, output: ToolOutput) -> Self {
+        Self {
+            content: output.render(),
+            tool_output: Some(output),
+            ..Self::tool_result(tool_call_id, "")
         }
     }
 }
@@ -257,6 +569,18 @@ mod tests {
         assert_eq!(deserialized[1].name, "list_files");
     }
 
+    #[test]
+    fn test_token_usage_add_assign_and_total() {
+        let mut usage = TokenUsage::new(100, 20);
+        usage += TokenUsage::new(50, 5);
+        assert_eq!(usage, TokenUsage::new(150, 25));
+        assert_eq!(usage.total(), 175);
+
+        let mut saturated = TokenUsage::new(u32::MAX, 0);
+        saturated += TokenUsage::new(1, 1);
+        assert_eq!(saturated.input_tokens, u32::MAX);
+    }
+
     #[test]
     fn test_tool_call_data_clone() {
         let tool_call = ToolCallData {
@@ -267,4 +591,95 @@ mod tests {
         let cloned = tool_call.clone();
         assert_eq!(tool_call, cloned);
     }
+
+    #[test]
+    fn test_tool_output_content_text_renders_all_blocks() {
+        let output = ToolOutput {
+            content: vec![
+                ToolContent::Text {
+                    text: "chart ready".to_string(),
+                },
+                ToolContent::Image {
+                    data: "iVBORw0KGgo=".to_string(),
+                    mime_type: "image/png".to_string(),
+                },
+                ToolContent::ResourceLink {
+                    uri: "file:///chart.png".to_string(),
+                    name: "chart".to_string(),
+                },
+            ],
+            ..ToolOutput::default()
+        };
+
+        assert_eq!(
+            output.content_text(),
+            "chart ready\n[image: image/png]\n[resource link: chart (file:///chart.png)]"
+        );
+        assert!(output.has_media());
+    }
+
+    #[test]
+    fn test_tool_output_structured_fallback() {
+        let output = ToolOutput {
+            structured: Some(serde_json::json!({"temperature": 21})),
+            ..ToolOutput::default()
+        };
+
+        assert_eq!(output.content_text(), r#"{"temperature":21}"#);
+        assert!(!output.has_media());
+    }
+
+    #[test]
+    fn test_tool_output_error_render() {
+        let output = ToolOutput::error("boom");
+        assert_eq!(output.render(), "Error: boom");
+        assert_eq!(output.content_text(), "boom");
+    }
+
+    #[test]
+    fn test_tool_output_serde_roundtrip() {
+        let output = ToolOutput {
+            content: vec![ToolContent::Resource {
+                uri: "mem://a".to_string(),
+                mime_type: None,
+                text: Some("hi".to_string()),
+            }],
+            structured: None,
+            is_error: true,
+        };
+
+        let json = serde_json::to_value(&output).unwrap();
+        assert_eq!(json["content"][0]["type"], "resource");
+        assert_eq!(json["is_error"], true);
+        assert!(json.get("structured").is_none());
+
+        let parsed: ToolOutput = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, output);
+    }
+
+    #[test]
+    fn test_attachment_describe() {
+        let image = Attachment::image("image/jpeg", vec![1, 2, 3]);
+        let pdf = Attachment::document("application/pdf", Some("report.pdf".to_string()), vec![]);
+        let linked = Attachment::Document {
+            media_type: "application/pdf".to_string(),
+            name: None,
+            source: MediaSource::Url("https://example.com/a.pdf".to_string()),
+        };
+
+        assert_eq!(image.describe(), "[image: image/jpeg]");
+        assert_eq!(pdf.describe(), "[document: report.pdf (application/pdf)]");
+        assert_eq!(linked.describe(), "[document: application/pdf]");
+        assert_eq!(pdf.media_type(), "application/pdf");
+        assert_eq!(image.source(), &MediaSource::Bytes(vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn test_message_tool_output() {
+        let msg = Message::tool_output("call_1", ToolOutput::error("denied"));
+        assert_eq!(msg.role, Role::Tool);
+        assert_eq!(msg.tool_call_id.as_deref(), Some("call_1"));
+        assert_eq!(msg.content, "Error: denied");
+        assert!(msg.tool_output.unwrap().is_error);
+    }
 }
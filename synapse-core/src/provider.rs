@@ -6,19 +6,30 @@
 mod anthropic;
 mod deepseek;
 mod factory;
+mod generation;
 mod mock;
 mod openai;
 mod openai_compat;
+mod openai_compatible;
+mod retry;
 mod streaming;
+#[cfg(test)]
+mod stub_server;
+mod transcription;
 
 pub use anthropic::AnthropicProvider;
 pub use deepseek::DeepSeekProvider;
-pub use factory::create_provider;
+pub use factory::{create_provider, create_transcriber};
+pub use generation::GenerationOptions;
 pub use mock::MockProvider;
 pub use openai::OpenAiProvider;
-pub use streaming::StreamEvent;
+pub use openai_compatible::OpenAiCompatibleProvider;
+pub use retry::RetryPolicy;
+pub use streaming::{StopReason, StreamEvent};
+pub use transcription::{OpenAiTranscriber, Transcriber};
 
 use std::pin::Pin;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use futures::Stream;
@@ -51,6 +62,37 @@ pub enum ProviderError {
     /// Unknown provider name in configuration.
     #[error("unknown provider: {0}")]
     UnknownProvider(String),
+
+    /// Provider configuration is incomplete or malformed.
+    #[error("invalid provider config: {0}")]
+    InvalidConfig(String),
+
+    /// The provider rejected the request due to rate limiting (HTTP 429).
+    #[error("rate limited: {message}")]
+    RateLimited {
+        /// The error message from the provider.
+        message: String,
+        /// Delay requested by the provider's `Retry-After` header, if any.
+        retry_after: Option<Duration>,
+    },
+
+    /// The provider is overloaded or temporarily unavailable (HTTP 5xx).
+    #[error("provider overloaded: {0}")]
+    Overloaded(String),
+
+    /// The request or response timed out.
+    #[error("request timed out: {0}")]
+    Timeout(String),
+}
+
+impl ProviderError {
+    /// Whether the failure is transient and the request may succeed if retried.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::RateLimited { .. } | Self::Overloaded(_) | Self::Timeout(_)
+        )
+    }
 }
 
 /// Trait for LLM providers.
@@ -89,6 +131,8 @@ pub trait LlmProvider: Send + Sync {
     ///
     /// Returns a stream of [`StreamEvent`] items. The stream ends with
     /// [`StreamEvent::Done`] on success or yields an error on failure.
+    /// Providers that report token counts emit [`StreamEvent::Usage`]
+    /// before `Done`.
     ///
     /// # Arguments
     ///
@@ -110,7 +154,8 @@ pub trait LlmProvider: Send + Sync {
     ///     while let Some(event) = stream.next().await {
     ///         match event {
     ///             Ok(StreamEvent::TextDelta(text)) => print!("{}", text),
-    ///             Ok(StreamEvent::Done) => break,
+    ///             Ok(StreamEvent::Done { .. }) => break,
+    ///             Ok(_) => {}
     ///             Err(e) => eprintln!("Error: {}", e),
     ///         }
     ///     }
@@ -132,4 +177,97 @@ pub trait LlmProvider: Send + Sync {
     ) -> Result<Message, ProviderError> {
         self.complete(messages).await
     }
+
+    /// Stream a response with tool definitions available to the model.
+    ///
+    /// Text arrives as [`StreamEvent::TextDelta`]. Each requested tool call
+    /// is announced with [`StreamEvent::ToolCallStarted`], may stream its
+    /// arguments as [`StreamEvent::ToolCallArgumentsDelta`], and is emitted as
+    /// a single [`StreamEvent::ToolCall`] once fully assembled, before the
+    /// final [`StreamEvent::Done`].
+    ///
+    /// The default implementation delegates to [`stream`](LlmProvider::stream)
+    /// when `tools` is empty, and otherwise calls
+    /// [`complete_with_tools`](LlmProvider::complete_with_tools) and replays
+    /// the result as events. Providers with native streaming tool support
+    /// should override this.
+    fn stream_with_tools(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+    ) -> Pin<Box<dyn Stream<Item = Result<StreamEvent, ProviderError>> + Send + '_>> {
+        if tools.is_empty() {
+            return self.stream(messages);
+        }
+
+        let messages = messages.to_vec();
+        let tools = tools.to_vec();
+
+        Box::pin(async_stream::stream! {
+            match self.complete_with_tools(&messages, &tools).await {
+                Ok(msg) => {
+                    if !msg.content.is_empty() {
+                        yield Ok(StreamEvent::TextDelta(msg.content));
+                    }
+                    let tool_calls = msg.tool_calls.unwrap_or_default();
+                    let stop_reason = if tool_calls.is_empty() {
+                        StopReason::EndTurn
+                    } else {
+                        StopReason::ToolUse
+                    };
+                    for call in tool_calls {
+                        yield Ok(StreamEvent::ToolCallStarted {
+                            id: call.id.clone(),
+                            name: call.name.clone(),
+                        });
+                        yield Ok(StreamEvent::ToolCall(call));
+                    }
+                    if let Some(usage) = msg.usage {
+                        yield Ok(StreamEvent::Usage {
+                            input: usage.input_tokens,
+                            output: usage.output_tokens,
+                        });
+                    }
+                    yield Ok(StreamEvent::Done { stop_reason });
+                }
+                Err(e) => {
+                    yield Err(e);
+                }
+            }
+        })
+    }
+
+    /// Send messages with tool definitions and sampling options.
+    ///
+    /// The default implementation warns if any option is set and delegates to
+    /// [`complete_with_tools`](LlmProvider::complete_with_tools). Providers
+    /// that support sampling parameters should override this and map the
+    /// options they support.
+    async fn complete_with_options(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+        options: &GenerationOptions,
+    ) -> Result<Message, ProviderError> {
+        if !options.is_empty() {
+            tracing::warn!("provider does not support generation options; ignoring");
+        }
+        self.complete_with_tools(messages, tools).await
+    }
+
+    /// Stream a response with tool definitions and sampling options.
+    ///
+    /// The default implementation warns if any option is set and delegates to
+    /// [`stream_with_tools`](LlmProvider::stream_with_tools).
+    fn stream_with_options(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+        options: &GenerationOptions,
+    ) -> Pin<Box<dyn Stream<Item = Result<StreamEvent, ProviderError>> + Send + '_>> {
+        if !options.is_empty() {
+            tracing::warn!("provider does not support generation options; ignoring");
+        }
+        self.stream_with_tools(messages, tools)
+    }
 }
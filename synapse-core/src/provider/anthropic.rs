@@ -3,28 +3,41 @@
 //! Implements the [`LlmProvider`] trait for Anthropic's Messages API,
 //! enabling real Claude completions through the Synapse agent.
 //!
-//! Serde request/response structs live in the [`types`] submodule.
+//! Serde request/response structs live in the [`types`] submodule; parsing of
+//! streaming responses lives in [`sse`].
 
+mod sse;
 mod types;
 
 use std::pin::Pin;
 
 use async_trait::async_trait;
-use futures::Stream;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use futures::{Stream, StreamExt};
 
-use super::{LlmProvider, ProviderError, StreamEvent};
+use super::generation::{STOP, TEMPERATURE, TOP_P};
+use super::{GenerationOptions, LlmProvider, ProviderError, RetryPolicy, StreamEvent};
 use crate::mcp::ToolDefinition;
-use crate::message::{Message, Role, ToolCallData};
+use crate::message::{
+    Attachment, MediaSource, Message, Role, TokenUsage, ToolCallData, ToolContent, ToolOutput,
+};
 use types::{
-    AnthropicTool, ApiContent, ApiError, ApiMessage, ApiRequest, ApiResponse, ContentBlock,
-    ErrorDetail,
+    AnthropicTool, ApiContent, ApiError, ApiMessage, ApiRequest, ApiResponse, BlockSource,
+    ContentBlock, ErrorDetail, ImageSource, SamplingParams, ToolResultBlock, ToolResultContent,
 };
 
 /// Anthropic API version header value.
 const ANTHROPIC_VERSION: &str = "2023-06-01";
 
-/// Anthropic Messages API endpoint.
-const API_ENDPOINT: &str = "https://api.anthropic.com/v1/messages";
+/// Default Anthropic API base URL.
+const DEFAULT_BASE_URL: &str = "https://api.anthropic.com";
+
+/// Generation options accepted by the Messages API.
+const SUPPORTED_OPTIONS: &[&str] = &[TEMPERATURE, TOP_P, STOP];
+
+/// Image types accepted in image blocks.
+const IMAGE_MEDIA_TYPES: &[&str] = &["image/jpeg", "image/png", "image/gif", "image/webp"];
 
 /// Anthropic Claude provider.
 ///
@@ -48,12 +61,16 @@ const API_ENDPOINT: &str = "https://api.anthropic.com/v1/messages";
 pub struct AnthropicProvider {
     /// HTTP client for API requests.
     client: reqwest::Client,
+    /// API base URL; requests go to `{base_url}/v1/messages`.
+    base_url: String,
     /// Anthropic API key.
     api_key: String,
     /// Model identifier (e.g., "claude-3-5-sonnet-20241022").
     model: String,
     /// Maximum tokens to generate in API responses.
     max_tokens: u32,
+    /// Retry and timeout settings for API requests.
+    retry: RetryPolicy,
 }
 
 impl AnthropicProvider {
@@ -65,14 +82,37 @@ impl AnthropicProvider {
     /// * `model` - Model identifier (e.g., "claude-3-5-sonnet-20241022")
     /// * `max_tokens` - Maximum tokens to generate in API responses
     pub fn new(api_key: impl Into<String>, model: impl Into<String>, max_tokens: u32) -> Self {
+        let retry = RetryPolicy::default();
         Self {
-            client: reqwest::Client::new(),
+            client: retry.build_client(),
+            base_url: DEFAULT_BASE_URL.to_string(),
             api_key: api_key.into(),
             model: model.into(),
             max_tokens,
+            retry,
         }
     }
 
+    /// Set the retry and timeout policy for API requests.
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.client = retry.build_client();
+        self.retry = retry;
+        self
+    }
+
+    /// Override the API base URL (default: `https://api.anthropic.com`).
+    ///
+    /// Requests go to `{base_url}/v1/messages`.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Messages API endpoint derived from the base URL.
+    fn endpoint(&self) -> String {
+        format!("{}/v1/messages", self.base_url.trim_end_matches('/'))
+    }
+
     /// Build API messages from conversation messages, handling Role::Tool translation.
     fn build_api_messages(messages: &[Message]) -> Vec<ApiMessage> {
         messages
@@ -82,6 +122,13 @@ impl AnthropicProvider {
                 if m.role == Role::Tool {
                     // Anthropic handles tool results as user messages with tool_result content blocks
                     let tool_call_id = m.tool_call_id.clone().unwrap_or_default();
+                    let (content, is_error) = match m.tool_output.as_ref() {
+                        Some(output) => (
+                            Self::tool_result_content(output),
+                            output.is_error.then_some(true),
+                        ),
+                        None => (ToolResultContent::Text(m.content.clone()), None),
+                    };
                     ApiMessage {
                         role: "user".to_string(),
                         content: ApiContent::Blocks(vec![ContentBlock {
@@ -91,7 +138,9 @@ impl AnthropicProvider {
                             name: None,
                             input: None,
                             tool_use_id: Some(tool_call_id),
-                            content: Some(m.content.clone()),
+                            content: Some(content),
+                            is_error,
+                            ..Default::default()
                         }]),
                     }
                 } else if let Some(tool_calls) = m.tool_calls.as_ref()
@@ -103,15 +152,7 @@ impl AnthropicProvider {
 
                     // Include text content if non-empty
                     if !m.content.is_empty() {
-                        blocks.push(ContentBlock {
-                            content_type: "text".to_string(),
-                            text: Some(m.content.clone()),
-                            id: None,
-                            name: None,
-                            input: None,
-                            tool_use_id: None,
-                            content: None,
-                        });
+                        blocks.push(Self::text_block(m.content.clone()));
                     }
 
                     // Add tool_use blocks
@@ -122,8 +163,7 @@ impl AnthropicProvider {
                             id: Some(tc.id.clone()),
                             name: Some(tc.name.clone()),
                             input: Some(tc.input.clone()),
-                            tool_use_id: None,
-                            content: None,
+                            ..Default::default()
                         });
                     }
 
@@ -132,19 +172,127 @@ impl AnthropicProvider {
                         content: ApiContent::Blocks(blocks),
                     }
                 } else {
+                    let content = if m.attachments.is_empty() {
+                        ApiContent::Text(m.content.clone())
+                    } else {
+                        // Attachments go first, as the API recommends for images.
+                        let mut blocks: Vec<ContentBlock> =
+                            m.attachments.iter().map(Self::attachment_block).collect();
+                        if !m.content.is_empty() {
+                            blocks.push(Self::text_block(m.content.clone()));
+                        }
+                        ApiContent::Blocks(blocks)
+                    };
                     ApiMessage {
                         role: match m.role {
                             Role::User => "user".to_string(),
                             Role::Assistant => "assistant".to_string(),
                             _ => "user".to_string(),
                         },
-                        content: ApiContent::Text(m.content.clone()),
+                        content,
                     }
                 }
             })
             .collect()
     }
 
+    /// A "text" content block.
+    fn text_block(text: String) -> ContentBlock {
+        ContentBlock {
+            content_type: "text".to_string(),
+            text: Some(text),
+            ..Default::default()
+        }
+    }
+
+    /// Convert an attachment into an "image" or "document" block.
+    ///
+    /// Images of unsupported types and documents that are neither PDF nor
+    /// plain text become text blocks describing them.
+    fn attachment_block(attachment: &Attachment) -> ContentBlock {
+        let base64 = |media_type: &str, data: &[u8]| BlockSource::Base64 {
+            media_type: media_type.to_string(),
+            data: BASE64.encode(data),
+        };
+        let (content_type, source, title) = match attachment {
+            Attachment::Image { media_type, source } => {
+                let source = match source {
+                    MediaSource::Url(url) => BlockSource::Url { url: url.clone() },
+                    MediaSource::Bytes(data)
+                        if IMAGE_MEDIA_TYPES.contains(&media_type.as_str()) =>
+                    {
+                        base64(media_type, data)
+                    }
+                    MediaSource::Bytes(_) => return Self::text_block(attachment.describe()),
+                };
+                ("image", source, None)
+            }
+            Attachment::Document {
+                media_type,
+                name,
+                source,
+            } => {
+                let source = match source {
+                    MediaSource::Url(url) => BlockSource::Url { url: url.clone() },
+                    MediaSource::Bytes(data) if media_type == "application/pdf" => {
+                        base64(media_type, data)
+                    }
+                    MediaSource::Bytes(data) if media_type.starts_with("text/") => {
+                        BlockSource::Text {
+                            media_type: "text/plain".to_string(),
+                            data: String::from_utf8_lossy(data).into_owned(),
+                        }
+                    }
+                    MediaSource::Bytes(_) => return Self::text_block(attachment.describe()),
+                };
+                ("document", source, name.clone())
+            }
+        };
+        ContentBlock {
+            content_type: content_type.to_string(),
+            source: Some(source),
+            title,
+            ..Default::default()
+        }
+    }
+
+    /// Convert a tool output into `tool_result` content.
+    ///
+    /// Outputs with supported images become text and image blocks; anything
+    /// else is sent as text. Failure is signalled by `is_error`, so the text
+    /// carries no `Error: ` prefix.
+    fn tool_result_content(output: &ToolOutput) -> ToolResultContent {
+        let has_image = output
+            .content
+            .iter()
+            .any(|c| matches!(c, ToolContent::Image { mime_type, .. } if IMAGE_MEDIA_TYPES.contains(&mime_type.as_str())));
+        if !has_image {
+            return ToolResultContent::Text(output.content_text());
+        }
+
+        let blocks = output
+            .content
+            .iter()
+            .map(|c| match c {
+                ToolContent::Image { data, mime_type }
+                    if IMAGE_MEDIA_TYPES.contains(&mime_type.as_str()) =>
+                {
+                    ToolResultBlock::Image {
+                        source: ImageSource {
+                            source_type: "base64".to_string(),
+                            media_type: mime_type.clone(),
+                            data: data.clone(),
+                        },
+                    }
+                }
+                other => ToolResultBlock::Text {
+                    text: other.to_text(),
+                },
+            })
+            .collect();
+        ToolResultContent::Blocks(blocks)
+    }
+
     /// Extract system prompt from messages.
     fn extract_system(messages: &[Message]) -> Option<String> {
         let system_messages: Vec<&Message> =
@@ -162,19 +310,62 @@ impl AnthropicProvider {
         }
     }
 
-    /// Send request and parse response.
-    async fn send_request(&self, request: &ApiRequest) -> Result<Message, ProviderError> {
-        tracing::debug!(endpoint = API_ENDPOINT, "anthropic: POST complete request");
+    /// Convert tool definitions to the Anthropic format.
+    ///
+    /// Returns `None` for an empty slice so `tools` is omitted from the request.
+    fn to_api_tools(tools: &[ToolDefinition]) -> Option<Vec<AnthropicTool>> {
+        if tools.is_empty() {
+            None
+        } else {
+            Some(
+                tools
+                    .iter()
+                    .map(|t| AnthropicTool {
+                        name: t.name.clone(),
+                        description: t.description.clone(),
+                        input_schema: t.input_schema.clone(),
+                    })
+                    .collect(),
+            )
+        }
+    }
+
+    /// Map generation options to Messages API sampling parameters.
+    ///
+    /// `seed` and the presence/frequency penalties have no Anthropic
+    /// equivalent and are dropped with a warning.
+    fn sampling(options: &GenerationOptions) -> SamplingParams {
+        let options = options.retain_supported("anthropic", SUPPORTED_OPTIONS);
+        SamplingParams {
+            temperature: options.temperature,
+            top_p: options.top_p,
+            stop_sequences: options.stop,
+        }
+    }
+
+    /// POST a request to the Messages API and return the successful response.
+    ///
+    /// Transient failures are retried according to the retry policy. Other
+    /// non-success statuses are converted to a [`ProviderError`] before the
+    /// body is handed back, so callers only deal with the happy path.
+    async fn post(&self, request: &ApiRequest) -> Result<reqwest::Response, ProviderError> {
+        let endpoint = self.endpoint();
+        tracing::debug!(
+            endpoint = %endpoint,
+            stream = request.stream,
+            "anthropic: POST request"
+        );
         let response = self
-            .client
-            .post(API_ENDPOINT)
-            .header("x-api-key", &self.api_key)
-            .header("anthropic-version", ANTHROPIC_VERSION)
-            .header("content-type", "application/json")
-            .json(request)
-            .send()
-            .await
-            .map_err(|e| ProviderError::RequestFailed(e.to_string()))?;
+            .retry
+            .send(|| {
+                self.client
+                    .post(&endpoint)
+                    .header("x-api-key", &self.api_key)
+                    .header("anthropic-version", ANTHROPIC_VERSION)
+                    .header("content-type", "application/json")
+                    .json(request)
+            })
+            .await?;
 
         let status = response.status();
         tracing::debug!(status = status.as_u16(), "anthropic: response status");
@@ -200,6 +391,13 @@ impl AnthropicProvider {
             )));
         }
 
+        Ok(response)
+    }
+
+    /// Send request and parse response.
+    async fn send_request(&self, request: &ApiRequest) -> Result<Message, ProviderError> {
+        let response = self.post(request).await?;
+
         let api_response: ApiResponse =
             response
                 .json()
@@ -235,6 +433,9 @@ impl AnthropicProvider {
         if !tool_calls.is_empty() {
             msg.tool_calls = Some(tool_calls);
         }
+        msg.usage = api_response
+            .usage
+            .map(|u| TokenUsage::new(u.input_tokens, u.output_tokens));
 
         Ok(msg)
     }
@@ -243,18 +444,8 @@ impl AnthropicProvider {
 #[async_trait]
 impl LlmProvider for AnthropicProvider {
     async fn complete(&self, messages: &[Message]) -> Result<Message, ProviderError> {
-        let system = Self::extract_system(messages);
-        let api_messages = Self::build_api_messages(messages);
-
-        let request = ApiRequest {
-            model: self.model.clone(),
-            max_tokens: self.max_tokens,
-            messages: api_messages,
-            system,
-            tools: None,
-        };
-
-        self.send_request(&request).await
+        self.complete_with_options(messages, &[], &GenerationOptions::default())
+            .await
     }
 
     async fn complete_with_tools(
@@ -262,52 +453,73 @@ impl LlmProvider for AnthropicProvider {
         messages: &[Message],
         tools: &[ToolDefinition],
     ) -> Result<Message, ProviderError> {
-        let system = Self::extract_system(messages);
-        let api_messages = Self::build_api_messages(messages);
+        self.complete_with_options(messages, tools, &GenerationOptions::default())
+            .await
+    }
 
-        let api_tools = if tools.is_empty() {
-            None
-        } else {
-            Some(
-                tools
-                    .iter()
-                    .map(|t| AnthropicTool {
-                        name: t.name.clone(),
-                        description: t.description.clone(),
-                        input_schema: t.input_schema.clone(),
-                    })
-                    .collect(),
-            )
-        };
+    fn stream(
+        &self,
+        messages: &[Message],
+    ) -> Pin<Box<dyn Stream<Item = Result<StreamEvent, ProviderError>> + Send + '_>> {
+        self.stream_with_options(messages, &[], &GenerationOptions::default())
+    }
 
+    fn stream_with_tools(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+    ) -> Pin<Box<dyn Stream<Item = Result<StreamEvent, ProviderError>> + Send + '_>> {
+        self.stream_with_options(messages, tools, &GenerationOptions::default())
+    }
+
+    async fn complete_with_options(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+        options: &GenerationOptions,
+    ) -> Result<Message, ProviderError> {
         let request = ApiRequest {
             model: self.model.clone(),
             max_tokens: self.max_tokens,
-            messages: api_messages,
-            system,
-            tools: api_tools,
+            messages: Self::build_api_messages(messages),
+            system: Self::extract_system(messages),
+            tools: Self::to_api_tools(tools),
+            stream: false,
+            sampling: Self::sampling(options),
         };
 
         self.send_request(&request).await
     }
 
-    fn stream(
+    fn stream_with_options(
         &self,
         messages: &[Message],
+        tools: &[ToolDefinition],
+        options: &GenerationOptions,
     ) -> Pin<Box<dyn Stream<Item = Result<StreamEvent, ProviderError>> + Send + '_>> {
-        // Clone messages for the async stream
-        let messages = messages.to_vec();
+        let request = ApiRequest {
+            model: self.model.clone(),
+            max_tokens: self.max_tokens,
+            messages: Self::build_api_messages(messages),
+            system: Self::extract_system(messages),
+            tools: Self::to_api_tools(tools),
+            stream: true,
+            sampling: Self::sampling(options),
+        };
 
         Box::pin(async_stream::stream! {
-            // Fallback: call complete() and yield as single delta
-            match self.complete(&messages).await {
-                Ok(msg) => {
-                    yield Ok(StreamEvent::TextDelta(msg.content));
-                    yield Ok(StreamEvent::Done);
-                }
+            let response = match self.post(&request).await {
+                Ok(r) => r,
                 Err(e) => {
                     yield Err(e);
+                    return;
                 }
+            };
+
+            tracing::debug!(endpoint = %self.endpoint(), "anthropic: SSE stream started");
+            let mut events = sse::parse_event_stream(response.bytes_stream());
+            while let Some(event) = events.next().await {
+                yield event;
             }
         })
     }
@@ -328,6 +540,8 @@ mod tests {
             }],
             system: None,
             tools: None,
+            stream: false,
+            sampling: SamplingParams::default(),
         };
 
         let json = serde_json::to_value(&request).unwrap();
@@ -338,6 +552,55 @@ mod tests {
         assert_eq!(json["messages"][0]["content"], "Hello, Claude");
         assert!(json.get("system").is_none());
         assert!(json.get("tools").is_none());
+        assert!(json.get("stream").is_none());
+    }
+
+    #[test]
+    fn test_api_request_serialization_streaming() {
+        let request = ApiRequest {
+            model: "claude-3-5-sonnet-20241022".to_string(),
+            max_tokens: 1024,
+            messages: vec![ApiMessage {
+                role: "user".to_string(),
+                content: ApiContent::Text("Hello".to_string()),
+            }],
+            system: None,
+            tools: None,
+            stream: true,
+            sampling: SamplingParams::default(),
+        };
+
+        let json = serde_json::to_value(&request).unwrap();
+
+        assert_eq!(json["stream"], true);
+    }
+
+    #[test]
+    fn test_sampling_maps_supported_options() {
+        let options = GenerationOptions::default()
+            .with_temperature(0.3)
+            .with_top_p(0.8)
+            .with_stop(vec!["END".to_string()])
+            .with_seed(42)
+            .with_frequency_penalty(0.5);
+
+        let request = ApiRequest {
+            model: "claude-3-5-sonnet-20241022".to_string(),
+            max_tokens: 1024,
+            messages: vec![],
+            system: None,
+            tools: None,
+            stream: false,
+            sampling: AnthropicProvider::sampling(&options),
+        };
+        let json = serde_json::to_value(&request).unwrap();
+
+        assert!((json["temperature"].as_f64().unwrap() - 0.3).abs() < 1e-6);
+        assert!((json["top_p"].as_f64().unwrap() - 0.8).abs() < 1e-6);
+        assert_eq!(json["stop_sequences"][0], "END");
+        assert!(json.get("stop").is_none());
+        assert!(json.get("seed").is_none());
+        assert!(json.get("frequency_penalty").is_none());
     }
 
     #[test]
@@ -351,6 +614,8 @@ mod tests {
             }],
             system: Some("You are a helpful assistant.".to_string()),
             tools: None,
+            stream: false,
+            sampling: SamplingParams::default(),
         };
 
         let json = serde_json::to_value(&request).unwrap();
@@ -376,6 +641,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_api_response_parsing_usage() {
+        let json = r#"{
+            "content": [{"type": "text", "text": "Hi"}],
+            "usage": {"input_tokens": 25, "output_tokens": 7}
+        }"#;
+
+        let response: ApiResponse = serde_json::from_str(json).unwrap();
+
+        let usage = response.usage.expect("usage missing");
+        assert_eq!(usage.input_tokens, 25);
+        assert_eq!(usage.output_tokens, 7);
+    }
+
     #[test]
     fn test_api_response_parsing_multiple_blocks() {
         let json = r#"{
@@ -470,6 +749,8 @@ mod tests {
             }],
             system: None,
             tools: Some(tools),
+            stream: false,
+            sampling: SamplingParams::default(),
         };
 
         let json = serde_json::to_value(&request).unwrap();
@@ -518,12 +799,114 @@ mod tests {
         if let ApiContent::Blocks(blocks) = &api_messages[1].content {
             assert_eq!(blocks[0].content_type, "tool_result");
             assert_eq!(blocks[0].tool_use_id, Some("call_1".to_string()));
-            assert_eq!(blocks[0].content, Some("Sunny, 20C".to_string()));
+            let json = serde_json::to_value(&blocks[0]).unwrap();
+            assert_eq!(json["content"], "Sunny, 20C");
+            assert!(json.get("is_error").is_none());
         } else {
             panic!("Expected Blocks content for tool result");
         }
     }
 
+    #[test]
+    fn test_tool_output_image_serialization() {
+        let output = ToolOutput {
+            content: vec![
+                ToolContent::Text {
+                    text: "chart:".to_string(),
+                },
+                ToolContent::Image {
+                    data: "iVBORw0KGgo=".to_string(),
+                    mime_type: "image/png".to_string(),
+                },
+                ToolContent::Image {
+                    data: "AAAA".to_string(),
+                    mime_type: "image/tiff".to_string(),
+                },
+            ],
+            ..ToolOutput::default()
+        };
+        let messages = vec![Message::tool_output("call_1", output)];
+
+        let api_messages = AnthropicProvider::build_api_messages(&messages);
+        let json = serde_json::to_value(&api_messages[0].content).unwrap();
+
+        assert_eq!(
+            json[0]["content"],
+            serde_json::json!([
+                {"type": "text", "text": "chart:"},
+                {
+                    "type": "image",
+                    "source": {"type": "base64", "media_type": "image/png", "data": "iVBORw0KGgo="}
+                },
+                {"type": "text", "text": "[image: image/tiff]"}
+            ])
+        );
+    }
+
+    #[test]
+    fn test_attachment_serialization() {
+        let message = Message::new(Role::User, "Compare these")
+            .with_attachment(Attachment::image("image/png", b"png".to_vec()))
+            .with_attachment(Attachment::Image {
+                media_type: "image/jpeg".to_string(),
+                source: MediaSource::Url("https://example.com/a.jpg".to_string()),
+            })
+            .with_attachment(Attachment::document(
+                "application/pdf",
+                Some("report.pdf".to_string()),
+                b"%PDF".to_vec(),
+            ))
+            .with_attachment(Attachment::document(
+                "text/markdown",
+                None,
+                b"# Notes".to_vec(),
+            ))
+            .with_attachment(Attachment::document("application/zip", None, vec![0]));
+
+        let api_messages = AnthropicProvider::build_api_messages(&[message]);
+        let json = serde_json::to_value(&api_messages[0].content).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!([
+                {
+                    "type": "image",
+                    "source": {"type": "base64", "media_type": "image/png", "data": "cG5n"}
+                },
+                {
+                    "type": "image",
+                    "source": {"type": "url", "url": "https://example.com/a.jpg"}
+                },
+                {
+                    "type": "document",
+                    "source": {"type": "base64", "media_type": "application/pdf", "data": "JVBERg=="},
+                    "title": "report.pdf"
+                },
+                {
+                    "type": "document",
+                    "source": {"type": "text", "media_type": "text/plain", "data": "# Notes"}
+                },
+                {"type": "text", "text": "[document: application/zip]"},
+                {"type": "text", "text": "Compare these"}
+            ])
+        );
+    }
+
+    #[test]
+    fn test_tool_output_error_serialization() {
+        let messages = vec![Message::tool_output(
+            "call_1",
+            ToolOutput::error("file not found"),
+        )];
+
+        let api_messages = AnthropicProvider::build_api_messages(&messages);
+        let json = serde_json::to_value(&api_messages[0].content).unwrap();
+
+        assert_eq!(json[0]["type"], "tool_result");
+        assert_eq!(json[0]["content"], "file not found");
+        assert_eq!(json[0]["is_error"], true);
+    }
+
     #[test]
     fn test_complete_with_tools_no_tools() {
         let request = ApiRequest {
@@ -535,6 +918,8 @@ mod tests {
             }],
             system: None,
             tools: None,
+            stream: false,
+            sampling: SamplingParams::default(),
         };
 
         let json = serde_json::to_value(&request).unwrap();
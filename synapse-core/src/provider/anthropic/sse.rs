@@ -0,0 +1,369 @@
+//! Server-sent event parsing for streaming Anthropic Messages API responses.
+//!
+//! Converts the raw `text/event-stream` body into [`StreamEvent`]s. Kept
+//! separate from the HTTP layer so it can be driven by recorded fixtures in
+//! tests.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::pin::Pin;
+
+use eventsource_stream::Eventsource;
+use futures::{Stream, StreamExt};
+
+use super::types::{ErrorDetail, SseContentBlock, SseDelta, SseEvent};
+use crate::provider::streaming::PartialToolCall;
+use crate::provider::{ProviderError, StopReason, StreamEvent};
+
+/// Parse a byte stream of Anthropic SSE frames into stream events.
+///
+/// Yields [`StreamEvent::TextDelta`] for each non-empty `text_delta`,
+/// [`StreamEvent::ToolCallStarted`] / [`StreamEvent::ToolCallArgumentsDelta`]
+/// while a `tool_use` block streams and [`StreamEvent::ToolCall`] when it
+/// stops, [`StreamEvent::Usage`] on `message_delta`, and
+/// [`StreamEvent::Done`] on `message_stop`. An `error` event, malformed
+/// frame or a body that ends before `message_stop` yields a single error and
+/// ends the stream.
+pub(super) fn parse_event_stream<S, B, E>(
+    bytes: S,
+) -> Pin<Box<dyn Stream<Item = Result<StreamEvent, ProviderError>> + Send>>
+where
+    S: Stream<Item = Result<B, E>> + Send + 'static,
+    B: AsRef<[u8]> + Send + 'static,
+    E: Display + Send + 'static,
+{
+    Box::pin(async_stream::stream! {
+        let sse_stream = bytes.eventsource();
+        futures::pin_mut!(sse_stream);
+        // Open tool_use blocks keyed by content block index.
+        let mut tool_calls: HashMap<usize, PartialToolCall> = HashMap::new();
+        let mut input_tokens = 0;
+        let mut stop_reason = StopReason::EndTurn;
+
+        while let Some(event) = sse_stream.next().await {
+            let event = match event {
+                Ok(event) => event,
+                Err(e) => {
+                    yield Err(ProviderError::RequestFailed(e.to_string()));
+                    return;
+                }
+            };
+
+            let parsed = match serde_json::from_str::<SseEvent>(&event.data) {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    yield Err(ProviderError::ProviderError {
+                        message: format!("failed to parse SSE event: {}", e),
+                    });
+                    return;
+                }
+            };
+
+            match parsed {
+                SseEvent::MessageStart { message } => {
+                    input_tokens = message.usage.input_tokens;
+                }
+                SseEvent::ContentBlockStart {
+                    index,
+                    content_block: SseContentBlock::ToolUse { id, name },
+                } => {
+                    yield Ok(StreamEvent::ToolCallStarted {
+                        id: id.clone(),
+                        name: name.clone(),
+                    });
+                    tool_calls.insert(index, PartialToolCall {
+                        id,
+                        name,
+                        arguments: String::new(),
+                    });
+                }
+                SseEvent::ContentBlockDelta {
+                    delta: SseDelta::TextDelta { text },
+                    ..
+                } if !text.is_empty() => {
+                    yield Ok(StreamEvent::TextDelta(text));
+                }
+                SseEvent::ContentBlockDelta {
+                    index,
+                    delta: SseDelta::InputJsonDelta { partial_json },
+                } => {
+                    if let Some(partial) = tool_calls.get_mut(&index)
+                        && !partial_json.is_empty()
+                    {
+                        partial.arguments.push_str(&partial_json);
+                        yield Ok(StreamEvent::ToolCallArgumentsDelta {
+                            id: partial.id.clone(),
+                            delta: partial_json,
+                        });
+                    }
+                }
+                SseEvent::ContentBlockStop { index } => {
+                    if let Some(partial) = tool_calls.remove(&index) {
+                        yield Ok(StreamEvent::ToolCall(partial.finish()));
+                    }
+                }
+                SseEvent::MessageDelta { delta, usage } => {
+                    if let Some(reason) = delta.stop_reason {
+                        stop_reason = StopReason::from(reason.as_str());
+                    }
+                    yield Ok(StreamEvent::Usage {
+                        input: input_tokens,
+                        output: usage.output_tokens,
+                    });
+                }
+                SseEvent::MessageStop => {
+                    tracing::debug!("anthropic: SSE stream ended");
+                    yield Ok(StreamEvent::Done { stop_reason });
+                    return;
+                }
+                SseEvent::Error { error } => {
+                    yield Err(map_stream_error(error));
+                    return;
+                }
+                _ => {}
+            }
+        }
+
+        // The connection closed mid-answer; whatever arrived is incomplete.
+        tracing::debug!("anthropic: SSE stream ended without message_stop");
+        yield Err(ProviderError::RequestFailed(
+            "stream ended before message_stop".to_string(),
+        ));
+    })
+}
+
+/// Map an in-stream `error` event to a [`ProviderError`].
+fn map_stream_error(error: ErrorDetail) -> ProviderError {
+    match error.error_type.as_str() {
+        "authentication_error" | "permission_error" => {
+            ProviderError::AuthenticationError(error.message)
+        }
+        "overloaded_error" | "api_error" => ProviderError::Overloaded(error.message),
+        "rate_limit_error" => ProviderError::RateLimited {
+            message: error.message,
+            retry_after: None,
+        },
+        "timeout_error" => ProviderError::Timeout(error.message),
+        _ => ProviderError::ProviderError {
+            message: format!("{}: {}", error.error_type, error.message),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use super::*;
+
+    /// Recorded stream for a plain text answer, including a `ping`.
+    const TEXT_STREAM: &str = include_str!("fixtures/text_stream.sse");
+    /// Recorded stream that fails mid-way with `overloaded_error`.
+    const ERROR_STREAM: &str = include_str!("fixtures/error_stream.sse");
+    /// Recorded stream with a text block followed by a `tool_use` block.
+    const TOOL_USE_STREAM: &str = include_str!("fixtures/tool_use_stream.sse");
+
+    /// Feed `fixture` to the parser in `chunk_size`-byte pieces and collect the output.
+    async fn collect(fixture: &str, chunk_size: usize) -> Vec<Result<StreamEvent, ProviderError>> {
+        let chunks: Vec<Result<Vec<u8>, Infallible>> = fixture
+            .as_bytes()
+            .chunks(chunk_size)
+            .map(|c| Ok(c.to_vec()))
+            .collect();
+        parse_event_stream(futures::stream::iter(chunks))
+            .collect()
+            .await
+    }
+
+    fn text_of(events: &[Result<StreamEvent, ProviderError>]) -> String {
+        events
+            .iter()
+            .filter_map(|e| match e {
+                Ok(StreamEvent::TextDelta(t)) => Some(t.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_parse_event_stream_text() {
+        let events = collect(TEXT_STREAM, usize::MAX).await;
+
+        assert_eq!(text_of(&events), "Hello! How can I help?");
+        assert!(matches!(events.last(), Some(Ok(StreamEvent::Done { .. }))));
+        assert!(events.iter().all(|e| e.is_ok()));
+    }
+
+    #[tokio::test]
+    async fn test_parse_event_stream_usage_and_stop_reason() {
+        let events = collect(TEXT_STREAM, usize::MAX).await;
+
+        assert!(events.iter().any(|e| matches!(
+            e,
+            Ok(StreamEvent::Usage {
+                input: 12,
+                output: 9
+            })
+        )));
+        assert!(matches!(
+            events.last(),
+            Some(Ok(StreamEvent::Done {
+                stop_reason: StopReason::EndTurn
+            }))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_parse_event_stream_split_across_chunks() {
+        // Frames arriving in arbitrary TCP-sized pieces must reassemble identically.
+        let events = collect(TEXT_STREAM, 7).await;
+
+        assert_eq!(text_of(&events), "Hello! How can I help?");
+        assert!(matches!(events.last(), Some(Ok(StreamEvent::Done { .. }))));
+    }
+
+    #[tokio::test]
+    async fn test_parse_event_stream_tool_use() {
+        let events = collect(TOOL_USE_STREAM, 11).await;
+
+        assert_eq!(text_of(&events), "Let me check the weather.");
+        let calls: Vec<_> = events
+            .iter()
+            .filter_map(|e| match e {
+                Ok(StreamEvent::ToolCall(call)) => Some(call),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].id, "toolu_01T1x1fJ34qAmk2tNTrN7Up6");
+        assert_eq!(calls[0].name, "get_weather");
+        assert_eq!(
+            calls[0].input,
+            serde_json::json!({"location": "San Francisco, CA", "unit": "celsius"})
+        );
+
+        assert!(matches!(
+            &events[2],
+            Ok(StreamEvent::ToolCallStarted { id, name })
+                if id == "toolu_01T1x1fJ34qAmk2tNTrN7Up6" && name == "get_weather"
+        ));
+        let arguments: String = events
+            .iter()
+            .filter_map(|e| match e {
+                Ok(StreamEvent::ToolCallArgumentsDelta { delta, .. }) => Some(delta.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            arguments,
+            "{\"location\": \"San Francisco, CA\", \"unit\": \"celsius\"}"
+        );
+        assert!(matches!(
+            events.last(),
+            Some(Ok(StreamEvent::Done {
+                stop_reason: StopReason::ToolUse
+            }))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_parse_event_stream_error_event() {
+        let events = collect(ERROR_STREAM, usize::MAX).await;
+
+        assert_eq!(text_of(&events), "Partial");
+        match events.last() {
+            Some(Err(ProviderError::Overloaded(message))) => {
+                assert!(message.contains("Overloaded"));
+            }
+            other => panic!("Expected Overloaded, got: {:?}", other),
+        }
+        assert!(
+            !events
+                .iter()
+                .any(|e| matches!(e, Ok(StreamEvent::Done { .. })))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_parse_event_stream_malformed_json() {
+        let events = collect(
+            "event: content_block_delta\ndata: {not json\n\n",
+            usize::MAX,
+        )
+        .await;
+
+        assert_eq!(events.len(), 1);
+        assert!(matches!(
+            &events[0],
+            Err(ProviderError::ProviderError { message }) if message.contains("parse SSE")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_parse_event_stream_without_message_stop() {
+        let fixture = "event: content_block_delta\n\
+                       data: {\"type\":\"content_block_delta\",\"index\":0,\
+                       \"delta\":{\"type\":\"text_delta\",\"text\":\"Hi\"}}\n\n";
+        let events = collect(fixture, usize::MAX).await;
+
+        assert_eq!(text_of(&events), "Hi");
+        assert!(matches!(
+            events.last(),
+            Some(Err(ProviderError::RequestFailed(message))) if message.contains("message_stop")
+        ));
+        assert!(
+            !events
+                .iter()
+                .any(|e| matches!(e, Ok(StreamEvent::Done { .. })))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_parse_event_stream_unknown_event_ignored() {
+        let fixture = "event: brand_new_event\n\
+                       data: {\"type\":\"brand_new_event\",\"payload\":1}\n\n\
+                       event: message_stop\n\
+                       data: {\"type\":\"message_stop\"}\n\n";
+        let events = collect(fixture, usize::MAX).await;
+
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], Ok(StreamEvent::Done { .. })));
+    }
+
+    #[test]
+    fn test_map_stream_error_authentication() {
+        let err = map_stream_error(ErrorDetail {
+            error_type: "authentication_error".to_string(),
+            message: "invalid x-api-key".to_string(),
+        });
+        assert!(matches!(err, ProviderError::AuthenticationError(m) if m == "invalid x-api-key"));
+    }
+
+    #[test]
+    fn test_map_stream_error_rate_limit() {
+        let err = map_stream_error(ErrorDetail {
+            error_type: "rate_limit_error".to_string(),
+            message: "Number of requests has exceeded your rate limit".to_string(),
+        });
+        assert!(err.is_retryable());
+        assert!(matches!(
+            err,
+            ProviderError::RateLimited {
+                retry_after: None,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_map_stream_error_other() {
+        let err = map_stream_error(ErrorDetail {
+            error_type: "invalid_request_error".to_string(),
+            message: "bad".to_string(),
+        });
+        assert!(!err.is_retryable());
+        assert!(
+            matches!(err, ProviderError::ProviderError { message } if message == "invalid_request_error: bad")
+        );
+    }
+}
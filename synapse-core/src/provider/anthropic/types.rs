@@ -23,6 +23,27 @@ pub(super) struct ApiRequest {
     /// Optional tool definitions.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(super) tools: Option<Vec<AnthropicTool>>,
+    /// Request a server-sent event stream instead of a single JSON body.
+    /// Omitted when `false` so non-streaming requests are unchanged on the wire.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(super) stream: bool,
+    /// Sampling parameters; unset fields are omitted.
+    #[serde(flatten)]
+    pub(super) sampling: SamplingParams,
+}
+
+/// Sampling parameters supported by the Messages API.
+#[derive(Debug, Default, Serialize)]
+pub(super) struct SamplingParams {
+    /// Sampling temperature (0.0 to 1.0).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) temperature: Option<f32>,
+    /// Nucleus sampling probability mass.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) top_p: Option<f32>,
+    /// Custom sequences that stop generation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) stop_sequences: Option<Vec<String>>,
 }
 
 /// Tool definition in Anthropic API format.
@@ -61,10 +82,13 @@ pub(super) struct ApiMessage {
 pub(super) struct ApiResponse {
     /// Response content blocks.
     pub(super) content: Vec<ContentBlock>,
+    /// Token usage for this request.
+    #[serde(default)]
+    pub(super) usage: Option<ApiUsage>,
 }
 
-/// Content block in API response.
-#[derive(Debug, Serialize, Deserialize)]
+/// Content block in API requests and responses.
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub(super) struct ContentBlock {
     /// Content type (e.g., "text", "tool_use", "tool_result").
     #[serde(rename = "type")]
@@ -86,7 +110,79 @@ pub(super) struct ContentBlock {
     pub(super) tool_use_id: Option<String>,
     /// Tool result content (present for "tool_result" type).
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub(super) content: Option<String>,
+    pub(super) content: Option<ToolResultContent>,
+    /// Whether the tool failed (present for failed "tool_result" blocks).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) is_error: Option<bool>,
+    /// Attachment data (present for "image" and "document" types).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) source: Option<BlockSource>,
+    /// Document title (present for named "document" blocks).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) title: Option<String>,
+}
+
+/// Data of an "image" or "document" block.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(super) enum BlockSource {
+    /// Inline base64 data.
+    Base64 {
+        /// MIME type (e.g., "image/png", "application/pdf").
+        media_type: String,
+        /// Base64-encoded data.
+        data: String,
+    },
+    /// Inline plain text (documents only).
+    Text {
+        /// Always "text/plain".
+        media_type: String,
+        /// The document text.
+        data: String,
+    },
+    /// A URL the API fetches.
+    Url {
+        /// The URL.
+        url: String,
+    },
+}
+
+/// Tool result content: plain text, or text and image blocks.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub(super) enum ToolResultContent {
+    /// Simple text content.
+    Text(String),
+    /// Array of content blocks (when the result includes images).
+    Blocks(Vec<ToolResultBlock>),
+}
+
+/// A content block inside a tool result.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(super) enum ToolResultBlock {
+    /// Text block.
+    Text {
+        /// The text.
+        text: String,
+    },
+    /// Image block.
+    Image {
+        /// Where the image data comes from.
+        source: ImageSource,
+    },
+}
+
+/// Inline base64 image data.
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct ImageSource {
+    /// Source type (always "base64").
+    #[serde(rename = "type")]
+    pub(super) source_type: String,
+    /// Image MIME type (e.g., "image/png").
+    pub(super) media_type: String,
+    /// Base64-encoded image data.
+    pub(super) data: String,
 }
 
 /// Error response from Anthropic API.
@@ -98,7 +194,6 @@ pub(super) struct ApiError {
 
 /// Error detail from API error response.
 #[derive(Debug, Deserialize)]
-#[allow(dead_code)] // Fields used for deserialization
 pub(super) struct ErrorDetail {
     /// Error type (e.g., "authentication_error").
     #[serde(rename = "type")]
@@ -106,3 +201,120 @@ pub(super) struct ErrorDetail {
     /// Human-readable error message.
     pub(super) message: String,
 }
+
+/// A single server-sent event from a streaming Messages API response.
+///
+/// Only the events and fields the provider acts on are modelled; anything
+/// else (including future event types) deserializes to [`SseEvent::Unknown`].
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(super) enum SseEvent {
+    /// First event of every stream, carrying the message envelope.
+    MessageStart {
+        /// The (still empty) message, including prompt token usage.
+        message: SseMessage,
+    },
+    /// A new content block (text or tool use) begins at `index`.
+    ContentBlockStart {
+        /// Position of the block within the message.
+        index: usize,
+        /// Initial block contents; tool use blocks carry the ID and name.
+        content_block: SseContentBlock,
+    },
+    /// Incremental content for the block at `index`.
+    ContentBlockDelta {
+        /// Position of the block within the message.
+        index: usize,
+        /// The incremental payload.
+        delta: SseDelta,
+    },
+    /// The content block at `index` is complete.
+    ContentBlockStop {
+        /// Position of the block within the message.
+        index: usize,
+    },
+    /// Top-level message changes such as the stop reason.
+    MessageDelta {
+        /// Changed message fields.
+        delta: SseMessageDelta,
+        /// Cumulative output token usage.
+        #[serde(default)]
+        usage: ApiUsage,
+    },
+    /// Final event of a successful stream.
+    MessageStop,
+    /// Keep-alive sent periodically by the API.
+    Ping,
+    /// An error that occurred after the HTTP response had started.
+    Error {
+        /// Error details in the same shape as a non-streaming error body.
+        error: ErrorDetail,
+    },
+    /// Any event type this client does not know about.
+    #[serde(other)]
+    Unknown,
+}
+
+/// Message envelope carried by `message_start`.
+#[derive(Debug, Deserialize)]
+pub(super) struct SseMessage {
+    /// Token usage known at stream start (prompt tokens).
+    #[serde(default)]
+    pub(super) usage: ApiUsage,
+}
+
+/// Message-level fields updated by `message_delta`.
+#[derive(Debug, Deserialize)]
+pub(super) struct SseMessageDelta {
+    /// Why generation stopped (e.g., "end_turn", "tool_use", "max_tokens").
+    #[serde(default)]
+    pub(super) stop_reason: Option<String>,
+}
+
+/// Token counts reported in responses and streaming events.
+///
+/// Fields absent from a given event deserialize to zero.
+#[derive(Debug, Default, Deserialize)]
+pub(super) struct ApiUsage {
+    /// Prompt tokens.
+    #[serde(default)]
+    pub(super) input_tokens: u32,
+    /// Generated tokens.
+    #[serde(default)]
+    pub(super) output_tokens: u32,
+}
+
+/// Initial contents of a block announced by `content_block_start`.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(super) enum SseContentBlock {
+    /// A tool invocation whose input follows as `input_json_delta` events.
+    ToolUse {
+        /// Tool use ID, echoed back in the matching `tool_result`.
+        id: String,
+        /// Name of the tool to invoke.
+        name: String,
+    },
+    /// Text and any other block type; content arrives via deltas.
+    #[serde(other)]
+    Other,
+}
+
+/// Payload of a `content_block_delta` event.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(super) enum SseDelta {
+    /// A fragment of assistant text.
+    TextDelta {
+        /// The text fragment.
+        text: String,
+    },
+    /// A fragment of a tool use block's input JSON.
+    InputJsonDelta {
+        /// The raw JSON fragment; only valid once all fragments are joined.
+        partial_json: String,
+    },
+    /// Any delta type this client does not handle.
+    #[serde(other)]
+    Other,
+}
@@ -9,13 +9,23 @@ use std::pin::Pin;
 use async_trait::async_trait;
 use futures::Stream;
 
+use super::generation::{FREQUENCY_PENALTY, PRESENCE_PENALTY, STOP, TEMPERATURE, TOP_P};
 use super::openai_compat::OpenAiCompatProvider;
-use super::{LlmProvider, ProviderError, StreamEvent};
+use super::{GenerationOptions, LlmProvider, ProviderError, RetryPolicy, StreamEvent};
 use crate::mcp::ToolDefinition;
 use crate::message::Message;
 
-/// DeepSeek Chat Completions API endpoint.
-const API_ENDPOINT: &str = "https://api.deepseek.com/chat/completions";
+/// Default DeepSeek API base URL.
+const DEFAULT_BASE_URL: &str = "https://api.deepseek.com";
+
+/// Generation options accepted by the DeepSeek API (no `seed`).
+const SUPPORTED_OPTIONS: &[&str] = &[
+    TEMPERATURE,
+    TOP_P,
+    STOP,
+    PRESENCE_PENALTY,
+    FREQUENCY_PENALTY,
+];
 
 /// DeepSeek LLM provider.
 ///
@@ -48,12 +58,22 @@ impl DeepSeekProvider {
     /// * `model` - Model identifier (e.g., "deepseek-chat")
     /// * `max_tokens` - Maximum tokens to generate in API responses
     pub fn new(api_key: impl Into<String>, model: impl Into<String>, max_tokens: u32) -> Self {
-        Self(OpenAiCompatProvider::new(
-            API_ENDPOINT,
-            api_key,
-            model,
-            max_tokens,
-        ))
+        Self(
+            OpenAiCompatProvider::new(DEFAULT_BASE_URL, api_key, model, max_tokens)
+                .with_supported_options("deepseek", SUPPORTED_OPTIONS),
+        )
+    }
+
+    /// Set the retry and timeout policy for API requests.
+    pub fn with_retry_policy(self, retry: RetryPolicy) -> Self {
+        Self(self.0.with_retry_policy(retry))
+    }
+
+    /// Override the API base URL (default: `https://api.deepseek.com`).
+    ///
+    /// Requests go to `{base_url}/chat/completions`.
+    pub fn with_base_url(self, base_url: impl Into<String>) -> Self {
+        Self(self.0.with_base_url(base_url))
     }
 }
 
@@ -77,6 +97,32 @@ impl LlmProvider for DeepSeekProvider {
     ) -> Pin<Box<dyn Stream<Item = Result<StreamEvent, ProviderError>> + Send + '_>> {
         self.0.stream(messages)
     }
+
+    fn stream_with_tools(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+    ) -> Pin<Box<dyn Stream<Item = Result<StreamEvent, ProviderError>> + Send + '_>> {
+        self.0.stream_with_tools(messages, tools)
+    }
+
+    async fn complete_with_options(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+        options: &GenerationOptions,
+    ) -> Result<Message, ProviderError> {
+        self.0.complete_with_options(messages, tools, options).await
+    }
+
+    fn stream_with_options(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+        options: &GenerationOptions,
+    ) -> Pin<Box<dyn Stream<Item = Result<StreamEvent, ProviderError>> + Send + '_>> {
+        self.0.stream_with_options(messages, tools, options)
+    }
 }
 
 #[cfg(test)]
@@ -3,9 +3,12 @@
 //! Creates the appropriate LLM provider based on configuration settings,
 //! handling API key resolution from environment variables and config files.
 
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+
 use crate::config::Config;
 use crate::provider::{
-    AnthropicProvider, DeepSeekProvider, LlmProvider, OpenAiProvider, ProviderError,
+    AnthropicProvider, DeepSeekProvider, LlmProvider, OpenAiCompatibleProvider, OpenAiProvider,
+    OpenAiTranscriber, ProviderError, RetryPolicy, Transcriber,
 };
 
 /// Environment variable name for the DeepSeek API key.
@@ -14,22 +17,30 @@ const DEEPSEEK_API_KEY_ENV: &str = "DEEPSEEK_API_KEY";
 const ANTHROPIC_API_KEY_ENV: &str = "ANTHROPIC_API_KEY";
 /// Environment variable name for the OpenAI API key.
 const OPENAI_API_KEY_ENV: &str = "OPENAI_API_KEY";
+/// Environment variable name for the generic OpenAI-compatible API key.
+const OPENAI_COMPATIBLE_API_KEY_ENV: &str = "OPENAI_COMPATIBLE_API_KEY";
 
 /// Create an LLM provider based on configuration.
 ///
 /// Selects the appropriate provider based on `config.provider` and retrieves
-/// the API key from environment variable or config file.
+/// the API key from environment variable or config file. `config.base_url`,
+/// when set, replaces the provider's default endpoint.
 ///
 /// # Environment Variables
 ///
 /// - `DEEPSEEK_API_KEY` for "deepseek" provider
 /// - `ANTHROPIC_API_KEY` for "anthropic" provider
 /// - `OPENAI_API_KEY` for "openai" provider
+/// - `OPENAI_COMPATIBLE_API_KEY` for "openai-compatible" provider (optional)
+///
+/// `config.api_key_env` overrides the variable name for any provider.
 ///
 /// # Errors
 ///
 /// - [`ProviderError::MissingApiKey`] if no API key is found
 /// - [`ProviderError::UnknownProvider`] if provider name is not recognized
+/// - [`ProviderError::InvalidConfig`] if `openai-compatible` has no `base_url`
+///   or a configured header is not a valid HTTP header
 ///
 /// # Examples
 ///
@@ -46,48 +57,111 @@ const OPENAI_API_KEY_ENV: &str = "OPENAI_API_KEY";
 pub fn create_provider(config: &Config) -> Result<Box<dyn LlmProvider>, ProviderError> {
     // Validate provider name first
     match config.provider.as_str() {
-        "deepseek" | "anthropic" | "openai" => {}
+        "deepseek" | "anthropic" | "openai" | "openai-compatible" => {}
         unknown => return Err(ProviderError::UnknownProvider(unknown.to_string())),
     }
 
-    let api_key = get_api_key(config)?;
+    let retry = config
+        .retry
+        .as_ref()
+        .map(RetryPolicy::from)
+        .unwrap_or_default();
 
     tracing::info!(provider = %config.provider, model = %config.model, "factory: creating provider");
 
+    if config.provider == "openai-compatible" {
+        let base_url = config.base_url.as_deref().ok_or_else(|| {
+            ProviderError::InvalidConfig(
+                "base_url is required for the openai-compatible provider".to_string(),
+            )
+        })?;
+        let api_key = get_api_key(config).ok();
+        return Ok(Box::new(
+            OpenAiCompatibleProvider::new(base_url, api_key, &config.model, config.max_tokens)
+                .with_headers(build_headers(config)?)
+                .with_retry_policy(retry),
+        ));
+    }
+
+    let api_key = get_api_key(config)?;
+    let base_url = config.base_url.as_deref();
+
     match config.provider.as_str() {
-        "deepseek" => Ok(Box::new(DeepSeekProvider::new(
-            api_key,
-            &config.model,
-            config.max_tokens,
-        ))),
-        "anthropic" => Ok(Box::new(AnthropicProvider::new(
-            api_key,
-            &config.model,
-            config.max_tokens,
-        ))),
-        "openai" => Ok(Box::new(OpenAiProvider::new(
-            api_key,
-            &config.model,
-            config.max_tokens,
-        ))),
+        "deepseek" => {
+            let mut provider = DeepSeekProvider::new(api_key, &config.model, config.max_tokens)
+                .with_retry_policy(retry);
+            if let Some(url) = base_url {
+                provider = provider.with_base_url(url);
+            }
+            Ok(Box::new(provider))
+        }
+        "anthropic" => {
+            let mut provider = AnthropicProvider::new(api_key, &config.model, config.max_tokens)
+                .with_retry_policy(retry);
+            if let Some(url) = base_url {
+                provider = provider.with_base_url(url);
+            }
+            Ok(Box::new(provider))
+        }
+        "openai" => {
+            let mut provider = OpenAiProvider::new(api_key, &config.model, config.max_tokens)
+                .with_retry_policy(retry);
+            if let Some(url) = base_url {
+                provider = provider.with_base_url(url);
+            }
+            Ok(Box::new(provider))
+        }
         _ => unreachable!("Provider validated above"),
     }
 }
 
+/// Create the speech-to-text backend from the `[transcription]` section.
+///
+/// Returns `None` when the section is absent. The API key comes from the
+/// `api_key_env` variable (default `OPENAI_API_KEY`), then `api_key`; without
+/// one, requests carry no `Authorization` header (local whisper servers).
+pub fn create_transcriber(config: &Config) -> Option<Box<dyn Transcriber>> {
+    let settings = config.transcription.as_ref()?;
+    let env_var = settings
+        .api_key_env
+        .as_deref()
+        .unwrap_or(OPENAI_API_KEY_ENV);
+    let api_key = std::env::var(env_var)
+        .ok()
+        .filter(|key| !key.is_empty())
+        .or_else(|| settings.api_key.clone());
+    let retry = config
+        .retry
+        .as_ref()
+        .map(RetryPolicy::from)
+        .unwrap_or_default();
+
+    tracing::info!(base_url = %settings.base_url, model = %settings.model, "factory: creating transcriber");
+    let mut transcriber = OpenAiTranscriber::new(&settings.base_url, api_key, &settings.model)
+        .with_retry_policy(retry);
+    if let Some(language) = &settings.language {
+        transcriber = transcriber.with_language(language);
+    }
+    Some(Box::new(transcriber))
+}
+
 /// Retrieve API key from environment variable or config file.
 ///
-/// Priority: environment variable > config.api_key
+/// Priority: environment variable (`config.api_key_env` or the provider
+/// default) > config.api_key
 ///
 /// # Panics
 ///
 /// Panics if called with an unknown provider (caller should validate first).
 fn get_api_key(config: &Config) -> Result<String, ProviderError> {
-    let env_var = match config.provider.as_str() {
+    let default_env_var = match config.provider.as_str() {
         "deepseek" => DEEPSEEK_API_KEY_ENV,
         "anthropic" => ANTHROPIC_API_KEY_ENV,
         "openai" => OPENAI_API_KEY_ENV,
+        "openai-compatible" => OPENAI_COMPATIBLE_API_KEY_ENV,
         _ => unreachable!("Provider should be validated before calling get_api_key"),
     };
+    let env_var = config.api_key_env.as_deref().unwrap_or(default_env_var);
 
     // Check environment variable first
     if let Ok(key) = std::env::var(env_var)
@@ -105,6 +179,19 @@ fn get_api_key(config: &Config) -> Result<String, ProviderError> {
     })
 }
 
+/// Convert `config.headers` into a validated [`HeaderMap`].
+fn build_headers(config: &Config) -> Result<HeaderMap, ProviderError> {
+    let mut headers = HeaderMap::new();
+    for (name, value) in &config.headers {
+        let header_name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| ProviderError::InvalidConfig(format!("header name '{}': {}", name, e)))?;
+        let header_value = HeaderValue::from_str(value)
+            .map_err(|e| ProviderError::InvalidConfig(format!("header '{}' value: {}", name, e)))?;
+        headers.insert(header_name, header_value);
+    }
+    Ok(headers)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -118,14 +205,28 @@ mod tests {
         Config {
             provider: provider.to_string(),
             model: "test-model".to_string(),
+            base_url: None,
+            api_key_env: None,
+            headers: Default::default(),
             api_key: api_key.map(|s| s.to_string()),
             max_tokens: 4096,
+            generation: Default::default(),
             system_prompt: None,
             system_prompt_file: None,
             session: None,
             mcp: None,
             telegram: None,
+            transcription: None,
             logging: None,
+            retry: None,
+            context: Default::default(),
+            approval: Default::default(),
+            tools: Default::default(),
+            agent: Default::default(),
+            pricing: Default::default(),
+            vision_models: Default::default(),
+            profile: None,
+            profiles: Default::default(),
         }
     }
 
@@ -163,6 +264,15 @@ mod tests {
         assert!(matches!(result, Err(ProviderError::UnknownProvider(name)) if name == "invalid"));
     }
 
+    #[test]
+    fn test_create_transcriber_requires_section() {
+        let mut config = make_config("deepseek", Some("key"));
+        assert!(create_transcriber(&config).is_none());
+
+        config.transcription = Some(Default::default());
+        assert!(create_transcriber(&config).is_some());
+    }
+
     #[test]
     fn test_get_api_key_from_env() {
         let _lock = ENV_MUTEX.lock().unwrap();
@@ -255,4 +365,79 @@ mod tests {
             matches!(result, Err(ProviderError::MissingApiKey(msg)) if msg.contains("OPENAI_API_KEY"))
         );
     }
+
+    #[test]
+    fn test_create_provider_openai_compatible_without_key() {
+        let _lock = ENV_MUTEX.lock().unwrap();
+        // SAFETY: Serialized via ENV_MUTEX; no concurrent env-var mutation.
+        unsafe { env::remove_var("OPENAI_COMPATIBLE_API_KEY") };
+
+        let mut config = make_config("openai-compatible", None);
+        config.base_url = Some("http://localhost:11434/v1".to_string());
+        let result = create_provider(&config);
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_create_provider_openai_compatible_missing_base_url() {
+        let config = make_config("openai-compatible", Some("key"));
+        let result = create_provider(&config);
+
+        assert!(
+            matches!(result, Err(ProviderError::InvalidConfig(msg)) if msg.contains("base_url"))
+        );
+    }
+
+    #[test]
+    fn test_create_provider_openai_compatible_invalid_header() {
+        let mut config = make_config("openai-compatible", None);
+        config.base_url = Some("http://localhost:8000/v1".to_string());
+        config
+            .headers
+            .insert("bad header".to_string(), "value".to_string());
+        let result = create_provider(&config);
+
+        assert!(
+            matches!(result, Err(ProviderError::InvalidConfig(msg)) if msg.contains("bad header"))
+        );
+    }
+
+    #[test]
+    fn test_create_provider_with_base_url() {
+        let mut config = make_config("openai", Some("key"));
+        config.base_url = Some("https://gateway.example.com/v1".to_string());
+        let result = create_provider(&config);
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_get_api_key_custom_env_var() {
+        let _lock = ENV_MUTEX.lock().unwrap();
+        // SAFETY: Serialized via ENV_MUTEX; no concurrent env-var mutation.
+        unsafe { env::set_var("SYNAPSE_TEST_VLLM_KEY", "custom-env-key") };
+
+        let mut config = make_config("openai-compatible", None);
+        config.api_key_env = Some("SYNAPSE_TEST_VLLM_KEY".to_string());
+        let result = get_api_key(&config);
+
+        unsafe { env::remove_var("SYNAPSE_TEST_VLLM_KEY") };
+        assert_eq!(result.unwrap(), "custom-env-key");
+    }
+
+    #[test]
+    fn test_get_api_key_missing_names_custom_env_var() {
+        let _lock = ENV_MUTEX.lock().unwrap();
+        // SAFETY: Serialized via ENV_MUTEX; no concurrent env-var mutation.
+        unsafe { env::remove_var("SYNAPSE_TEST_MISSING_KEY") };
+
+        let mut config = make_config("openai", None);
+        config.api_key_env = Some("SYNAPSE_TEST_MISSING_KEY".to_string());
+        let result = get_api_key(&config);
+
+        assert!(
+            matches!(result, Err(ProviderError::MissingApiKey(msg)) if msg.contains("SYNAPSE_TEST_MISSING_KEY"))
+        );
+    }
 }
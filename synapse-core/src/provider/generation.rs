@@ -0,0 +1,210 @@
+//! Sampling parameters for LLM requests.
+//!
+//! Defines [`GenerationOptions`], set globally in the `[generation]` config
+//! section, per profile, or per request via
+//! [`LlmProvider::complete_with_options`](super::LlmProvider::complete_with_options).
+
+use serde::{Deserialize, Serialize};
+
+/// Option name for [`GenerationOptions::temperature`].
+pub(super) const TEMPERATURE: &str = "temperature";
+/// Option name for [`GenerationOptions::top_p`].
+pub(super) const TOP_P: &str = "top_p";
+/// Option name for [`GenerationOptions::stop`].
+pub(super) const STOP: &str = "stop";
+/// Option name for [`GenerationOptions::seed`].
+pub(super) const SEED: &str = "seed";
+/// Option name for [`GenerationOptions::presence_penalty`].
+pub(super) const PRESENCE_PENALTY: &str = "presence_penalty";
+/// Option name for [`GenerationOptions::frequency_penalty`].
+pub(super) const FREQUENCY_PENALTY: &str = "frequency_penalty";
+
+/// Sampling parameters sent with a completion request.
+///
+/// Every field is optional; unset fields are left to the provider's defaults.
+/// Field names follow the OpenAI Chat Completions API, so the struct
+/// serializes directly into OpenAI-compatible request bodies. Providers map
+/// the options they support and log a warning for the rest.
+///
+/// # Examples
+///
+/// ```
+/// use synapse_core::provider::GenerationOptions;
+///
+/// let options = GenerationOptions::default()
+///     .with_temperature(0.2)
+///     .with_stop(vec!["END".to_string()]);
+/// assert_eq!(options.temperature, Some(0.2));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GenerationOptions {
+    /// Sampling temperature; higher values give more random output.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+
+    /// Nucleus sampling: only tokens within this cumulative probability are considered.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+
+    /// Sequences that stop generation when produced.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+
+    /// Seed for best-effort deterministic sampling.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+
+    /// Penalty for tokens that already appeared, encouraging new topics.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f32>,
+
+    /// Penalty proportional to how often a token already appeared.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+}
+
+impl GenerationOptions {
+    /// Set the sampling temperature.
+    pub fn with_temperature(mut self, temperature: f32) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    /// Set the nucleus sampling probability mass.
+    pub fn with_top_p(mut self, top_p: f32) -> Self {
+        self.top_p = Some(top_p);
+        self
+    }
+
+    /// Set the stop sequences.
+    pub fn with_stop(mut self, stop: Vec<String>) -> Self {
+        self.stop = Some(stop);
+        self
+    }
+
+    /// Set the sampling seed.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Set the presence penalty.
+    pub fn with_presence_penalty(mut self, penalty: f32) -> Self {
+        self.presence_penalty = Some(penalty);
+        self
+    }
+
+    /// Set the frequency penalty.
+    pub fn with_frequency_penalty(mut self, penalty: f32) -> Self {
+        self.frequency_penalty = Some(penalty);
+        self
+    }
+
+    /// Whether no option is set.
+    pub fn is_empty(&self) -> bool {
+        self.set_options().next().is_none()
+    }
+
+    /// Combine with `overrides`, whose set fields take precedence.
+    pub fn merge(&self, overrides: &Self) -> Self {
+        Self {
+            temperature: overrides.temperature.or(self.temperature),
+            top_p: overrides.top_p.or(self.top_p),
+            stop: overrides.stop.clone().or_else(|| self.stop.clone()),
+            seed: overrides.seed.or(self.seed),
+            presence_penalty: overrides.presence_penalty.or(self.presence_penalty),
+            frequency_penalty: overrides.frequency_penalty.or(self.frequency_penalty),
+        }
+    }
+
+    /// Names of the options that are set.
+    fn set_options(&self) -> impl Iterator<Item = &'static str> {
+        [
+            (TEMPERATURE, self.temperature.is_some()),
+            (TOP_P, self.top_p.is_some()),
+            (STOP, self.stop.is_some()),
+            (SEED, self.seed.is_some()),
+            (PRESENCE_PENALTY, self.presence_penalty.is_some()),
+            (FREQUENCY_PENALTY, self.frequency_penalty.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(name, set)| set.then_some(name))
+    }
+
+    /// Keep only the options in `supported`, warning about each dropped one.
+    pub(super) fn retain_supported(&self, provider: &str, supported: &[&str]) -> Self {
+        let mut options = self.clone();
+        for name in self.set_options().filter(|name| !supported.contains(name)) {
+            tracing::warn!(
+                provider,
+                option = name,
+                "generation option not supported; ignoring"
+            );
+            match name {
+                TEMPERATURE => options.temperature = None,
+                TOP_P => options.top_p = None,
+                STOP => options.stop = None,
+                SEED => options.seed = None,
+                PRESENCE_PENALTY => options.presence_penalty = None,
+                _ => options.frequency_penalty = None,
+            }
+        }
+        options
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generation_options_default_is_empty() {
+        assert!(GenerationOptions::default().is_empty());
+        assert!(!GenerationOptions::default().with_seed(7).is_empty());
+    }
+
+    #[test]
+    fn test_generation_options_merge_overrides_set_fields() {
+        let base = GenerationOptions::default()
+            .with_temperature(0.7)
+            .with_top_p(0.9);
+        let overrides = GenerationOptions::default()
+            .with_temperature(0.1)
+            .with_stop(vec!["END".to_string()]);
+
+        let merged = base.merge(&overrides);
+
+        assert_eq!(merged.temperature, Some(0.1));
+        assert_eq!(merged.top_p, Some(0.9));
+        assert_eq!(merged.stop, Some(vec!["END".to_string()]));
+        assert_eq!(merged.seed, None);
+    }
+
+    #[test]
+    fn test_generation_options_retain_supported() {
+        let options = GenerationOptions::default()
+            .with_temperature(0.5)
+            .with_seed(42)
+            .with_presence_penalty(0.3);
+
+        let retained = options.retain_supported("test", &[TEMPERATURE, TOP_P]);
+
+        assert_eq!(retained.temperature, Some(0.5));
+        assert_eq!(retained.seed, None);
+        assert_eq!(retained.presence_penalty, None);
+    }
+
+    #[test]
+    fn test_generation_options_serialization_omits_unset() {
+        let options = GenerationOptions::default()
+            .with_temperature(0.5)
+            .with_stop(vec!["\n\n".to_string()]);
+
+        let json = serde_json::to_value(&options).unwrap();
+
+        assert_eq!(json["temperature"], 0.5);
+        assert_eq!(json["stop"][0], "\n\n");
+        assert!(json.get("top_p").is_none());
+        assert!(json.get("seed").is_none());
+    }
+}
@@ -9,7 +9,7 @@ use std::sync::Mutex;
 use async_trait::async_trait;
 use futures::Stream;
 
-use super::{LlmProvider, ProviderError, StreamEvent};
+use super::{LlmProvider, ProviderError, StopReason, StreamEvent};
 use crate::mcp::ToolDefinition;
 use crate::message::{Message, Role, ToolCallData};
 
@@ -137,6 +137,22 @@ impl MockProvider {
         self
     }
 
+    /// Add a complete message, e.g. one carrying token usage, to be returned
+    /// on the next call to `complete`.
+    #[must_use]
+    pub fn with_message(self, message: Message) -> Self {
+        match self.responses.lock() {
+            Ok(mut responses) => {
+                responses.push(message);
+            }
+            Err(poisoned) => {
+                let mut responses = poisoned.into_inner();
+                responses.push(message);
+            }
+        }
+        self
+    }
+
     /// Configure tokens to yield when streaming.
     ///
     /// When streaming is called, each token is yielded as a `TextDelta`
@@ -227,13 +243,17 @@ impl LlmProvider for MockProvider {
             if let Some(content) = fallback_content {
                 // Fallback: yield the complete response as single delta
                 yield Ok(StreamEvent::TextDelta(content));
-                yield Ok(StreamEvent::Done);
+                yield Ok(StreamEvent::Done {
+                    stop_reason: StopReason::EndTurn,
+                });
             } else {
                 // Yield each token as a TextDelta
                 for token in tokens {
                     yield Ok(StreamEvent::TextDelta(token));
                 }
-                yield Ok(StreamEvent::Done);
+                yield Ok(StreamEvent::Done {
+                    stop_reason: StopReason::EndTurn,
+                });
             }
         })
     }
@@ -318,7 +338,7 @@ mod tests {
         while let Some(event) = stream.next().await {
             match event {
                 Ok(StreamEvent::TextDelta(text)) => tokens.push(text),
-                Ok(StreamEvent::Done) => break,
+                Ok(StreamEvent::Done { .. }) => break,
                 _ => {}
             }
         }
@@ -341,7 +361,7 @@ mod tests {
         while let Some(event) = stream.next().await {
             match event {
                 Ok(StreamEvent::TextDelta(text)) => tokens.push(text),
-                Ok(StreamEvent::Done) => {
+                Ok(StreamEvent::Done { .. }) => {
                     done_received = true;
                     break;
                 }
@@ -368,7 +388,7 @@ mod tests {
         }
 
         assert!(
-            matches!(last_event, Some(Ok(StreamEvent::Done))),
+            matches!(last_event, Some(Ok(StreamEvent::Done { .. }))),
             "Stream should end with Done event"
         );
     }
@@ -431,4 +451,40 @@ mod tests {
             .unwrap();
         assert_eq!(response.content, "Tool-aware response");
     }
+
+    #[tokio::test]
+    async fn test_mock_stream_with_tools_replays_tool_calls() {
+        use futures::StreamExt;
+
+        // MockProvider relies on the default stream_with_tools implementation.
+        let provider = MockProvider::new().with_tool_call_response(vec![ToolCallData {
+            id: "call_1".to_string(),
+            name: "get_weather".to_string(),
+            input: serde_json::json!({"location": "London"}),
+        }]);
+        let messages = vec![Message::new(Role::User, "Weather?")];
+        let tools = vec![crate::mcp::ToolDefinition {
+            name: "get_weather".to_string(),
+            description: None,
+            input_schema: serde_json::json!({}),
+        }];
+
+        let events: Vec<_> = provider
+            .stream_with_tools(&messages, &tools)
+            .collect()
+            .await;
+
+        assert_eq!(events.len(), 3);
+        assert!(matches!(
+            &events[0],
+            Ok(StreamEvent::ToolCallStarted { id, name }) if id == "call_1" && name == "get_weather"
+        ));
+        assert!(matches!(&events[1], Ok(StreamEvent::ToolCall(call)) if call.id == "call_1"));
+        assert!(matches!(
+            events[2],
+            Ok(StreamEvent::Done {
+                stop_reason: StopReason::ToolUse
+            })
+        ));
+    }
 }
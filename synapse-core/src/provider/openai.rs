@@ -10,12 +10,12 @@ use async_trait::async_trait;
 use futures::Stream;
 
 use super::openai_compat::OpenAiCompatProvider;
-use super::{LlmProvider, ProviderError, StreamEvent};
+use super::{GenerationOptions, LlmProvider, ProviderError, RetryPolicy, StreamEvent};
 use crate::mcp::ToolDefinition;
 use crate::message::Message;
 
-/// OpenAI Chat Completions API endpoint.
-const API_ENDPOINT: &str = "https://api.openai.com/v1/chat/completions";
+/// Default OpenAI API base URL.
+const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
 
 /// OpenAI LLM provider.
 ///
@@ -32,12 +32,24 @@ impl OpenAiProvider {
     /// * `max_tokens` - Maximum tokens to generate in API responses
     pub fn new(api_key: impl Into<String>, model: impl Into<String>, max_tokens: u32) -> Self {
         Self(OpenAiCompatProvider::new(
-            API_ENDPOINT,
+            DEFAULT_BASE_URL,
             api_key,
             model,
             max_tokens,
         ))
     }
+
+    /// Set the retry and timeout policy for API requests.
+    pub fn with_retry_policy(self, retry: RetryPolicy) -> Self {
+        Self(self.0.with_retry_policy(retry))
+    }
+
+    /// Override the API base URL (default: `https://api.openai.com/v1`).
+    ///
+    /// Requests go to `{base_url}/chat/completions`.
+    pub fn with_base_url(self, base_url: impl Into<String>) -> Self {
+        Self(self.0.with_base_url(base_url))
+    }
 }
 
 #[async_trait]
@@ -60,6 +72,32 @@ impl LlmProvider for OpenAiProvider {
     ) -> Pin<Box<dyn Stream<Item = Result<StreamEvent, ProviderError>> + Send + '_>> {
         self.0.stream(messages)
     }
+
+    fn stream_with_tools(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+    ) -> Pin<Box<dyn Stream<Item = Result<StreamEvent, ProviderError>> + Send + '_>> {
+        self.0.stream_with_tools(messages, tools)
+    }
+
+    async fn complete_with_options(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+        options: &GenerationOptions,
+    ) -> Result<Message, ProviderError> {
+        self.0.complete_with_options(messages, tools, options).await
+    }
+
+    fn stream_with_options(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+        options: &GenerationOptions,
+    ) -> Pin<Box<dyn Stream<Item = Result<StreamEvent, ProviderError>> + Send + '_>> {
+        self.0.stream_with_options(messages, tools, options)
+    }
 }
 
 #[cfg(test)]
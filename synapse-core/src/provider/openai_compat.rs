@@ -11,16 +11,22 @@ mod types;
 #[cfg(test)]
 mod tests;
 
+use std::collections::BTreeMap;
 use std::pin::Pin;
 
 use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
 use eventsource_stream::Eventsource;
 use futures::{Stream, StreamExt};
+use reqwest::header::HeaderMap;
 use types::*;
 
-use super::{LlmProvider, ProviderError, StreamEvent};
+use super::generation::{FREQUENCY_PENALTY, PRESENCE_PENALTY, SEED, STOP, TEMPERATURE, TOP_P};
+use super::streaming::PartialToolCall;
+use super::{GenerationOptions, LlmProvider, ProviderError, RetryPolicy, StopReason, StreamEvent};
 use crate::mcp::ToolDefinition;
-use crate::message::{Message, Role, ToolCallData};
+use crate::message::{Attachment, MediaSource, Message, Role, TokenUsage, ToolCallData};
 
 // ---------------------------------------------------------------------------
 // Shared helper functions
@@ -50,9 +56,22 @@ pub(super) fn build_api_messages(messages: &[Message]) -> Vec<ApiMessage> {
                         .collect()
                 });
 
+            let content = if m.attachments.is_empty() {
+                ApiContent::Text(m.content.clone())
+            } else {
+                let mut parts: Vec<ContentPart> =
+                    m.attachments.iter().map(attachment_part).collect();
+                if !m.content.is_empty() {
+                    parts.push(ContentPart::Text {
+                        text: m.content.clone(),
+                    });
+                }
+                ApiContent::Parts(parts)
+            };
+
             ApiMessage {
                 role,
-                content: Some(m.content.clone()),
+                content: Some(content),
                 tool_calls,
                 tool_call_id: m.tool_call_id.clone(),
             }
@@ -60,22 +79,95 @@ pub(super) fn build_api_messages(messages: &[Message]) -> Vec<ApiMessage> {
         .collect()
 }
 
+/// Convert an attachment into an `image_url`, `file` or text part.
+///
+/// Images and PDFs are sent as parts, text documents as their text; anything
+/// else becomes a text part describing it.
+fn attachment_part(attachment: &Attachment) -> ContentPart {
+    let data_url = |media_type: &str, data: &[u8]| {
+        format!("data:{};base64,{}", media_type, BASE64.encode(data))
+    };
+    match attachment {
+        Attachment::Image { media_type, source } => ContentPart::ImageUrl {
+            image_url: ImageUrl {
+                url: match source {
+                    MediaSource::Url(url) => url.clone(),
+                    MediaSource::Bytes(data) => data_url(media_type, data),
+                },
+            },
+        },
+        Attachment::Document {
+            media_type,
+            name,
+            source: MediaSource::Bytes(data),
+        } if media_type == "application/pdf" => ContentPart::File {
+            file: FileData {
+                filename: name.clone().unwrap_or_else(|| "document.pdf".to_string()),
+                file_data: data_url(media_type, data),
+            },
+        },
+        Attachment::Document {
+            media_type,
+            source: MediaSource::Bytes(data),
+            ..
+        } if media_type.starts_with("text/") => ContentPart::Text {
+            text: String::from_utf8_lossy(data).into_owned(),
+        },
+        Attachment::Document {
+            source: MediaSource::Url(url),
+            ..
+        } => ContentPart::Text {
+            text: format!("[document: {}]", url),
+        },
+        Attachment::Document { .. } => ContentPart::Text {
+            text: attachment.describe(),
+        },
+    }
+}
+
+/// Build the Chat Completions endpoint URL from an API base URL.
+///
+/// `https://api.openai.com/v1` becomes `https://api.openai.com/v1/chat/completions`;
+/// a trailing slash on the base URL is ignored.
+pub(super) fn chat_completions_url(base_url: &str) -> String {
+    format!("{}/chat/completions", base_url.trim_end_matches('/'))
+}
+
+/// Build a JSON POST request with bearer auth and any extra headers.
+///
+/// The `Authorization` header is omitted when `api_key` is empty, for local
+/// servers that do not require a key.
+fn post_request(
+    client: &reqwest::Client,
+    endpoint: &str,
+    api_key: &str,
+    headers: &HeaderMap,
+) -> reqwest::RequestBuilder {
+    let mut builder = client
+        .post(endpoint)
+        .header("Content-Type", "application/json")
+        .headers(headers.clone());
+    if !api_key.is_empty() {
+        builder = builder.header("Authorization", format!("Bearer {}", api_key));
+    }
+    builder
+}
+
 /// Send a non-streaming completion request and parse the response into a [`Message`].
+///
+/// Transient failures are retried according to `retry`.
 pub(super) async fn complete_request(
     client: &reqwest::Client,
+    retry: &RetryPolicy,
     endpoint: &str,
     api_key: &str,
+    headers: &HeaderMap,
     request: &ApiRequest,
 ) -> Result<Message, ProviderError> {
     tracing::debug!(endpoint, "openai_compat: POST complete request");
-    let response = client
-        .post(endpoint)
-        .header("Authorization", format!("Bearer {}", api_key))
-        .header("Content-Type", "application/json")
-        .json(request)
-        .send()
-        .await
-        .map_err(|e| ProviderError::RequestFailed(e.to_string()))?;
+    let response = retry
+        .send(|| post_request(client, endpoint, api_key, headers).json(request))
+        .await?;
 
     let status = response.status();
     tracing::debug!(
@@ -120,6 +212,10 @@ pub(super) async fn complete_request(
 
     let content = choice.message.content.clone().unwrap_or_default();
     let mut msg = Message::new(Role::Assistant, content);
+    msg.usage = api_response
+        .usage
+        .as_ref()
+        .map(|u| TokenUsage::new(u.prompt_tokens, u.completion_tokens));
 
     if let Some(ref tool_calls) = choice.message.tool_calls {
         let parsed: Vec<ToolCallData> = tool_calls
@@ -169,39 +265,26 @@ pub(super) fn to_oai_tools(tools: &[ToolDefinition]) -> Option<Vec<OaiTool>> {
 /// Stream SSE tokens from an OpenAI-compatible endpoint.
 ///
 /// Returns a pinned, owned stream so callers do not need to hold a reference
-/// to the provider. Yields [`StreamEvent::TextDelta`] for each non-empty token
-/// and [`StreamEvent::Done`] when the stream ends.
+/// to the provider. Yields [`StreamEvent::TextDelta`] for each non-empty token,
+/// [`StreamEvent::ToolCall`] for each assembled tool call, and
+/// [`StreamEvent::Done`] when the stream ends.
 pub(super) fn stream_sse(
     client: reqwest::Client,
+    retry: RetryPolicy,
     endpoint: String,
     api_key: String,
-    model: String,
-    messages: Vec<Message>,
-    max_tokens: u32,
+    headers: HeaderMap,
+    request: StreamingApiRequest,
 ) -> Pin<Box<dyn Stream<Item = Result<StreamEvent, ProviderError>> + Send>> {
     Box::pin(async_stream::stream! {
-        let api_messages = build_api_messages(&messages);
-
-        let request = StreamingApiRequest {
-            model,
-            messages: api_messages,
-            max_tokens,
-            stream: true,
-            tools: None,
-        };
-
-        let response = client
-            .post(&endpoint)
-            .header("Authorization", format!("Bearer {}", api_key))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
+        let response = retry
+            .send(|| post_request(&client, &endpoint, &api_key, &headers).json(&request))
             .await;
 
         let response = match response {
             Ok(r) => r,
             Err(e) => {
-                yield Err(ProviderError::RequestFailed(e.to_string()));
+                yield Err(e);
                 return;
             }
         };
@@ -220,24 +303,85 @@ pub(super) fn stream_sse(
         }
 
         tracing::debug!(endpoint, "openai_compat: SSE stream started");
-        let mut sse_stream = response.bytes_stream().eventsource();
+        let mut events = parse_sse_stream(response.bytes_stream());
+        while let Some(event) = events.next().await {
+            yield event;
+        }
+        tracing::debug!(endpoint, "openai_compat: SSE stream ended");
+    })
+}
+
+/// Parse a byte stream of Chat Completions SSE chunks into stream events.
+///
+/// Tool call fragments are buffered by `index`: the first fragment yields
+/// [`StreamEvent::ToolCallStarted`], argument fragments yield
+/// [`StreamEvent::ToolCallArgumentsDelta`], and the complete
+/// [`StreamEvent::ToolCall`]s are emitted in index order just before
+/// [`StreamEvent::Done`]. A usage chunk yields [`StreamEvent::Usage`].
+pub(super) fn parse_sse_stream<S, B, E>(
+    bytes: S,
+) -> Pin<Box<dyn Stream<Item = Result<StreamEvent, ProviderError>> + Send>>
+where
+    S: Stream<Item = Result<B, E>> + Send + 'static,
+    B: AsRef<[u8]> + Send + 'static,
+    E: std::fmt::Display + Send + 'static,
+{
+    Box::pin(async_stream::stream! {
+        let sse_stream = bytes.eventsource();
+        futures::pin_mut!(sse_stream);
+        let mut tool_calls: BTreeMap<usize, PartialToolCall> = BTreeMap::new();
+        let mut stop_reason = StopReason::EndTurn;
 
         while let Some(event) = sse_stream.next().await {
             match event {
                 Ok(event) => {
                     if event.data == SSE_DONE_MARKER {
-                        tracing::debug!(endpoint, "openai_compat: SSE stream ended");
-                        yield Ok(StreamEvent::Done);
-                        return;
+                        break;
                     }
 
                     match serde_json::from_str::<StreamChunk>(&event.data) {
                         Ok(chunk) => {
-                            if let Some(choice) = chunk.choices.first()
-                                && let Some(content) = &choice.delta.content
+                            if let Some(usage) = chunk.usage {
+                                yield Ok(StreamEvent::Usage {
+                                    input: usage.prompt_tokens,
+                                    output: usage.completion_tokens,
+                                });
+                            }
+                            let Some(choice) = chunk.choices.into_iter().next() else {
+                                continue;
+                            };
+                            if let Some(reason) = choice.finish_reason {
+                                stop_reason = StopReason::from(reason.as_str());
+                            }
+                            if let Some(content) = choice.delta.content
                                 && !content.is_empty()
                             {
-                                yield Ok(StreamEvent::TextDelta(content.clone()));
+                                yield Ok(StreamEvent::TextDelta(content));
+                            }
+                            for fragment in choice.delta.tool_calls.unwrap_or_default() {
+                                let partial = tool_calls.entry(fragment.index).or_default();
+                                if let Some(id) = fragment.id {
+                                    partial.id = id;
+                                }
+                                let Some(function) = fragment.function else {
+                                    continue;
+                                };
+                                if let Some(name) = function.name {
+                                    partial.name.push_str(&name);
+                                    yield Ok(StreamEvent::ToolCallStarted {
+                                        id: partial.id.clone(),
+                                        name: partial.name.clone(),
+                                    });
+                                }
+                                if let Some(arguments) = function.arguments
+                                    && !arguments.is_empty()
+                                {
+                                    partial.arguments.push_str(&arguments);
+                                    yield Ok(StreamEvent::ToolCallArgumentsDelta {
+                                        id: partial.id.clone(),
+                                        delta: arguments,
+                                    });
+                                }
                             }
                         }
                         Err(e) => {
@@ -255,9 +399,11 @@ pub(super) fn stream_sse(
             }
         }
 
-        // Stream ended without [DONE] – still signal completion.
-        tracing::debug!(endpoint, "openai_compat: SSE stream ended");
-        yield Ok(StreamEvent::Done);
+        // Emit on [DONE] or when the stream ends without it.
+        for partial in std::mem::take(&mut tool_calls).into_values() {
+            yield Ok(StreamEvent::ToolCall(partial.finish()));
+        }
+        yield Ok(StreamEvent::Done { stop_reason });
     })
 }
 
@@ -265,16 +411,32 @@ pub(super) fn stream_sse(
 // Generic OpenAI-compatible provider struct
 // ---------------------------------------------------------------------------
 
+/// Generation options understood by the OpenAI Chat Completions API.
+pub(super) const ALL_GENERATION_OPTIONS: &[&str] = &[
+    TEMPERATURE,
+    TOP_P,
+    STOP,
+    SEED,
+    PRESENCE_PENALTY,
+    FREQUENCY_PENALTY,
+];
+
 /// Generic provider for OpenAI-compatible Chat Completions APIs.
 ///
-/// Holds the base URL, API key, model, and max tokens. Implements all
-/// `LlmProvider` methods using the shared helpers in this module.
+/// Holds the base URL, API key, extra headers, model, max tokens, retry
+/// policy, and the generation options the backend accepts. Implements all
+/// `LlmProvider` methods using the shared helpers in this module. An empty
+/// API key sends no `Authorization` header.
 pub(super) struct OpenAiCompatProvider {
     pub(super) client: reqwest::Client,
     pub(super) base_url: String,
     pub(super) api_key: String,
+    pub(super) headers: HeaderMap,
     pub(super) model: String,
     pub(super) max_tokens: u32,
+    pub(super) retry: RetryPolicy,
+    pub(super) name: &'static str,
+    pub(super) supported_options: &'static [&'static str],
 }
 
 impl OpenAiCompatProvider {
@@ -285,28 +447,57 @@ impl OpenAiCompatProvider {
         model: impl Into<String>,
         max_tokens: u32,
     ) -> Self {
+        let retry = RetryPolicy::default();
         Self {
-            client: reqwest::Client::new(),
+            client: retry.build_client(),
             base_url: base_url.into(),
             api_key: api_key.into(),
+            headers: HeaderMap::new(),
             model: model.into(),
             max_tokens,
+            retry,
+            name: "openai-compatible",
+            supported_options: ALL_GENERATION_OPTIONS,
         }
     }
+
+    /// Set the retry and timeout policy for API requests.
+    pub(super) fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.client = retry.build_client();
+        self.retry = retry;
+        self
+    }
+
+    /// Replace the API base URL (e.g. for a proxy or self-hosted gateway).
+    pub(super) fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Set extra headers sent with every request.
+    pub(super) fn with_headers(mut self, headers: HeaderMap) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    /// Restrict the generation options sent to the backend; others are
+    /// dropped with a warning naming `provider`.
+    pub(super) fn with_supported_options(
+        mut self,
+        provider: &'static str,
+        supported: &'static [&'static str],
+    ) -> Self {
+        self.name = provider;
+        self.supported_options = supported;
+        self
+    }
 }
 
 #[async_trait]
 impl LlmProvider for OpenAiCompatProvider {
     async fn complete(&self, messages: &[Message]) -> Result<Message, ProviderError> {
-        let api_messages = build_api_messages(messages);
-        let request = ApiRequest {
-            model: self.model.clone(),
-            messages: api_messages,
-            max_tokens: self.max_tokens,
-            tools: None,
-            tool_choice: None,
-        };
-        complete_request(&self.client, &self.base_url, &self.api_key, &request).await
+        self.complete_with_options(messages, &[], &GenerationOptions::default())
+            .await
     }
 
     async fn complete_with_tools(
@@ -314,10 +505,34 @@ impl LlmProvider for OpenAiCompatProvider {
         messages: &[Message],
         tools: &[ToolDefinition],
     ) -> Result<Message, ProviderError> {
-        let api_messages = build_api_messages(messages);
+        self.complete_with_options(messages, tools, &GenerationOptions::default())
+            .await
+    }
+
+    fn stream(
+        &self,
+        messages: &[Message],
+    ) -> Pin<Box<dyn Stream<Item = Result<StreamEvent, ProviderError>> + Send + '_>> {
+        self.stream_with_options(messages, &[], &GenerationOptions::default())
+    }
+
+    fn stream_with_tools(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+    ) -> Pin<Box<dyn Stream<Item = Result<StreamEvent, ProviderError>> + Send + '_>> {
+        self.stream_with_options(messages, tools, &GenerationOptions::default())
+    }
+
+    async fn complete_with_options(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+        options: &GenerationOptions,
+    ) -> Result<Message, ProviderError> {
         let request = ApiRequest {
             model: self.model.clone(),
-            messages: api_messages,
+            messages: build_api_messages(messages),
             max_tokens: self.max_tokens,
             tools: to_oai_tools(tools),
             tool_choice: if tools.is_empty() {
@@ -325,21 +540,48 @@ impl LlmProvider for OpenAiCompatProvider {
             } else {
                 Some("auto".to_string())
             },
+            options: options.retain_supported(self.name, self.supported_options),
         };
-        complete_request(&self.client, &self.base_url, &self.api_key, &request).await
+        complete_request(
+            &self.client,
+            &self.retry,
+            &chat_completions_url(&self.base_url),
+            &self.api_key,
+            &self.headers,
+            &request,
+        )
+        .await
     }
 
-    fn stream(
+    fn stream_with_options(
         &self,
         messages: &[Message],
+        tools: &[ToolDefinition],
+        options: &GenerationOptions,
     ) -> Pin<Box<dyn Stream<Item = Result<StreamEvent, ProviderError>> + Send + '_>> {
+        let request = StreamingApiRequest {
+            model: self.model.clone(),
+            messages: build_api_messages(messages),
+            max_tokens: self.max_tokens,
+            stream: true,
+            tools: to_oai_tools(tools),
+            tool_choice: if tools.is_empty() {
+                None
+            } else {
+                Some("auto".to_string())
+            },
+            stream_options: Some(StreamOptions {
+                include_usage: true,
+            }),
+            options: options.retain_supported(self.name, self.supported_options),
+        };
         stream_sse(
             self.client.clone(),
-            self.base_url.clone(),
+            self.retry.clone(),
+            chat_completions_url(&self.base_url),
             self.api_key.clone(),
-            self.model.clone(),
-            messages.to_vec(),
-            self.max_tokens,
+            self.headers.clone(),
+            request,
         )
     }
 }
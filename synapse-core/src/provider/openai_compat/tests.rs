@@ -1,6 +1,9 @@
+use std::time::Duration;
+
 use super::types::*;
 use super::*;
-use crate::message::ToolCallData;
+use crate::message::{Attachment, MediaSource, ToolCallData};
+use crate::provider::stub_server::StubServer;
 
 // -- ApiRequest serialisation --
 
@@ -10,13 +13,14 @@ fn test_api_request_serialization() {
         model: "test-model".to_string(),
         messages: vec![ApiMessage {
             role: "user".to_string(),
-            content: Some("Hello".to_string()),
+            content: Some(ApiContent::Text("Hello".to_string())),
             tool_calls: None,
             tool_call_id: None,
         }],
         max_tokens: 1024,
         tools: None,
         tool_choice: None,
+        options: GenerationOptions::default(),
     };
 
     let json = serde_json::to_value(&request).unwrap();
@@ -34,13 +38,13 @@ fn test_api_request_with_system_message() {
         messages: vec![
             ApiMessage {
                 role: "system".to_string(),
-                content: Some("You are a helpful assistant.".to_string()),
+                content: Some(ApiContent::Text("You are a helpful assistant.".to_string())),
                 tool_calls: None,
                 tool_call_id: None,
             },
             ApiMessage {
                 role: "user".to_string(),
-                content: Some("Hello".to_string()),
+                content: Some(ApiContent::Text("Hello".to_string())),
                 tool_calls: None,
                 tool_call_id: None,
             },
@@ -48,6 +52,7 @@ fn test_api_request_with_system_message() {
         max_tokens: 1024,
         tools: None,
         tool_choice: None,
+        options: GenerationOptions::default(),
     };
 
     let json = serde_json::to_value(&request).unwrap();
@@ -66,18 +71,24 @@ fn test_streaming_request_serialization() {
         model: "test-model".to_string(),
         messages: vec![ApiMessage {
             role: "user".to_string(),
-            content: Some("Hello".to_string()),
+            content: Some(ApiContent::Text("Hello".to_string())),
             tool_calls: None,
             tool_call_id: None,
         }],
         max_tokens: 1024,
         stream: true,
         tools: None,
+        tool_choice: None,
+        stream_options: Some(StreamOptions {
+            include_usage: true,
+        }),
+        options: GenerationOptions::default(),
     };
 
     let json = serde_json::to_value(&request).unwrap();
     assert_eq!(json["model"], "test-model");
     assert_eq!(json["stream"], true);
+    assert_eq!(json["stream_options"]["include_usage"], true);
     assert_eq!(json["max_tokens"], 1024);
 }
 
@@ -108,6 +119,21 @@ fn test_api_response_parsing() {
     );
 }
 
+#[test]
+fn test_api_response_parsing_usage() {
+    let json = r#"{
+        "choices": [
+            {"index": 0, "message": {"role": "assistant", "content": "Hi"}, "finish_reason": "stop"}
+        ],
+        "usage": {"prompt_tokens": 18, "completion_tokens": 4, "total_tokens": 22}
+    }"#;
+
+    let response: ApiResponse = serde_json::from_str(json).unwrap();
+    let usage = response.usage.expect("usage missing");
+    assert_eq!(usage.prompt_tokens, 18);
+    assert_eq!(usage.completion_tokens, 4);
+}
+
 #[test]
 fn test_api_error_parsing() {
     let json = r#"{
@@ -220,13 +246,14 @@ fn test_complete_with_tools_serialization() {
         model: "test-model".to_string(),
         messages: vec![ApiMessage {
             role: "user".to_string(),
-            content: Some("What's the weather?".to_string()),
+            content: Some(ApiContent::Text("What's the weather?".to_string())),
             tool_calls: None,
             tool_call_id: None,
         }],
         max_tokens: 1024,
         tools: Some(tools),
         tool_choice: Some("auto".to_string()),
+        options: GenerationOptions::default(),
     };
 
     let json = serde_json::to_value(&request).unwrap();
@@ -242,13 +269,14 @@ fn test_complete_with_tools_no_tools() {
         model: "test-model".to_string(),
         messages: vec![ApiMessage {
             role: "user".to_string(),
-            content: Some("Hello".to_string()),
+            content: Some(ApiContent::Text("Hello".to_string())),
             tool_calls: None,
             tool_call_id: None,
         }],
         max_tokens: 1024,
         tools: None,
         tool_choice: None,
+        options: GenerationOptions::default(),
     };
 
     let json = serde_json::to_value(&request).unwrap();
@@ -261,13 +289,14 @@ fn test_api_request_tool_choice_absent_without_tools() {
         model: "test-model".to_string(),
         messages: vec![ApiMessage {
             role: "user".to_string(),
-            content: Some("Hello".to_string()),
+            content: Some(ApiContent::Text("Hello".to_string())),
             tool_calls: None,
             tool_call_id: None,
         }],
         max_tokens: 1024,
         tools: None,
         tool_choice: None,
+        options: GenerationOptions::default(),
     };
 
     let json = serde_json::to_value(&request).unwrap();
@@ -312,7 +341,10 @@ fn test_tool_role_message_serialization() {
 
     assert_eq!(api_messages[0].role, "tool");
     assert_eq!(api_messages[0].tool_call_id, Some("call_1".to_string()));
-    assert_eq!(api_messages[0].content, Some("Sunny, 20C".to_string()));
+    assert_eq!(
+        api_messages[0].content,
+        Some(ApiContent::Text("Sunny, 20C".to_string()))
+    );
 }
 
 #[test]
@@ -343,6 +375,43 @@ fn test_assistant_tool_call_message_serialization() {
     assert_eq!(args["location"], "London");
 }
 
+#[test]
+fn test_attachment_parts_serialization() {
+    let message = Message::new(Role::User, "Compare these")
+        .with_attachment(Attachment::image("image/png", b"png".to_vec()))
+        .with_attachment(Attachment::Image {
+            media_type: "image/jpeg".to_string(),
+            source: MediaSource::Url("https://example.com/a.jpg".to_string()),
+        })
+        .with_attachment(Attachment::document(
+            "application/pdf",
+            Some("report.pdf".to_string()),
+            b"%PDF".to_vec(),
+        ))
+        .with_attachment(Attachment::document(
+            "text/markdown",
+            None,
+            b"# Notes".to_vec(),
+        ));
+
+    let api_messages = build_api_messages(&[message]);
+    let json = serde_json::to_value(&api_messages[0]).unwrap();
+
+    assert_eq!(
+        json["content"],
+        serde_json::json!([
+            {"type": "image_url", "image_url": {"url": "data:image/png;base64,cG5n"}},
+            {"type": "image_url", "image_url": {"url": "https://example.com/a.jpg"}},
+            {
+                "type": "file",
+                "file": {"filename": "report.pdf", "file_data": "data:application/pdf;base64,JVBERg=="}
+            },
+            {"type": "text", "text": "# Notes"},
+            {"type": "text", "text": "Compare these"}
+        ])
+    );
+}
+
 // -- to_oai_tools helper --
 
 #[test]
@@ -363,3 +432,195 @@ fn test_to_oai_tools_conversion() {
     assert_eq!(result[0].tool_type, "function");
     assert_eq!(result[0].function.name, "test_tool");
 }
+
+// -- parse_sse_stream --
+
+/// Recorded stream with leading text and two parallel tool calls.
+const TOOL_CALL_STREAM: &str = include_str!("fixtures/tool_call_stream.sse");
+
+async fn collect_sse(fixture: &str) -> Vec<Result<StreamEvent, ProviderError>> {
+    let chunks: Vec<Result<Vec<u8>, std::convert::Infallible>> = fixture
+        .as_bytes()
+        .chunks(16)
+        .map(|c| Ok(c.to_vec()))
+        .collect();
+    parse_sse_stream(futures::stream::iter(chunks))
+        .collect()
+        .await
+}
+
+#[tokio::test]
+async fn test_parse_sse_stream_assembles_tool_calls() {
+    let events = collect_sse(TOOL_CALL_STREAM).await;
+
+    assert!(matches!(&events[0], Ok(StreamEvent::TextDelta(t)) if t == "Checking."));
+    let calls: Vec<&ToolCallData> = events
+        .iter()
+        .filter_map(|e| match e {
+            Ok(StreamEvent::ToolCall(call)) => Some(call),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(calls.len(), 2);
+    assert_eq!(calls[0].id, "call_abc");
+    assert_eq!(calls[0].name, "get_weather");
+    assert_eq!(calls[0].input, serde_json::json!({"location": "London"}));
+    assert_eq!(calls[1].id, "call_def");
+    assert_eq!(calls[1].name, "get_time");
+
+    let started: Vec<(&str, &str)> = events
+        .iter()
+        .filter_map(|e| match e {
+            Ok(StreamEvent::ToolCallStarted { id, name }) => Some((id.as_str(), name.as_str())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        started,
+        vec![("call_abc", "get_weather"), ("call_def", "get_time")]
+    );
+    let arguments: String = events
+        .iter()
+        .filter_map(|e| match e {
+            Ok(StreamEvent::ToolCallArgumentsDelta { id, delta }) if id == "call_abc" => {
+                Some(delta.as_str())
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(arguments, "{\"location\": \"London\"}");
+
+    assert!(events.iter().any(|e| matches!(
+        e,
+        Ok(StreamEvent::Usage {
+            input: 82,
+            output: 41
+        })
+    )));
+    assert!(matches!(
+        events.last(),
+        Some(Ok(StreamEvent::Done {
+            stop_reason: StopReason::ToolUse
+        }))
+    ));
+}
+
+#[tokio::test]
+async fn test_parse_sse_stream_text_only() {
+    let fixture = "data: {\"choices\":[{\"delta\":{\"content\":\"Hi\"}}]}\n\n\
+                   data: {\"choices\":[{\"delta\":{\"content\":\"\"}}]}\n\n\
+                   data: [DONE]\n\n";
+    let events = collect_sse(fixture).await;
+
+    assert_eq!(events.len(), 2);
+    assert!(matches!(&events[0], Ok(StreamEvent::TextDelta(t)) if t == "Hi"));
+    assert!(matches!(events[1], Ok(StreamEvent::Done { .. })));
+}
+
+#[test]
+fn test_streaming_request_with_tools_serialization() {
+    let request = StreamingApiRequest {
+        model: "test-model".to_string(),
+        messages: vec![],
+        max_tokens: 1024,
+        stream: true,
+        tools: to_oai_tools(&[ToolDefinition {
+            name: "test_tool".to_string(),
+            description: None,
+            input_schema: serde_json::json!({"type": "object"}),
+        }]),
+        tool_choice: Some("auto".to_string()),
+        stream_options: None,
+        options: GenerationOptions::default(),
+    };
+
+    let json = serde_json::to_value(&request).unwrap();
+    assert_eq!(json["tools"][0]["function"]["name"], "test_tool");
+    assert_eq!(json["tool_choice"], "auto");
+}
+
+// -- Retry against a stub server --
+
+#[tokio::test]
+async fn test_complete_retries_server_error() {
+    let body = r#"{"choices":[{"index":0,"message":{"role":"assistant","content":"Recovered"},"finish_reason":"stop"}]}"#;
+    let server = StubServer::start(vec![
+        StubServer::response(503, "", r#"{"error":{"message":"Service Unavailable"}}"#),
+        StubServer::response(200, "content-type: application/json\r\n", body),
+    ])
+    .await;
+    let provider = OpenAiCompatProvider::new(server.url(), "key", "model", 64).with_retry_policy(
+        RetryPolicy {
+            initial_backoff: Duration::from_millis(1),
+            ..RetryPolicy::default()
+        },
+    );
+
+    let response = provider
+        .complete(&[Message::new(Role::User, "Hi")])
+        .await
+        .expect("should succeed after retry");
+
+    assert_eq!(response.content, "Recovered");
+    assert_eq!(server.request_count(), 2);
+}
+
+#[test]
+fn test_api_request_serializes_generation_options() {
+    let request = ApiRequest {
+        model: "test-model".to_string(),
+        messages: vec![],
+        max_tokens: 1024,
+        tools: None,
+        tool_choice: None,
+        options: GenerationOptions::default()
+            .with_seed(7)
+            .with_presence_penalty(0.5)
+            .with_stop(vec!["END".to_string()]),
+    };
+
+    let json = serde_json::to_value(&request).unwrap();
+    assert_eq!(json["seed"], 7);
+    assert_eq!(json["presence_penalty"], 0.5);
+    assert_eq!(json["stop"][0], "END");
+    assert!(json.get("temperature").is_none());
+}
+
+#[tokio::test]
+async fn test_complete_with_options_drops_unsupported() {
+    let body = r#"{"choices":[{"index":0,"message":{"role":"assistant","content":"Ok"},"finish_reason":"stop"}]}"#;
+    let server = StubServer::start(vec![StubServer::response(
+        200,
+        "content-type: application/json\r\n",
+        body,
+    )])
+    .await;
+    let provider = OpenAiCompatProvider::new(server.url(), "key", "model", 64)
+        .with_supported_options("test", &[TEMPERATURE]);
+    let options = GenerationOptions::default()
+        .with_temperature(0.25)
+        .with_seed(42);
+
+    provider
+        .complete_with_options(&[Message::new(Role::User, "Hi")], &[], &options)
+        .await
+        .expect("request should succeed");
+
+    let request = server.last_request().expect("request recorded");
+    assert!(request.contains(r#""temperature":0.25"#));
+    assert!(!request.contains("seed"));
+}
+
+// -- Endpoint construction --
+
+#[test]
+fn test_chat_completions_url_trims_trailing_slash() {
+    assert_eq!(
+        chat_completions_url("http://localhost:11434/v1"),
+        "http://localhost:11434/v1/chat/completions"
+    );
+    assert_eq!(
+        chat_completions_url("http://localhost:8000/v1/"),
+        "http://localhost:8000/v1/chat/completions"
+    );
+}
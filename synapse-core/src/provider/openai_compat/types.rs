@@ -5,6 +5,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::provider::GenerationOptions;
+
 /// SSE "[DONE]" marker sent by OpenAI-compatible streaming APIs.
 pub(in super::super) const SSE_DONE_MARKER: &str = "[DONE]";
 
@@ -15,7 +17,7 @@ pub(in super::super) struct ApiMessage {
     pub(in super::super) role: String,
     /// Message content.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub(in super::super) content: Option<String>,
+    pub(in super::super) content: Option<ApiContent>,
     /// Tool calls (present in assistant messages with tool use).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(in super::super) tool_calls: Option<Vec<OaiToolCall>>,
@@ -24,6 +26,41 @@ pub(in super::super) struct ApiMessage {
     pub(in super::super) tool_call_id: Option<String>,
 }
 
+/// Message content: plain text, or parts when the message has attachments.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+pub(in super::super) enum ApiContent {
+    /// Simple text content.
+    Text(String),
+    /// Text, image and file parts.
+    Parts(Vec<ContentPart>),
+}
+
+/// A part of multi-part message content.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(in super::super) enum ContentPart {
+    /// Text part.
+    Text { text: String },
+    /// Image given by URL or `data:` URL.
+    ImageUrl { image_url: ImageUrl },
+    /// File sent inline as a `data:` URL.
+    File { file: FileData },
+}
+
+/// Image reference within an `image_url` part.
+#[derive(Debug, PartialEq, Serialize)]
+pub(in super::super) struct ImageUrl {
+    pub(in super::super) url: String,
+}
+
+/// Inline file within a `file` part.
+#[derive(Debug, PartialEq, Serialize)]
+pub(in super::super) struct FileData {
+    pub(in super::super) filename: String,
+    pub(in super::super) file_data: String,
+}
+
 /// Request body for a non-streaming Chat Completions API call.
 #[derive(Debug, Serialize)]
 pub(in super::super) struct ApiRequest {
@@ -40,6 +77,9 @@ pub(in super::super) struct ApiRequest {
     /// Omitted when `None` so the API default (`"auto"`) applies implicitly.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(in super::super) tool_choice: Option<String>,
+    /// Sampling parameters; unset options are omitted.
+    #[serde(flatten)]
+    pub(in super::super) options: GenerationOptions,
 }
 
 /// Request body for a streaming Chat Completions API call.
@@ -56,6 +96,22 @@ pub(in super::super) struct StreamingApiRequest {
     /// Optional tool definitions.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(in super::super) tools: Option<Vec<OaiTool>>,
+    /// Tool choice strategy; omitted when `None`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(in super::super) tool_choice: Option<String>,
+    /// Extra streaming options; used to request a final usage chunk.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(in super::super) stream_options: Option<StreamOptions>,
+    /// Sampling parameters; unset options are omitted.
+    #[serde(flatten)]
+    pub(in super::super) options: GenerationOptions,
+}
+
+/// Options for a streaming Chat Completions API call.
+#[derive(Debug, Serialize)]
+pub(in super::super) struct StreamOptions {
+    /// Ask the API to send token usage in a final chunk with empty `choices`.
+    pub(in super::super) include_usage: bool,
 }
 
 /// Tool definition in OpenAI-compatible format.
@@ -95,6 +151,9 @@ pub(in super::super) struct OaiToolCallFunction {
 #[derive(Debug, Deserialize)]
 pub(in super::super) struct ApiResponse {
     pub(in super::super) choices: Vec<Choice>,
+    /// Token usage for this request.
+    #[serde(default)]
+    pub(in super::super) usage: Option<ApiUsage>,
 }
 
 /// A choice in the completion response.
@@ -126,18 +185,27 @@ pub(in super::super) struct ErrorDetail {
 /// An SSE streaming response chunk.
 #[derive(Debug, Deserialize)]
 pub(in super::super) struct StreamChunk {
+    #[serde(default)]
     pub(in super::super) choices: Vec<StreamChoice>,
+    /// Token usage, present only in the final chunk when requested via
+    /// [`StreamOptions::include_usage`].
+    #[serde(default)]
+    pub(in super::super) usage: Option<ApiUsage>,
+}
+
+/// Token usage reported in a response body or the final streaming chunk.
+#[derive(Debug, Deserialize)]
+pub(in super::super) struct ApiUsage {
+    pub(in super::super) prompt_tokens: u32,
+    pub(in super::super) completion_tokens: u32,
 }
 
 /// A choice in a streaming response chunk.
 #[derive(Debug, Deserialize)]
 pub(in super::super) struct StreamChoice {
     pub(in super::super) delta: StreamDelta,
-    /// Deserialized from the API response for serde completeness but not
-    /// read at runtime. Suppressed with `#[allow(dead_code)]` to avoid a
-    /// compiler warning while keeping the field available for future use.
+    /// Why generation stopped; set only on the last content chunk.
     #[serde(default)]
-    #[allow(dead_code)]
     pub(in super::super) finish_reason: Option<String>,
 }
 
@@ -145,4 +213,29 @@ pub(in super::super) struct StreamChoice {
 #[derive(Debug, Deserialize)]
 pub(in super::super) struct StreamDelta {
     pub(in super::super) content: Option<String>,
+    /// Tool call fragments, keyed by `index` across chunks.
+    #[serde(default)]
+    pub(in super::super) tool_calls: Option<Vec<StreamToolCallDelta>>,
+}
+
+/// A fragment of a tool call in a streaming delta.
+///
+/// The first fragment for an `index` carries the `id` and function name;
+/// later fragments only append to `function.arguments`.
+#[derive(Debug, Deserialize)]
+pub(in super::super) struct StreamToolCallDelta {
+    pub(in super::super) index: usize,
+    #[serde(default)]
+    pub(in super::super) id: Option<String>,
+    #[serde(default)]
+    pub(in super::super) function: Option<StreamFunctionDelta>,
+}
+
+/// Function name and argument fragment within a streaming tool call delta.
+#[derive(Debug, Deserialize)]
+pub(in super::super) struct StreamFunctionDelta {
+    #[serde(default)]
+    pub(in super::super) name: Option<String>,
+    #[serde(default)]
+    pub(in super::super) arguments: Option<String>,
 }
@@ -0,0 +1,164 @@
+//! Generic OpenAI-compatible LLM provider.
+//!
+//! Implements the [`LlmProvider`] trait for any server speaking the OpenAI
+//! Chat Completions wire format (Ollama, vLLM, LM Studio, gateways) by
+//! delegating to the shared [`openai_compat`](super::openai_compat) module.
+//! Unlike the hosted providers, the base URL is required and the API key is
+//! optional.
+
+use std::pin::Pin;
+
+use async_trait::async_trait;
+use futures::Stream;
+use reqwest::header::HeaderMap;
+
+use super::openai_compat::OpenAiCompatProvider;
+use super::{GenerationOptions, LlmProvider, ProviderError, RetryPolicy, StreamEvent};
+use crate::mcp::ToolDefinition;
+use crate::message::Message;
+
+/// Provider for self-hosted or third-party OpenAI-compatible APIs.
+///
+/// # Examples
+///
+/// ```no_run
+/// use synapse_core::provider::{LlmProvider, OpenAiCompatibleProvider};
+/// use synapse_core::message::{Message, Role};
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// // Ollama exposes its OpenAI-compatible API under /v1 and needs no key.
+/// let provider = OpenAiCompatibleProvider::new("http://localhost:11434/v1", None, "llama3.1", 4096);
+/// let messages = vec![Message::new(Role::User, "Hello!")];
+///
+/// let response = provider.complete(&messages).await?;
+/// println!("{}", response.content);
+/// # Ok(())
+/// # }
+/// ```
+pub struct OpenAiCompatibleProvider(pub(super) OpenAiCompatProvider);
+
+impl OpenAiCompatibleProvider {
+    /// Create a new OpenAI-compatible provider.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_url` - API base URL; requests go to `{base_url}/chat/completions`
+    /// * `api_key` - Bearer token, or `None` to send no `Authorization` header
+    /// * `model` - Model identifier as known to the server
+    /// * `max_tokens` - Maximum tokens to generate in API responses
+    pub fn new(
+        base_url: impl Into<String>,
+        api_key: Option<String>,
+        model: impl Into<String>,
+        max_tokens: u32,
+    ) -> Self {
+        Self(OpenAiCompatProvider::new(
+            base_url,
+            api_key.unwrap_or_default(),
+            model,
+            max_tokens,
+        ))
+    }
+
+    /// Set the retry and timeout policy for API requests.
+    pub fn with_retry_policy(self, retry: RetryPolicy) -> Self {
+        Self(self.0.with_retry_policy(retry))
+    }
+
+    /// Set extra headers sent with every request (e.g. gateway auth).
+    pub fn with_headers(self, headers: HeaderMap) -> Self {
+        Self(self.0.with_headers(headers))
+    }
+}
+
+#[async_trait]
+impl LlmProvider for OpenAiCompatibleProvider {
+    async fn complete(&self, messages: &[Message]) -> Result<Message, ProviderError> {
+        self.0.complete(messages).await
+    }
+
+    async fn complete_with_tools(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+    ) -> Result<Message, ProviderError> {
+        self.0.complete_with_tools(messages, tools).await
+    }
+
+    fn stream(
+        &self,
+        messages: &[Message],
+    ) -> Pin<Box<dyn Stream<Item = Result<StreamEvent, ProviderError>> + Send + '_>> {
+        self.0.stream(messages)
+    }
+
+    fn stream_with_tools(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+    ) -> Pin<Box<dyn Stream<Item = Result<StreamEvent, ProviderError>> + Send + '_>> {
+        self.0.stream_with_tools(messages, tools)
+    }
+
+    async fn complete_with_options(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+        options: &GenerationOptions,
+    ) -> Result<Message, ProviderError> {
+        self.0.complete_with_options(messages, tools, options).await
+    }
+
+    fn stream_with_options(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+        options: &GenerationOptions,
+    ) -> Pin<Box<dyn Stream<Item = Result<StreamEvent, ProviderError>> + Send + '_>> {
+        self.0.stream_with_options(messages, tools, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+    use crate::message::Role;
+    use crate::provider::stub_server::StubServer;
+
+    #[test]
+    fn test_openai_compatible_provider_new_without_key() {
+        let provider =
+            OpenAiCompatibleProvider::new("http://localhost:11434/v1", None, "llama3.1", 2048);
+        assert_eq!(provider.0.base_url, "http://localhost:11434/v1");
+        assert!(provider.0.api_key.is_empty());
+        assert_eq!(provider.0.model, "llama3.1");
+    }
+
+    #[tokio::test]
+    async fn test_openai_compatible_provider_sends_custom_headers() {
+        let body = r#"{"choices":[{"index":0,"message":{"role":"assistant","content":"Hi"},"finish_reason":"stop"}]}"#;
+        let server = StubServer::start(vec![StubServer::response(
+            200,
+            "content-type: application/json\r\n",
+            body,
+        )])
+        .await;
+        let mut headers = HeaderMap::new();
+        headers.insert("x-gateway-token", HeaderValue::from_static("secret"));
+        let provider = OpenAiCompatibleProvider::new(server.url(), None, "local-model", 64)
+            .with_headers(headers);
+
+        let response = provider
+            .complete(&[Message::new(Role::User, "Hello")])
+            .await
+            .expect("request should succeed");
+
+        assert_eq!(response.content, "Hi");
+        let request = server.last_request().expect("request recorded");
+        assert!(request.starts_with("POST /chat/completions "));
+        assert!(request.contains("x-gateway-token: secret"));
+        assert!(!request.to_ascii_lowercase().contains("authorization:"));
+    }
+}
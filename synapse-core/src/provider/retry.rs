@@ -0,0 +1,417 @@
+//! Retry layer for transient provider failures.
+//!
+//! Wraps HTTP request sending with jittered exponential backoff. Rate limits
+//! (HTTP 429), overload and server errors (5xx, Anthropic's 529), timeouts and
+//! connection failures are retried; any other response is handed back to the
+//! provider unchanged so it can apply its own error mapping.
+
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use reqwest::{RequestBuilder, Response, StatusCode};
+
+use super::ProviderError;
+use crate::config::RetryConfig;
+
+/// Retry and timeout settings applied to provider HTTP requests.
+///
+/// Built from the `[retry]` section of `config.toml` via `From<&RetryConfig>`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt; `0` disables retrying.
+    pub max_retries: u32,
+    /// Base delay before the first retry; doubled on each further retry.
+    pub initial_backoff: Duration,
+    /// Upper bound for any single delay, including `Retry-After` values.
+    pub max_backoff: Duration,
+    /// Timeout for establishing a connection.
+    pub connect_timeout: Duration,
+    /// Timeout for each read from the response body, so long streams are not
+    /// cut off while tokens keep arriving.
+    pub read_timeout: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::from(&RetryConfig::default())
+    }
+}
+
+impl From<&RetryConfig> for RetryPolicy {
+    fn from(config: &RetryConfig) -> Self {
+        Self {
+            max_retries: config.max_retries,
+            initial_backoff: Duration::from_millis(config.initial_backoff_ms),
+            max_backoff: Duration::from_millis(config.max_backoff_ms),
+            connect_timeout: Duration::from_secs(config.connect_timeout_secs),
+            read_timeout: Duration::from_secs(config.read_timeout_secs),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Build an HTTP client with this policy's timeouts.
+    pub(super) fn build_client(&self) -> reqwest::Client {
+        reqwest::Client::builder()
+            .connect_timeout(self.connect_timeout)
+            .read_timeout(self.read_timeout)
+            .build()
+            .unwrap_or_else(|e| {
+                tracing::warn!("retry: failed to build HTTP client, using defaults: {}", e);
+                reqwest::Client::new()
+            })
+    }
+
+    /// Delay before retry number `attempt` (zero-based).
+    ///
+    /// Uses "equal jitter": half of the capped exponential delay is fixed and
+    /// the other half is random, so concurrent clients spread out.
+    pub(super) fn backoff(&self, attempt: u32) -> Duration {
+        let exponential = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_backoff);
+        let half = exponential / 2;
+        half + half.mul_f64(jitter())
+    }
+
+    /// Send the request built by `build`, retrying transient failures.
+    ///
+    /// `build` is called once per attempt. Successful responses and
+    /// non-transient error responses (e.g. 400, 401) are returned as-is.
+    ///
+    /// # Errors
+    ///
+    /// Once retries are exhausted, returns [`ProviderError::RateLimited`],
+    /// [`ProviderError::Overloaded`], [`ProviderError::Timeout`], or
+    /// [`ProviderError::RequestFailed`] for connection failures.
+    pub(super) async fn send<F>(&self, build: F) -> Result<Response, ProviderError>
+    where
+        F: Fn() -> RequestBuilder,
+    {
+        let mut attempt = 0;
+        loop {
+            let error = match build().send().await {
+                Ok(response) if !is_transient_status(response.status()) => return Ok(response),
+                Ok(response) => transient_status_error(response).await,
+                Err(e) if e.is_builder() => {
+                    return Err(ProviderError::RequestFailed(e.to_string()));
+                }
+                Err(e) if e.is_timeout() => ProviderError::Timeout(e.to_string()),
+                Err(e) => ProviderError::RequestFailed(e.to_string()),
+            };
+
+            if attempt >= self.max_retries {
+                return Err(error);
+            }
+
+            let delay = match &error {
+                ProviderError::RateLimited {
+                    retry_after: Some(retry_after),
+                    ..
+                } => (*retry_after).min(self.max_backoff),
+                _ => self.backoff(attempt),
+            };
+            attempt += 1;
+            tracing::warn!(
+                attempt,
+                max_retries = self.max_retries,
+                delay_ms = delay.as_millis() as u64,
+                "retry: transient provider failure: {}",
+                error
+            );
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+/// Whether an HTTP status indicates a failure worth retrying.
+fn is_transient_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS
+        || status == StatusCode::REQUEST_TIMEOUT
+        || status.is_server_error()
+        || status.as_u16() == 529
+}
+
+/// Convert a transient error response into the matching [`ProviderError`].
+async fn transient_status_error(response: Response) -> ProviderError {
+    let status = response.status();
+    let retry_after = parse_retry_after(response.headers(), Utc::now());
+    let body = response.text().await.unwrap_or_default();
+    let message = format!("HTTP {}: {}", status, error_message(&body));
+
+    match status {
+        StatusCode::TOO_MANY_REQUESTS => ProviderError::RateLimited {
+            message,
+            retry_after,
+        },
+        StatusCode::REQUEST_TIMEOUT | StatusCode::GATEWAY_TIMEOUT => {
+            ProviderError::Timeout(message)
+        }
+        _ => ProviderError::Overloaded(message),
+    }
+}
+
+/// Extract `error.message` from a JSON error body, falling back to the raw text.
+fn error_message(body: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|v| v.pointer("/error/message")?.as_str().map(str::to_string))
+        .unwrap_or_else(|| body.trim().to_string())
+}
+
+/// Parse the delay requested by `retry-after-ms` or `Retry-After`.
+///
+/// `Retry-After` may be a number of seconds or an HTTP date; dates in the
+/// past yield a zero delay. Negative or out-of-range numbers are ignored.
+fn parse_retry_after(headers: &reqwest::header::HeaderMap, now: DateTime<Utc>) -> Option<Duration> {
+    let header = |name: &str| headers.get(name)?.to_str().ok().map(str::trim);
+
+    if let Some(delay) = header("retry-after-ms")
+        .and_then(|v| v.parse::<f64>().ok())
+        .and_then(|ms| Duration::try_from_secs_f64(ms / 1000.0).ok())
+    {
+        return Some(delay);
+    }
+
+    let value = header("retry-after")?;
+    if let Ok(secs) = value.parse::<f64>() {
+        return Duration::try_from_secs_f64(secs).ok();
+    }
+    let date = DateTime::parse_from_rfc2822(value)
+        .ok()?
+        .with_timezone(&Utc);
+    Some((date - now).to_std().unwrap_or(Duration::ZERO))
+}
+
+/// A pseudo-random fraction in `[0, 1)` for backoff jitter.
+///
+/// `RandomState` is randomly keyed per instance, which is plenty for spreading
+/// retries without pulling in an RNG dependency.
+fn jitter() -> f64 {
+    let bits = RandomState::new().hash_one(std::time::SystemTime::now());
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::{HeaderMap, HeaderValue};
+
+    use super::*;
+    use crate::provider::stub_server::StubServer;
+
+    fn fast_policy(max_retries: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_retries,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(5),
+            ..RetryPolicy::default()
+        }
+    }
+
+    #[test]
+    fn test_backoff_grows_and_is_capped() {
+        let policy = RetryPolicy {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(1000),
+            ..RetryPolicy::default()
+        };
+
+        for attempt in 0..10 {
+            let exponential =
+                Duration::from_millis(100 * 2u64.pow(attempt)).min(policy.max_backoff);
+            let delay = policy.backoff(attempt);
+            assert!(delay >= exponential / 2, "attempt {attempt}: {delay:?}");
+            assert!(delay <= exponential, "attempt {attempt}: {delay:?}");
+        }
+    }
+
+    #[test]
+    fn test_provider_error_is_retryable() {
+        assert!(
+            ProviderError::RateLimited {
+                message: String::new(),
+                retry_after: None,
+            }
+            .is_retryable()
+        );
+        assert!(ProviderError::Overloaded(String::new()).is_retryable());
+        assert!(ProviderError::Timeout(String::new()).is_retryable());
+        assert!(!ProviderError::AuthenticationError(String::new()).is_retryable());
+        assert!(!ProviderError::RequestFailed(String::new()).is_retryable());
+    }
+
+    #[test]
+    fn test_parse_retry_after_seconds() {
+        let mut headers = HeaderMap::new();
+        headers.insert("retry-after", HeaderValue::from_static("7"));
+        assert_eq!(
+            parse_retry_after(&headers, Utc::now()),
+            Some(Duration::from_secs(7))
+        );
+    }
+
+    #[test]
+    fn test_parse_retry_after_ms_takes_priority() {
+        let mut headers = HeaderMap::new();
+        headers.insert("retry-after", HeaderValue::from_static("7"));
+        headers.insert("retry-after-ms", HeaderValue::from_static("250"));
+        assert_eq!(
+            parse_retry_after(&headers, Utc::now()),
+            Some(Duration::from_millis(250))
+        );
+    }
+
+    #[test]
+    fn test_parse_retry_after_http_date() {
+        let now = DateTime::parse_from_rfc2822("Wed, 21 Oct 2026 07:28:00 GMT")
+            .unwrap()
+            .with_timezone(&Utc);
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "retry-after",
+            HeaderValue::from_static("Wed, 21 Oct 2026 07:28:30 GMT"),
+        );
+        assert_eq!(
+            parse_retry_after(&headers, now),
+            Some(Duration::from_secs(30))
+        );
+
+        headers.insert(
+            "retry-after",
+            HeaderValue::from_static("Wed, 21 Oct 2026 07:27:00 GMT"),
+        );
+        assert_eq!(parse_retry_after(&headers, now), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_parse_retry_after_invalid() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(parse_retry_after(&headers, Utc::now()), None);
+        headers.insert("retry-after", HeaderValue::from_static("soon"));
+        assert_eq!(parse_retry_after(&headers, Utc::now()), None);
+    }
+
+    #[test]
+    fn test_parse_retry_after_out_of_range() {
+        let mut headers = HeaderMap::new();
+        headers.insert("retry-after", HeaderValue::from_static("1e300"));
+        assert_eq!(parse_retry_after(&headers, Utc::now()), None);
+        headers.insert("retry-after", HeaderValue::from_static("-5"));
+        assert_eq!(parse_retry_after(&headers, Utc::now()), None);
+
+        headers.insert("retry-after", HeaderValue::from_static("7"));
+        headers.insert("retry-after-ms", HeaderValue::from_static("1e300"));
+        assert_eq!(
+            parse_retry_after(&headers, Utc::now()),
+            Some(Duration::from_secs(7))
+        );
+    }
+
+    #[test]
+    fn test_error_message_extraction() {
+        let body = r#"{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#;
+        assert_eq!(error_message(body), "Overloaded");
+        assert_eq!(error_message("  bad gateway \n"), "bad gateway");
+    }
+
+    #[tokio::test]
+    async fn test_send_retries_until_success() {
+        let server = StubServer::start(vec![
+            StubServer::response(529, "", r#"{"error":{"message":"Overloaded"}}"#),
+            StubServer::response(429, "retry-after: 0\r\n", "{}"),
+            StubServer::response(200, "", "ok"),
+        ])
+        .await;
+        let client = reqwest::Client::new();
+
+        let response = fast_policy(3)
+            .send(|| client.get(server.url()))
+            .await
+            .expect("should succeed after retries");
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(server.request_count(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_send_returns_rate_limited_when_exhausted() {
+        let server = StubServer::start(vec![
+            StubServer::response(
+                429,
+                "retry-after: 0\r\n",
+                r#"{"error":{"message":"slow down"}}"#,
+            ),
+            StubServer::response(
+                429,
+                "retry-after: 0\r\n",
+                r#"{"error":{"message":"slow down"}}"#,
+            ),
+        ])
+        .await;
+        let client = reqwest::Client::new();
+
+        let err = fast_policy(1)
+            .send(|| client.get(server.url()))
+            .await
+            .expect_err("should give up");
+
+        match err {
+            ProviderError::RateLimited {
+                message,
+                retry_after,
+            } => {
+                assert!(message.contains("slow down"));
+                assert_eq!(retry_after, Some(Duration::ZERO));
+            }
+            other => panic!("Expected RateLimited, got: {:?}", other),
+        }
+        assert_eq!(server.request_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_send_does_not_retry_client_errors() {
+        let server = StubServer::start(vec![StubServer::response(400, "", "bad request")]).await;
+        let client = reqwest::Client::new();
+
+        let response = fast_policy(3)
+            .send(|| client.get(server.url()))
+            .await
+            .expect("non-transient responses are returned");
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(server.request_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_send_maps_gateway_timeout() {
+        let server =
+            StubServer::start(vec![StubServer::response(504, "", "upstream timed out")]).await;
+        let client = reqwest::Client::new();
+
+        let err = fast_policy(0)
+            .send(|| client.get(server.url()))
+            .await
+            .expect_err("should fail");
+
+        assert!(matches!(err, ProviderError::Timeout(m) if m.contains("upstream timed out")));
+    }
+
+    #[tokio::test]
+    async fn test_send_retries_connection_failure() {
+        // Bind then drop a listener to get a port nothing is listening on.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        drop(listener);
+        let client = reqwest::Client::new();
+
+        let err = fast_policy(1)
+            .send(|| client.get(&url))
+            .await
+            .expect_err("nothing is listening");
+
+        assert!(matches!(err, ProviderError::RequestFailed(_)));
+    }
+}
@@ -1,7 +1,10 @@
 //! Streaming event types for LLM provider responses.
 //!
 //! This module defines the [`StreamEvent`] enum representing events
-//! emitted during streaming LLM responses.
+//! emitted during streaming LLM responses, plus the [`PartialToolCall`]
+//! buffer providers use to assemble tool calls from argument deltas.
+
+use crate::message::{Message, ToolCallData};
 
 /// Events emitted during streaming LLM responses.
 ///
@@ -11,13 +14,15 @@
 /// # Examples
 ///
 /// ```
-/// use synapse_core::provider::StreamEvent;
+/// use synapse_core::provider::{StopReason, StreamEvent};
 ///
 /// // Text fragment from response
 /// let delta = StreamEvent::TextDelta("Hello".to_string());
 ///
 /// // Stream completed
-/// let done = StreamEvent::Done;
+/// let done = StreamEvent::Done {
+///     stop_reason: StopReason::EndTurn,
+/// };
 /// ```
 #[derive(Debug, Clone)]
 pub enum StreamEvent {
@@ -27,11 +32,153 @@ pub enum StreamEvent {
     /// The content is guaranteed to be non-empty.
     TextDelta(String),
 
+    /// The model started a tool call.
+    ///
+    /// Followed by zero or more [`StreamEvent::ToolCallArgumentsDelta`]
+    /// events with the same `id`, then [`StreamEvent::ToolCall`].
+    ToolCallStarted {
+        /// Tool call ID assigned by the provider.
+        id: String,
+        /// Name of the tool being called.
+        name: String,
+    },
+
+    /// A fragment of a tool call's JSON arguments.
+    ///
+    /// Fragments are not valid JSON on their own; the assembled arguments
+    /// are available in the following [`StreamEvent::ToolCall`].
+    ToolCallArgumentsDelta {
+        /// ID of the tool call the fragment belongs to.
+        id: String,
+        /// The raw argument fragment.
+        delta: String,
+    },
+
+    /// A fully assembled tool call requested by the model.
+    ///
+    /// Providers emit this once all argument fragments for the call have
+    /// arrived, before [`StreamEvent::Done`]. A turn that produced tool
+    /// calls must be followed by another request carrying their results.
+    ToolCall(ToolCallData),
+
+    /// The result of executing a tool call.
+    ///
+    /// Emitted by the [`Agent`](crate::agent::Agent), never by providers.
+    ToolResult {
+        /// ID of the tool call this result answers.
+        id: String,
+        /// Result text as sent back to the model.
+        content: String,
+        /// Whether the tool failed.
+        is_error: bool,
+    },
+
+    /// A message the agent appended to the conversation.
+    ///
+    /// Emitted by the [`Agent`](crate::agent::Agent) for each intermediate
+    /// assistant message carrying tool calls and each tool result message,
+    /// in conversation order, so frontends can persist them. The final
+    /// assistant answer is not reported; it is the concatenated text deltas.
+    MessageAppended(Message),
+
+    /// Token usage for a single provider request.
+    ///
+    /// The agent forwards one event per request in a tool loop; consumers
+    /// wanting a per-turn total should sum them.
+    Usage {
+        /// Prompt tokens consumed.
+        input: u32,
+        /// Completion tokens generated.
+        output: u32,
+    },
+
     /// Stream completed successfully.
     ///
     /// This event signals the end of the stream. No more events
     /// will be yielded after this.
-    Done,
+    Done {
+        /// Why the model stopped generating.
+        stop_reason: StopReason,
+    },
+}
+
+/// Why a model stopped generating.
+///
+/// Normalises Anthropic `stop_reason` and OpenAI `finish_reason` values.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum StopReason {
+    /// The model finished its answer naturally.
+    #[default]
+    EndTurn,
+    /// The model stopped to request tool calls.
+    ToolUse,
+    /// Generation hit the `max_tokens` limit; the answer is truncated.
+    MaxTokens,
+    /// A configured stop sequence was generated.
+    StopSequence,
+    /// A provider-specific reason not covered above.
+    Other(String),
+}
+
+impl From<&str> for StopReason {
+    fn from(reason: &str) -> Self {
+        match reason {
+            "end_turn" | "stop" => Self::EndTurn,
+            "tool_use" | "tool_calls" | "function_call" => Self::ToolUse,
+            "max_tokens" | "length" => Self::MaxTokens,
+            "stop_sequence" => Self::StopSequence,
+            other => Self::Other(other.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for StopReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::EndTurn => write!(f, "end of turn"),
+            Self::ToolUse => write!(f, "tool use"),
+            Self::MaxTokens => write!(f, "max tokens reached"),
+            Self::StopSequence => write!(f, "stop sequence"),
+            Self::Other(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+/// A tool call whose JSON arguments are still arriving as string fragments.
+///
+/// Both OpenAI-style `delta.tool_calls` and Anthropic `input_json_delta`
+/// events deliver arguments piecewise; providers push fragments here and
+/// call [`finish`](PartialToolCall::finish) once the call is complete.
+#[derive(Debug, Default)]
+pub(super) struct PartialToolCall {
+    /// Tool call ID assigned by the provider.
+    pub(super) id: String,
+    /// Name of the tool to invoke.
+    pub(super) name: String,
+    /// Concatenated argument JSON received so far.
+    pub(super) arguments: String,
+}
+
+impl PartialToolCall {
+    /// Parse the accumulated arguments and produce the final [`ToolCallData`].
+    ///
+    /// Empty or malformed argument JSON yields an empty object, matching how
+    /// non-streaming responses are handled.
+    pub(super) fn finish(self) -> ToolCallData {
+        let input = if self.arguments.trim().is_empty() {
+            serde_json::json!({})
+        } else {
+            serde_json::from_str(&self.arguments).unwrap_or_else(|e| {
+                tracing::warn!(tool = %self.name, "streamed tool arguments are not valid JSON: {}", e);
+                serde_json::json!({})
+            })
+        };
+        ToolCallData {
+            id: self.id,
+            name: self.name,
+            input,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -45,8 +192,10 @@ mod tests {
         assert!(matches!(text_delta, StreamEvent::TextDelta(s) if s == "Hello"));
 
         // Done variant
-        let done = StreamEvent::Done;
-        assert!(matches!(done, StreamEvent::Done));
+        let done = StreamEvent::Done {
+            stop_reason: StopReason::EndTurn,
+        };
+        assert!(matches!(done, StreamEvent::Done { .. }));
     }
 
     #[test]
@@ -63,4 +212,55 @@ mod tests {
         let cloned = original.clone();
         assert!(matches!(cloned, StreamEvent::TextDelta(s) if s == "clone me"));
     }
+
+    #[test]
+    fn test_stop_reason_from_provider_values() {
+        assert_eq!(StopReason::from("end_turn"), StopReason::EndTurn);
+        assert_eq!(StopReason::from("stop"), StopReason::EndTurn);
+        assert_eq!(StopReason::from("tool_use"), StopReason::ToolUse);
+        assert_eq!(StopReason::from("tool_calls"), StopReason::ToolUse);
+        assert_eq!(StopReason::from("max_tokens"), StopReason::MaxTokens);
+        assert_eq!(StopReason::from("length"), StopReason::MaxTokens);
+        assert_eq!(StopReason::from("stop_sequence"), StopReason::StopSequence);
+        assert_eq!(
+            StopReason::from("content_filter"),
+            StopReason::Other("content_filter".to_string())
+        );
+    }
+
+    #[test]
+    fn test_partial_tool_call_finish() {
+        let mut partial = PartialToolCall {
+            id: "call_1".to_string(),
+            name: "get_weather".to_string(),
+            arguments: String::new(),
+        };
+        partial.arguments.push_str("{\"location\":");
+        partial.arguments.push_str(" \"London\"}");
+
+        let call = partial.finish();
+        assert_eq!(call.id, "call_1");
+        assert_eq!(call.name, "get_weather");
+        assert_eq!(call.input, serde_json::json!({"location": "London"}));
+    }
+
+    #[test]
+    fn test_partial_tool_call_finish_empty_arguments() {
+        let partial = PartialToolCall {
+            id: "call_1".to_string(),
+            name: "list".to_string(),
+            arguments: String::new(),
+        };
+        assert_eq!(partial.finish().input, serde_json::json!({}));
+    }
+
+    #[test]
+    fn test_partial_tool_call_finish_invalid_json() {
+        let partial = PartialToolCall {
+            id: "call_1".to_string(),
+            name: "list".to_string(),
+            arguments: "{\"path\": ".to_string(),
+        };
+        assert_eq!(partial.finish().input, serde_json::json!({}));
+    }
 }
@@ -0,0 +1,104 @@
+//! Minimal local HTTP server for provider tests.
+//!
+//! Serves a fixed sequence of canned responses, one per connection, and
+//! records the requests it receives.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// A stub HTTP server listening on an ephemeral local port.
+pub(crate) struct StubServer {
+    url: String,
+    requests: Arc<AtomicUsize>,
+    last_request: Arc<Mutex<Option<String>>>,
+}
+
+impl StubServer {
+    /// Start serving `responses` in order; the last one repeats once exhausted.
+    pub(crate) async fn start(responses: Vec<String>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let url = format!("http://{}/", listener.local_addr().expect("local addr"));
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&requests);
+        let last_request = Arc::new(Mutex::new(None));
+        let recorded = Arc::clone(&last_request);
+
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    return;
+                };
+                let index = counter.fetch_add(1, Ordering::SeqCst);
+                let response = responses
+                    .get(index)
+                    .or(responses.last())
+                    .cloned()
+                    .unwrap_or_default();
+                let request = read_request(&mut socket).await;
+                *recorded.lock().expect("lock") = Some(request);
+                let _ = socket.write_all(response.as_bytes()).await;
+                let _ = socket.shutdown().await;
+            }
+        });
+
+        Self {
+            url,
+            requests,
+            last_request,
+        }
+    }
+
+    /// Build a raw HTTP response; `headers` are CRLF-terminated extra lines.
+    pub(crate) fn response(status: u16, headers: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 {} Stub\r\ncontent-length: {}\r\nconnection: close\r\n{}\r\n{}",
+            status,
+            body.len(),
+            headers,
+            body
+        )
+    }
+
+    /// Base URL of the server.
+    pub(crate) fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Number of requests accepted so far.
+    pub(crate) fn request_count(&self) -> usize {
+        self.requests.load(Ordering::SeqCst)
+    }
+
+    /// Raw text (request line, headers and body) of the latest request.
+    pub(crate) fn last_request(&self) -> Option<String> {
+        self.last_request.lock().expect("lock").clone()
+    }
+}
+
+/// Read one request (headers plus a `content-length` body) from the socket.
+async fn read_request(socket: &mut tokio::net::TcpStream) -> String {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    while let Ok(n) = socket.read(&mut chunk).await {
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        let Some(header_end) = buf.windows(4).position(|w| w == b"\r\n\r\n") else {
+            continue;
+        };
+        let headers = String::from_utf8_lossy(&buf[..header_end]).to_ascii_lowercase();
+        let content_length = headers
+            .lines()
+            .find_map(|l| l.strip_prefix("content-length:"))
+            .and_then(|v| v.trim().parse::<usize>().ok())
+            .unwrap_or(0);
+        if buf.len() >= header_end + 4 + content_length {
+            break;
+        }
+    }
+    String::from_utf8_lossy(&buf).into_owned()
+}
@@ -0,0 +1,212 @@
+//! Speech-to-text transcription.
+//!
+//! Defines the [`Transcriber`] port and [`OpenAiTranscriber`], an adapter for
+//! the OpenAI `/audio/transcriptions` endpoint. Local whisper servers
+//! (whisper.cpp, faster-whisper-server, LocalAI) speak the same API.
+
+use async_trait::async_trait;
+use reqwest::multipart::{Form, Part};
+use serde::Deserialize;
+
+use super::{ProviderError, RetryPolicy};
+
+/// Trait for speech-to-text backends.
+///
+/// # Examples
+///
+/// ```no_run
+/// use synapse_core::provider::{OpenAiTranscriber, Transcriber};
+///
+/// # async fn example(audio: Vec<u8>) -> Result<(), Box<dyn std::error::Error>> {
+/// let transcriber = OpenAiTranscriber::new("http://localhost:8000/v1", None, "whisper-1");
+/// let text = transcriber.transcribe(audio, "voice.ogg").await?;
+/// println!("{}", text);
+/// # Ok(())
+/// # }
+/// ```
+#[async_trait]
+pub trait Transcriber: Send + Sync {
+    /// Transcribe an audio file to text.
+    ///
+    /// `file_name` tells the backend the audio format (e.g. `voice.ogg`).
+    async fn transcribe(&self, audio: Vec<u8>, file_name: &str) -> Result<String, ProviderError>;
+}
+
+/// Transcriber for OpenAI-compatible `/audio/transcriptions` endpoints.
+pub struct OpenAiTranscriber {
+    client: reqwest::Client,
+    retry: RetryPolicy,
+    base_url: String,
+    api_key: String,
+    model: String,
+    language: Option<String>,
+}
+
+/// Response body of a `json` format transcription.
+#[derive(Deserialize)]
+struct TranscriptionResponse {
+    text: String,
+}
+
+impl OpenAiTranscriber {
+    /// Create a new transcriber.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_url` - API base URL; requests go to `{base_url}/audio/transcriptions`
+    /// * `api_key` - Bearer token, or `None` to send no `Authorization` header
+    /// * `model` - Transcription model (e.g. `whisper-1`)
+    pub fn new(
+        base_url: impl Into<String>,
+        api_key: Option<String>,
+        model: impl Into<String>,
+    ) -> Self {
+        let retry = RetryPolicy::default();
+        Self {
+            client: retry.build_client(),
+            retry,
+            base_url: base_url.into(),
+            api_key: api_key.unwrap_or_default(),
+            model: model.into(),
+            language: None,
+        }
+    }
+
+    /// Set the retry and timeout policy for API requests.
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.client = retry.build_client();
+        self.retry = retry;
+        self
+    }
+
+    /// Hint the spoken language (ISO-639-1, e.g. `en`) instead of auto-detecting it.
+    pub fn with_language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    /// Build the multipart request body.
+    fn form(&self, audio: Vec<u8>, file_name: &str) -> Form {
+        let mut form = Form::new()
+            .text("model", self.model.clone())
+            .text("response_format", "json")
+            .part("file", Part::bytes(audio).file_name(file_name.to_string()));
+        if let Some(language) = &self.language {
+            form = form.text("language", language.clone());
+        }
+        form
+    }
+}
+
+#[async_trait]
+impl Transcriber for OpenAiTranscriber {
+    async fn transcribe(&self, audio: Vec<u8>, file_name: &str) -> Result<String, ProviderError> {
+        let endpoint = format!(
+            "{}/audio/transcriptions",
+            self.base_url.trim_end_matches('/')
+        );
+        tracing::debug!(endpoint, bytes = audio.len(), "transcription: POST request");
+        let response = self
+            .retry
+            .send(|| {
+                let mut builder = self
+                    .client
+                    .post(&endpoint)
+                    .multipart(self.form(audio.clone(), file_name));
+                if !self.api_key.is_empty() {
+                    builder = builder.bearer_auth(&self.api_key);
+                }
+                builder
+            })
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "unknown error".to_string());
+            let message = format!("HTTP {}: {}", status, error_text);
+            return Err(if status == reqwest::StatusCode::UNAUTHORIZED {
+                ProviderError::AuthenticationError(message)
+            } else {
+                ProviderError::RequestFailed(message)
+            });
+        }
+
+        let body: TranscriptionResponse =
+            response
+                .json()
+                .await
+                .map_err(|e| ProviderError::ProviderError {
+                    message: format!("failed to parse transcription: {}", e),
+                })?;
+        Ok(body.text.trim().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::stub_server::StubServer;
+
+    #[tokio::test]
+    async fn test_transcribe_posts_multipart_form() {
+        let server = StubServer::start(vec![StubServer::response(
+            200,
+            "content-type: application/json\r\n",
+            r#"{"text": " Hello there. "}"#,
+        )])
+        .await;
+        let transcriber = OpenAiTranscriber::new(server.url(), Some("key".into()), "whisper-1")
+            .with_language("en");
+
+        let text = transcriber
+            .transcribe(b"OggS".to_vec(), "voice.ogg")
+            .await
+            .unwrap();
+
+        assert_eq!(text, "Hello there.");
+        let request = server.last_request().unwrap();
+        assert!(request.starts_with("POST /audio/transcriptions"));
+        assert!(
+            request
+                .to_ascii_lowercase()
+                .contains("authorization: bearer key")
+        );
+        assert!(request.contains("multipart/form-data"));
+        assert!(request.contains("filename=\"voice.ogg\""));
+        assert!(request.contains("whisper-1"));
+        assert!(request.contains("name=\"language\"\r\n\r\nen"));
+    }
+
+    #[tokio::test]
+    async fn test_transcribe_without_key_sends_no_auth() {
+        let server = StubServer::start(vec![StubServer::response(
+            200,
+            "content-type: application/json\r\n",
+            r#"{"text": "hi"}"#,
+        )])
+        .await;
+        let transcriber = OpenAiTranscriber::new(server.url(), None, "whisper-1");
+
+        transcriber.transcribe(vec![0], "a.mp3").await.unwrap();
+
+        let request = server.last_request().unwrap().to_ascii_lowercase();
+        assert!(!request.contains("authorization:"));
+    }
+
+    #[tokio::test]
+    async fn test_transcribe_maps_unauthorized() {
+        let server = StubServer::start(vec![StubServer::response(
+            401,
+            "",
+            r#"{"error": {"message": "bad key"}}"#,
+        )])
+        .await;
+        let transcriber = OpenAiTranscriber::new(server.url(), Some("bad".into()), "whisper-1");
+
+        let err = transcriber.transcribe(vec![0], "a.ogg").await.unwrap_err();
+        assert!(matches!(err, ProviderError::AuthenticationError(_)));
+    }
+}
@@ -4,9 +4,10 @@
 //! and their associated messages.
 
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::message::Role;
+use crate::message::{Attachment, Message, Role, TokenUsage, ToolCallData, ToolOutput};
 
 /// A conversation session containing metadata.
 ///
@@ -22,6 +23,8 @@ pub struct Session {
     pub provider: String,
     /// The model name used (e.g., "deepseek-chat", "claude-3-opus").
     pub model: String,
+    /// Configuration profile the session runs with, if one was selected.
+    pub profile: Option<String>,
     /// When the session was created.
     pub created_at: DateTime<Utc>,
     /// When the session was last updated (message added).
@@ -39,6 +42,7 @@ impl Session {
             name: None,
             provider: provider.into(),
             model: model.into(),
+            profile: None,
             created_at: now,
             updated_at: now,
         }
@@ -49,6 +53,12 @@ impl Session {
         self.name = Some(name.into());
         self
     }
+
+    /// Record the configuration profile the session runs with.
+    pub fn with_profile(mut self, profile: Option<String>) -> Self {
+        self.profile = profile;
+        self
+    }
 }
 
 /// Summary information for listing sessions.
@@ -93,6 +103,10 @@ pub struct StoredMessage {
     pub tool_calls: Option<String>,
     /// JSON-serialized tool results (for tool result messages).
     pub tool_results: Option<String>,
+    /// Token usage reported for this message (assistant messages only).
+    pub usage: Option<TokenUsage>,
+    /// Images and documents sent with the message.
+    pub attachments: Vec<Attachment>,
     /// When the message was created.
     pub timestamp: DateTime<Utc>,
 }
@@ -101,7 +115,7 @@ impl StoredMessage {
     /// Create a new stored message.
     ///
     /// Generates a UUID v7 (time-sortable) for the message ID.
-    /// Tool-related fields default to `None`.
+    /// Tool-related fields and usage default to `None`, attachments to empty.
     pub fn new(session_id: Uuid, role: Role, content: impl Into<String>) -> Self {
         Self {
             id: Uuid::now_v7(),
@@ -110,6 +124,8 @@ impl StoredMessage {
             content: content.into(),
             tool_calls: None,
             tool_results: None,
+            usage: None,
+            attachments: Vec::new(),
             timestamp: Utc::now(),
         }
     }
@@ -125,6 +141,144 @@ impl StoredMessage {
         self.tool_results = Some(tool_results.into());
         self
     }
+
+    /// Set token usage on this message.
+    pub fn with_usage(mut self, usage: TokenUsage) -> Self {
+        self.usage = Some(usage);
+        self
+    }
+
+    /// Create a stored message from a conversation [`Message`].
+    ///
+    /// Assistant tool calls are serialized into `tool_calls` as a JSON array of
+    /// [`ToolCallData`]; a tool result's call ID and [`ToolOutput`] are
+    /// serialized into `tool_results` as `{"tool_call_id": "...", "output": {...}}`.
+    /// [`to_message`](Self::to_message) reverses the conversion.
+    pub fn from_message(session_id: Uuid, message: &Message) -> Self {
+        let mut stored = Self::new(session_id, message.role, &message.content);
+        if let Some(ref tool_calls) = message.tool_calls
+            && !tool_calls.is_empty()
+        {
+            // Serializing plain structs with `serde_json::Value` fields cannot fail.
+            stored.tool_calls = serde_json::to_string(tool_calls).ok();
+        }
+        if let Some(ref tool_call_id) = message.tool_call_id {
+            stored.tool_results = serde_json::to_string(&ToolResultMeta {
+                tool_call_id: tool_call_id.clone(),
+                output: message.tool_output.clone(),
+            })
+            .ok();
+        }
+        stored.usage = message.usage;
+        stored.attachments = message.attachments.clone();
+        stored
+    }
+
+    /// Reconstruct the conversation [`Message`], including tool call data.
+    ///
+    /// Malformed tool JSON is logged and dropped rather than failing the
+    /// whole history load.
+    pub fn to_message(&self) -> Message {
+        let mut message = Message::new(self.role, &self.content);
+        message.usage = self.usage;
+        message.attachments = self.attachments.clone();
+        if let Some(ref json) = self.tool_calls {
+            match serde_json::from_str::<Vec<ToolCallData>>(json) {
+                Ok(calls) if !calls.is_empty() => message.tool_calls = Some(calls),
+                Ok(_) => {}
+                Err(e) => tracing::warn!(id = %self.id, "invalid stored tool_calls: {}", e),
+            }
+        }
+        if let Some(ref json) = self.tool_results {
+            match serde_json::from_str::<ToolResultMeta>(json) {
+                Ok(meta) => {
+                    message.tool_call_id = Some(meta.tool_call_id);
+                    message.tool_output = meta.output;
+                }
+                Err(e) => tracing::warn!(id = %self.id, "invalid stored tool_results: {}", e),
+            }
+        }
+        message
+    }
+}
+
+/// JSON shape of [`StoredMessage::tool_results`].
+#[derive(Debug, Serialize, Deserialize)]
+struct ToolResultMeta {
+    /// ID of the tool call the message answers.
+    tool_call_id: String,
+    /// Full tool output; absent for text-only results stored before it existed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    output: Option<ToolOutput>,
+}
+
+/// Kind of derived data stored in a [`SessionArtifact`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArtifactKind {
+    /// Summary of older messages, produced when the history outgrows the
+    /// model's context window.
+    Summary,
+}
+
+impl ArtifactKind {
+    /// Return the string stored in the database for this kind.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ArtifactKind::Summary => "summary",
+        }
+    }
+}
+
+impl std::str::FromStr for ArtifactKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "summary" => Ok(ArtifactKind::Summary),
+            other => Err(format!("unknown artifact kind: {}", other)),
+        }
+    }
+}
+
+/// Data derived from a session's messages and stored alongside them.
+///
+/// An artifact covers the first `message_count` messages of its session, in
+/// the order returned by [`SessionStore::get_messages`](crate::storage::SessionStore::get_messages).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionArtifact {
+    /// Unique identifier for the artifact.
+    pub id: Uuid,
+    /// The session this artifact belongs to.
+    pub session_id: Uuid,
+    /// What the artifact contains.
+    pub kind: ArtifactKind,
+    /// The artifact text.
+    pub content: String,
+    /// Number of leading session messages the artifact covers.
+    pub message_count: u32,
+    /// When the artifact was created.
+    pub created_at: DateTime<Utc>,
+}
+
+impl SessionArtifact {
+    /// Create a new artifact covering the first `message_count` messages.
+    ///
+    /// Generates a UUID v7 (time-sortable) and sets the creation time to now.
+    pub fn new(
+        session_id: Uuid,
+        kind: ArtifactKind,
+        content: impl Into<String>,
+        message_count: u32,
+    ) -> Self {
+        Self {
+            id: Uuid::now_v7(),
+            session_id,
+            kind,
+            content: content.into(),
+            message_count,
+            created_at: Utc::now(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -249,4 +403,84 @@ mod tests {
         );
         assert!(msg.tool_calls.is_none());
     }
+
+    #[test]
+    fn test_stored_message_from_message_tool_calls_roundtrip() {
+        let session_id = Uuid::new_v4();
+        let mut message = Message::new(Role::Assistant, "Checking.");
+        message.tool_calls = Some(vec![ToolCallData {
+            id: "call_1".to_string(),
+            name: "get_weather".to_string(),
+            input: serde_json::json!({"location": "London"}),
+        }]);
+        message.usage = Some(TokenUsage::new(120, 15));
+
+        let stored = StoredMessage::from_message(session_id, &message);
+        assert_eq!(stored.session_id, session_id);
+        assert!(stored.tool_calls.is_some());
+        assert!(stored.tool_results.is_none());
+        assert_eq!(stored.usage, Some(TokenUsage::new(120, 15)));
+
+        assert_eq!(stored.to_message(), message);
+    }
+
+    #[test]
+    fn test_stored_message_from_message_tool_result_roundtrip() {
+        let session_id = Uuid::new_v4();
+        let message = Message::tool_result("call_1", "Sunny");
+
+        let stored = StoredMessage::from_message(session_id, &message);
+        assert_eq!(
+            stored.tool_results.as_deref(),
+            Some(r#"{"tool_call_id":"call_1"}"#)
+        );
+
+        assert_eq!(stored.to_message(), message);
+    }
+
+    #[test]
+    fn test_stored_message_from_message_tool_output_roundtrip() {
+        let session_id = Uuid::new_v4();
+        let output = ToolOutput {
+            content: vec![crate::message::ToolContent::Image {
+                data: "iVBORw0KGgo=".to_string(),
+                mime_type: "image/png".to_string(),
+            }],
+            structured: Some(serde_json::json!({"width": 1})),
+            is_error: true,
+        };
+        let message = Message::tool_output("call_1", output);
+
+        let stored = StoredMessage::from_message(session_id, &message);
+        let json: serde_json::Value =
+            serde_json::from_str(stored.tool_results.as_deref().unwrap()).unwrap();
+        assert_eq!(json["tool_call_id"], "call_1");
+        assert_eq!(json["output"]["is_error"], true);
+
+        assert_eq!(stored.to_message(), message);
+    }
+
+    #[test]
+    fn test_stored_message_to_message_plain() {
+        let stored = StoredMessage::new(Uuid::new_v4(), Role::User, "Hi");
+        assert_eq!(stored.to_message(), Message::new(Role::User, "Hi"));
+    }
+
+    #[test]
+    fn test_stored_message_to_message_invalid_json() {
+        let stored = StoredMessage::new(Uuid::new_v4(), Role::Assistant, "text")
+            .with_tool_calls("not json")
+            .with_tool_results("{}");
+        let message = stored.to_message();
+        assert_eq!(message.content, "text");
+        assert!(message.tool_calls.is_none());
+        assert!(message.tool_call_id.is_none());
+    }
+
+    #[test]
+    fn test_artifact_kind_roundtrip() {
+        let kind: ArtifactKind = ArtifactKind::Summary.as_str().parse().unwrap();
+        assert_eq!(kind, ArtifactKind::Summary);
+        assert!("bogus".parse::<ArtifactKind>().is_err());
+    }
 }
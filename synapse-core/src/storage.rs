@@ -12,7 +12,8 @@ use thiserror::Error;
 use uuid::Uuid;
 
 use crate::config::SessionConfig;
-use crate::session::{Session, SessionSummary, StoredMessage};
+use crate::message::TokenUsage;
+use crate::session::{ArtifactKind, Session, SessionArtifact, SessionSummary, StoredMessage};
 
 /// Errors that can occur during storage operations.
 #[derive(Debug, Error)]
@@ -101,6 +102,30 @@ pub trait SessionStore: Send + Sync {
     /// Returns [`StorageError::Database`] if the update fails.
     async fn touch_session(&self, id: Uuid) -> Result<(), StorageError>;
 
+    /// Record a new provider, model and profile for a session.
+    ///
+    /// Used when the model is switched mid-session so that resuming the
+    /// session later continues with the same model and profile settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The session UUID to update
+    /// * `provider` - The new provider name
+    /// * `model` - The new model name
+    /// * `profile` - The profile the model was selected with, if any
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StorageError::NotFound`] if the session doesn't exist.
+    /// Returns [`StorageError::Database`] if the update fails.
+    async fn update_session_model(
+        &self,
+        id: Uuid,
+        provider: &str,
+        model: &str,
+        profile: Option<&str>,
+    ) -> Result<(), StorageError>;
+
     /// Delete a session and all its messages.
     ///
     /// # Arguments
@@ -142,6 +167,51 @@ pub trait SessionStore: Send + Sync {
     /// Returns [`StorageError::Database`] if the query fails.
     async fn get_messages(&self, session_id: Uuid) -> Result<Vec<StoredMessage>, StorageError>;
 
+    /// Get the total token usage recorded for a session.
+    ///
+    /// Sums the usage of every message in the session. Messages without
+    /// recorded usage count as zero, so unknown sessions return zero usage.
+    ///
+    /// # Arguments
+    ///
+    /// * `session_id` - The session UUID to aggregate usage for
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StorageError::Database`] if the query fails.
+    async fn get_session_usage(&self, session_id: Uuid) -> Result<TokenUsage, StorageError>;
+
+    /// Store an artifact derived from a session's messages.
+    ///
+    /// # Arguments
+    ///
+    /// * `artifact` - The artifact to store
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StorageError::Database`] if the insert fails.
+    async fn add_artifact(&self, artifact: &SessionArtifact) -> Result<(), StorageError>;
+
+    /// Get the most recently created artifact of `kind` for a session.
+    ///
+    /// # Arguments
+    ///
+    /// * `session_id` - The session UUID to look up
+    /// * `kind` - The artifact kind to return
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(None)` if the session has no artifact of that kind.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StorageError::Database`] if the query fails.
+    async fn get_latest_artifact(
+        &self,
+        session_id: Uuid,
+        kind: ArtifactKind,
+    ) -> Result<Option<SessionArtifact>, StorageError>;
+
     /// Run cleanup based on configuration.
     ///
     /// Deletes sessions that exceed the `max_sessions` limit (oldest first)
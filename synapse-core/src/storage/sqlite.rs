@@ -21,6 +21,7 @@
 //!
 //! No manual setup is required - the database is ready on first use.
 
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 use async_trait::async_trait;
@@ -30,8 +31,8 @@ use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
 use uuid::Uuid;
 
 use crate::config::SessionConfig;
-use crate::message::Role;
-use crate::session::{Session, SessionSummary, StoredMessage};
+use crate::message::{Attachment, MediaSource, Role, TokenUsage};
+use crate::session::{ArtifactKind, Session, SessionArtifact, SessionSummary, StoredMessage};
 use crate::storage::{CleanupResult, SessionStore, StorageError};
 
 /// Maximum number of characters for session preview text in `list_sessions`.
@@ -103,6 +104,35 @@ impl SqliteStore {
     fn role_to_string(role: Role) -> &'static str {
         role.as_str()
     }
+
+    /// Rebuild an attachment from a `message_attachments` row.
+    fn parse_attachment(row: &sqlx::sqlite::SqliteRow) -> Result<Attachment, StorageError> {
+        let url: Option<String> = row.get("url");
+        let data: Option<Vec<u8>> = row.get("data");
+        let source = match (url, data) {
+            (Some(url), None) => MediaSource::Url(url),
+            (None, Some(data)) => MediaSource::Bytes(data),
+            _ => {
+                return Err(StorageError::InvalidData(
+                    "attachment needs exactly one of url and data".to_string(),
+                ));
+            }
+        };
+        let media_type: String = row.get("media_type");
+        let kind: String = row.get("kind");
+        match kind.as_str() {
+            "image" => Ok(Attachment::Image { media_type, source }),
+            "document" => Ok(Attachment::Document {
+                media_type,
+                name: row.get("name"),
+                source,
+            }),
+            other => Err(StorageError::InvalidData(format!(
+                "unknown attachment kind: {}",
+                other
+            ))),
+        }
+    }
 }
 
 #[async_trait]
@@ -111,14 +141,15 @@ impl SessionStore for SqliteStore {
         tracing::debug!(session_id = %session.id, "sqlite: creating session");
         sqlx::query(
             r#"
-            INSERT INTO sessions (id, name, provider, model, created_at, updated_at)
-            VALUES (?, ?, ?, ?, ?, ?)
+            INSERT INTO sessions (id, name, provider, model, profile, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(session.id.to_string())
         .bind(&session.name)
         .bind(&session.provider)
         .bind(&session.model)
+        .bind(&session.profile)
         .bind(session.created_at.to_rfc3339())
         .bind(session.updated_at.to_rfc3339())
         .execute(&self.pool)
@@ -132,7 +163,7 @@ impl SessionStore for SqliteStore {
         tracing::debug!(session_id = %id, "sqlite: retrieving session");
         let row = sqlx::query(
             r#"
-            SELECT id, name, provider, model, created_at, updated_at
+            SELECT id, name, provider, model, profile, created_at, updated_at
             FROM sessions
             WHERE id = ?
             "#,
@@ -163,6 +194,7 @@ impl SessionStore for SqliteStore {
                     name: row.get("name"),
                     provider: row.get("provider"),
                     model: row.get("model"),
+                    profile: row.get("profile"),
                     created_at,
                     updated_at,
                 }))
@@ -242,6 +274,35 @@ impl SessionStore for SqliteStore {
         Ok(())
     }
 
+    async fn update_session_model(
+        &self,
+        id: Uuid,
+        provider: &str,
+        model: &str,
+        profile: Option<&str>,
+    ) -> Result<(), StorageError> {
+        tracing::debug!(session_id = %id, provider, model, profile, "sqlite: updating session model");
+        let result = sqlx::query(
+            r#"
+            UPDATE sessions SET provider = ?, model = ?, profile = ?, updated_at = ? WHERE id = ?
+            "#,
+        )
+        .bind(provider)
+        .bind(model)
+        .bind(profile)
+        .bind(Utc::now().to_rfc3339())
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StorageError::Database(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(StorageError::NotFound(id));
+        }
+
+        Ok(())
+    }
+
     async fn delete_session(&self, id: Uuid) -> Result<bool, StorageError> {
         tracing::debug!(session_id = %id, "sqlite: deleting session");
         let result = sqlx::query(
@@ -259,11 +320,18 @@ impl SessionStore for SqliteStore {
 
     async fn add_message(&self, message: &StoredMessage) -> Result<(), StorageError> {
         tracing::debug!(session_id = %message.session_id, role = %message.role.as_str(), "sqlite: adding message");
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+
         // Insert message
         sqlx::query(
             r#"
-            INSERT INTO messages (id, session_id, role, content, tool_calls, tool_results, timestamp)
-            VALUES (?, ?, ?, ?, ?, ?, ?)
+            INSERT INTO messages (id, session_id, role, content, tool_calls, tool_results,
+                                  input_tokens, output_tokens, timestamp)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(message.id.to_string())
@@ -272,11 +340,41 @@ impl SessionStore for SqliteStore {
         .bind(&message.content)
         .bind(&message.tool_calls)
         .bind(&message.tool_results)
+        .bind(message.usage.map(|u| i64::from(u.input_tokens)))
+        .bind(message.usage.map(|u| i64::from(u.output_tokens)))
         .bind(message.timestamp.to_rfc3339())
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await
         .map_err(|e| StorageError::Database(e.to_string()))?;
 
+        // Insert attachments
+        for (position, attachment) in message.attachments.iter().enumerate() {
+            let (kind, name) = match attachment {
+                Attachment::Image { .. } => ("image", None),
+                Attachment::Document { name, .. } => ("document", name.as_deref()),
+            };
+            let (url, data) = match attachment.source() {
+                MediaSource::Url(url) => (Some(url.as_str()), None),
+                MediaSource::Bytes(data) => (None, Some(data.as_slice())),
+            };
+            sqlx::query(
+                r#"
+                INSERT INTO message_attachments (message_id, position, kind, media_type, name, url, data)
+                VALUES (?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(message.id.to_string())
+            .bind(position as i64)
+            .bind(kind)
+            .bind(attachment.media_type())
+            .bind(name)
+            .bind(url)
+            .bind(data)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+        }
+
         // Update session's updated_at
         sqlx::query(
             r#"
@@ -285,20 +383,23 @@ impl SessionStore for SqliteStore {
         )
         .bind(Utc::now().to_rfc3339())
         .bind(message.session_id.to_string())
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await
         .map_err(|e| StorageError::Database(e.to_string()))?;
 
-        Ok(())
+        tx.commit()
+            .await
+            .map_err(|e| StorageError::Database(e.to_string()))
     }
 
     async fn get_messages(&self, session_id: Uuid) -> Result<Vec<StoredMessage>, StorageError> {
         let rows = sqlx::query(
             r#"
-            SELECT id, session_id, role, content, tool_calls, tool_results, timestamp
+            SELECT id, session_id, role, content, tool_calls, tool_results,
+                   input_tokens, output_tokens, timestamp
             FROM messages
             WHERE session_id = ?
-            ORDER BY timestamp ASC
+            ORDER BY timestamp ASC, rowid ASC
             "#,
         )
         .bind(session_id.to_string())
@@ -306,6 +407,28 @@ impl SessionStore for SqliteStore {
         .await
         .map_err(|e| StorageError::Database(e.to_string()))?;
 
+        let attachment_rows = sqlx::query(
+            r#"
+            SELECT a.message_id, a.kind, a.media_type, a.name, a.url, a.data
+            FROM message_attachments a
+            JOIN messages m ON m.id = a.message_id
+            WHERE m.session_id = ?
+            ORDER BY a.message_id, a.position
+            "#,
+        )
+        .bind(session_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StorageError::Database(e.to_string()))?;
+
+        let mut attachments: HashMap<String, Vec<Attachment>> = HashMap::new();
+        for row in &attachment_rows {
+            attachments
+                .entry(row.get("message_id"))
+                .or_default()
+                .push(Self::parse_attachment(row)?);
+        }
+
         let mut messages = Vec::new();
         for row in rows {
             let id_str: String = row.get("id");
@@ -324,6 +447,16 @@ impl SessionStore for SqliteStore {
                 .map_err(|e| StorageError::InvalidData(format!("invalid datetime: {}", e)))?
                 .with_timezone(&Utc);
 
+            let input_tokens: Option<i64> = row.get("input_tokens");
+            let output_tokens: Option<i64> = row.get("output_tokens");
+            let usage = match (input_tokens, output_tokens) {
+                (None, None) => None,
+                (input, output) => Some(TokenUsage::new(
+                    input.unwrap_or(0) as u32,
+                    output.unwrap_or(0) as u32,
+                )),
+            };
+
             messages.push(StoredMessage {
                 id,
                 session_id,
@@ -331,6 +464,8 @@ impl SessionStore for SqliteStore {
                 content: row.get("content"),
                 tool_calls: row.get("tool_calls"),
                 tool_results: row.get("tool_results"),
+                usage,
+                attachments: attachments.remove(&id_str).unwrap_or_default(),
                 timestamp,
             });
         }
@@ -338,6 +473,101 @@ impl SessionStore for SqliteStore {
         Ok(messages)
     }
 
+    async fn get_session_usage(&self, session_id: Uuid) -> Result<TokenUsage, StorageError> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                COALESCE(SUM(input_tokens), 0) as input_tokens,
+                COALESCE(SUM(output_tokens), 0) as output_tokens
+            FROM messages
+            WHERE session_id = ?
+            "#,
+        )
+        .bind(session_id.to_string())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| StorageError::Database(e.to_string()))?;
+
+        let input_tokens: i64 = row.get("input_tokens");
+        let output_tokens: i64 = row.get("output_tokens");
+        Ok(TokenUsage::new(
+            u32::try_from(input_tokens).unwrap_or(u32::MAX),
+            u32::try_from(output_tokens).unwrap_or(u32::MAX),
+        ))
+    }
+
+    async fn add_artifact(&self, artifact: &SessionArtifact) -> Result<(), StorageError> {
+        tracing::debug!(
+            session_id = %artifact.session_id,
+            kind = artifact.kind.as_str(),
+            "sqlite: adding artifact"
+        );
+        sqlx::query(
+            r#"
+            INSERT INTO session_artifacts (id, session_id, kind, content, message_count, created_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(artifact.id.to_string())
+        .bind(artifact.session_id.to_string())
+        .bind(artifact.kind.as_str())
+        .bind(&artifact.content)
+        .bind(i64::from(artifact.message_count))
+        .bind(artifact.created_at.to_rfc3339())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StorageError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn get_latest_artifact(
+        &self,
+        session_id: Uuid,
+        kind: ArtifactKind,
+    ) -> Result<Option<SessionArtifact>, StorageError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, content, message_count, created_at
+            FROM session_artifacts
+            WHERE session_id = ? AND kind = ?
+            ORDER BY created_at DESC, rowid DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(session_id.to_string())
+        .bind(kind.as_str())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| StorageError::Database(e.to_string()))?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        let id_str: String = row.get("id");
+        let id = Uuid::parse_str(&id_str)
+            .map_err(|e| StorageError::InvalidData(format!("invalid UUID: {}", e)))?;
+
+        let created_at_str: String = row.get("created_at");
+        let created_at = DateTime::parse_from_rfc3339(&created_at_str)
+            .map_err(|e| StorageError::InvalidData(format!("invalid datetime: {}", e)))?
+            .with_timezone(&Utc);
+
+        let message_count: i64 = row.get("message_count");
+        let message_count = u32::try_from(message_count)
+            .map_err(|e| StorageError::InvalidData(format!("invalid message count: {}", e)))?;
+
+        Ok(Some(SessionArtifact {
+            id,
+            session_id,
+            kind,
+            content: row.get("content"),
+            message_count,
+            created_at,
+        }))
+    }
+
     async fn cleanup(&self, config: &SessionConfig) -> Result<CleanupResult, StorageError> {
         let mut result = CleanupResult::default();
 
@@ -116,6 +116,44 @@ async fn test_touch_session_not_found() {
     assert!(matches!(result, Err(StorageError::NotFound(_))));
 }
 
+#[tokio::test]
+async fn test_update_session_model() {
+    let store = create_test_store().await;
+    let session = Session::new("deepseek", "deepseek-chat");
+    let session_id = session.id;
+
+    store.create_session(&session).await.expect("create failed");
+    store
+        .update_session_model(session_id, "anthropic", "claude-sonnet-4-5", Some("claude"))
+        .await
+        .expect("update failed");
+
+    let updated = store
+        .get_session(session_id)
+        .await
+        .expect("get failed")
+        .unwrap();
+    assert_eq!(updated.provider, "anthropic");
+    assert_eq!(updated.model, "claude-sonnet-4-5");
+    assert_eq!(updated.profile.as_deref(), Some("claude"));
+
+    store
+        .update_session_model(session_id, "deepseek", "deepseek-chat", None)
+        .await
+        .expect("update failed");
+    let updated = store.get_session(session_id).await.unwrap().unwrap();
+    assert_eq!(updated.profile, None);
+}
+
+#[tokio::test]
+async fn test_update_session_model_not_found() {
+    let store = create_test_store().await;
+    let result = store
+        .update_session_model(Uuid::new_v4(), "openai", "gpt-4o", None)
+        .await;
+    assert!(matches!(result, Err(StorageError::NotFound(_))));
+}
+
 #[tokio::test]
 async fn test_delete_session() {
     let store = create_test_store().await;
@@ -276,6 +314,44 @@ async fn test_cascade_delete() {
     assert!(messages.is_empty());
 }
 
+#[tokio::test]
+async fn test_sqlite_attachments_roundtrip() {
+    use crate::message::{Attachment, MediaSource, Message};
+
+    let store = create_test_store().await;
+    let session = Session::new("test", "model");
+    store.create_session(&session).await.expect("create failed");
+
+    let message = Message::new(Role::User, "What is this?")
+        .with_attachment(Attachment::image("image/png", vec![0x89, 0x50, 0x00, 0xff]))
+        .with_attachment(Attachment::Document {
+            media_type: "application/pdf".to_string(),
+            name: Some("report.pdf".to_string()),
+            source: MediaSource::Url("https://example.com/report.pdf".to_string()),
+        });
+    let stored = StoredMessage::from_message(session.id, &message);
+    store.add_message(&stored).await.expect("add failed");
+    store
+        .add_message(&StoredMessage::new(session.id, Role::Assistant, "A chart."))
+        .await
+        .expect("add failed");
+
+    let messages = store.get_messages(session.id).await.expect("get failed");
+    assert_eq!(messages[0].to_message(), message);
+    assert!(messages[1].attachments.is_empty());
+
+    // Attachments go with their session.
+    store
+        .delete_session(session.id)
+        .await
+        .expect("delete failed");
+    let (remaining,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM message_attachments")
+        .fetch_one(&store.pool)
+        .await
+        .unwrap();
+    assert_eq!(remaining, 0);
+}
+
 #[tokio::test]
 async fn test_cleanup_by_max_sessions() {
     let store = create_test_store().await;
@@ -431,3 +507,123 @@ async fn test_sqlite_tool_calls_roundtrip() {
     assert_eq!(messages[1].tool_results.as_deref(), Some(tool_results_json));
     assert!(messages[1].tool_calls.is_none());
 }
+
+#[tokio::test]
+async fn test_sqlite_conversation_roundtrip_preserves_tool_data() {
+    use crate::message::{Message, ToolCallData};
+
+    let store = create_test_store().await;
+    let session = Session::new("test", "model");
+    store.create_session(&session).await.expect("create failed");
+
+    let mut assistant = Message::new(Role::Assistant, "");
+    assistant.tool_calls = Some(vec![ToolCallData {
+        id: "call_1".to_string(),
+        name: "get_weather".to_string(),
+        input: serde_json::json!({"city": "London"}),
+    }]);
+    let conversation = vec![
+        Message::new(Role::User, "Weather?"),
+        assistant,
+        Message::tool_result("call_1", "sunny"),
+        Message::new(Role::Assistant, "It is sunny."),
+    ];
+
+    // Messages written in one burst can share a timestamp; insertion order
+    // must still be preserved.
+    let timestamp = Utc::now();
+    for message in &conversation {
+        let mut stored = StoredMessage::from_message(session.id, message);
+        stored.timestamp = timestamp;
+        store.add_message(&stored).await.expect("add failed");
+    }
+
+    let reloaded: Vec<Message> = store
+        .get_messages(session.id)
+        .await
+        .expect("get failed")
+        .iter()
+        .map(StoredMessage::to_message)
+        .collect();
+    assert_eq!(reloaded, conversation);
+}
+
+#[tokio::test]
+async fn test_sqlite_session_usage_aggregation() {
+    let store = create_test_store().await;
+    let session = Session::new("test", "model");
+    store.create_session(&session).await.expect("create failed");
+
+    let messages = [
+        StoredMessage::new(session.id, Role::User, "Hi"),
+        StoredMessage::new(session.id, Role::Assistant, "").with_usage(TokenUsage::new(100, 20)),
+        StoredMessage::new(session.id, Role::Assistant, "Hello")
+            .with_usage(TokenUsage::new(150, 30)),
+    ];
+    for message in &messages {
+        store.add_message(message).await.expect("add failed");
+    }
+
+    let stored = store.get_messages(session.id).await.expect("get failed");
+    assert_eq!(stored[0].usage, None);
+    assert_eq!(stored[2].usage, Some(TokenUsage::new(150, 30)));
+
+    let usage = store
+        .get_session_usage(session.id)
+        .await
+        .expect("usage failed");
+    assert_eq!(usage, TokenUsage::new(250, 50));
+
+    let empty = store
+        .get_session_usage(Uuid::new_v4())
+        .await
+        .expect("usage failed");
+    assert_eq!(empty, TokenUsage::default());
+}
+
+#[tokio::test]
+async fn test_sqlite_latest_artifact() {
+    let store = create_test_store().await;
+    let session = Session::new("test", "model");
+    store.create_session(&session).await.expect("create failed");
+
+    let none = store
+        .get_latest_artifact(session.id, ArtifactKind::Summary)
+        .await
+        .expect("get failed");
+    assert!(none.is_none());
+
+    let first = SessionArtifact::new(session.id, ArtifactKind::Summary, "first", 4);
+    let second = SessionArtifact::new(session.id, ArtifactKind::Summary, "second", 10);
+    store.add_artifact(&first).await.expect("add failed");
+    store.add_artifact(&second).await.expect("add failed");
+
+    let latest = store
+        .get_latest_artifact(session.id, ArtifactKind::Summary)
+        .await
+        .expect("get failed")
+        .expect("artifact missing");
+    assert_eq!(latest.id, second.id);
+    assert_eq!(latest.content, "second");
+    assert_eq!(latest.message_count, 10);
+}
+
+#[tokio::test]
+async fn test_sqlite_artifacts_deleted_with_session() {
+    let store = create_test_store().await;
+    let session = Session::new("test", "model");
+    store.create_session(&session).await.expect("create failed");
+    let artifact = SessionArtifact::new(session.id, ArtifactKind::Summary, "summary", 2);
+    store.add_artifact(&artifact).await.expect("add failed");
+
+    store
+        .delete_session(session.id)
+        .await
+        .expect("delete failed");
+
+    let artifact = store
+        .get_latest_artifact(session.id, ArtifactKind::Summary)
+        .await
+        .expect("get failed");
+    assert!(artifact.is_none());
+}
@@ -0,0 +1,233 @@
+//! Native tools that run inside the agent process.
+//!
+//! A [`Tool`] is a tool implemented in Rust and offered to the model next to
+//! MCP tools, so simple capabilities need no external server.
+//! [`ToolRegistry`] holds the enabled native tools;
+//! [`ToolRegistry::from_config`] builds the starter set from the `[tools.*]`
+//! config sections:
+//!
+//! - `current_time` — the current date and time
+//! - `read_file` — read a text file under an allow-listed root
+//! - `fetch` — HTTP GET from allow-listed hosts
+//! - `search_sessions` — search stored conversations
+
+mod current_time;
+mod fetch;
+mod read_file;
+mod search_sessions;
+
+pub use current_time::CurrentTimeTool;
+pub use fetch::FetchTool;
+pub use read_file::ReadFileTool;
+pub use search_sessions::SearchSessionsTool;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::config::ToolsConfig;
+use crate::mcp::ToolDefinition;
+use crate::message::ToolOutput;
+use crate::storage::SessionStore;
+
+/// Errors returned by native tools.
+#[derive(Debug, thiserror::Error)]
+pub enum ToolError {
+    /// The model passed arguments the tool cannot use.
+    #[error("invalid arguments: {0}")]
+    InvalidArguments(String),
+
+    /// The call asks for something outside the tool's allow-list.
+    #[error("not allowed: {0}")]
+    NotAllowed(String),
+
+    /// The tool ran but failed.
+    #[error("{0}")]
+    Failed(String),
+}
+
+/// A tool implemented in Rust.
+///
+/// # Examples
+///
+/// ```
+/// use async_trait::async_trait;
+/// use synapse_core::mcp::ToolDefinition;
+/// use synapse_core::message::ToolOutput;
+/// use synapse_core::tools::{Tool, ToolError, ToolRegistry};
+///
+/// struct Ping;
+///
+/// #[async_trait]
+/// impl Tool for Ping {
+///     fn definition(&self) -> ToolDefinition {
+///         ToolDefinition {
+///             name: "ping".to_string(),
+///             description: Some("Answer with pong".to_string()),
+///             input_schema: serde_json::json!({"type": "object", "properties": {}}),
+///         }
+///     }
+///
+///     async fn call(&self, _input: serde_json::Value) -> Result<ToolOutput, ToolError> {
+///         Ok(ToolOutput::text("pong"))
+///     }
+/// }
+///
+/// let registry = ToolRegistry::new().with_tool(Ping);
+/// assert!(registry.get("ping").is_some());
+/// ```
+#[async_trait]
+pub trait Tool: Send + Sync {
+    /// Name, description and input schema offered to the model.
+    fn definition(&self) -> ToolDefinition;
+
+    /// Run the tool with the arguments the model passed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ToolError`] if the arguments are invalid, the call is not
+    /// allowed, or the tool fails. The agent reports it to the model as a
+    /// tool error.
+    async fn call(&self, input: serde_json::Value) -> Result<ToolOutput, ToolError>;
+}
+
+/// The native tools offered to the model.
+///
+/// Native tools take precedence over MCP tools with the same name.
+#[derive(Clone, Default)]
+pub struct ToolRegistry {
+    /// Registered tools with their definitions.
+    tools: Vec<(ToolDefinition, Arc<dyn Tool>)>,
+}
+
+impl ToolRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a registry with the native tools enabled in `config`.
+    ///
+    /// `search_sessions` needs `storage` and is skipped without it.
+    pub fn from_config(config: &ToolsConfig, storage: Option<Arc<dyn SessionStore>>) -> Self {
+        let mut registry = Self::new();
+        if config.current_time.enabled {
+            registry = registry.with_tool(CurrentTimeTool);
+        }
+        if config.read_file.enabled {
+            registry = registry.with_tool(ReadFileTool::new(
+                config.read_file.roots.clone(),
+                config.read_file.max_bytes,
+            ));
+        }
+        if config.fetch.enabled {
+            registry = registry.with_tool(FetchTool::new(
+                config.fetch.allowed_hosts.clone(),
+                config.fetch.max_bytes,
+            ));
+        }
+        if config.search_sessions.enabled {
+            match storage {
+                Some(storage) => {
+                    registry = registry.with_tool(SearchSessionsTool::new(
+                        storage,
+                        config.search_sessions.max_results,
+                    ));
+                }
+                None => tracing::debug!("tools: search_sessions needs session storage, skipped"),
+            }
+        }
+        registry
+    }
+
+    /// Add `tool`, replacing a registered tool with the same name.
+    pub fn with_tool(mut self, tool: impl Tool + 'static) -> Self {
+        let definition = tool.definition();
+        self.tools
+            .retain(|(registered, _)| registered.name != definition.name);
+        self.tools.push((definition, Arc::new(tool)));
+        self
+    }
+
+    /// Definitions of all registered tools.
+    pub fn definitions(&self) -> Vec<ToolDefinition> {
+        self.tools
+            .iter()
+            .map(|(definition, _)| definition.clone())
+            .collect()
+    }
+
+    /// The tool named `name`, if registered.
+    pub fn get(&self, name: &str) -> Option<&dyn Tool> {
+        self.tools
+            .iter()
+            .find(|(definition, _)| definition.name == name)
+            .map(|(_, tool)| tool.as_ref())
+    }
+
+    /// Whether no tools are registered.
+    pub fn is_empty(&self) -> bool {
+        self.tools.is_empty()
+    }
+}
+
+/// Get the required string argument `key` from tool call arguments.
+fn string_arg<'a>(input: &'a serde_json::Value, key: &str) -> Result<&'a str, ToolError> {
+    input
+        .get(key)
+        .and_then(|value| value.as_str())
+        .ok_or_else(|| ToolError::InvalidArguments(format!("missing string argument '{}'", key)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{CurrentTimeToolConfig, FetchToolConfig, SearchSessionsToolConfig};
+
+    fn names(registry: &ToolRegistry) -> Vec<String> {
+        registry.definitions().into_iter().map(|t| t.name).collect()
+    }
+
+    #[test]
+    fn test_registry_from_config_enables_listed_tools() {
+        let config = ToolsConfig {
+            current_time: CurrentTimeToolConfig { enabled: true },
+            fetch: FetchToolConfig {
+                enabled: true,
+                ..Default::default()
+            },
+            search_sessions: SearchSessionsToolConfig {
+                enabled: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let registry = ToolRegistry::from_config(&config, None);
+
+        // search_sessions is skipped without storage.
+        assert_eq!(names(&registry), vec!["current_time", "fetch"]);
+        assert!(ToolRegistry::from_config(&ToolsConfig::default(), None).is_empty());
+    }
+
+    #[test]
+    fn test_registry_with_tool_replaces_same_name() {
+        let registry = ToolRegistry::new()
+            .with_tool(CurrentTimeTool)
+            .with_tool(CurrentTimeTool);
+
+        assert_eq!(names(&registry), vec!["current_time"]);
+        assert!(registry.get("current_time").is_some());
+        assert!(registry.get("missing").is_none());
+    }
+
+    #[test]
+    fn test_string_arg() {
+        let input = serde_json::json!({"path": "a.txt", "limit": 3});
+        assert_eq!(string_arg(&input, "path").unwrap(), "a.txt");
+        assert!(matches!(
+            string_arg(&input, "limit"),
+            Err(ToolError::InvalidArguments(_))
+        ));
+    }
+}
@@ -0,0 +1,46 @@
+//! The `current_time` tool.
+
+use async_trait::async_trait;
+use chrono::{Local, Utc};
+
+use super::{Tool, ToolError};
+use crate::mcp::ToolDefinition;
+use crate::message::ToolOutput;
+
+/// Reports the current local date and time, with the weekday and UTC time.
+pub struct CurrentTimeTool;
+
+#[async_trait]
+impl Tool for CurrentTimeTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "current_time".to_string(),
+            description: Some("Get the current date and time".to_string()),
+            input_schema: serde_json::json!({"type": "object", "properties": {}}),
+        }
+    }
+
+    async fn call(&self, _input: serde_json::Value) -> Result<ToolOutput, ToolError> {
+        let local = Local::now();
+        Ok(ToolOutput::text(format!(
+            "Local time: {} ({})\nUTC: {}",
+            local.to_rfc3339(),
+            local.format("%A"),
+            Utc::now().to_rfc3339()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_current_time_reports_local_and_utc() {
+        let output = CurrentTimeTool.call(serde_json::json!({})).await.unwrap();
+        let text = output.content_text();
+
+        assert!(text.starts_with("Local time: "));
+        assert!(text.contains("\nUTC: "));
+    }
+}
@@ -0,0 +1,227 @@
+//! The `fetch` tool.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use reqwest::Url;
+use reqwest::redirect::Policy;
+
+use super::{Tool, ToolError, string_arg};
+use crate::mcp::ToolDefinition;
+use crate::message::{ToolContent, ToolOutput};
+
+/// Most redirects followed for one request.
+const MAX_REDIRECTS: usize = 5;
+
+/// Fetches URLs with HTTP GET from allow-listed hosts.
+///
+/// Only `http` and `https` URLs are fetched. Redirects to hosts outside the
+/// allow-list are refused.
+pub struct FetchTool {
+    /// HTTP client enforcing the allow-list on redirects.
+    client: reqwest::Client,
+    /// Hosts that may be fetched, including their subdomains.
+    allowed_hosts: Arc<Vec<String>>,
+    /// Bytes of a response body returned before it is truncated.
+    max_bytes: usize,
+}
+
+impl FetchTool {
+    /// Create a tool that fetches from `allowed_hosts` and their subdomains,
+    /// returning at most `max_bytes` of each response body.
+    pub fn new(allowed_hosts: Vec<String>, max_bytes: usize) -> Self {
+        let allowed_hosts = Arc::new(allowed_hosts);
+        let redirect_hosts = Arc::clone(&allowed_hosts);
+        let redirect = Policy::custom(move |attempt| {
+            if attempt.previous().len() >= MAX_REDIRECTS {
+                attempt.error("too many redirects")
+            } else if is_allowed(attempt.url(), &redirect_hosts) {
+                attempt.follow()
+            } else {
+                attempt.error("redirect to a host that is not allowed")
+            }
+        });
+        let client = reqwest::Client::builder()
+            .redirect(redirect)
+            .user_agent(concat!("synapse/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .expect("HTTP client configuration is valid");
+        Self {
+            client,
+            allowed_hosts,
+            max_bytes,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for FetchTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "fetch".to_string(),
+            description: Some(format!(
+                "Fetch a web page or API response with HTTP GET. Allowed hosts: {}",
+                self.allowed_hosts.join(", ")
+            )),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "url": { "type": "string", "description": "http or https URL to fetch" }
+                },
+                "required": ["url"]
+            }),
+        }
+    }
+
+    async fn call(&self, input: serde_json::Value) -> Result<ToolOutput, ToolError> {
+        let url = string_arg(&input, "url")?;
+        let parsed = Url::parse(url)
+            .map_err(|e| ToolError::InvalidArguments(format!("invalid URL '{}': {}", url, e)))?;
+        if !is_allowed(&parsed, &self.allowed_hosts) {
+            return Err(ToolError::NotAllowed(format!(
+                "{} is not on an allowed host",
+                url
+            )));
+        }
+
+        let response = self
+            .client
+            .get(parsed)
+            .send()
+            .await
+            .map_err(|e| ToolError::Failed(format!("failed to fetch {}: {}", url, e)))?;
+        let status = response.status();
+
+        let mut body = Vec::new();
+        let mut total = 0;
+        let mut chunks = response.bytes_stream();
+        while let Some(chunk) = chunks.next().await {
+            let chunk =
+                chunk.map_err(|e| ToolError::Failed(format!("failed to read {}: {}", url, e)))?;
+            total += chunk.len();
+            let room = self.max_bytes.saturating_sub(body.len());
+            body.extend_from_slice(&chunk[..chunk.len().min(room)]);
+            if total > self.max_bytes {
+                break;
+            }
+        }
+
+        let mut text = format!("HTTP {}\n\n{}", status, String::from_utf8_lossy(&body));
+        if total > self.max_bytes {
+            text.push_str(&format!("\n[truncated: first {} bytes shown]", body.len()));
+        }
+        Ok(ToolOutput {
+            content: vec![ToolContent::Text { text }],
+            structured: None,
+            is_error: !status.is_success(),
+        })
+    }
+}
+
+/// Whether `url` is an http(s) URL on one of `allowed_hosts` or their subdomains.
+fn is_allowed(url: &Url, allowed_hosts: &[String]) -> bool {
+    if !matches!(url.scheme(), "http" | "https") {
+        return false;
+    }
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    let host = host.to_ascii_lowercase();
+    allowed_hosts.iter().any(|allowed| {
+        let allowed = allowed.to_ascii_lowercase();
+        host == allowed || host.ends_with(&format!(".{}", allowed))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::response::Redirect;
+    use axum::routing::get;
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    /// Serve a page, a large page, a redirect to `localhost` and a 404 on
+    /// an ephemeral port, returning the base URL.
+    async fn serve() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let router = Router::new()
+            .route("/page", get(|| async { "hello" }))
+            .route("/large", get(|| async { "x".repeat(1000) }))
+            .route(
+                "/away",
+                get(move || async move {
+                    Redirect::temporary(&format!("http://localhost:{}/page", port))
+                }),
+            );
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, router).await;
+        });
+        format!("http://127.0.0.1:{}", port)
+    }
+
+    async fn fetch(tool: &FetchTool, url: &str) -> Result<ToolOutput, ToolError> {
+        tool.call(serde_json::json!({ "url": url })).await
+    }
+
+    #[test]
+    fn test_is_allowed_matches_hosts_and_subdomains() {
+        let allowed = vec!["example.com".to_string()];
+        let allowed_url = |url: &str| is_allowed(&Url::parse(url).unwrap(), &allowed);
+
+        assert!(allowed_url("https://example.com/a"));
+        assert!(allowed_url("https://API.example.com/a"));
+        assert!(!allowed_url("https://badexample.com/"));
+        assert!(!allowed_url("https://example.com.evil.net/"));
+        assert!(!allowed_url("ftp://example.com/file"));
+        assert!(!allowed_url("file:///etc/passwd"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_allowed_host() {
+        let base = serve().await;
+        let tool = FetchTool::new(vec!["127.0.0.1".to_string()], 100);
+
+        let output = fetch(&tool, &format!("{}/page", base)).await.unwrap();
+        assert_eq!(output.content_text(), "HTTP 200 OK\n\nhello");
+        assert!(!output.is_error);
+
+        let output = fetch(&tool, &format!("{}/missing", base)).await.unwrap();
+        assert!(output.content_text().starts_with("HTTP 404 Not Found"));
+        assert!(output.is_error);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_truncates_large_bodies() {
+        let base = serve().await;
+        let tool = FetchTool::new(vec!["127.0.0.1".to_string()], 10);
+
+        let output = fetch(&tool, &format!("{}/large", base)).await.unwrap();
+        assert_eq!(
+            output.content_text(),
+            "HTTP 200 OK\n\nxxxxxxxxxx\n[truncated: first 10 bytes shown]"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_refuses_other_hosts_and_redirects() {
+        let base = serve().await;
+        let tool = FetchTool::new(vec!["127.0.0.1".to_string()], 100);
+
+        assert!(matches!(
+            fetch(&tool, "http://localhost/page").await,
+            Err(ToolError::NotAllowed(_))
+        ));
+        assert!(matches!(
+            fetch(&tool, &format!("{}/away", base)).await,
+            Err(ToolError::Failed(_))
+        ));
+        assert!(matches!(
+            fetch(&tool, "not a url").await,
+            Err(ToolError::InvalidArguments(_))
+        ));
+    }
+}
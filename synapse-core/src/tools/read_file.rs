@@ -0,0 +1,278 @@
+//! The `read_file` tool.
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+
+use super::{Tool, ToolError, string_arg};
+use crate::mcp::ToolDefinition;
+use crate::message::ToolOutput;
+
+/// Reads UTF-8 text files located under allow-listed root directories.
+///
+/// Paths are resolved with symlinks followed before they are checked, so a
+/// link inside a root cannot reach files outside it.
+pub struct ReadFileTool {
+    /// Directories whose files may be read.
+    roots: Vec<PathBuf>,
+    /// Bytes returned before the file is truncated.
+    max_bytes: usize,
+}
+
+impl ReadFileTool {
+    /// Create a tool that reads files under `roots`, returning at most
+    /// `max_bytes` of each. A leading `~` in a root expands to the home
+    /// directory.
+    pub fn new(roots: Vec<PathBuf>, max_bytes: usize) -> Self {
+        Self {
+            roots: roots.iter().map(|root| expand_home(root)).collect(),
+            max_bytes,
+        }
+    }
+
+    /// Resolve `path` and check that it lies under one of the roots.
+    ///
+    /// A path that cannot be resolved is only reported as such when it
+    /// fails inside a root; elsewhere the error would reveal which files
+    /// exist, so it is rejected like any other path outside the roots.
+    fn resolve(&self, path: &str) -> Result<PathBuf, ToolError> {
+        let requested = Path::new(path);
+        let candidate = match self.roots.first() {
+            Some(root) if requested.is_relative() => root.join(requested),
+            _ => requested.to_path_buf(),
+        };
+        let outside =
+            || ToolError::NotAllowed(format!("{} is outside the allowed directories", path));
+        let resolved = match candidate.canonicalize() {
+            Ok(resolved) => resolved,
+            Err(e) => {
+                let existing = candidate
+                    .ancestors()
+                    .skip(1)
+                    .find_map(|ancestor| ancestor.canonicalize().ok());
+                return Err(match existing {
+                    Some(ancestor) if self.is_allowed(&ancestor) => {
+                        ToolError::Failed(format!("cannot open {}: {}", path, e))
+                    }
+                    _ => outside(),
+                });
+            }
+        };
+        if self.is_allowed(&resolved) {
+            Ok(resolved)
+        } else {
+            Err(outside())
+        }
+    }
+
+    /// Whether the resolved path `path` lies under one of the roots.
+    fn is_allowed(&self, path: &Path) -> bool {
+        self.roots
+            .iter()
+            .filter_map(|root| root.canonicalize().ok())
+            .any(|root| path.starts_with(root))
+    }
+}
+
+#[async_trait]
+impl Tool for ReadFileTool {
+    fn definition(&self) -> ToolDefinition {
+        let roots = self
+            .roots
+            .iter()
+            .map(|root| root.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        ToolDefinition {
+            name: "read_file".to_string(),
+            description: Some(format!(
+                "Read a local text file. Allowed directories: {}",
+                roots
+            )),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "File path, absolute or relative to the first allowed directory"
+                    }
+                },
+                "required": ["path"]
+            }),
+        }
+    }
+
+    async fn call(&self, input: serde_json::Value) -> Result<ToolOutput, ToolError> {
+        let path = string_arg(&input, "path")?;
+        let resolved = self.resolve(path)?;
+        let max_bytes = self.max_bytes;
+
+        let (bytes, size) = tokio::task::spawn_blocking(move || {
+            let file = std::fs::File::open(&resolved)?;
+            let size = file.metadata()?.len();
+            let mut bytes = Vec::new();
+            file.take(max_bytes as u64).read_to_end(&mut bytes)?;
+            Ok::<_, std::io::Error>((bytes, size))
+        })
+        .await
+        .map_err(|e| ToolError::Failed(e.to_string()))?
+        .map_err(|e| ToolError::Failed(format!("cannot read {}: {}", path, e)))?;
+
+        let truncated = (bytes.len() as u64) < size;
+        let mut text = match String::from_utf8(bytes) {
+            Ok(text) => text,
+            // The cut may split a multi-byte character; keep the valid prefix.
+            Err(e) if truncated && e.utf8_error().error_len().is_none() => {
+                let valid = e.utf8_error().valid_up_to();
+                let mut bytes = e.into_bytes();
+                bytes.truncate(valid);
+                String::from_utf8(bytes).expect("valid prefix")
+            }
+            Err(_) => {
+                return Err(ToolError::Failed(format!(
+                    "{} is not a UTF-8 text file",
+                    path
+                )));
+            }
+        };
+        if truncated {
+            let shown = text.len();
+            text.push_str(&format!("\n[truncated: {} of {} bytes shown]", shown, size));
+        }
+        Ok(ToolOutput::text(text))
+    }
+}
+
+/// Expand a leading `~` in `path` to the home directory.
+fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Create a fresh temporary directory with `files` written into it.
+    fn temp_root(files: &[(&str, &[u8])]) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("synapse_read_file_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        for (name, contents) in files {
+            std::fs::write(root.join(name), contents).unwrap();
+        }
+        root
+    }
+
+    async fn read(tool: &ReadFileTool, path: &str) -> Result<String, ToolError> {
+        tool.call(serde_json::json!({ "path": path }))
+            .await
+            .map(|output| output.content_text())
+    }
+
+    #[tokio::test]
+    async fn test_read_file_relative_and_absolute_paths() {
+        let root = temp_root(&[("notes.txt", b"hello")]);
+        let tool = ReadFileTool::new(vec![root.clone()], 1000);
+
+        assert_eq!(read(&tool, "notes.txt").await.unwrap(), "hello");
+        let absolute = root.join("notes.txt");
+        assert_eq!(
+            read(&tool, absolute.to_str().unwrap()).await.unwrap(),
+            "hello"
+        );
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_read_file_rejects_paths_outside_roots() {
+        let root = temp_root(&[]);
+        let outside = temp_root(&[("secret.txt", b"secret")]);
+        let tool = ReadFileTool::new(vec![root.clone()], 1000);
+
+        let escaped = format!(
+            "../{}/secret.txt",
+            outside.file_name().unwrap().to_str().unwrap()
+        );
+        assert!(matches!(
+            read(&tool, &escaped).await,
+            Err(ToolError::NotAllowed(_))
+        ));
+        let absolute = outside.join("secret.txt");
+        assert!(matches!(
+            read(&tool, absolute.to_str().unwrap()).await,
+            Err(ToolError::NotAllowed(_))
+        ));
+        std::fs::remove_dir_all(root).unwrap();
+        std::fs::remove_dir_all(outside).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_read_file_hides_missing_files_outside_roots() {
+        let root = temp_root(&[]);
+        let outside = temp_root(&[("secret.txt", b"secret")]);
+        let tool = ReadFileTool::new(vec![root.clone()], 1000);
+
+        let existing = outside.join("secret.txt");
+        let missing = outside.join("missing.txt");
+        let existing = read(&tool, existing.to_str().unwrap()).await;
+        let missing = read(&tool, missing.to_str().unwrap()).await;
+        // Both fail the same way, so the model cannot probe for files.
+        assert!(matches!(
+            &existing,
+            Err(ToolError::NotAllowed(m)) if m.ends_with("is outside the allowed directories")
+        ));
+        assert!(matches!(
+            &missing,
+            Err(ToolError::NotAllowed(m)) if m.ends_with("is outside the allowed directories")
+        ));
+        assert!(matches!(
+            read(&tool, "../no-such-dir/file.txt").await,
+            Err(ToolError::NotAllowed(_))
+        ));
+        std::fs::remove_dir_all(root).unwrap();
+        std::fs::remove_dir_all(outside).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_read_file_truncates_large_files() {
+        let root = temp_root(&[("long.txt", "aé".repeat(10).as_bytes())]);
+        let tool = ReadFileTool::new(vec![root.clone()], 5);
+
+        // The fifth byte starts an "é"; the cut keeps whole characters only.
+        assert_eq!(
+            read(&tool, "long.txt").await.unwrap(),
+            "aéa\n[truncated: 4 of 30 bytes shown]"
+        );
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_read_file_rejects_binary_and_missing_files() {
+        let root = temp_root(&[("image.bin", &[0xff, 0xfe, 0x00])]);
+        let tool = ReadFileTool::new(vec![root.clone()], 1000);
+
+        assert!(matches!(
+            read(&tool, "image.bin").await,
+            Err(ToolError::Failed(_))
+        ));
+        assert!(matches!(
+            read(&tool, "missing.txt").await,
+            Err(ToolError::Failed(_))
+        ));
+        assert!(matches!(
+            tool.call(serde_json::json!({})).await,
+            Err(ToolError::InvalidArguments(_))
+        ));
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_expand_home() {
+        let home = dirs::home_dir().unwrap();
+        assert_eq!(expand_home(Path::new("~/notes")), home.join("notes"));
+        assert_eq!(expand_home(Path::new("/srv")), PathBuf::from("/srv"));
+    }
+}
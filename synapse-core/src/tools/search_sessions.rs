@@ -0,0 +1,208 @@
+//! The `search_sessions` tool.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use super::{Tool, ToolError, string_arg};
+use crate::mcp::ToolDefinition;
+use crate::message::{Role, ToolOutput};
+use crate::storage::SessionStore;
+use crate::text::truncate;
+
+/// Maximum characters of a matching message shown in search results.
+const SNIPPET_CHARS: usize = 200;
+
+/// Searches the user and assistant messages of stored sessions.
+///
+/// Matching is a case-insensitive substring search; the most recently
+/// updated sessions are searched first. All sessions are searched unless
+/// the tool is limited to one scope with [`with_scope`](Self::with_scope).
+pub struct SearchSessionsTool {
+    /// Store holding the sessions to search.
+    storage: Arc<dyn SessionStore>,
+    /// Most matching messages returned by one search.
+    max_results: usize,
+    /// Only sessions with this name are searched, if set.
+    scope: Option<String>,
+}
+
+impl SearchSessionsTool {
+    /// Create a tool that searches `storage`, returning at most
+    /// `max_results` messages per search.
+    pub fn new(storage: Arc<dyn SessionStore>, max_results: usize) -> Self {
+        Self {
+            storage,
+            max_results,
+            scope: None,
+        }
+    }
+
+    /// Only search sessions named `scope`, such as the sessions of one
+    /// Telegram chat, so one user's conversations stay hidden from another.
+    pub fn with_scope(mut self, scope: impl Into<String>) -> Self {
+        self.scope = Some(scope.into());
+        self
+    }
+}
+
+#[async_trait]
+impl Tool for SearchSessionsTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "search_sessions".to_string(),
+            description: Some(
+                "Search earlier conversations for messages containing a phrase".to_string(),
+            ),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "Text to look for" }
+                },
+                "required": ["query"]
+            }),
+        }
+    }
+
+    async fn call(&self, input: serde_json::Value) -> Result<ToolOutput, ToolError> {
+        let query = string_arg(&input, "query")?.trim();
+        if query.is_empty() {
+            return Err(ToolError::InvalidArguments("query is empty".to_string()));
+        }
+        let needle = query.to_lowercase();
+
+        let storage_error = |e: crate::storage::StorageError| ToolError::Failed(e.to_string());
+        // Listed most recently updated first.
+        let sessions = self
+            .storage
+            .list_sessions()
+            .await
+            .map_err(storage_error)?
+            .into_iter()
+            .filter(|session| match &self.scope {
+                Some(scope) => session.name.as_deref() == Some(scope.as_str()),
+                None => true,
+            });
+
+        let mut results = Vec::new();
+        'sessions: for session in sessions {
+            let messages = self
+                .storage
+                .get_messages(session.id)
+                .await
+                .map_err(storage_error)?;
+            for message in messages {
+                if !matches!(message.role, Role::User | Role::Assistant)
+                    || !message.content.to_lowercase().contains(&needle)
+                {
+                    continue;
+                }
+                results.push(format!(
+                    "[{}] session {}{} — {}: {}",
+                    message.timestamp.format("%Y-%m-%d %H:%M"),
+                    session.id,
+                    session
+                        .name
+                        .as_deref()
+                        .map(|name| format!(" ({})", name))
+                        .unwrap_or_default(),
+                    message.role.as_str(),
+                    truncate(message.content.trim(), SNIPPET_CHARS)
+                ));
+                if results.len() >= self.max_results {
+                    break 'sessions;
+                }
+            }
+        }
+
+        if results.is_empty() {
+            return Ok(ToolOutput::text(format!("No messages match '{}'.", query)));
+        }
+        Ok(ToolOutput::text(results.join("\n")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::session::{Session, StoredMessage};
+    use crate::storage::SqliteStore;
+
+    async fn store_with_sessions(sessions: &[(&str, &[(Role, &str)])]) -> Arc<dyn SessionStore> {
+        let db_path = std::env::temp_dir().join(format!("synapse_test_{}.db", Uuid::new_v4()));
+        let store = SqliteStore::new(&format!("sqlite:{}", db_path.display()))
+            .await
+            .expect("failed to create test store");
+        for (name, messages) in sessions {
+            let session = Session::new("mock", "mock-model").with_name(*name);
+            store.create_session(&session).await.unwrap();
+            for (role, content) in *messages {
+                let message = StoredMessage::new(session.id, *role, *content);
+                store.add_message(&message).await.unwrap();
+            }
+        }
+        Arc::new(store)
+    }
+
+    async fn store_with_messages(messages: &[(Role, &str)]) -> Arc<dyn SessionStore> {
+        store_with_sessions(&[("Trip", messages)]).await
+    }
+
+    async fn search(tool: &SearchSessionsTool, query: &str) -> String {
+        tool.call(serde_json::json!({ "query": query }))
+            .await
+            .unwrap()
+            .content_text()
+    }
+
+    #[tokio::test]
+    async fn test_search_sessions_finds_matching_messages() {
+        let storage = store_with_messages(&[
+            (Role::User, "Book a train to Lisbon"),
+            (Role::Assistant, "Lisbon trains leave hourly."),
+            (Role::Tool, "lisbon timetable"),
+            (Role::User, "Thanks"),
+        ])
+        .await;
+        let tool = SearchSessionsTool::new(storage, 10);
+
+        let results = search(&tool, "LISBON").await;
+
+        let lines: Vec<_> = results.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("(Trip) — user: Book a train to Lisbon"));
+        assert!(lines[1].contains("assistant: Lisbon trains leave hourly."));
+    }
+
+    #[tokio::test]
+    async fn test_search_sessions_limits_results() {
+        let storage =
+            store_with_messages(&[(Role::User, "note one"), (Role::User, "note two")]).await;
+        let tool = SearchSessionsTool::new(storage, 1);
+
+        assert_eq!(search(&tool, "note").await.lines().count(), 1);
+        assert_eq!(search(&tool, "absent").await, "No messages match 'absent'.");
+        assert!(matches!(
+            tool.call(serde_json::json!({"query": "  "})).await,
+            Err(ToolError::InvalidArguments(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_search_sessions_with_scope_hides_other_scopes() {
+        let storage = store_with_sessions(&[
+            ("tg:1", &[(Role::User, "my secret plan")]),
+            ("tg:2", &[(Role::User, "their secret plan")]),
+            ("cli notes", &[(Role::User, "owner's secret plan")]),
+        ])
+        .await;
+        let tool = SearchSessionsTool::new(storage, 10).with_scope("tg:1");
+
+        let results = search(&tool, "secret").await;
+
+        assert_eq!(results.lines().count(), 1);
+        assert!(results.contains("(tg:1) — user: my secret plan"));
+    }
+}
@@ -0,0 +1,390 @@
+//! Tool call approval via inline keyboard buttons.
+//!
+//! [`TelegramApprover`] posts an approval request with Allow / Always / Deny
+//! buttons to the chat and waits for a tap. Taps arrive as callback queries
+//! with `"allow:N"`, `"always:N"` or `"deny:N"` data (`N` is the request ID)
+//! and are resolved through [`resolve_callback`]. Only the user whose message
+//! started the turn may answer. Requests left unanswered for
+//! [`APPROVAL_TIMEOUT`] are denied.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use synapse_core::message::ToolCallData;
+use synapse_core::text::truncate;
+use synapse_core::{ApprovalDecision, ToolApprover};
+use teloxide::prelude::*;
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, ParseMode};
+use tokio::sync::oneshot;
+
+use crate::format::escape_html;
+use crate::handlers::tg_session_name;
+
+/// How long an approval request waits for a tap before the call is denied.
+pub const APPROVAL_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Maximum characters of tool arguments shown in an approval request.
+const ARGUMENTS_PREVIEW_CHARS: usize = 500;
+
+/// Notice shown to a group member who taps someone else's approval buttons.
+const NOT_YOUR_REQUEST: &str = "Only the person who asked can answer this request.";
+
+/// Pending approval requests of all chats.
+static APPROVALS: LazyLock<Approvals> = LazyLock::new(Approvals::default);
+
+/// The user's answer to an approval request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApprovalAnswer {
+    /// Run this call.
+    Allow,
+    /// Run this call and every later call to the same tool in this session
+    /// scope (the chat, or one member's sessions with per-user group sessions).
+    Always,
+    /// Deny this call.
+    Deny,
+}
+
+impl ApprovalAnswer {
+    /// Parse the action part of `"action:N"` callback data.
+    pub fn from_action(action: &str) -> Option<Self> {
+        match action {
+            "allow" => Some(Self::Allow),
+            "always" => Some(Self::Always),
+            "deny" => Some(Self::Deny),
+            _ => None,
+        }
+    }
+}
+
+/// The outcome of a tap on an approval button.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Resolution {
+    /// The request was answered or has expired; the text replaces the request.
+    Answered(String),
+    /// The tap came from someone other than the requester; the text is shown
+    /// to the tapper only, and the request keeps waiting.
+    Refused(String),
+}
+
+/// An approval request waiting for a tap.
+struct Pending {
+    /// Chat the request was posted to; taps from other chats are ignored.
+    chat: ChatId,
+    /// User whose message started the turn; only they may answer.
+    user: UserId,
+    /// Session scope an "always" answer applies to.
+    scope: String,
+    /// Name of the tool the request is about.
+    tool: String,
+    /// Sends the answer back to the waiting agent.
+    reply: oneshot::Sender<ApprovalAnswer>,
+}
+
+/// Registry of pending approval requests and per-scope "always" answers.
+#[derive(Default)]
+struct Approvals {
+    /// ID of the next request.
+    next_id: AtomicUsize,
+    /// Requests waiting for a tap, keyed by ID.
+    pending: Mutex<HashMap<usize, Pending>>,
+    /// Tools each session scope approved with "always".
+    always: Mutex<HashMap<String, HashSet<String>>>,
+}
+
+impl Approvals {
+    /// Register a request and return its ID and the receiver for the answer.
+    fn register(
+        &self,
+        requester: &TelegramApprover,
+        tool: &str,
+    ) -> (usize, oneshot::Receiver<ApprovalAnswer>) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (reply, answer) = oneshot::channel();
+        let pending = Pending {
+            chat: requester.chat,
+            user: requester.user,
+            scope: requester.scope.clone(),
+            tool: tool.to_string(),
+            reply,
+        };
+        self.pending.lock().expect("lock").insert(id, pending);
+        (id, answer)
+    }
+
+    /// Drop a request that can no longer be answered.
+    fn cancel(&self, id: usize) {
+        self.pending.lock().expect("lock").remove(&id);
+    }
+
+    /// Whether session scope `scope` approved `tool` with "always".
+    fn is_always(&self, scope: &str, tool: &str) -> bool {
+        self.always
+            .lock()
+            .expect("lock")
+            .get(scope)
+            .is_some_and(|tools| tools.contains(tool))
+    }
+
+    /// Answer request `id` with a tap by `user` in `chat`.
+    fn resolve(&self, id: usize, chat: ChatId, user: UserId, answer: ApprovalAnswer) -> Resolution {
+        let pending = {
+            let mut pending = self.pending.lock().expect("lock");
+            match pending.get(&id) {
+                Some(request) if request.chat == chat && request.user != user => {
+                    return Resolution::Refused(NOT_YOUR_REQUEST.to_string());
+                }
+                Some(request) if request.chat == chat => pending.remove(&id),
+                _ => None,
+            }
+        };
+        let Some(pending) = pending else {
+            return Resolution::Answered("This approval request has expired.".to_string());
+        };
+
+        if answer == ApprovalAnswer::Always {
+            self.always
+                .lock()
+                .expect("lock")
+                .entry(pending.scope.clone())
+                .or_default()
+                .insert(pending.tool.clone());
+        }
+        let reply = match answer {
+            ApprovalAnswer::Allow => format!("✅ Allowed {}.", pending.tool),
+            ApprovalAnswer::Always if pending.scope == tg_session_name(pending.chat.0) => {
+                format!("✅ Allowed {} for this chat.", pending.tool)
+            }
+            ApprovalAnswer::Always => format!("✅ Allowed {} for you in this chat.", pending.tool),
+            ApprovalAnswer::Deny => format!("❌ Denied {}.", pending.tool),
+        };
+        // The agent may have given up waiting; the reply is still accurate.
+        let _ = pending.reply.send(answer);
+        Resolution::Answered(reply)
+    }
+}
+
+/// Answer approval request `id` with a button tap by `user` in `chat`.
+pub fn resolve_callback(
+    id: usize,
+    chat: ChatId,
+    user: UserId,
+    answer: ApprovalAnswer,
+) -> Resolution {
+    APPROVALS.resolve(id, chat, user, answer)
+}
+
+/// [`ToolApprover`] that asks the user who started a turn with inline
+/// keyboard buttons.
+pub struct TelegramApprover {
+    /// Bot used to post approval requests.
+    bot: Bot,
+    /// Chat whose conversation is running the tool call.
+    chat: ChatId,
+    /// User whose message started the turn.
+    user: UserId,
+    /// Session scope of the conversation, which "always" answers apply to.
+    scope: String,
+}
+
+impl TelegramApprover {
+    /// Create an approver that asks `user` in `chat`, remembering "always"
+    /// answers for session scope `scope`.
+    pub fn new(bot: Bot, chat: ChatId, user: UserId, scope: impl Into<String>) -> Self {
+        Self {
+            bot,
+            chat,
+            user,
+            scope: scope.into(),
+        }
+    }
+
+    /// Chat the approver asks in.
+    pub fn chat(&self) -> ChatId {
+        self.chat
+    }
+}
+
+#[async_trait]
+impl ToolApprover for TelegramApprover {
+    async fn approve(&self, call: &ToolCallData, server: Option<&str>) -> ApprovalDecision {
+        if APPROVALS.is_always(&self.scope, &call.name) {
+            return ApprovalDecision::Allow;
+        }
+
+        let (id, answer) = APPROVALS.register(self, &call.name);
+        let sent = self
+            .bot
+            .send_message(self.chat, approval_request(call, server))
+            .parse_mode(ParseMode::Html)
+            .reply_markup(approval_keyboard(id))
+            .await;
+        let request = match sent {
+            Ok(request) => request,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to send approval request to chat {}: {}",
+                    self.chat.0,
+                    e
+                );
+                APPROVALS.cancel(id);
+                return ApprovalDecision::Deny;
+            }
+        };
+
+        match tokio::time::timeout(APPROVAL_TIMEOUT, answer).await {
+            Ok(Ok(ApprovalAnswer::Allow | ApprovalAnswer::Always)) => ApprovalDecision::Allow,
+            Ok(Ok(ApprovalAnswer::Deny)) | Ok(Err(_)) => ApprovalDecision::Deny,
+            Err(_) => {
+                APPROVALS.cancel(id);
+                let expired = format!("⌛ No answer — denied {}.", call.name);
+                if let Err(e) = self
+                    .bot
+                    .edit_message_text(self.chat, request.id, expired)
+                    .await
+                {
+                    tracing::warn!("Failed to expire approval request: {}", e);
+                }
+                ApprovalDecision::Deny
+            }
+        }
+    }
+}
+
+/// Build the HTML approval request naming the tool and its arguments.
+pub fn approval_request(call: &ToolCallData, server: Option<&str>) -> String {
+    let tool = match server {
+        Some(server) => format!("{}/{}", server, call.name),
+        None => call.name.clone(),
+    };
+    let arguments = format!("{:#}", call.input);
+    format!(
+        "🔐 Allow <code>{}</code>?\n<pre>{}</pre>",
+        escape_html(&tool),
+        escape_html(&truncate(&arguments, ARGUMENTS_PREVIEW_CHARS))
+    )
+}
+
+/// Build the Allow / Always / Deny keyboard for request `id`.
+pub fn approval_keyboard(id: usize) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(vec![vec![
+        InlineKeyboardButton::callback("✅ Allow", format!("allow:{}", id)),
+        InlineKeyboardButton::callback("♾ Always", format!("always:{}", id)),
+        InlineKeyboardButton::callback("❌ Deny", format!("deny:{}", id)),
+    ]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_approval_answer_from_action() {
+        assert_eq!(
+            ApprovalAnswer::from_action("allow"),
+            Some(ApprovalAnswer::Allow)
+        );
+        assert_eq!(
+            ApprovalAnswer::from_action("always"),
+            Some(ApprovalAnswer::Always)
+        );
+        assert_eq!(
+            ApprovalAnswer::from_action("deny"),
+            Some(ApprovalAnswer::Deny)
+        );
+        assert_eq!(ApprovalAnswer::from_action("switch"), None);
+    }
+
+    #[test]
+    fn test_approval_keyboard_callback_data() {
+        let keyboard = approval_keyboard(7);
+        let data: Vec<_> = keyboard.inline_keyboard[0]
+            .iter()
+            .map(|button| match &button.kind {
+                teloxide::types::InlineKeyboardButtonKind::CallbackData(data) => data.clone(),
+                other => panic!("unexpected button kind: {:?}", other),
+            })
+            .collect();
+
+        assert_eq!(data, vec!["allow:7", "always:7", "deny:7"]);
+    }
+
+    #[test]
+    fn test_approval_request_escapes_html() {
+        let call = ToolCallData {
+            id: "call_1".to_string(),
+            name: "write_file".to_string(),
+            input: serde_json::json!({"text": "<b>"}),
+        };
+
+        let html = approval_request(&call, Some("fs"));
+
+        assert!(html.starts_with("🔐 Allow <code>fs/write_file</code>?"));
+        assert!(html.contains("&lt;b&gt;"));
+    }
+
+    fn approver(chat: i64, user: u64, scope: &str) -> TelegramApprover {
+        TelegramApprover::new(Bot::new("0:test"), ChatId(chat), UserId(user), scope)
+    }
+
+    #[tokio::test]
+    async fn test_resolve_sends_answer() {
+        let approvals = Approvals::default();
+        let (id, answer) = approvals.register(&approver(1, 10, "tg:1"), "write_file");
+
+        let reply = approvals.resolve(id, ChatId(1), UserId(10), ApprovalAnswer::Deny);
+
+        assert_eq!(
+            reply,
+            Resolution::Answered("❌ Denied write_file.".to_string())
+        );
+        assert_eq!(answer.await.unwrap(), ApprovalAnswer::Deny);
+        assert!(!approvals.is_always("tg:1", "write_file"));
+    }
+
+    #[test]
+    fn test_resolve_always_is_per_scope() {
+        let approvals = Approvals::default();
+        let (id, _answer) = approvals.register(&approver(-5, 10, "tg:-5:10"), "write_file");
+
+        approvals.resolve(id, ChatId(-5), UserId(10), ApprovalAnswer::Always);
+
+        assert!(approvals.is_always("tg:-5:10", "write_file"));
+        assert!(!approvals.is_always("tg:-5:11", "write_file"));
+        assert!(!approvals.is_always("tg:-5", "write_file"));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_refuses_other_user() {
+        let approvals = Approvals::default();
+        let (id, answer) = approvals.register(&approver(-5, 10, "tg:-5"), "write_file");
+
+        let reply = approvals.resolve(id, ChatId(-5), UserId(11), ApprovalAnswer::Always);
+
+        assert_eq!(reply, Resolution::Refused(NOT_YOUR_REQUEST.to_string()));
+        assert!(!approvals.is_always("tg:-5", "write_file"));
+        // The request still waits for its requester.
+        let reply = approvals.resolve(id, ChatId(-5), UserId(10), ApprovalAnswer::Allow);
+        assert_eq!(
+            reply,
+            Resolution::Answered("✅ Allowed write_file.".to_string())
+        );
+        assert_eq!(answer.await.unwrap(), ApprovalAnswer::Allow);
+    }
+
+    #[test]
+    fn test_resolve_rejects_other_chat_and_expired() {
+        let approvals = Approvals::default();
+        let (id, _answer) = approvals.register(&approver(1, 10, "tg:1"), "write_file");
+        let expired = Resolution::Answered("This approval request has expired.".to_string());
+
+        let reply = approvals.resolve(id, ChatId(2), UserId(10), ApprovalAnswer::Allow);
+        assert_eq!(reply, expired);
+
+        approvals.cancel(id);
+        let reply = approvals.resolve(id, ChatId(1), UserId(10), ApprovalAnswer::Allow);
+        assert_eq!(reply, expired);
+    }
+}
@@ -1,8 +1,9 @@
 //! Telegram bot slash-command handlers for Synapse session management.
 //!
-//! Implements the `/start`, `/help`, `/new`, `/history`, `/list`, `/switch [N]`, and
-//! `/delete [N]` commands. None of these commands invoke LLM inference — they manage
-//! sessions only. When `/switch` or `/delete` are used without an argument, an inline
+//! Implements the `/start`, `/help`, `/new`, `/history`, `/list`, `/switch [N]`,
+//! `/delete [N]`, `/model [name]`, `/resources`, `/prompt [name args]` and
+//! `/mcp [reload]` commands. Only `/prompt` invokes LLM inference — the others
+//! manage sessions or MCP servers; `/mcp reload` is limited to `admin_users`. When `/switch` or `/delete` are used without an argument, an inline
 //! keyboard is displayed so the user can select a session by tapping a button.
 //!
 //! Keyboard builders and callback logic are in the [`keyboard`] submodule.
@@ -14,16 +15,20 @@ pub use keyboard::handle_callback;
 use std::sync::Arc;
 
 use chrono::TimeZone;
+use synapse_core::mcp::{PromptDefinition, ResourceDefinition, parse_prompt_command};
 use synapse_core::message::Role;
 use synapse_core::session::{Session, StoredMessage};
 use synapse_core::text::truncate;
-use synapse_core::{Config, SessionStore};
+use synapse_core::{
+    Agent, Config, McpClient, SessionStore, TokenUsage, create_provider, load_mcp_config,
+};
 use teloxide::prelude::*;
 use teloxide::types::Message as TgMessage;
 use teloxide::utils::command::BotCommands;
 
 use crate::handlers::{
-    ChatSessionMap, ChatSessions, NO_SESSIONS_HINT, check_auth, chunk_message, tg_session_name,
+    ChatSessionMap, ChatSessions, NO_SESSIONS_HINT, check_auth, chunk_message, is_admin, run_turn,
+    session_scope,
 };
 
 /// Maximum number of messages shown in `/history`.
@@ -63,6 +68,18 @@ pub enum Command {
     /// Delete session number N (1-based index). Omit N to see a keyboard.
     #[command(description = "Delete session N")]
     Delete(String),
+    /// Show or switch the active session's model (profile or model name).
+    #[command(description = "Show or switch the model")]
+    Model(String),
+    /// List the resources offered by MCP servers.
+    #[command(description = "List MCP resources")]
+    Resources,
+    /// Run an MCP prompt (`/prompt name key=value`). Omit the name to list prompts.
+    #[command(description = "Run an MCP prompt")]
+    Prompt(String),
+    /// List the MCP servers, or reload them from the MCP config file (`/mcp reload`, admins only).
+    #[command(description = "List or reload MCP servers")]
+    Mcp(String),
 }
 
 /// Entry-point handler for all slash commands.
@@ -73,21 +90,34 @@ pub async fn handle_command(
     msg: TgMessage,
     cmd: Command,
     config: Arc<Config>,
+    agent: Arc<Agent>,
     storage: Arc<dyn SessionStore>,
     chat_map: ChatSessionMap,
 ) -> ResponseResult<()> {
     if let Some(result) = check_auth(&msg, &config).await {
         return result;
     }
+    let user_id = msg.from.as_ref().map(|u| u.id.0).unwrap_or(0);
+    let scope = session_scope(&msg.chat, user_id, &config);
 
     match cmd {
         Command::Start => cmd_start(&bot, &msg).await,
         Command::Help => cmd_help(&bot, &msg).await,
-        Command::New => cmd_new(&bot, &msg, &config, &storage, &chat_map).await,
-        Command::History => cmd_history(&bot, &msg, &storage, &chat_map).await,
-        Command::List => cmd_list(&bot, &msg, &storage, &chat_map).await,
-        Command::Switch(ref arg) => cmd_switch(&bot, &msg, arg, &storage, &chat_map).await,
-        Command::Delete(ref arg) => cmd_delete(&bot, &msg, arg, &config, &storage, &chat_map).await,
+        Command::New => cmd_new(&bot, &msg, &scope, &config, &storage, &chat_map).await,
+        Command::History => cmd_history(&bot, &msg, &scope, &config, &storage, &chat_map).await,
+        Command::List => cmd_list(&bot, &msg, &scope, &storage, &chat_map).await,
+        Command::Switch(ref arg) => cmd_switch(&bot, &msg, arg, &scope, &storage, &chat_map).await,
+        Command::Delete(ref arg) => {
+            cmd_delete(&bot, &msg, arg, &scope, &config, &storage, &chat_map).await
+        }
+        Command::Model(ref arg) => {
+            cmd_model(&bot, &msg, arg, &scope, &config, &storage, &chat_map).await
+        }
+        Command::Resources => cmd_resources(&bot, &msg, &agent).await,
+        Command::Prompt(ref arg) => {
+            cmd_prompt(&bot, &msg, arg, &config, &agent, &storage, &chat_map).await
+        }
+        Command::Mcp(ref arg) => cmd_mcp(&bot, &msg, arg, &config, &agent).await,
     }
 }
 
@@ -101,7 +131,8 @@ pub async fn handle_command(
 fn format_history(messages: &[StoredMessage]) -> String {
     let filtered: Vec<&StoredMessage> = messages
         .iter()
-        .filter(|m| matches!(m.role, Role::User | Role::Assistant))
+        // Tool results and tool-call-only assistant turns are not shown.
+        .filter(|m| matches!(m.role, Role::User | Role::Assistant) && !m.content.is_empty())
         .collect();
     let skip = filtered.len().saturating_sub(HISTORY_MESSAGE_LIMIT);
     let recent = &filtered[skip..];
@@ -123,6 +154,78 @@ fn format_history(messages: &[StoredMessage]) -> String {
     output
 }
 
+/// Format the session token usage footer shown after `/history`.
+///
+/// The estimated cost is included only when the model has a configured price.
+fn format_usage(usage: TokenUsage, cost: Option<f64>) -> String {
+    let mut footer = format!(
+        "Tokens: {} in / {} out",
+        usage.input_tokens, usage.output_tokens
+    );
+    if let Some(cost) = cost {
+        footer.push_str(&format!(" (est. ${:.4})", cost));
+    }
+    footer
+}
+
+/// Format the reply to `/model` without an argument.
+///
+/// Shows the session's provider, model and profile, and the configured profile names.
+fn format_model_info(session: &Session, config: &Config) -> String {
+    let mut output = format!("Current model: {} / {}", session.provider, session.model);
+    if let Some(profile) = &session.profile {
+        output.push_str(&format!(" (profile {})", profile));
+    }
+    if !config.profiles.is_empty() {
+        let mut names: Vec<&str> = config.profiles.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        output.push_str(&format!("\nProfiles: {}", names.join(", ")));
+    }
+    output.push_str("\nUse /model <profile or model> to switch.");
+    output
+}
+
+/// Format the reply to `/resources`: one `@server:uri` reference per resource.
+fn format_resources(resources: &[ResourceDefinition]) -> String {
+    if resources.is_empty() {
+        return "No MCP resources available.".to_string();
+    }
+    let mut output = String::from("Resources — mention @server:uri in a message to attach one:\n");
+    for resource in resources {
+        output.push_str(&format!(
+            "\n@{}:{} — {}",
+            resource.server, resource.uri, resource.name
+        ));
+        if let Some(ref description) = resource.description {
+            output.push_str(&format!(": {}", description));
+        }
+    }
+    output
+}
+
+/// Format the reply to `/prompt` without an argument: each prompt with its
+/// arguments, optional ones in brackets.
+fn format_prompts(prompts: &[PromptDefinition]) -> String {
+    if prompts.is_empty() {
+        return "No MCP prompts available.".to_string();
+    }
+    let mut output = String::from("Prompts — run with /prompt name key=value:\n");
+    for prompt in prompts {
+        output.push_str(&format!("\n{}", prompt.name));
+        for arg in &prompt.arguments {
+            if arg.required {
+                output.push_str(&format!(" {}=…", arg.name));
+            } else {
+                output.push_str(&format!(" [{}=…]", arg.name));
+            }
+        }
+        if let Some(ref description) = prompt.description {
+            output.push_str(&format!(" — {}", description));
+        }
+    }
+    output
+}
+
 /// Send a welcome message for new users or re-opening the bot.
 async fn cmd_start(bot: &Bot, msg: &TgMessage) -> ResponseResult<()> {
     let welcome = "Welcome to Synapse! I'm an AI assistant.\n\n\
@@ -157,15 +260,15 @@ async fn cmd_help(bot: &Bot, msg: &TgMessage) -> ResponseResult<()> {
     Ok(())
 }
 
-/// Create a new session for this chat, evicting the oldest if the cap is reached.
+/// Create a new session in `scope`, evicting the oldest if the cap is reached.
 async fn cmd_new(
     bot: &Bot,
     msg: &TgMessage,
+    scope: &str,
     config: &Config,
     storage: &Arc<dyn SessionStore>,
     chat_map: &ChatSessionMap,
 ) -> ResponseResult<()> {
-    let chat_id = msg.chat.id.0;
     let max_sessions = config
         .telegram
         .as_ref()
@@ -176,10 +279,12 @@ async fn cmd_new(
 
     {
         let mut map = chat_map.write().await;
-        let chat_sessions = map.entry(chat_id).or_insert_with(|| ChatSessions {
-            sessions: vec![],
-            active_idx: 0,
-        });
+        let chat_sessions = map
+            .entry(scope.to_string())
+            .or_insert_with(|| ChatSessions {
+                sessions: vec![],
+                active_idx: 0,
+            });
 
         // Enforce session cap: evict the oldest session (last in the vec = oldest).
         if chat_sessions.sessions.len() >= max_sessions
@@ -191,11 +296,10 @@ async fn cmd_new(
         }
 
         // Create the new session.
-        let session =
-            Session::new(&config.provider, &config.model).with_name(tg_session_name(chat_id));
+        let session = Session::new(&config.provider, &config.model).with_name(scope);
 
         if let Err(e) = storage.create_session(&session).await {
-            tracing::error!("Failed to create session for chat {}: {}", chat_id, e);
+            tracing::error!("Failed to create session {}: {}", scope, e);
             drop(map);
             bot.send_message(msg.chat.id, "Failed to create session. Please try again.")
                 .await?;
@@ -220,14 +324,14 @@ async fn cmd_new(
 async fn cmd_history(
     bot: &Bot,
     msg: &TgMessage,
+    scope: &str,
+    config: &Config,
     storage: &Arc<dyn SessionStore>,
     chat_map: &ChatSessionMap,
 ) -> ResponseResult<()> {
-    let chat_id = msg.chat.id.0;
-
     let session_id = {
         let map = chat_map.read().await;
-        map.get(&chat_id).and_then(|cs| cs.active_session_id())
+        map.get(scope).and_then(|cs| cs.active_session_id())
     };
 
     let session_id = match session_id {
@@ -243,7 +347,7 @@ async fn cmd_history(
     };
 
     let messages = storage.get_messages(session_id).await.unwrap_or_default();
-    let output = format_history(&messages);
+    let mut output = format_history(&messages);
 
     if output.is_empty() {
         bot.send_message(msg.chat.id, "No messages in current session.")
@@ -251,6 +355,18 @@ async fn cmd_history(
         return Ok(());
     }
 
+    let usage = storage
+        .get_session_usage(session_id)
+        .await
+        .unwrap_or_default();
+    if usage.total() > 0 {
+        let model = match storage.get_session(session_id).await {
+            Ok(Some(session)) => session.model,
+            _ => config.model.clone(),
+        };
+        output.push_str(&format_usage(usage, config.estimate_cost(&model, usage)));
+    }
+
     for chunk in chunk_message(output.trim()) {
         bot.send_message(msg.chat.id, chunk).await?;
     }
@@ -261,13 +377,12 @@ async fn cmd_history(
 async fn cmd_list(
     bot: &Bot,
     msg: &TgMessage,
+    scope: &str,
     storage: &Arc<dyn SessionStore>,
     chat_map: &ChatSessionMap,
 ) -> ResponseResult<()> {
-    let chat_id = msg.chat.id.0;
-
     let (chat_session_list, active_id) =
-        match keyboard::fetch_chat_sessions(chat_id, storage, chat_map).await {
+        match keyboard::fetch_chat_sessions(scope, storage, chat_map).await {
             None => {
                 bot.send_message(msg.chat.id, NO_SESSIONS_HINT).await?;
                 return Ok(());
@@ -305,11 +420,10 @@ async fn cmd_switch(
     bot: &Bot,
     msg: &TgMessage,
     arg: &str,
+    scope: &str,
     storage: &Arc<dyn SessionStore>,
     chat_map: &ChatSessionMap,
 ) -> ResponseResult<()> {
-    let chat_id = msg.chat.id.0;
-
     match parse_session_arg(arg) {
         Ok(None) => {
             keyboard::build_action_keyboard(
@@ -317,13 +431,14 @@ async fn cmd_switch(
                 "Select a session to switch to:",
                 bot,
                 msg,
+                scope,
                 storage,
                 chat_map,
             )
             .await
         }
         Ok(Some(n)) => {
-            let reply = keyboard::do_switch(n, chat_id, storage, chat_map)
+            let reply = keyboard::do_switch(n, scope, storage, chat_map)
                 .await
                 .unwrap_or_else(|e| e);
             bot.send_message(msg.chat.id, reply).await?;
@@ -349,12 +464,11 @@ async fn cmd_delete(
     bot: &Bot,
     msg: &TgMessage,
     arg: &str,
+    scope: &str,
     config: &Config,
     storage: &Arc<dyn SessionStore>,
     chat_map: &ChatSessionMap,
 ) -> ResponseResult<()> {
-    let chat_id = msg.chat.id.0;
-
     match parse_session_arg(arg) {
         Ok(None) => {
             keyboard::build_action_keyboard(
@@ -362,13 +476,14 @@ async fn cmd_delete(
                 "Select a session to delete:",
                 bot,
                 msg,
+                scope,
                 storage,
                 chat_map,
             )
             .await
         }
         Ok(Some(n)) => {
-            let reply = keyboard::do_delete(n, chat_id, config, storage, chat_map)
+            let reply = keyboard::do_delete(n, scope, config, storage, chat_map)
                 .await
                 .unwrap_or_else(|e| e);
             bot.send_message(msg.chat.id, reply).await?;
@@ -383,3 +498,171 @@ async fn cmd_delete(
 
 #[cfg(test)]
 mod tests;
+
+/// Show or switch the model of the currently active session.
+///
+/// Without an argument, replies with the session's provider and model. With an
+/// argument naming a profile or a model, checks that the provider can be
+/// created (e.g. its API key is set) and records the choice on the session;
+/// the next message is answered by the new model.
+async fn cmd_model(
+    bot: &Bot,
+    msg: &TgMessage,
+    arg: &str,
+    scope: &str,
+    config: &Config,
+    storage: &Arc<dyn SessionStore>,
+    chat_map: &ChatSessionMap,
+) -> ResponseResult<()> {
+    let session_id = {
+        let map = chat_map.read().await;
+        map.get(scope).and_then(|cs| cs.active_session_id())
+    };
+    let session = match session_id {
+        Some(id) => storage.get_session(id).await.ok().flatten(),
+        None => None,
+    };
+    let Some(session) = session else {
+        bot.send_message(msg.chat.id, NO_SESSIONS_HINT).await?;
+        return Ok(());
+    };
+
+    let spec = arg.trim();
+    if spec.is_empty() {
+        bot.send_message(msg.chat.id, format_model_info(&session, config))
+            .await?;
+        return Ok(());
+    }
+
+    let current = config.for_session(
+        &session.provider,
+        &session.model,
+        session.profile.as_deref(),
+    );
+    let switched = match current.switch_model(spec) {
+        Ok(switched) => switched,
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("Cannot switch model: {}", e))
+                .await?;
+            return Ok(());
+        }
+    };
+    if let Err(e) = create_provider(&switched) {
+        bot.send_message(msg.chat.id, format!("Cannot switch model: {}", e))
+            .await?;
+        return Ok(());
+    }
+
+    let reply = match storage
+        .update_session_model(
+            session.id,
+            &switched.provider,
+            &switched.model,
+            switched.profile.as_deref(),
+        )
+        .await
+    {
+        Ok(()) => format!("Switched to {} / {}.", switched.provider, switched.model),
+        Err(e) => {
+            tracing::error!("Failed to update model for {}: {}", scope, e);
+            "Failed to switch model. Please try again.".to_string()
+        }
+    };
+    bot.send_message(msg.chat.id, reply).await?;
+    Ok(())
+}
+
+/// List the resources offered by the connected MCP servers.
+async fn cmd_resources(bot: &Bot, msg: &TgMessage, agent: &Agent) -> ResponseResult<()> {
+    let resources = agent
+        .mcp_client()
+        .map(McpClient::resources)
+        .unwrap_or_default();
+    for chunk in chunk_message(&format_resources(&resources)) {
+        bot.send_message(msg.chat.id, chunk).await?;
+    }
+    Ok(())
+}
+
+/// List the connected MCP servers, or reload them from the MCP config file.
+///
+/// `/mcp reload` starts, stops and restarts servers to match the file without
+/// restarting the bot; only `admin_users` may run it.
+async fn cmd_mcp(
+    bot: &Bot,
+    msg: &TgMessage,
+    arg: &str,
+    config: &Config,
+    agent: &Agent,
+) -> ResponseResult<()> {
+    let user_id = msg.from.as_ref().map(|u| u.id.0).unwrap_or(0);
+    let reply = match (arg.trim(), agent.mcp_client()) {
+        (_, None) => "MCP is not available.".to_string(),
+        ("", Some(client)) => {
+            let names = client.server_names();
+            if names.is_empty() {
+                "No MCP servers connected.".to_string()
+            } else {
+                format!("MCP servers: {}", names.join(", "))
+            }
+        }
+        ("reload", Some(_)) if !is_admin(user_id, config) => {
+            "Only admins can reload MCP servers.".to_string()
+        }
+        ("reload", Some(client)) => {
+            let mcp_path = config.mcp.as_ref().and_then(|m| m.config_path.as_deref());
+            match load_mcp_config(mcp_path) {
+                Ok(mcp_config) => {
+                    let report = client.reload(&mcp_config.unwrap_or_default()).await;
+                    tracing::info!(chat_id = msg.chat.id.0, %report, "mcp: reloaded");
+                    format!("MCP reloaded: {}", report)
+                }
+                Err(e) => format!("MCP reload failed: {}", e),
+            }
+        }
+        _ => "Usage: /mcp [reload]".to_string(),
+    };
+    for chunk in chunk_message(&reply) {
+        bot.send_message(msg.chat.id, chunk).await?;
+    }
+    Ok(())
+}
+
+/// Run an MCP prompt in the active session.
+///
+/// Without an argument, lists the available prompts. Otherwise renders the
+/// prompt and adds its messages to the session; the agent answers when the
+/// rendered prompt ends with a user message.
+async fn cmd_prompt(
+    bot: &Bot,
+    msg: &TgMessage,
+    arg: &str,
+    config: &Config,
+    agent: &Agent,
+    storage: &Arc<dyn SessionStore>,
+    chat_map: &ChatSessionMap,
+) -> ResponseResult<()> {
+    if arg.trim().is_empty() {
+        let prompts = agent
+            .mcp_client()
+            .map(McpClient::prompts)
+            .unwrap_or_default();
+        for chunk in chunk_message(&format_prompts(&prompts)) {
+            bot.send_message(msg.chat.id, chunk).await?;
+        }
+        return Ok(());
+    }
+
+    let rendered = match parse_prompt_command(arg) {
+        Ok((name, arguments)) => agent.render_prompt(&name, arguments).await,
+        Err(e) => Err(e.into()),
+    };
+    match rendered {
+        Ok(messages) => run_turn(bot, msg, config, agent, storage, chat_map, messages).await,
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("Cannot run prompt: {}", e))
+                .await?;
+            Ok(())
+        }
+    }
+}
@@ -6,7 +6,12 @@
 //! - `do_switch` / `do_delete` — executes the action given a 1-based index
 //! - `handle_callback` — processes `CallbackQuery` updates from button taps
 //! - `fetch_chat_sessions` — shared session-list fetcher
-//! - `parse_callback_data` — parses `"action:N"` callback data strings
+//! - `parse_callback_data` — parses `"action:N:U"` callback data strings
+//!
+//! Session keyboards carry the user ID `U` of whoever opened them; taps by
+//! anyone else are refused, so in group chats one member's keyboard cannot
+//! act on another member's sessions. Tool approval buttons (`"allow:N"`, `"always:N"`, `"deny:N"`) share the
+//! callback format and are resolved by [`crate::approval`].
 
 use std::sync::Arc;
 
@@ -15,27 +20,29 @@ use synapse_core::text::truncate;
 use synapse_core::{Config, SessionStore, SessionSummary};
 use teloxide::payloads::SendMessageSetters;
 use teloxide::prelude::*;
-use teloxide::types::{CallbackQuery, InlineKeyboardButton, InlineKeyboardMarkup};
+use teloxide::types::{CallbackQuery, Chat, InlineKeyboardButton, InlineKeyboardMarkup};
 use uuid::Uuid;
 
+use crate::approval::{ApprovalAnswer, Resolution, resolve_callback};
 use crate::handlers::{
-    ChatSessionMap, ChatSessions, NO_SESSIONS_HINT, is_authorized, tg_session_name,
+    ChatSessionMap, ChatSessions, NO_SESSIONS_HINT, is_authorized, is_group, is_group_allowed,
+    session_scope,
 };
 
 use super::KEYBOARD_PREVIEW_MAX_CHARS;
 
-/// Fetch the display-ordered session list for a chat.
+/// Fetch the display-ordered session list for a session scope.
 ///
-/// Returns `Some((sessions, active_id))` if the chat has sessions, `None` otherwise.
+/// Returns `Some((sessions, active_id))` if the scope has sessions, `None` otherwise.
 /// Sessions are ordered by `updated_at DESC` (matching `/list` ordering).
 pub(super) async fn fetch_chat_sessions(
-    chat_id: i64,
+    scope: &str,
     storage: &Arc<dyn SessionStore>,
     chat_map: &ChatSessionMap,
 ) -> Option<(Vec<SessionSummary>, Option<Uuid>)> {
     let (session_uuids, active_id) = {
         let map = chat_map.read().await;
-        match map.get(&chat_id) {
+        match map.get(scope) {
             Some(cs) if !cs.sessions.is_empty() => (cs.sessions.clone(), cs.active_session_id()),
             _ => return None,
         }
@@ -54,15 +61,20 @@ pub(super) async fn fetch_chat_sessions(
     }
 }
 
+/// Notice shown to a user who taps a session keyboard someone else opened.
+const NOT_YOUR_KEYBOARD: &str = "Only the person who opened this menu can use it.";
+
 /// Build an inline keyboard with one button per session.
 ///
-/// Each button's callback data follows the format `"action:N"` where `action` is
-/// `"switch"` or `"delete"` and `N` is the 1-based session index. The active session
-/// is marked with `*` in the button label.
+/// Each button's callback data follows the format `"action:N:U"` where `action` is
+/// `"switch"` or `"delete"`, `N` is the 1-based session index and `U` is the
+/// `owner` who opened the keyboard. The active session is marked with `*` in the
+/// button label.
 pub(super) fn build_session_keyboard(
     action: &str,
     sessions: &[&SessionSummary],
     active_id: Option<Uuid>,
+    owner: UserId,
 ) -> InlineKeyboardMarkup {
     let buttons: Vec<Vec<InlineKeyboardButton>> = sessions
         .iter()
@@ -79,7 +91,7 @@ pub(super) fn build_session_keyboard(
                 "{}. [{}] {} | {} msgs | {}",
                 idx, active_marker, date, s.message_count, preview
             );
-            let data = format!("{}:{}", action, idx);
+            let data = format!("{}:{}:{}", action, idx, owner.0);
             vec![InlineKeyboardButton::callback(label, data)]
         })
         .collect();
@@ -95,18 +107,18 @@ pub(super) async fn build_action_keyboard(
     prompt: &str,
     bot: &Bot,
     msg: &teloxide::types::Message,
+    scope: &str,
     storage: &Arc<dyn SessionStore>,
     chat_map: &ChatSessionMap,
 ) -> ResponseResult<()> {
-    let chat_id = msg.chat.id.0;
-
-    match fetch_chat_sessions(chat_id, storage, chat_map).await {
+    match fetch_chat_sessions(scope, storage, chat_map).await {
         None => {
             bot.send_message(msg.chat.id, NO_SESSIONS_HINT).await?;
         }
         Some((sessions, active_id)) => {
             let refs: Vec<&SessionSummary> = sessions.iter().collect();
-            let keyboard = build_session_keyboard(action, &refs, active_id);
+            let owner = msg.from.as_ref().map_or(UserId(0), |u| u.id);
+            let keyboard = build_session_keyboard(action, &refs, active_id, owner);
             bot.send_message(msg.chat.id, prompt)
                 .reply_markup(keyboard)
                 .await?;
@@ -121,11 +133,11 @@ pub(super) async fn build_action_keyboard(
 /// Returns `Ok(reply)` on success or `Err(error_message)` on invalid index.
 pub(super) async fn do_switch(
     n: usize,
-    chat_id: i64,
+    scope: &str,
     storage: &Arc<dyn SessionStore>,
     chat_map: &ChatSessionMap,
 ) -> Result<String, String> {
-    let (sessions, _) = fetch_chat_sessions(chat_id, storage, chat_map)
+    let (sessions, _) = fetch_chat_sessions(scope, storage, chat_map)
         .await
         .ok_or_else(|| NO_SESSIONS_HINT.to_string())?;
 
@@ -140,7 +152,7 @@ pub(super) async fn do_switch(
 
     {
         let mut map = chat_map.write().await;
-        if let Some(cs) = map.get_mut(&chat_id)
+        if let Some(cs) = map.get_mut(scope)
             && let Some(pos) = cs.sessions.iter().position(|&id| id == target_id)
         {
             cs.active_idx = pos;
@@ -157,12 +169,12 @@ pub(super) async fn do_switch(
 /// Returns `Ok(reply)` on success or `Err(error_message)` on invalid index.
 pub(super) async fn do_delete(
     n: usize,
-    chat_id: i64,
+    scope: &str,
     config: &Config,
     storage: &Arc<dyn SessionStore>,
     chat_map: &ChatSessionMap,
 ) -> Result<String, String> {
-    let (sessions, _) = fetch_chat_sessions(chat_id, storage, chat_map)
+    let (sessions, _) = fetch_chat_sessions(scope, storage, chat_map)
         .await
         .ok_or_else(|| NO_SESSIONS_HINT.to_string())?;
 
@@ -178,10 +190,12 @@ pub(super) async fn do_delete(
 
     let reply = {
         let mut map = chat_map.write().await;
-        let chat_sessions = map.entry(chat_id).or_insert_with(|| ChatSessions {
-            sessions: vec![],
-            active_idx: 0,
-        });
+        let chat_sessions = map
+            .entry(scope.to_string())
+            .or_insert_with(|| ChatSessions {
+                sessions: vec![],
+                active_idx: 0,
+            });
 
         let deleted_vec_pos = chat_sessions
             .sessions
@@ -195,8 +209,7 @@ pub(super) async fn do_delete(
 
         if chat_sessions.sessions.is_empty() {
             // Auto-create a new session.
-            let session =
-                Session::new(&config.provider, &config.model).with_name(tg_session_name(chat_id));
+            let session = Session::new(&config.provider, &config.model).with_name(scope);
             if let Ok(()) = storage.create_session(&session).await {
                 chat_sessions.sessions.push(session.id);
                 chat_sessions.active_idx = 0;
@@ -219,18 +232,42 @@ pub(super) async fn do_delete(
     Ok(reply)
 }
 
-/// Parse callback data in the format "action:N" (e.g., "switch:2", "delete:1").
-pub(crate) fn parse_callback_data(data: &str) -> Option<(&str, usize)> {
-    let (action, n_str) = data.split_once(':')?;
+/// Parse callback data in the format "action:N" (e.g., "allow:7") or
+/// "action:N:U" with the keyboard owner's user ID (e.g., "switch:2:42").
+pub(crate) fn parse_callback_data(data: &str) -> Option<(&str, usize, Option<UserId>)> {
+    let (action, rest) = data.split_once(':')?;
+    let (n_str, owner) = match rest.split_once(':') {
+        Some((n_str, owner)) => (n_str, Some(UserId(owner.parse::<u64>().ok()?))),
+        None => (rest, None),
+    };
     let n = n_str.parse::<usize>().ok()?;
-    Some((action, n))
+    Some((action, n, owner))
+}
+
+/// Session scope a session keyboard tap by `tapper` acts on.
+///
+/// Keyboards sent before owners were recorded (`owner` is `None`) act for the
+/// tapper. Returns the notice for the tapper when someone else owns the keyboard.
+pub(crate) fn keyboard_scope(
+    chat: &Chat,
+    owner: Option<UserId>,
+    tapper: UserId,
+    config: &Config,
+) -> Result<String, &'static str> {
+    let owner = owner.unwrap_or(tapper);
+    if owner != tapper {
+        return Err(NOT_YOUR_KEYBOARD);
+    }
+    Ok(session_scope(chat, owner.0, config))
 }
 
 /// Handle inline keyboard button taps (CallbackQuery updates).
 ///
-/// Parses callback data (`"switch:N"` or `"delete:N"`), executes the action
-/// via `do_switch` / `do_delete`, and edits the keyboard message to show
-/// the result text (removing the keyboard).
+/// Parses callback data (`"switch:N:U"` or `"delete:N:U"`), executes the action
+/// on the sessions of the keyboard's owner via `do_switch` / `do_delete`, and
+/// edits the keyboard message to show the result text (removing the keyboard).
+/// Taps by anyone but the owner are answered with a notice. Tool approval taps
+/// (`"allow:N"`, `"always:N"`, `"deny:N"`) answer the pending request.
 pub async fn handle_callback(
     bot: Bot,
     q: CallbackQuery,
@@ -240,60 +277,85 @@ pub async fn handle_callback(
 ) -> ResponseResult<()> {
     // 1. Authorization check — silent drop for unauthorized users.
     let user_id = q.from.id.0;
-    let allowed_users = config
+    let (allowed_users, allowed_groups) = config
         .telegram
         .as_ref()
-        .map(|t| t.allowed_users.as_slice())
-        .unwrap_or(&[]);
+        .map(|t| (t.allowed_users.as_slice(), t.allowed_groups.as_slice()))
+        .unwrap_or((&[], &[]));
     if !is_authorized(user_id, allowed_users) {
         return Ok(()); // Silent drop.
     }
 
-    // 2. Answer callback query immediately — dismiss Telegram's loading spinner.
-    bot.answer_callback_query(q.id.clone()).await?;
-
-    // 3. Parse callback data.
+    // 2. Parse callback data.
     let data = match q.data.as_deref() {
         Some(d) if !d.is_empty() => d,
-        _ => return Ok(()),
+        _ => {
+            bot.answer_callback_query(q.id.clone()).await?;
+            return Ok(());
+        }
     };
 
-    // 4. Extract chat_id and message_id from the original message.
+    // 3. Extract chat_id and message_id from the original message.
     let message = match q.regular_message() {
         Some(m) => m,
         None => {
             tracing::warn!("Callback query without regular message, skipping edit");
+            bot.answer_callback_query(q.id.clone()).await?;
             return Ok(());
         }
     };
-    let chat_id = message.chat.id.0;
+    if is_group(&message.chat) && !is_group_allowed(message.chat.id.0, allowed_groups) {
+        return Ok(());
+    }
     let message_id = message.id;
     let tg_chat_id = message.chat.id;
 
-    // 5. Parse "action:N" format.
-    let (action, n) = match parse_callback_data(data) {
+    // 4. Parse "action:N" or "action:N:U" format.
+    let (action, n, owner) = match parse_callback_data(data) {
         Some(parsed) => parsed,
         None => {
             tracing::warn!("Invalid callback data: {}", data);
+            bot.answer_callback_query(q.id.clone()).await?;
             return Ok(());
         }
     };
 
-    // 6. Execute action.
-    let reply = match action {
-        "switch" => do_switch(n, chat_id, &storage, &chat_map)
-            .await
-            .unwrap_or_else(|e| e),
-        "delete" => do_delete(n, chat_id, &config, &storage, &chat_map)
-            .await
-            .unwrap_or_else(|e| e),
-        _ => {
-            tracing::warn!("Unknown callback action: {}", action);
-            return Ok(());
+    // 5. Execute action. Taps refused for this user are answered with a
+    //    notice and leave the message unchanged.
+    let reply = if let Some(answer) = ApprovalAnswer::from_action(action) {
+        match resolve_callback(n, tg_chat_id, q.from.id, answer) {
+            Resolution::Answered(reply) => reply,
+            Resolution::Refused(notice) => {
+                bot.answer_callback_query(q.id.clone()).text(notice).await?;
+                return Ok(());
+            }
+        }
+    } else {
+        let scope = match keyboard_scope(&message.chat, owner, q.from.id, &config) {
+            Ok(scope) => scope,
+            Err(notice) => {
+                bot.answer_callback_query(q.id.clone()).text(notice).await?;
+                return Ok(());
+            }
+        };
+        match action {
+            "switch" => do_switch(n, &scope, &storage, &chat_map)
+                .await
+                .unwrap_or_else(|e| e),
+            "delete" => do_delete(n, &scope, &config, &storage, &chat_map)
+                .await
+                .unwrap_or_else(|e| e),
+            _ => {
+                tracing::warn!("Unknown callback action: {}", action);
+                bot.answer_callback_query(q.id.clone()).await?;
+                return Ok(());
+            }
         }
     };
 
-    // 7. Edit message to remove keyboard and show result.
+    // 6. Dismiss Telegram's loading spinner, then edit the message to remove
+    //    the keyboard and show the result.
+    bot.answer_callback_query(q.id.clone()).await?;
     if let Err(e) = bot.edit_message_text(tg_chat_id, message_id, reply).await {
         tracing::warn!("Failed to edit callback message: {}", e);
     }
@@ -326,12 +388,21 @@ mod tests {
 
     #[test]
     fn test_parse_callback_data_valid_switch() {
-        assert_eq!(parse_callback_data("switch:2"), Some(("switch", 2)));
+        assert_eq!(parse_callback_data("switch:2"), Some(("switch", 2, None)));
     }
 
     #[test]
     fn test_parse_callback_data_valid_delete() {
-        assert_eq!(parse_callback_data("delete:1"), Some(("delete", 1)));
+        assert_eq!(parse_callback_data("delete:1"), Some(("delete", 1, None)));
+    }
+
+    #[test]
+    fn test_parse_callback_data_with_owner() {
+        assert_eq!(
+            parse_callback_data("switch:2:42"),
+            Some(("switch", 2, Some(UserId(42))))
+        );
+        assert_eq!(parse_callback_data("switch:2:bob"), None);
     }
 
     #[test]
@@ -344,6 +415,36 @@ mod tests {
         assert_eq!(parse_callback_data("switch:abc"), None);
     }
 
+    // --- keyboard_scope ---
+
+    #[test]
+    fn test_keyboard_scope_refuses_other_users() {
+        let chat: Chat = serde_json::from_value(serde_json::json!({
+            "id": -100, "type": "supergroup", "title": "Team"
+        }))
+        .unwrap();
+        let config = Config {
+            telegram: Some(synapse_core::TelegramConfig {
+                group_sessions: synapse_core::GroupSessions::PerUser,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            keyboard_scope(&chat, Some(UserId(42)), UserId(42), &config),
+            Ok("tg:-100:42".to_string())
+        );
+        assert_eq!(
+            keyboard_scope(&chat, Some(UserId(42)), UserId(7), &config),
+            Err(NOT_YOUR_KEYBOARD)
+        );
+        assert_eq!(
+            keyboard_scope(&chat, None, UserId(7), &config),
+            Ok("tg:-100:7".to_string())
+        );
+    }
+
     // --- build_session_keyboard ---
 
     #[test]
@@ -354,7 +455,7 @@ mod tests {
         let s2 = make_session(id2, Some("Session B"), 3);
         let refs: Vec<&SessionSummary> = vec![&s1, &s2];
 
-        let markup = build_session_keyboard("switch", &refs, None);
+        let markup = build_session_keyboard("switch", &refs, None, UserId(42));
         let rows = markup.inline_keyboard;
 
         assert_eq!(rows.len(), 2);
@@ -362,12 +463,12 @@ mod tests {
         let data2 = rows[1][0].kind.clone();
 
         if let teloxide::types::InlineKeyboardButtonKind::CallbackData(d) = data1 {
-            assert_eq!(d, "switch:1");
+            assert_eq!(d, "switch:1:42");
         } else {
             panic!("Expected CallbackData for button 1");
         }
         if let teloxide::types::InlineKeyboardButtonKind::CallbackData(d) = data2 {
-            assert_eq!(d, "switch:2");
+            assert_eq!(d, "switch:2:42");
         } else {
             panic!("Expected CallbackData for button 2");
         }
@@ -382,7 +483,7 @@ mod tests {
         let refs: Vec<&SessionSummary> = vec![&s1, &s2];
 
         // Second session is active.
-        let markup = build_session_keyboard("switch", &refs, Some(id2));
+        let markup = build_session_keyboard("switch", &refs, Some(id2), UserId(42));
         let rows = markup.inline_keyboard;
 
         let label1 = &rows[0][0].text;
@@ -399,7 +500,7 @@ mod tests {
     #[test]
     fn test_build_session_keyboard_empty_sessions() {
         let refs: Vec<&SessionSummary> = vec![];
-        let markup = build_session_keyboard("delete", &refs, None);
+        let markup = build_session_keyboard("delete", &refs, None, UserId(42));
         assert!(
             markup.inline_keyboard.is_empty(),
             "Empty sessions should produce no keyboard rows"
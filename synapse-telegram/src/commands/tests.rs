@@ -70,6 +70,22 @@ fn test_format_history_filters_system_and_tool() {
     assert!(!output.contains("Tool"), "Should NOT contain 'Tool'");
 }
 
+#[test]
+fn test_format_history_skips_tool_call_only_assistant() {
+    let messages = vec![
+        make_stored_message(Role::User, "weather?"),
+        make_stored_message(Role::Assistant, "")
+            .with_tool_calls(r#"[{"id":"call_1","name":"get_weather","input":{}}]"#),
+        make_stored_message(Role::Tool, "temp=21C")
+            .with_tool_results(r#"{"tool_call_id":"call_1"}"#),
+        make_stored_message(Role::Assistant, "It is sunny."),
+    ];
+    let output = format_history(&messages);
+    assert_eq!(output.matches("[Assistant]").count(), 1);
+    assert!(output.contains("It is sunny."));
+    assert!(!output.contains("sunny\n"));
+}
+
 #[test]
 fn test_format_history_keeps_user_and_assistant() {
     let mut messages = Vec::new();
@@ -397,3 +413,89 @@ fn test_delete_non_active_above_active_no_change() {
     assert_eq!(cs.active_idx, 0);
     assert_eq!(cs.active_session_id(), Some(id0));
 }
+
+// --- format_usage ---
+
+#[test]
+fn test_format_usage_with_cost() {
+    let output = format_usage(TokenUsage::new(1200, 300), Some(0.0081));
+    assert_eq!(output, "Tokens: 1200 in / 300 out (est. $0.0081)");
+}
+
+#[test]
+fn test_format_usage_without_cost() {
+    let output = format_usage(TokenUsage::new(1200, 300), None);
+    assert_eq!(output, "Tokens: 1200 in / 300 out");
+}
+
+// --- format_model_info ---
+
+#[test]
+fn test_format_model_info_lists_profiles() {
+    let session =
+        Session::new("anthropic", "claude-sonnet-4-5").with_profile(Some("claude".to_string()));
+    let mut config = Config::default();
+    config
+        .profiles
+        .insert("local".to_string(), Default::default());
+    config
+        .profiles
+        .insert("claude".to_string(), Default::default());
+
+    let output = format_model_info(&session, &config);
+
+    assert!(output.starts_with("Current model: anthropic / claude-sonnet-4-5 (profile claude)"));
+    assert!(output.contains("Profiles: claude, local"));
+}
+
+#[test]
+fn test_format_model_info_without_profiles() {
+    let session = Session::new("deepseek", "deepseek-chat");
+    let output = format_model_info(&session, &Config::default());
+
+    assert!(output.contains("deepseek / deepseek-chat"));
+    assert!(!output.contains("Profiles:"));
+}
+
+// --- format_resources / format_prompts ---
+
+#[test]
+fn test_format_resources_lists_references() {
+    let output = format_resources(&[ResourceDefinition {
+        server: "fs".to_string(),
+        uri: "file:///notes.md".to_string(),
+        name: "notes".to_string(),
+        description: None,
+        mime_type: Some("text/markdown".to_string()),
+    }]);
+
+    assert!(output.contains("@fs:file:///notes.md — notes"));
+}
+
+#[test]
+fn test_format_resources_empty() {
+    assert_eq!(format_resources(&[]), "No MCP resources available.");
+}
+
+#[test]
+fn test_format_prompts_lists_arguments() {
+    let output = format_prompts(&[PromptDefinition {
+        server: "code".to_string(),
+        name: "review".to_string(),
+        description: Some("Review code".to_string()),
+        arguments: vec![
+            synapse_core::mcp::PromptArgument {
+                name: "language".to_string(),
+                description: None,
+                required: true,
+            },
+            synapse_core::mcp::PromptArgument {
+                name: "focus".to_string(),
+                description: None,
+                required: false,
+            },
+        ],
+    }]);
+
+    assert!(output.contains("review language=… [focus=…] — Review code"));
+}
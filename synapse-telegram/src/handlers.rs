@@ -7,19 +7,35 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use anyhow::anyhow;
+use futures::StreamExt;
 use synapse_core::message::{Message as CoreMessage, Role};
 use synapse_core::session::Session;
-use synapse_core::{Agent, Config, SessionStore, StoredMessage};
+use synapse_core::tools::SearchSessionsTool;
+use synapse_core::{
+    Agent, AgentError, Config, GroupSessions, SessionStore, StopReason, StoredMessage, StreamEvent,
+    TelegramConfig, TokenUsage, ToolRegistry, Transcriber,
+};
 use teloxide::prelude::*;
-use teloxide::types::{ChatAction, Message as TgMessage, ParseMode};
+use teloxide::types::{
+    Chat, ChatAction, Me, Message as TgMessage, MessageEntityKind, ParseMode, User,
+};
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
-use crate::format::TELEGRAM_MSG_LIMIT;
+use crate::approval::TelegramApprover;
+use crate::format::{TELEGRAM_MSG_LIMIT, escape_html};
+use crate::live::LiveReply;
+use crate::media::{self, Upload};
 
 /// Error message sent to the user when agent or session operations fail.
 const ERROR_REPLY: &str = "Sorry, I encountered an error. Please try again.";
 
+/// Maximum characters of tool error text shown in a failure notice.
+const TOOL_ERROR_PREVIEW_CHARS: usize = 200;
+
+/// Notice sent after an answer cut off by the `max_tokens` limit.
+const TRUNCATED_NOTICE: &str = "⚠️ Response truncated: max tokens reached.";
+
 /// Per-chat session state: ordered list of session UUIDs and the active session index.
 ///
 /// Sessions are ordered by `updated_at DESC` (most recent first), matching the
@@ -56,59 +72,102 @@ pub fn tg_session_name(chat_id: i64) -> String {
     format!("tg:{}", chat_id)
 }
 
+/// Session name, and [`ChatSessionMap`] key, for `user_id` writing in `chat`.
+///
+/// Private chats and shared group sessions use `"tg:<chat_id>"`; with
+/// `group_sessions = "per_user"` each group member gets
+/// `"tg:<chat_id>:<user_id>"`.
+pub fn session_scope(chat: &Chat, user_id: u64, config: &Config) -> String {
+    let per_user = config
+        .telegram
+        .as_ref()
+        .is_some_and(|t| t.group_sessions == GroupSessions::PerUser);
+    if is_group(chat) && per_user {
+        format!("{}:{}", tg_session_name(chat.id.0), user_id)
+    } else {
+        tg_session_name(chat.id.0)
+    }
+}
+
+/// Whether `chat` is a group or supergroup.
+pub fn is_group(chat: &Chat) -> bool {
+    chat.is_group() || chat.is_supergroup()
+}
+
 /// Check authorization for an incoming message.
 ///
+/// In group chats the group must also pass `allowed_groups`.
+///
 /// Returns `Some(Ok(()))` to instruct the caller to return early (unauthorized).
 /// Returns `None` when the user is authorized and processing should continue.
 pub async fn check_auth(msg: &TgMessage, config: &Config) -> Option<ResponseResult<()>> {
     let user_id = msg.from.as_ref().map(|u| u.id.0).unwrap_or(0);
-    let allowed_users = config
+    let (allowed_users, allowed_groups) = config
         .telegram
         .as_ref()
-        .map(|t| t.allowed_users.as_slice())
-        .unwrap_or(&[]);
-    if !is_authorized(user_id, allowed_users) {
+        .map(|t| (t.allowed_users.as_slice(), t.allowed_groups.as_slice()))
+        .unwrap_or((&[], &[]));
+    if !is_authorized(user_id, allowed_users)
+        || (is_group(&msg.chat) && !is_group_allowed(msg.chat.id.0, allowed_groups))
+    {
         Some(Ok(())) // Silent drop — do not reveal bot existence.
     } else {
         None
     }
 }
 
-/// In-memory map from Telegram chat IDs to multi-session state.
-pub type ChatSessionMap = Arc<RwLock<HashMap<i64, ChatSessions>>>;
+/// In-memory map from session scopes (see [`session_scope`]) to multi-session state.
+pub type ChatSessionMap = Arc<RwLock<HashMap<String, ChatSessions>>>;
 
 /// Handle an incoming Telegram message.
 ///
 /// Steps:
-/// 1. Check user authorization (silent drop if not in `allowed_users`).
-/// 2. Look up or create a session for this chat.
-/// 3. Load conversation history, append the new user message.
-/// 4. Store the user message in the database.
-/// 5. Send a typing indicator.
-/// 6. Call the agent for a response.
-/// 7. Store and send the response (chunked if > 4096 chars).
+/// 1. Check user authorization (silent drop if not in `allowed_users`). In
+///    group chats, messages that neither mention the bot nor reply to it are
+///    ignored.
+/// 2. Attach the MCP resources referenced as `@server:uri`.
+/// 3. Download an attached photo or document (see [`media::read_upload`]):
+///    images and PDFs become attachments, text files are inlined. The caption
+///    is the prompt.
+/// 4. Run the turn (see [`run_turn`]).
+///
+/// Voice notes and audio files are first transcribed (see
+/// [`media::transcribe_audio`]); the transcript is echoed back and used as the
+/// message text. In group chats the sender's name is prefixed to the text so
+/// the model can tell participants apart.
+#[allow(clippy::too_many_arguments)]
 pub async fn handle_message(
     bot: Bot,
     msg: TgMessage,
+    me: Me,
     config: Arc<Config>,
     agent: Arc<Agent>,
     storage: Arc<dyn SessionStore>,
     chat_map: ChatSessionMap,
+    transcriber: Option<Arc<dyn Transcriber>>,
 ) -> ResponseResult<()> {
     // Step 1: User authorization.
     if let Some(result) = check_auth(&msg, &config).await {
         return result;
     }
 
-    // Step 2: Extract text content. Non-text updates are ignored.
-    let text = match msg.text() {
+    // In groups, only answer messages addressed to the bot.
+    let group = is_group(&msg.chat);
+    if group && !is_addressed(&msg, &me) {
+        return Ok(());
+    }
+
+    // Extract the text or caption. Updates with neither a text nor a file are ignored.
+    let text = match msg.text().or(msg.caption()) {
+        Some(t) if group => strip_mention(t, me.username()),
         Some(t) => t.to_string(),
+        None if media::has_file(&msg) || media::has_audio(&msg) => String::new(),
         None => return Ok(()),
     };
 
     // Defensive guard: any message starting with "/" that reached here was not
     // parsed by filter_command. Reply with a hint instead of forwarding to the LLM.
-    if text.starts_with('/') {
+    if msg.text().is_some() && text.starts_with('/') {
         bot.send_message(
             msg.chat.id,
             "I didn't understand that command. Use /help to see available commands.",
@@ -117,10 +176,107 @@ pub async fn handle_message(
         return Ok(());
     }
 
+    // Size limit for voice messages, photos and documents.
+    let max_file_bytes = config.telegram.as_ref().map_or_else(
+        || TelegramConfig::default().max_file_bytes,
+        |t| t.max_file_bytes,
+    );
+
+    // Transcribe voice notes and audio files, echoing the transcript.
+    let text =
+        match media::transcribe_audio(&bot, &msg, transcriber.as_deref(), max_file_bytes).await {
+            Ok(None) => text,
+            Ok(Some(transcript)) => {
+                for chunk in chunk_message(&format!("🎤 {}", transcript)) {
+                    bot.send_message(msg.chat.id, chunk).await?;
+                }
+                if text.is_empty() {
+                    transcript
+                } else {
+                    format!("{}\n\n{}", text, transcript)
+                }
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to transcribe audio for chat {}: {:#}",
+                    msg.chat.id.0,
+                    e
+                );
+                bot.send_message(msg.chat.id, e.to_string()).await?;
+                return Ok(());
+            }
+        };
+
+    // Step 2: Attach referenced MCP resources to the user message.
+    let mut text = match agent.attach_resources(&text).await {
+        Ok((text, _)) => text,
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("Could not attach resource: {}", e))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    // Step 3: Download the photo or document, if any.
+    let mut attachments = Vec::new();
+    match media::read_upload(&bot, &msg, max_file_bytes).await {
+        Ok(None) => {}
+        Ok(Some(Upload::Attachment(attachment))) => attachments.push(attachment),
+        Ok(Some(Upload::Text(file))) => text.push_str(&file),
+        Err(e) => {
+            tracing::warn!("Failed to read file for chat {}: {:#}", msg.chat.id.0, e);
+            bot.send_message(msg.chat.id, e.to_string()).await?;
+            return Ok(());
+        }
+    }
+
+    // Step 4: Run the turn.
+    if group && let Some(user) = &msg.from {
+        text = format!("{}: {}", speaker_name(user), text.trim_start());
+    }
+    let mut message = CoreMessage::new(Role::User, text.trim_start());
+    message.attachments = attachments;
+    run_turn(
+        &bot,
+        &msg,
+        &config,
+        &agent,
+        &storage,
+        &chat_map,
+        vec![message],
+    )
+    .await
+}
+
+/// Add `turn` to the chat's active session and answer it.
+///
+/// Steps:
+/// 1. Look up or create a session for this chat, using its recorded model.
+///    Images and PDFs are refused when that model cannot read them.
+/// 2. Load conversation history, append the turn's messages, and apply any
+///    summary of older turns (see `Agent::compact_history`).
+/// 3. Store the turn's messages in the database. A turn that does not end
+///    with a user message (e.g. a prompt ending with an assistant message)
+///    waits for the user's reply instead of calling the agent.
+/// 4. Send a typing indicator.
+/// 5. Stream the agent response into a [`LiveReply`], posting tool call
+///    notices as they happen.
+/// 6. Store the response and render it as HTML (chunked if > 4096 chars).
+pub(crate) async fn run_turn(
+    bot: &Bot,
+    msg: &TgMessage,
+    config: &Config,
+    agent: &Agent,
+    storage: &Arc<dyn SessionStore>,
+    chat_map: &ChatSessionMap,
+    turn: Vec<CoreMessage>,
+) -> ResponseResult<()> {
     let chat_id = msg.chat.id.0;
+    let user_id = msg.from.as_ref().map(|u| u.id.0).unwrap_or(0);
+    let scope = session_scope(&msg.chat, user_id, config);
 
-    // Step 3: Resolve or create session for this chat.
-    let session_id = match resolve_session(chat_id, &config, &storage, &chat_map).await {
+    // Step 1: Resolve or create session for this chat.
+    let session_id = match resolve_session(&scope, config, storage, chat_map).await {
         Ok(id) => id,
         Err(e) => {
             tracing::error!("Failed to resolve session for chat {}: {}", chat_id, e);
@@ -129,34 +285,86 @@ pub async fn handle_message(
         }
     };
 
-    // Step 4: Load conversation history and append user message.
+    // The shared agent runs the configured model; sessions switched with
+    // `/model` get an agent for their own provider and model. Turns with
+    // `search_sessions` enabled get an agent whose search is limited to
+    // this scope's sessions.
+    let session_config = session_config(config, storage.as_ref(), session_id).await;
+    let model_config = session_config.as_ref().unwrap_or(config);
+    if turn.iter().any(|m| !m.attachments.is_empty()) && !model_config.supports_vision() {
+        bot.send_message(msg.chat.id, vision_unsupported_reply(&model_config.model))
+            .await?;
+        return Ok(());
+    }
+    let session_agent = if session_config.is_some() || model_config.tools.search_sessions.enabled {
+        agent
+            .fork(model_config)
+            .map(|a| Some(a.with_native_tools(scoped_tools(model_config, storage, &scope))))
+    } else {
+        Ok(None)
+    };
+    let session_agent = match session_agent {
+        Ok(session_agent) => session_agent,
+        Err(e) => {
+            tracing::error!("Failed to create agent for chat {}: {}", chat_id, e);
+            bot.send_message(msg.chat.id, ERROR_REPLY).await?;
+            return Ok(());
+        }
+    };
+    let agent = session_agent.as_ref().unwrap_or(agent);
+
+    // Step 2: Load conversation history and append the turn.
     let stored_messages = storage.get_messages(session_id).await.unwrap_or_default();
 
     let mut messages: Vec<CoreMessage> = stored_messages
-        .into_iter()
-        .map(|m| CoreMessage::new(m.role, m.content))
+        .iter()
+        .map(StoredMessage::to_message)
         .collect();
 
-    let user_message = CoreMessage::new(Role::User, &text);
-    messages.push(user_message);
-
-    // Step 5: Store the user message before calling the agent.
-    let stored_user_msg = StoredMessage::new(session_id, Role::User, &text);
-    if let Err(e) = storage.add_message(&stored_user_msg).await {
-        tracing::warn!("Failed to store user message for chat {}: {}", chat_id, e);
+    // Step 3: Store the turn's messages before calling the agent.
+    for message in &turn {
+        let stored = StoredMessage::from_message(session_id, message);
+        if let Err(e) = storage.add_message(&stored).await {
+            tracing::warn!("Failed to store message for chat {}: {}", chat_id, e);
+        }
+    }
+    let awaits_reply = turn.last().is_some_and(|m| m.role == Role::User);
+    messages.extend(turn);
+    if !awaits_reply {
+        bot.send_message(msg.chat.id, "Prompt added. Send a message to continue.")
+            .await?;
+        return Ok(());
     }
 
-    // Step 6: Send typing indicator.
+    // Step 4: Send typing indicator.
     bot.send_chat_action(msg.chat.id, ChatAction::Typing)
         .await
         .ok(); // Non-critical — ignore failure.
 
-    // Step 7: Call agent for a response.
-    match agent.complete(&mut messages).await {
-        Ok(response) => {
+    // Replace older turns with a stored summary when the history outgrows the
+    // model's context window (no-op unless `[context] strategy = "summarize"`).
+    let mut messages = agent
+        .compact_history(storage.as_ref(), session_id, messages)
+        .await;
+
+    // Step 5: Stream the agent response into a live reply, posting tool
+    // activity as it happens.
+    let mut live = LiveReply::start(bot, msg.chat.id).await;
+    let approver = TelegramApprover::new(bot.clone(), msg.chat.id, UserId(user_id), &scope);
+    let stream_result = stream_response(
+        bot,
+        approver,
+        agent,
+        storage.as_ref(),
+        session_id,
+        &mut messages,
+        &mut live,
+    )
+    .await;
+    match stream_result {
+        Ok((reply, stop_reason)) => {
             // Store the assistant response.
-            let stored_response =
-                StoredMessage::new(session_id, Role::Assistant, &response.content);
+            let stored_response = StoredMessage::from_message(session_id, &reply);
             if let Err(e) = storage.add_message(&stored_response).await {
                 tracing::warn!(
                     "Failed to store assistant message for chat {}: {}",
@@ -165,37 +373,15 @@ pub async fn handle_message(
                 );
             }
 
-            // Convert Markdown to Telegram HTML, chunk, and send with fallback.
-            let html = crate::format::md_to_telegram_html(&response.content);
-            let chunks = crate::format::chunk_html(&html);
-            let mut html_failed = false;
-            for chunk in &chunks {
-                match bot
-                    .send_message(msg.chat.id, chunk)
-                    .parse_mode(ParseMode::Html)
-                    .await
-                {
-                    Ok(_) => {}
-                    Err(e) => {
-                        tracing::warn!(
-                            "HTML send failed for chat {}, falling back to plain text: {}",
-                            chat_id,
-                            e
-                        );
-                        html_failed = true;
-                        break;
-                    }
-                }
-            }
-            if html_failed {
-                let plain_chunks = chunk_message(&response.content);
-                for plain_chunk in plain_chunks {
-                    bot.send_message(msg.chat.id, plain_chunk).await?;
-                }
+            // Re-render the finished answer as Telegram HTML.
+            live.finish().await;
+            if stop_reason == StopReason::MaxTokens {
+                send_notice(bot, msg.chat.id, TRUNCATED_NOTICE.to_string()).await;
             }
         }
         Err(e) => {
             tracing::error!("Agent error for chat {}: {}", chat_id, e);
+            live.abandon().await;
             bot.send_message(msg.chat.id, ERROR_REPLY).await?;
         }
     }
@@ -203,12 +389,180 @@ pub async fn handle_message(
     Ok(())
 }
 
+/// Configuration for the session's provider, model and profile when they
+/// differ from the configured default.
+///
+/// Returns `None` when the configured model already matches (or the session
+/// cannot be loaded, in which case the default model is used).
+async fn session_config(
+    config: &Config,
+    storage: &dyn SessionStore,
+    session_id: Uuid,
+) -> Option<Config> {
+    match storage.get_session(session_id).await {
+        Ok(Some(session))
+            if session.provider != config.provider
+                || session.model != config.model
+                || (session.profile.is_some() && session.profile != config.profile) =>
+        {
+            Some(config.for_session(
+                &session.provider,
+                &session.model,
+                session.profile.as_deref(),
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Native tools for a turn in `scope`.
+///
+/// `search_sessions` only searches the scope's own sessions, so a chat or
+/// group member cannot read other chats' conversations or CLI sessions.
+fn scoped_tools(config: &Config, storage: &Arc<dyn SessionStore>, scope: &str) -> ToolRegistry {
+    let tools = ToolRegistry::from_config(&config.tools, None);
+    let search = &config.tools.search_sessions;
+    if !search.enabled {
+        return tools;
+    }
+    tools.with_tool(
+        SearchSessionsTool::new(Arc::clone(storage), search.max_results).with_scope(scope),
+    )
+}
+
+/// Reply sent when a photo or PDF arrives for a model without vision input.
+pub fn vision_unsupported_reply(model: &str) -> String {
+    format!(
+        "The current model ({}) can't read images or PDFs. Switch to a vision model with /model, or send text.",
+        model
+    )
+}
+
+/// Run the agent over `messages` and return the final assistant message
+/// (answer text and its token usage) and the reason generation stopped.
+///
+/// Answer text is shown in `live` as it streams. Text written before a tool
+/// call is finished off as its own message above the tool notices.
+///
+/// Tool calls that need approval are asked about in the chat with inline
+/// keyboard buttons (see [`TelegramApprover`]). Tool calls are announced in
+/// the chat as they start, failures are reported
+/// when their results arrive, and the typing indicator is refreshed while
+/// tools run. Tool-call and tool-result messages are stored in the session as
+/// the agent appends them. Notice delivery and storage failures are logged and
+/// otherwise ignored.
+async fn stream_response(
+    bot: &Bot,
+    approver: TelegramApprover,
+    agent: &Agent,
+    storage: &dyn SessionStore,
+    session_id: Uuid,
+    messages: &mut Vec<CoreMessage>,
+    live: &mut LiveReply,
+) -> Result<(CoreMessage, StopReason), AgentError> {
+    let chat = approver.chat();
+    let mut stream = agent.stream_with_approver(messages, Some(Arc::new(approver)));
+    let mut content = String::new();
+    // Usage of the current turn; tool-calling turns carry their own.
+    let mut usage: Option<TokenUsage> = None;
+    let mut tool_names: HashMap<String, String> = HashMap::new();
+
+    while let Some(event) = stream.next().await {
+        match event? {
+            StreamEvent::TextDelta(text) => {
+                content.push_str(&text);
+                live.push(&text).await;
+            }
+            StreamEvent::ToolCallStarted { id, name } => {
+                live.finish().await;
+                send_notice(bot, chat, tool_call_notice(&name)).await;
+                tool_names.insert(id, name);
+            }
+            // Text so far belonged to the tool-calling turn; only the final
+            // answer is returned for storage.
+            StreamEvent::ToolCall(_) => {
+                live.finish().await;
+                content.clear();
+            }
+            StreamEvent::ToolResult {
+                id,
+                content: result,
+                is_error,
+            } => {
+                if is_error {
+                    let name = tool_names.get(&id).map_or("tool", String::as_str);
+                    send_notice(bot, chat, tool_error_notice(name, &result)).await;
+                }
+                bot.send_chat_action(chat, ChatAction::Typing).await.ok();
+            }
+            StreamEvent::MessageAppended(appended) => {
+                let stored = StoredMessage::from_message(session_id, &appended);
+                if let Err(e) = storage.add_message(&stored).await {
+                    tracing::warn!("Failed to store tool message for chat {}: {}", chat.0, e);
+                }
+                usage = None;
+            }
+            StreamEvent::Usage { input, output } => {
+                tracing::debug!(chat_id = chat.0, input, output, "token usage");
+                *usage.get_or_insert_default() += TokenUsage::new(input, output);
+            }
+            StreamEvent::ToolCallArgumentsDelta { .. } => {}
+            StreamEvent::Done { stop_reason } => {
+                if stop_reason != StopReason::EndTurn {
+                    tracing::warn!(chat_id = chat.0, %stop_reason, "response stopped early");
+                }
+                return Ok((final_reply(content, usage), stop_reason));
+            }
+        }
+    }
+
+    Ok((final_reply(content, usage), StopReason::EndTurn))
+}
+
+/// Build the final assistant message from streamed text and its turn usage.
+fn final_reply(content: String, usage: Option<TokenUsage>) -> CoreMessage {
+    let mut reply = CoreMessage::new(Role::Assistant, content);
+    reply.usage = usage;
+    reply
+}
+
+/// Send an HTML-formatted status notice, logging delivery failures.
+async fn send_notice(bot: &Bot, chat: ChatId, html: String) {
+    if let Err(e) = bot
+        .send_message(chat, html)
+        .parse_mode(ParseMode::Html)
+        .await
+    {
+        tracing::warn!("Failed to send notice to chat {}: {}", chat.0, e);
+    }
+}
+
+/// Build the HTML notice shown when the agent starts a tool call.
+pub fn tool_call_notice(name: &str) -> String {
+    format!("🔧 Calling <code>{}</code>…", escape_html(name))
+}
+
+/// Build the HTML notice shown when a tool call fails.
+///
+/// The error text is truncated so the notice stays a single short message.
+pub fn tool_error_notice(name: &str, error: &str) -> String {
+    let error = match error.char_indices().nth(TOOL_ERROR_PREVIEW_CHARS) {
+        Some((idx, _)) => format!("{}…", &error[..idx]),
+        None => error.to_string(),
+    };
+    format!(
+        "⚠️ <code>{}</code> failed: {}",
+        escape_html(name),
+        escape_html(&error)
+    )
+}
+
 /// Resolve the session ID for a chat, creating a new session if needed.
 ///
 /// Uses a read-lock first for the common case (session already exists),
 /// then a write-lock with double-check to prevent race conditions on creation.
 async fn resolve_session(
-    chat_id: i64,
+    scope: &str,
     config: &Config,
     storage: &Arc<dyn SessionStore>,
     chat_map: &ChatSessionMap,
@@ -216,7 +570,7 @@ async fn resolve_session(
     // Fast path: check with a read lock.
     {
         let map = chat_map.read().await;
-        if let Some(chat_sessions) = map.get(&chat_id)
+        if let Some(chat_sessions) = map.get(scope)
             && let Some(id) = chat_sessions.active_session_id()
         {
             storage.touch_session(id).await.ok();
@@ -225,21 +579,21 @@ async fn resolve_session(
     }
 
     // Slow path: create a new session, with write-lock double-check.
-    let session = Session::new(&config.provider, &config.model).with_name(tg_session_name(chat_id));
+    let session = Session::new(&config.provider, &config.model).with_name(scope);
 
     storage
         .create_session(&session)
         .await
-        .map_err(|e| anyhow!("failed to create session for chat {}: {}", chat_id, e))?;
+        .map_err(|e| anyhow!("failed to create session {}: {}", scope, e))?;
 
     let mut map = chat_map.write().await;
     // Double-check: another task might have inserted while we awaited the write lock.
-    if let Some(existing) = map.get(&chat_id)
+    if let Some(existing) = map.get(scope)
         && let Some(existing_id) = existing.active_session_id()
     {
         return Ok(existing_id);
     }
-    map.insert(chat_id, ChatSessions::new(session.id));
+    map.insert(scope.to_string(), ChatSessions::new(session.id));
     Ok(session.id)
 }
 
@@ -268,7 +622,7 @@ pub fn chunk_message(text: &str) -> Vec<&str> {
             .or_else(|| slice.rfind('\n'))
             .or_else(|| slice.rfind(' '))
             .map(|pos| pos + 1) // Include the delimiter in the first chunk.
-            .unwrap_or(TELEGRAM_MSG_LIMIT); // Hard split as last resort.
+            .unwrap_or(limit); // Hard split at a char boundary as last resort.
 
         let (chunk, rest) = remaining.split_at(split_at);
         chunks.push(chunk);
@@ -282,6 +636,59 @@ pub fn chunk_message(text: &str) -> Vec<&str> {
     chunks
 }
 
+/// Check whether a group chat passes the `allowed_groups` list.
+///
+/// An empty list allows every group.
+pub fn is_group_allowed(chat_id: i64, allowed_groups: &[i64]) -> bool {
+    allowed_groups.is_empty() || allowed_groups.contains(&chat_id)
+}
+
+/// Whether a group message is addressed to the bot: it mentions the bot's
+/// `@username` (or links to the bot's account) or replies to one of its messages.
+fn is_addressed(msg: &TgMessage, me: &Me) -> bool {
+    let replies_to_bot = msg
+        .reply_to_message()
+        .and_then(|reply| reply.from.as_ref())
+        .is_some_and(|user| user.id == me.id);
+    let entities = msg
+        .parse_entities()
+        .or_else(|| msg.parse_caption_entities())
+        .unwrap_or_default();
+    let mentions_bot = entities.iter().any(|entity| match entity.kind() {
+        MessageEntityKind::Mention => is_bot_mention(entity.text(), me.username()),
+        MessageEntityKind::TextMention { user } => user.id == me.id,
+        _ => false,
+    });
+    replies_to_bot || mentions_bot
+}
+
+/// Whether `mention` (e.g. `@SynapseBot`) names the bot; usernames are case-insensitive.
+pub fn is_bot_mention(mention: &str, username: &str) -> bool {
+    mention
+        .strip_prefix('@')
+        .is_some_and(|name| name.eq_ignore_ascii_case(username))
+}
+
+/// Remove `@username` mentions of the bot from a group message.
+pub fn strip_mention(text: &str, username: &str) -> String {
+    text.split(' ')
+        .filter(|word| !is_bot_mention(word.trim_end_matches([',', ':', '.', '!', '?']), username))
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim()
+        .to_string()
+}
+
+/// Display name prefixed to a group member's messages: their full name, or
+/// `@username` when the name is empty.
+pub fn speaker_name(user: &User) -> String {
+    let name = user.full_name();
+    match &user.username {
+        Some(username) if name.trim().is_empty() => format!("@{}", username),
+        _ => name,
+    }
+}
+
 /// Check whether a Telegram user ID is in the allowed users list.
 ///
 /// Returns `false` for an empty list (secure by default).
@@ -289,10 +696,99 @@ pub fn is_authorized(user_id: u64, allowed_users: &[u64]) -> bool {
     allowed_users.contains(&user_id)
 }
 
+/// Check whether a Telegram user ID may run admin commands.
+///
+/// Returns `false` when `admin_users` is empty or unset.
+pub fn is_admin(user_id: u64, config: &Config) -> bool {
+    config
+        .telegram
+        .as_ref()
+        .is_some_and(|t| is_authorized(user_id, &t.admin_users))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    // Native tool tests
+
+    #[tokio::test]
+    async fn test_scoped_tools_search_only_own_scope() {
+        let db_path = std::env::temp_dir().join(format!("synapse_test_{}.db", Uuid::new_v4()));
+        let store =
+            synapse_core::storage::SqliteStore::new(&format!("sqlite:{}", db_path.display()))
+                .await
+                .unwrap();
+        for scope in ["tg:1", "tg:2"] {
+            let session = Session::new("mock", "mock-model").with_name(scope);
+            store.create_session(&session).await.unwrap();
+            let message =
+                StoredMessage::new(session.id, Role::User, format!("note from {}", scope));
+            store.add_message(&message).await.unwrap();
+        }
+        let storage: Arc<dyn SessionStore> = Arc::new(store);
+        let mut config = Config::default();
+        config.tools.search_sessions.enabled = true;
+
+        let tools = scoped_tools(&config, &storage, "tg:1");
+        let output = tools
+            .get("search_sessions")
+            .unwrap()
+            .call(serde_json::json!({"query": "note"}))
+            .await
+            .unwrap()
+            .content_text();
+
+        assert!(output.contains("note from tg:1"));
+        assert!(!output.contains("note from tg:2"));
+
+        config.tools.search_sessions.enabled = false;
+        assert!(
+            scoped_tools(&config, &storage, "tg:1")
+                .get("search_sessions")
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_session_config_applies_same_provider_profile() {
+        let db_path = std::env::temp_dir().join(format!("synapse_test_{}.db", Uuid::new_v4()));
+        let store =
+            synapse_core::storage::SqliteStore::new(&format!("sqlite:{}", db_path.display()))
+                .await
+                .unwrap();
+        let careful = synapse_core::config::ProfileConfig {
+            system_prompt: Some("Double-check every answer.".to_string()),
+            max_tokens: Some(2048),
+            ..Default::default()
+        };
+        let config = Config {
+            provider: "deepseek".to_string(),
+            model: "deepseek-chat".to_string(),
+            profiles: HashMap::from([("careful".to_string(), careful)]),
+            ..Default::default()
+        };
+        // `/model careful` keeps the default provider and model.
+        let session = Session::new("deepseek", "deepseek-chat");
+        store.create_session(&session).await.unwrap();
+        store
+            .update_session_model(session.id, "deepseek", "deepseek-chat", Some("careful"))
+            .await
+            .unwrap();
+
+        let resumed = session_config(&config, &store, session.id).await.unwrap();
+
+        assert_eq!(
+            resumed.system_prompt.as_deref(),
+            Some("Double-check every answer.")
+        );
+        assert_eq!(resumed.max_tokens, 2048);
+
+        let plain = Session::new("deepseek", "deepseek-chat");
+        store.create_session(&plain).await.unwrap();
+        assert!(session_config(&config, &store, plain.id).await.is_none());
+    }
+
     // Authorization tests
 
     #[test]
@@ -313,6 +809,111 @@ mod tests {
         assert!(!is_authorized(123456789, &allowed));
     }
 
+    #[test]
+    fn test_is_admin_requires_listed_user() {
+        let mut config = Config::default();
+        assert!(!is_admin(123456789, &config));
+
+        config.telegram = Some(synapse_core::config::TelegramConfig {
+            allowed_users: vec![123456789, 987654321],
+            admin_users: vec![123456789],
+            ..Default::default()
+        });
+        assert!(is_admin(123456789, &config));
+        assert!(!is_admin(987654321, &config));
+    }
+
+    // Group chat tests
+
+    fn chat(id: i64, kind: &str) -> Chat {
+        serde_json::from_value(serde_json::json!({"id": id, "type": kind, "title": "Team"}))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_is_group_allowed_empty_list_allows_all() {
+        assert!(is_group_allowed(-100123, &[]));
+        assert!(is_group_allowed(-100123, &[-100123]));
+        assert!(!is_group_allowed(-100456, &[-100123]));
+    }
+
+    #[test]
+    fn test_session_scope_per_user_groups() {
+        let mut config = Config::default();
+        let group = chat(-100123, "supergroup");
+        let private: Chat = serde_json::from_value(
+            serde_json::json!({"id": 42, "type": "private", "first_name": "Ada"}),
+        )
+        .unwrap();
+        assert_eq!(session_scope(&group, 42, &config), "tg:-100123");
+
+        config.telegram = Some(synapse_core::config::TelegramConfig {
+            group_sessions: GroupSessions::PerUser,
+            ..Default::default()
+        });
+        assert_eq!(session_scope(&group, 42, &config), "tg:-100123:42");
+        assert_eq!(session_scope(&private, 42, &config), "tg:42");
+    }
+
+    #[test]
+    fn test_is_bot_mention_case_insensitive() {
+        assert!(is_bot_mention("@SynapseBot", "synapsebot"));
+        assert!(!is_bot_mention("@OtherBot", "synapsebot"));
+        assert!(!is_bot_mention("SynapseBot", "synapsebot"));
+    }
+
+    #[test]
+    fn test_strip_mention() {
+        assert_eq!(
+            strip_mention("@SynapseBot what's the time?", "SynapseBot"),
+            "what's the time?"
+        );
+        assert_eq!(
+            strip_mention("hey @synapsebot, ask @alice", "SynapseBot"),
+            "hey ask @alice"
+        );
+    }
+
+    #[test]
+    fn test_speaker_name_falls_back_to_username() {
+        let user: User = serde_json::from_value(serde_json::json!({
+            "id": 1, "is_bot": false, "first_name": "Ada", "last_name": "Lovelace"
+        }))
+        .unwrap();
+        assert_eq!(speaker_name(&user), "Ada Lovelace");
+
+        let user: User = serde_json::from_value(serde_json::json!({
+            "id": 2, "is_bot": false, "first_name": " ", "username": "ada"
+        }))
+        .unwrap();
+        assert_eq!(speaker_name(&user), "@ada");
+    }
+
+    // Tool notice tests
+
+    #[test]
+    fn test_tool_call_notice_escapes_name() {
+        assert_eq!(
+            tool_call_notice("a<b>"),
+            "🔧 Calling <code>a&lt;b&gt;</code>…"
+        );
+    }
+
+    #[test]
+    fn test_tool_error_notice_truncates() {
+        let long = "x".repeat(TOOL_ERROR_PREVIEW_CHARS + 50);
+        let notice = tool_error_notice("search", &long);
+        assert!(notice.starts_with("⚠️ <code>search</code> failed: "));
+        assert!(notice.ends_with("…"));
+        assert_eq!(notice.matches('x').count(), TOOL_ERROR_PREVIEW_CHARS);
+    }
+
+    #[test]
+    fn test_tool_error_notice_escapes_error() {
+        let notice = tool_error_notice("t", "Error: <bad> & worse");
+        assert!(notice.contains("Error: &lt;bad&gt; &amp; worse"));
+    }
+
     // Message chunking tests
 
     #[test]
@@ -363,4 +964,22 @@ mod tests {
             let _ = chunk.chars().count();
         }
     }
+
+    #[test]
+    fn test_chunk_message_multibyte_without_spaces() {
+        // 3-byte CJK chars: 4096 is not a char boundary and there is no
+        // whitespace, so the hard split must back off to one.
+        let text = "漢".repeat(1500);
+        let chunks = chunk_message(&text);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].len(), 4095);
+        assert_eq!(chunks.concat(), text);
+        assert_eq!(crate::live::split_point(&text), Some(4095));
+
+        // 4-byte emoji.
+        let text = "🎉".repeat(1100);
+        let chunks = chunk_message(&text);
+        assert!(chunks.iter().all(|c| c.len() <= TELEGRAM_MSG_LIMIT));
+        assert_eq!(chunks.concat(), text);
+    }
 }
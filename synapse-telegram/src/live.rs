@@ -0,0 +1,263 @@
+//! Live streaming replies via message edits.
+//!
+//! [`LiveReply`] shows an answer while it is generated: a placeholder message
+//! is sent up front and edited with the accumulated plain text at most once
+//! per [`EDIT_INTERVAL`], continuing in a new message whenever the text
+//! outgrows [`TELEGRAM_MSG_LIMIT`]. Once the answer is complete it is
+//! re-rendered as Telegram HTML.
+
+use std::future::Future;
+use std::time::Duration;
+
+use teloxide::ApiError;
+use teloxide::RequestError;
+use teloxide::prelude::*;
+use teloxide::requests::HasPayload;
+use teloxide::types::{MessageId, ParseMode};
+use tokio::time::Instant;
+
+use crate::format::{TELEGRAM_MSG_LIMIT, chunk_html, md_to_telegram_html};
+use crate::handlers::chunk_message;
+
+/// Minimum time between edits of a live reply, keeping well inside
+/// Telegram's per-chat rate limit.
+pub const EDIT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Text of the placeholder message shown before the first tokens arrive.
+const PLACEHOLDER: &str = "…";
+
+/// A reply that is progressively edited as text streams in.
+///
+/// Text between tool calls forms a segment: [`finish`](Self::finish) renders
+/// the current segment and detaches it, so later text starts a new message
+/// below any tool notices. Delivery failures are logged and otherwise ignored.
+pub struct LiveReply {
+    bot: Bot,
+    chat: ChatId,
+    /// Messages showing the current segment, with the byte offset in `text`
+    /// where each one starts.
+    messages: Vec<(MessageId, usize)>,
+    /// Text of the current segment.
+    text: String,
+    /// Length of `text` shown by the latest edit.
+    shown: usize,
+    /// Earliest time the next edit may be sent.
+    next_edit: Instant,
+}
+
+impl LiveReply {
+    /// Send the placeholder message and start a live reply in `chat`.
+    pub async fn start(bot: &Bot, chat: ChatId) -> Self {
+        let mut reply = Self {
+            bot: bot.clone(),
+            chat,
+            messages: Vec::new(),
+            text: String::new(),
+            shown: 0,
+            next_edit: Instant::now() + EDIT_INTERVAL,
+        };
+        if let Some(id) = reply.send_placeholder().await {
+            reply.messages.push((id, 0));
+        }
+        reply
+    }
+
+    /// Append streamed text, editing the reply if the throttle allows.
+    pub async fn push(&mut self, delta: &str) {
+        self.text.push_str(delta);
+        if Instant::now() >= self.next_edit {
+            self.update().await;
+        }
+    }
+
+    /// Render the current segment as HTML and detach it.
+    ///
+    /// An empty segment deletes its placeholder instead. Falls back to plain
+    /// text when Telegram rejects the HTML.
+    pub async fn finish(&mut self) {
+        let mut messages: Vec<MessageId> = self.messages.drain(..).map(|(id, _)| id).collect();
+        let text = std::mem::take(&mut self.text);
+        self.shown = 0;
+        if text.trim().is_empty() {
+            for id in messages {
+                self.bot.delete_message(self.chat, id).await.ok();
+            }
+            return;
+        }
+
+        tokio::time::sleep_until(self.next_edit).await;
+        let html = md_to_telegram_html(&text);
+        if let Err(e) = self
+            .deliver(&mut messages, &chunk_html(&html), Some(ParseMode::Html))
+            .await
+        {
+            tracing::warn!(
+                "HTML send failed for chat {}, falling back to plain text: {}",
+                self.chat.0,
+                e
+            );
+            if let Err(e) = self
+                .deliver(&mut messages, &chunk_message(&text), None)
+                .await
+            {
+                tracing::warn!("Failed to send reply to chat {}: {}", self.chat.0, e);
+            }
+        }
+        self.next_edit = Instant::now() + EDIT_INTERVAL;
+    }
+
+    /// Delete the placeholder after a failed turn, keeping any partial text.
+    pub async fn abandon(self) {
+        if self.text.trim().is_empty() {
+            for (id, _) in self.messages {
+                self.bot.delete_message(self.chat, id).await.ok();
+            }
+        }
+    }
+
+    /// Show the text received so far, continuing in new messages as needed.
+    async fn update(&mut self) {
+        if self.text.len() == self.shown {
+            return;
+        }
+        loop {
+            let Some(&(id, start)) = self.messages.last() else {
+                // The segment has no message yet (text after a tool call).
+                let Some(id) = self.send_placeholder().await else {
+                    return;
+                };
+                self.messages.push((id, 0));
+                continue;
+            };
+            let pending = self.text[start..].to_string();
+            let Some(split) = split_point(&pending) else {
+                self.edit(id, &pending).await;
+                break;
+            };
+            self.edit(id, &pending[..split]).await;
+            let Some(next) = self.send_placeholder().await else {
+                return;
+            };
+            self.messages.push((next, start + split));
+        }
+        self.shown = self.text.len();
+    }
+
+    /// Edit a live message with plain text, deferring further edits when
+    /// Telegram asks to slow down.
+    async fn edit(&mut self, id: MessageId, text: &str) {
+        self.next_edit = Instant::now() + EDIT_INTERVAL;
+        if text.trim().is_empty() {
+            return;
+        }
+        match self.bot.edit_message_text(self.chat, id, text).await {
+            Ok(_) | Err(RequestError::Api(ApiError::MessageNotModified)) => {}
+            Err(RequestError::RetryAfter(wait)) => {
+                self.next_edit = Instant::now() + wait.duration();
+            }
+            Err(e) => tracing::warn!("Failed to edit reply in chat {}: {}", self.chat.0, e),
+        }
+    }
+
+    /// Send a placeholder message, returning its ID.
+    async fn send_placeholder(&self) -> Option<MessageId> {
+        match self.bot.send_message(self.chat, PLACEHOLDER).await {
+            Ok(message) => Some(message.id),
+            Err(e) => {
+                tracing::warn!("Failed to send reply to chat {}: {}", self.chat.0, e);
+                None
+            }
+        }
+    }
+
+    /// Put `chunks` into the segment's messages, sending new ones as needed
+    /// and deleting unused ones. New message IDs are added to `messages`.
+    async fn deliver<S: AsRef<str>>(
+        &self,
+        messages: &mut Vec<MessageId>,
+        chunks: &[S],
+        parse_mode: Option<ParseMode>,
+    ) -> Result<(), RequestError> {
+        for (i, chunk) in chunks.iter().enumerate() {
+            let chunk = chunk.as_ref();
+            match messages.get(i) {
+                Some(&id) => {
+                    let result = retry_after(|| {
+                        let mut request = self.bot.edit_message_text(self.chat, id, chunk);
+                        request.payload_mut().parse_mode = parse_mode;
+                        request.send()
+                    })
+                    .await;
+                    match result {
+                        Ok(_) | Err(RequestError::Api(ApiError::MessageNotModified)) => {}
+                        Err(e) => return Err(e),
+                    }
+                }
+                None => {
+                    let sent = retry_after(|| {
+                        let mut request = self.bot.send_message(self.chat, chunk);
+                        request.payload_mut().parse_mode = parse_mode;
+                        request.send()
+                    })
+                    .await?;
+                    messages.push(sent.id);
+                }
+            }
+        }
+        for id in messages.drain(chunks.len().min(messages.len())..) {
+            self.bot.delete_message(self.chat, id).await.ok();
+        }
+        Ok(())
+    }
+}
+
+/// Send a request, waiting and retrying once if Telegram asks to slow down.
+async fn retry_after<F, Fut, T>(send: F) -> Result<T, RequestError>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<T, RequestError>>,
+{
+    match send().await {
+        Err(RequestError::RetryAfter(wait)) => {
+            tokio::time::sleep(wait.duration()).await;
+            send().await
+        }
+        result => result,
+    }
+}
+
+/// Byte offset at which `text` must continue in a new message, or `None`
+/// if it fits in one.
+///
+/// Splits at the same paragraph, line or word boundaries as
+/// [`chunk_message`].
+pub fn split_point(text: &str) -> Option<usize> {
+    if text.len() <= TELEGRAM_MSG_LIMIT {
+        return None;
+    }
+    chunk_message(text).first().map(|chunk| chunk.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_point_short_text() {
+        assert_eq!(split_point("hello"), None);
+        assert_eq!(split_point(&"a".repeat(TELEGRAM_MSG_LIMIT)), None);
+    }
+
+    #[test]
+    fn test_split_point_at_paragraph() {
+        let first = "a".repeat(3000);
+        let text = format!("{}\n\n{}", first, "b".repeat(2000));
+        assert_eq!(split_point(&text), Some(first.len() + 1));
+    }
+
+    #[test]
+    fn test_split_point_hard_split() {
+        let text = "a".repeat(TELEGRAM_MSG_LIMIT + 10);
+        assert_eq!(split_point(&text), Some(TELEGRAM_MSG_LIMIT));
+    }
+}
@@ -4,9 +4,12 @@
 //! SessionStore, and MCP subsystems as the CLI interface. Validates the
 //! hexagonal architecture by proving a second frontend can reuse all core logic.
 
+mod approval;
 mod commands;
 mod format;
 mod handlers;
+mod live;
+mod media;
 mod startup;
 
 use std::path::PathBuf;
@@ -17,8 +20,12 @@ use clap::Parser;
 use handlers::ChatSessionMap;
 use startup::{rebuild_chat_map, resolve_bot_token};
 use synapse_core::config::Rotation;
-use synapse_core::{Agent, Config, SessionStore, create_storage, init_mcp_client};
+use synapse_core::{
+    Agent, Config, McpClient, SessionStore, Transcriber, create_storage, create_transcriber,
+    init_mcp_client,
+};
 use teloxide::prelude::*;
+use teloxide::types::UpdateKind;
 use teloxide::utils::command::BotCommands;
 use tokio::sync::RwLock;
 use tracing_subscriber::prelude::*;
@@ -156,13 +163,23 @@ async fn main() -> anyhow::Result<()> {
         }
     }
 
-    // 6. Initialize MCP client.
+    // 6. Initialize MCP client. Without servers an empty client still lets
+    //    `/mcp reload` add them later.
     let mcp_path = config.mcp.as_ref().and_then(|m| m.config_path.as_deref());
-    let mcp_client = init_mcp_client(mcp_path).await;
+    let mcp_client = init_mcp_client(mcp_path)
+        .await
+        .unwrap_or_else(McpClient::empty);
 
-    // 7. Create Agent from config and wrap in Arc.
+    // 7. Create Agent from config and wrap in Arc. `search_sessions` is added
+    //    per turn, limited to the caller's sessions (see `run_turn`).
     let agent =
-        Arc::new(Agent::from_config(&config, mcp_client).context("Failed to create agent")?);
+        Arc::new(Agent::from_config(&config, Some(mcp_client)).context("Failed to create agent")?);
+
+    // 8. Create the speech-to-text backend for voice messages, if configured.
+    let transcriber: Option<Arc<dyn Transcriber>> = create_transcriber(&config).map(Arc::from);
+    if transcriber.is_none() {
+        tracing::info!("No [transcription] section — voice messages are disabled");
+    }
 
     // 9. Rebuild chat-to-session map from persisted sessions.
     let initial_map = rebuild_chat_map(storage.as_ref()).await;
@@ -206,8 +223,15 @@ async fn main() -> anyhow::Result<()> {
             Arc::clone(&config),
             Arc::clone(&agent),
             Arc::clone(&storage),
-            chat_map
+            chat_map,
+            transcriber
         ])
+        // Callback queries skip the per-chat queue: a turn waiting for a tool
+        // approval tap would otherwise block the tap itself.
+        .distribution_function(|update| match update.kind {
+            UpdateKind::CallbackQuery(_) => None,
+            _ => update.chat().map(|chat| chat.id),
+        })
         .enable_ctrlc_handler()
         .build()
         .dispatch()
@@ -0,0 +1,344 @@
+//! Photo, document and voice ingestion for incoming Telegram messages.
+//!
+//! Photos, image files and PDFs become message attachments for vision models;
+//! plain-text, Markdown and source files are inlined into the prompt. Voice
+//! notes and audio files are transcribed with the configured [`Transcriber`].
+
+use anyhow::{Context, anyhow, bail};
+use synapse_core::{Attachment, Transcriber};
+use teloxide::net::Download;
+use teloxide::prelude::*;
+use teloxide::types::{FileId, Message as TgMessage};
+
+/// Media types read as text in addition to `text/*`.
+const TEXT_MEDIA_TYPES: &[&str] = &[
+    "application/json",
+    "application/xml",
+    "application/toml",
+    "application/x-yaml",
+    "application/yaml",
+    "application/javascript",
+    "application/x-sh",
+];
+
+/// File extensions read as text whatever media type Telegram reports.
+const TEXT_EXTENSIONS: &[&str] = &[
+    "txt", "md", "markdown", "rst", "log", "csv", "json", "xml", "yaml", "yml", "toml", "ini",
+    "cfg", "conf", "html", "css", "sql", "sh", "bash", "zsh", "rs", "py", "js", "ts", "jsx", "tsx",
+    "go", "java", "kt", "swift", "c", "h", "cpp", "hpp", "cs", "rb", "php", "lua",
+];
+
+/// How an incoming file is passed to the model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    /// Image attachment with the given media type.
+    Image(&'static str),
+    /// PDF document attachment.
+    Pdf,
+    /// UTF-8 text inlined into the prompt.
+    Text,
+}
+
+/// Content taken from a message's photo or document.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Upload {
+    /// Image or PDF sent to the model as an attachment.
+    Attachment(Attachment),
+    /// Text file contents, formatted for appending to the prompt.
+    Text(String),
+}
+
+/// A photo or document on a message, before download.
+struct IncomingFile {
+    id: FileId,
+    size: u32,
+    kind: Option<FileKind>,
+    media_type: String,
+    name: Option<String>,
+}
+
+/// Whether `msg` carries a photo or document.
+pub fn has_file(msg: &TgMessage) -> bool {
+    msg.photo().is_some() || msg.document().is_some()
+}
+
+/// Whether `msg` carries a voice note or audio file.
+pub fn has_audio(msg: &TgMessage) -> bool {
+    msg.voice().is_some() || msg.audio().is_some()
+}
+
+/// Classify a file by its reported media type and file name.
+///
+/// Returns `None` for files the bot cannot pass to the model.
+pub fn classify(media_type: Option<&str>, name: Option<&str>) -> Option<FileKind> {
+    let extension = name
+        .and_then(|n| n.rsplit_once('.'))
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+    match (media_type.unwrap_or_default(), extension.as_str()) {
+        ("image/png", _) | (_, "png") => Some(FileKind::Image("image/png")),
+        ("image/jpeg", _) | (_, "jpg" | "jpeg") => Some(FileKind::Image("image/jpeg")),
+        ("image/gif", _) | (_, "gif") => Some(FileKind::Image("image/gif")),
+        ("image/webp", _) | (_, "webp") => Some(FileKind::Image("image/webp")),
+        ("application/pdf", _) | (_, "pdf") => Some(FileKind::Pdf),
+        (media_type, extension)
+            if media_type.starts_with("text/")
+                || TEXT_MEDIA_TYPES.contains(&media_type)
+                || TEXT_EXTENSIONS.contains(&extension) =>
+        {
+            Some(FileKind::Text)
+        }
+        _ => None,
+    }
+}
+
+/// Format text file contents for appending to the prompt.
+pub fn inline_text(name: &str, text: &str) -> String {
+    format!("\n\n<file name=\"{}\">\n{}\n</file>", name, text.trim_end())
+}
+
+/// File name sent to the transcriber, whose extension tells it the audio format.
+///
+/// Falls back to an extension derived from `media_type`, then to `.ogg`
+/// (Telegram voice notes are OGG/Opus).
+pub fn audio_file_name(name: Option<&str>, media_type: Option<&str>) -> String {
+    if let Some(name) = name.filter(|n| n.contains('.')) {
+        return name.to_string();
+    }
+    let extension = match media_type.unwrap_or_default() {
+        "audio/mpeg" | "audio/mp3" => "mp3",
+        "audio/mp4" | "audio/x-m4a" | "audio/m4a" => "m4a",
+        "audio/wav" | "audio/x-wav" => "wav",
+        "audio/webm" => "webm",
+        "audio/flac" | "audio/x-flac" => "flac",
+        _ => "ogg",
+    };
+    format!("audio.{}", extension)
+}
+
+/// Format a byte count as megabytes for user-facing messages.
+pub fn format_megabytes(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+}
+
+/// Download and convert the photo or document on `msg`.
+///
+/// Returns `Ok(None)` when the message has neither. Files over `max_bytes`
+/// are rejected before download.
+///
+/// # Errors
+///
+/// Returns an error with a user-facing message when the file is too large,
+/// of an unsupported type, not valid UTF-8 text, or cannot be downloaded.
+pub async fn read_upload(
+    bot: &Bot,
+    msg: &TgMessage,
+    max_bytes: u64,
+) -> anyhow::Result<Option<Upload>> {
+    let Some(file) = incoming_file(msg) else {
+        return Ok(None);
+    };
+    let Some(kind) = file.kind else {
+        bail!(
+            "I can't read {} files. Send images, PDFs or text files.",
+            file.media_type
+        );
+    };
+    check_size(file.size, max_bytes)?;
+
+    let data = download(bot, file.id)
+        .await
+        .context("Could not download the file. Please try again.")?;
+    let upload = match kind {
+        FileKind::Image(media_type) => Upload::Attachment(Attachment::image(media_type, data)),
+        FileKind::Pdf => {
+            Upload::Attachment(Attachment::document("application/pdf", file.name, data))
+        }
+        FileKind::Text => {
+            let name = file.name.unwrap_or_else(|| "file.txt".to_string());
+            let text = String::from_utf8(data)
+                .map_err(|_| anyhow!("{} is not a UTF-8 text file.", name))?;
+            Upload::Text(inline_text(&name, &text))
+        }
+    };
+    Ok(Some(upload))
+}
+
+/// Download and transcribe the voice note or audio file on `msg`.
+///
+/// Returns `Ok(None)` when the message has neither.
+///
+/// # Errors
+///
+/// Returns an error with a user-facing message when transcription is not
+/// configured, the file is too large, or download or transcription fails.
+pub async fn transcribe_audio(
+    bot: &Bot,
+    msg: &TgMessage,
+    transcriber: Option<&dyn Transcriber>,
+    max_bytes: u64,
+) -> anyhow::Result<Option<String>> {
+    let (id, size, file_name) = if let Some(voice) = msg.voice() {
+        let media_type = voice.mime_type.as_ref().map(|m| m.essence_str());
+        (
+            &voice.file.id,
+            voice.file.size,
+            audio_file_name(None, media_type),
+        )
+    } else if let Some(audio) = msg.audio() {
+        let media_type = audio.mime_type.as_ref().map(|m| m.essence_str());
+        let name = audio_file_name(audio.file_name.as_deref(), media_type);
+        (&audio.file.id, audio.file.size, name)
+    } else {
+        return Ok(None);
+    };
+    let Some(transcriber) = transcriber else {
+        bail!("Voice messages are not enabled on this bot. Please send text.");
+    };
+    check_size(size, max_bytes)?;
+
+    let audio = download(bot, id.clone())
+        .await
+        .context("Could not download the voice message. Please try again.")?;
+    let text = transcriber
+        .transcribe(audio, &file_name)
+        .await
+        .context("Could not transcribe the voice message. Please try again.")?;
+    if text.is_empty() {
+        bail!("I couldn't make out any speech in that message.");
+    }
+    Ok(Some(text))
+}
+
+/// Reject files over `max_bytes` before downloading them.
+fn check_size(size: u32, max_bytes: u64) -> anyhow::Result<()> {
+    if u64::from(size) > max_bytes {
+        bail!(
+            "File too large ({}). The limit is {}.",
+            format_megabytes(size.into()),
+            format_megabytes(max_bytes)
+        );
+    }
+    Ok(())
+}
+
+/// Describe the photo (largest size) or document on `msg`.
+fn incoming_file(msg: &TgMessage) -> Option<IncomingFile> {
+    if let Some(photo) = msg
+        .photo()
+        .and_then(|sizes| sizes.iter().max_by_key(|p| p.width * p.height))
+    {
+        // Telegram re-encodes photos as JPEG.
+        return Some(IncomingFile {
+            id: photo.file.id.clone(),
+            size: photo.file.size,
+            kind: Some(FileKind::Image("image/jpeg")),
+            media_type: "image/jpeg".to_string(),
+            name: None,
+        });
+    }
+    let document = msg.document()?;
+    let media_type = document
+        .mime_type
+        .as_ref()
+        .map(|m| m.essence_str().to_string());
+    Some(IncomingFile {
+        id: document.file.id.clone(),
+        size: document.file.size,
+        kind: classify(media_type.as_deref(), document.file_name.as_deref()),
+        media_type: media_type.unwrap_or_else(|| "unknown".to_string()),
+        name: document.file_name.clone(),
+    })
+}
+
+/// Fetch a file's contents through the Bot API.
+async fn download(bot: &Bot, id: FileId) -> anyhow::Result<Vec<u8>> {
+    let file = bot.get_file(id).await?;
+    let mut data = Vec::with_capacity(file.meta.size as usize);
+    bot.download_file(&file.path, &mut data).await?;
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_images_by_media_type() {
+        assert_eq!(
+            classify(Some("image/png"), Some("shot")),
+            Some(FileKind::Image("image/png"))
+        );
+        assert_eq!(
+            classify(Some("image/webp"), None),
+            Some(FileKind::Image("image/webp"))
+        );
+    }
+
+    #[test]
+    fn test_classify_falls_back_to_extension() {
+        assert_eq!(
+            classify(None, Some("Photo.JPG")),
+            Some(FileKind::Image("image/jpeg"))
+        );
+        assert_eq!(
+            classify(Some("application/octet-stream"), Some("report.pdf")),
+            Some(FileKind::Pdf)
+        );
+        assert_eq!(
+            classify(Some("application/octet-stream"), Some("main.rs")),
+            Some(FileKind::Text)
+        );
+    }
+
+    #[test]
+    fn test_classify_text_media_types() {
+        assert_eq!(classify(Some("text/markdown"), None), Some(FileKind::Text));
+        assert_eq!(
+            classify(Some("application/json"), Some("data")),
+            Some(FileKind::Text)
+        );
+    }
+
+    #[test]
+    fn test_classify_unsupported() {
+        assert_eq!(classify(Some("application/zip"), Some("a.zip")), None);
+        assert_eq!(classify(None, None), None);
+    }
+
+    #[test]
+    fn test_inline_text_wraps_contents() {
+        assert_eq!(
+            inline_text("notes.md", "# Notes\n"),
+            "\n\n<file name=\"notes.md\">\n# Notes\n</file>"
+        );
+    }
+
+    #[test]
+    fn test_audio_file_name() {
+        assert_eq!(audio_file_name(None, Some("audio/ogg")), "audio.ogg");
+        assert_eq!(audio_file_name(None, Some("audio/mpeg")), "audio.mp3");
+        assert_eq!(
+            audio_file_name(Some("memo"), Some("audio/mp4")),
+            "audio.m4a"
+        );
+        assert_eq!(audio_file_name(Some("talk.flac"), None), "talk.flac");
+        assert_eq!(audio_file_name(None, None), "audio.ogg");
+    }
+
+    #[test]
+    fn test_check_size_limit() {
+        assert!(check_size(1024, 1024).is_ok());
+        let err = check_size(2 * 1024 * 1024, 1024 * 1024).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "File too large (2.0 MB). The limit is 1.0 MB."
+        );
+    }
+
+    #[test]
+    fn test_format_megabytes() {
+        assert_eq!(format_megabytes(10 * 1024 * 1024), "10.0 MB");
+        assert_eq!(format_megabytes(1536 * 1024), "1.5 MB");
+    }
+}
@@ -36,31 +36,27 @@ pub fn resolve_bot_token(config: &Config) -> anyhow::Result<String> {
         })
 }
 
-/// Rebuild the in-memory chat-ID-to-session map from persisted sessions.
+/// Rebuild the in-memory scope-to-session map from persisted sessions.
 ///
-/// Sessions created by this bot follow the naming convention `"tg:<chat_id>"`.
-/// Any session without this prefix is ignored. `list_sessions()` returns sessions
-/// ordered by `updated_at DESC`, so the first UUID encountered per chat is the
-/// most recently updated and becomes the active session (index 0).
-pub async fn rebuild_chat_map(storage: &dyn SessionStore) -> HashMap<i64, ChatSessions> {
+/// Sessions created by this bot are named after their scope: `"tg:<chat_id>"`,
+/// or `"tg:<chat_id>:<user_id>"` for per-user group sessions. Any other
+/// session is ignored. `list_sessions()` returns sessions ordered by
+/// `updated_at DESC`, so the first UUID encountered per scope is the most
+/// recently updated and becomes the active session (index 0).
+pub async fn rebuild_chat_map(storage: &dyn SessionStore) -> HashMap<String, ChatSessions> {
     let sessions: Vec<SessionSummary> = storage.list_sessions().await.unwrap_or_default();
-    let mut map: HashMap<i64, Vec<uuid::Uuid>> = HashMap::new();
+    let mut map: HashMap<String, Vec<uuid::Uuid>> = HashMap::new();
 
     for s in &sessions {
-        if let Some(chat_id) = s
-            .name
-            .as_deref()
-            .and_then(|n| n.strip_prefix("tg:"))
-            .and_then(|id_str| id_str.parse::<i64>().ok())
-        {
-            map.entry(chat_id).or_default().push(s.id);
+        if let Some(name) = s.name.as_deref().filter(|n| is_scope_name(n)) {
+            map.entry(name.to_string()).or_default().push(s.id);
         }
     }
 
     map.into_iter()
-        .map(|(chat_id, session_ids)| {
+        .map(|(scope, session_ids)| {
             (
-                chat_id,
+                scope,
                 ChatSessions {
                     sessions: session_ids,
                     active_idx: 0,
@@ -69,3 +65,15 @@ pub async fn rebuild_chat_map(storage: &dyn SessionStore) -> HashMap<i64, ChatSe
         })
         .collect()
 }
+
+/// Whether a session name is a Telegram scope: `tg:<chat_id>` or
+/// `tg:<chat_id>:<user_id>`.
+fn is_scope_name(name: &str) -> bool {
+    let Some(rest) = name.strip_prefix("tg:") else {
+        return false;
+    };
+    match rest.split_once(':') {
+        Some((chat, user)) => chat.parse::<i64>().is_ok() && user.parse::<u64>().is_ok(),
+        None => rest.parse::<i64>().is_ok(),
+    }
+}
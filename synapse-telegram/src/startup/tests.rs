@@ -3,9 +3,9 @@ use std::sync::Mutex;
 use async_trait::async_trait;
 use chrono::Utc;
 use synapse_core::config::SessionConfig;
-use synapse_core::session::{Session, StoredMessage};
+use synapse_core::session::{ArtifactKind, Session, SessionArtifact, StoredMessage};
 use synapse_core::storage::{CleanupResult, SessionStore, StorageError};
-use synapse_core::{Config, SessionSummary, TelegramConfig};
+use synapse_core::{Config, SessionSummary, TelegramConfig, TokenUsage};
 use uuid::Uuid;
 
 use super::*;
@@ -42,6 +42,16 @@ impl SessionStore for MockSessionStore {
         Ok(())
     }
 
+    async fn update_session_model(
+        &self,
+        _id: Uuid,
+        _provider: &str,
+        _model: &str,
+        _profile: Option<&str>,
+    ) -> Result<(), StorageError> {
+        Ok(())
+    }
+
     async fn delete_session(&self, _id: Uuid) -> Result<bool, StorageError> {
         Ok(false)
     }
@@ -54,6 +64,22 @@ impl SessionStore for MockSessionStore {
         Ok(vec![])
     }
 
+    async fn get_session_usage(&self, _session_id: Uuid) -> Result<TokenUsage, StorageError> {
+        Ok(TokenUsage::default())
+    }
+
+    async fn add_artifact(&self, _artifact: &SessionArtifact) -> Result<(), StorageError> {
+        Ok(())
+    }
+
+    async fn get_latest_artifact(
+        &self,
+        _session_id: Uuid,
+        _kind: ArtifactKind,
+    ) -> Result<Option<SessionArtifact>, StorageError> {
+        Ok(None)
+    }
+
     async fn cleanup(&self, _config: &SessionConfig) -> Result<CleanupResult, StorageError> {
         Ok(CleanupResult::default())
     }
@@ -177,10 +203,10 @@ async fn test_rebuild_chat_map_with_telegram_sessions() {
     let map = rebuild_chat_map(&store).await;
 
     assert_eq!(map.len(), 2);
-    let cs1 = map.get(&111222333i64).unwrap();
+    let cs1 = map.get("tg:111222333").unwrap();
     assert_eq!(cs1.sessions, vec![id1]);
     assert_eq!(cs1.active_idx, 0);
-    let cs2 = map.get(&444555666i64).unwrap();
+    let cs2 = map.get("tg:444555666").unwrap();
     assert_eq!(cs2.sessions, vec![id2]);
     assert_eq!(cs2.active_idx, 0);
 }
@@ -228,7 +254,7 @@ async fn test_rebuild_chat_map_ignores_non_telegram() {
 
     // Only the tg: session should be in the map.
     assert_eq!(map.len(), 1);
-    let cs = map.get(&123456789i64).unwrap();
+    let cs = map.get("tg:123456789").unwrap();
     assert_eq!(cs.sessions, vec![tg_id]);
     assert_eq!(cs.active_idx, 0);
 }
@@ -269,10 +295,38 @@ async fn test_rebuild_chat_map_multi_session_per_chat() {
 
     // Both sessions should be grouped under the same chat_id.
     assert_eq!(map.len(), 1);
-    let cs = map.get(&999888777i64).unwrap();
+    let cs = map.get("tg:999888777").unwrap();
     assert_eq!(cs.sessions.len(), 2);
     // Most recently updated session should be first (index 0 = active).
     assert_eq!(cs.sessions[0], newest_id);
     assert_eq!(cs.sessions[1], older_id);
     assert_eq!(cs.active_idx, 0);
 }
+
+#[tokio::test]
+async fn test_rebuild_chat_map_per_user_group_sessions() {
+    let now = Utc::now();
+    let alice = Uuid::new_v4();
+    let bob = Uuid::new_v4();
+    let summary = |id: Uuid, name: &str| SessionSummary {
+        id,
+        name: Some(name.to_string()),
+        provider: "deepseek".to_string(),
+        model: "deepseek-chat".to_string(),
+        created_at: now,
+        updated_at: now,
+        message_count: 1,
+        preview: None,
+    };
+
+    let store = MockSessionStore::with_sessions(vec![
+        summary(alice, "tg:-100123:42"),
+        summary(bob, "tg:-100123:43"),
+        summary(Uuid::new_v4(), "tg:-100123:bob"),
+    ]);
+    let map = rebuild_chat_map(&store).await;
+
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get("tg:-100123:42").unwrap().sessions, vec![alice]);
+    assert_eq!(map.get("tg:-100123:43").unwrap().sessions, vec![bob]);
+}